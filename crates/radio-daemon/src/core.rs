@@ -601,6 +601,36 @@ impl DaemonCore {
                 // Telemetry only available in TUI mode
                 tracing::info!("Telemetry commands only available in TUI mode");
             }
+            Command::StartRecording { .. }
+            | Command::StopRecording { .. }
+            | Command::CancelRecording { .. }
+            | Command::ScheduleRecording { .. }
+            | Command::UnscheduleRecording { .. } => {
+                // The recorder taps the stream proxy, which only exists in r4dio
+                tracing::info!("Recording commands only available in TUI mode");
+            }
+            Command::JumpToLive => {
+                // No timeshift buffer without the stream proxy; stations always play live
+                tracing::debug!("JumpToLive ignored: timeshift only available in TUI mode");
+            }
+            Command::SetSleepTimer { .. }
+            | Command::CancelSleepTimer
+            | Command::SetAlarm { .. }
+            | Command::CancelAlarm => {
+                tracing::info!("Sleep timer and alarm only available in TUI mode");
+            }
+            Command::SetLoudnessGain { .. } => {
+                tracing::debug!("SetLoudnessGain ignored: only available in TUI mode");
+            }
+            Command::PreviewStation { .. } | Command::AddStation { .. } => {
+                // Previews play through the stream proxy, which only exists in r4dio
+                tracing::info!("Station discovery only available in TUI mode");
+            }
+            Command::UpdateStation { .. }
+            | Command::DeleteStation { .. }
+            | Command::DuplicateStation { .. } => {
+                tracing::info!("Station editing only available in TUI mode");
+            }
         }
         Ok(())
     }
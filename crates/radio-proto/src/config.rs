@@ -21,6 +21,20 @@ pub struct Config {
     pub viz: VizConfig,
     #[serde(default)]
     pub binaries: BinariesConfig,
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+    #[serde(default)]
+    pub timers: TimersConfig,
+    #[serde(default)]
+    pub mpris: MprisConfig,
+    #[serde(default)]
+    pub tcp: TcpConfig,
+    #[serde(default)]
+    pub loudness: LoudnessConfig,
+    #[serde(default)]
+    pub recognition: RecognitionConfig,
+    #[serde(default)]
+    pub discover: DiscoverConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,7 +85,7 @@ pub struct PollingConfig {
     pub max_jobs_per_cycle: usize,
 }
 
-/// Linux-specific audio visualization configuration.
+/// Audio visualization configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VizConfig {
     /// Use PipeWire/PulseAudio monitor for VU meter and oscilloscope visualization
@@ -82,6 +96,14 @@ pub struct VizConfig {
     /// PipeWire/PulseAudio device name to monitor. None = default monitor source
     #[serde(default)]
     pub pipewire_device: Option<String>,
+    /// FFT window in samples for the spectrum and spectrogram scope modes;
+    /// rounded to a power of two between 512 and 16384. Default: 4096
+    #[serde(default = "default_fft_size")]
+    pub fft_size: usize,
+    /// How long spectrum peak markers hold before falling, in ms. 0 disables
+    /// peak-hold. Default: 1500
+    #[serde(default = "default_peak_hold_ms")]
+    pub peak_hold_ms: u64,
 }
 
 /// Configuration for external binary dependencies.
@@ -93,11 +115,120 @@ pub struct BinariesConfig {
     pub use_system_deps: bool,
 }
 
+/// Local stream proxy configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// Minutes of audio kept per open station stream for pause/rewind.
+    /// Costs roughly 1 MB per minute at 128 kbps. 0 disables timeshift. Default: 30
+    #[serde(default = "default_timeshift_minutes")]
+    pub timeshift_minutes: u64,
+    /// Highest HLS variant bandwidth to pick, in bits/s; 0 picks the best
+    /// one. Default: 0
+    #[serde(default)]
+    pub hls_max_bandwidth: u64,
+}
+
+/// Sleep timer and wake-up alarm configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimersConfig {
+    /// Seconds over which the sleep timer fades the volume out. Default: 60
+    #[serde(default = "default_sleep_fade_secs")]
+    pub sleep_fade_secs: u64,
+    /// Seconds over which an alarm ramps the volume up from zero. Default: 120
+    #[serde(default = "default_alarm_ramp_secs")]
+    pub alarm_ramp_secs: u64,
+    /// Wall-clock time ("HH:MM") used when an alarm is set without one,
+    /// e.g. from the TUI. Default: "07:30"
+    #[serde(default = "default_alarm_time")]
+    pub alarm_time: String,
+}
+
+/// MPRIS2 D-Bus player interface (Linux only).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MprisConfig {
+    /// Register on the session bus for media keys and `playerctl`. Default: true
+    #[serde(default = "default_mpris_enabled")]
+    pub enabled: bool,
+}
+
+/// Client protocol server that `r4dio --attach` connects to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TcpConfig {
+    #[serde(default = "default_tcp_enabled")]
+    pub enabled: bool,
+    /// Set to "0.0.0.0" to let TUIs on other machines attach. Default: "127.0.0.1"
+    #[serde(default = "default_bind_address")]
+    pub bind_address: String,
+    /// Default: 9876
+    #[serde(default = "default_tcp_port")]
+    pub port: u16,
+}
+
+/// EBU R128 loudness normalization of stations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoudnessConfig {
+    /// Apply a per-station gain, learned from each station's measured
+    /// integrated loudness, so switching stations lands at `target_lufs`.
+    /// Default: false
+    #[serde(default)]
+    pub normalize: bool,
+    /// Default: -16.0
+    #[serde(default = "default_target_lufs")]
+    pub target_lufs: f32,
+    /// Largest boost or cut applied, in dB. Default: 12.0
+    #[serde(default = "default_max_gain_db")]
+    pub max_gain_db: f32,
+}
+
+/// Song recognition (`i`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecognitionConfig {
+    /// Seconds of audio, ending at what was just heard, per recognition. Default: 10
+    #[serde(default = "default_capture_secs")]
+    pub capture_secs: u64,
+    /// Backends tried in order until one names the track. Default: library, vibra
+    #[serde(default = "default_recognizers")]
+    pub backends: Vec<RecognizerConfig>,
+    /// Auto-ID (`I`): identify this often even without a track change. Default: 180
+    #[serde(default = "default_auto_interval_secs")]
+    pub auto_interval_secs: u64,
+    /// Auto-ID never identifies more often than this, boundaries included. Default: 45
+    #[serde(default = "default_auto_min_gap_secs")]
+    pub auto_min_gap_secs: u64,
+}
+
+/// Station discovery (`b`) through a Radio Browser-compatible directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoverConfig {
+    /// Directory API base URL. Default: https://de1.api.radio-browser.info
+    #[serde(default = "default_discover_api_url")]
+    pub api_url: String,
+    /// Results per search. Default: 100
+    #[serde(default = "default_discover_limit")]
+    pub limit: usize,
+}
+
+/// One `[[recognition.backends]]` entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum RecognizerConfig {
+    /// The local fingerprint library (`fingerprints.tsv`), built from earlier
+    /// matches and tagged files in `downloads_dir`.
+    Library,
+    /// Shazam fingerprinting through the vibra CLI.
+    Vibra,
+    /// A program that reads s16le PCM on stdin and prints JSON.  `{rate}`,
+    /// `{channels}` and `{seconds}` in `command` are filled in.
+    Command { name: String, command: Vec<String> },
+}
+
 impl Default for VizConfig {
     fn default() -> Self {
         Self {
             pipewire_viz: default_pipewire_viz(),
             pipewire_device: None,
+            fft_size: default_fft_size(),
+            peak_hold_ms: default_peak_hold_ms(),
         }
     }
 }
@@ -110,6 +241,73 @@ impl Default for BinariesConfig {
     }
 }
 
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            timeshift_minutes: default_timeshift_minutes(),
+            hls_max_bandwidth: 0,
+        }
+    }
+}
+
+impl Default for TimersConfig {
+    fn default() -> Self {
+        Self {
+            sleep_fade_secs: default_sleep_fade_secs(),
+            alarm_ramp_secs: default_alarm_ramp_secs(),
+            alarm_time: default_alarm_time(),
+        }
+    }
+}
+
+impl Default for MprisConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_mpris_enabled(),
+        }
+    }
+}
+
+impl Default for TcpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_tcp_enabled(),
+            bind_address: default_bind_address(),
+            port: default_tcp_port(),
+        }
+    }
+}
+
+impl Default for LoudnessConfig {
+    fn default() -> Self {
+        Self {
+            normalize: false,
+            target_lufs: default_target_lufs(),
+            max_gain_db: default_max_gain_db(),
+        }
+    }
+}
+
+impl Default for RecognitionConfig {
+    fn default() -> Self {
+        Self {
+            capture_secs: default_capture_secs(),
+            backends: default_recognizers(),
+            auto_interval_secs: default_auto_interval_secs(),
+            auto_min_gap_secs: default_auto_min_gap_secs(),
+        }
+    }
+}
+
+impl Default for DiscoverConfig {
+    fn default() -> Self {
+        Self {
+            api_url: default_discover_api_url(),
+            limit: default_discover_limit(),
+        }
+    }
+}
+
 impl Default for PathsConfig {
     fn default() -> Self {
         Self {
@@ -240,10 +438,78 @@ fn default_pipewire_viz() -> bool {
     false
 }
 
+fn default_fft_size() -> usize {
+    4096
+}
+
+fn default_peak_hold_ms() -> u64 {
+    1500
+}
+
 fn default_use_system_deps() -> bool {
     false
 }
 
+fn default_timeshift_minutes() -> u64 {
+    30
+}
+
+fn default_sleep_fade_secs() -> u64 {
+    60
+}
+
+fn default_alarm_ramp_secs() -> u64 {
+    120
+}
+
+fn default_alarm_time() -> String {
+    "07:30".to_string()
+}
+
+fn default_mpris_enabled() -> bool {
+    true
+}
+
+fn default_tcp_enabled() -> bool {
+    true
+}
+
+fn default_tcp_port() -> u16 {
+    platform::DAEMON_TCP_PORT
+}
+
+fn default_target_lufs() -> f32 {
+    -16.0
+}
+
+fn default_max_gain_db() -> f32 {
+    12.0
+}
+
+fn default_capture_secs() -> u64 {
+    10
+}
+
+fn default_recognizers() -> Vec<RecognizerConfig> {
+    vec![RecognizerConfig::Library, RecognizerConfig::Vibra]
+}
+
+fn default_auto_interval_secs() -> u64 {
+    180
+}
+
+fn default_auto_min_gap_secs() -> u64 {
+    45
+}
+
+fn default_discover_api_url() -> String {
+    "https://de1.api.radio-browser.info".to_string()
+}
+
+fn default_discover_limit() -> usize {
+    100
+}
+
 fn default_m3u_url() -> String {
     "https://raw.githubusercontent.com/ja-mf/radio-curation/refs/heads/main/jamf_radios.m3u"
         .to_string()
@@ -307,6 +573,13 @@ impl Default for Config {
             polling: PollingConfig::default(),
             viz: VizConfig::default(),
             binaries: BinariesConfig::default(),
+            proxy: ProxyConfig::default(),
+            timers: TimersConfig::default(),
+            mpris: MprisConfig::default(),
+            tcp: TcpConfig::default(),
+            loudness: LoudnessConfig::default(),
+            recognition: RecognitionConfig::default(),
+            discover: DiscoverConfig::default(),
         }
     }
 }
@@ -324,9 +597,50 @@ mod tests {
         assert!(config.stations.m3u_url.starts_with("https://"));
         assert!(config.polling.auto_polling);
         assert_eq!(config.polling.poll_interval_secs, 120);
+        assert_eq!(config.proxy.timeshift_minutes, 30);
+        assert_eq!(config.timers.alarm_time, "07:30");
+        assert!(config.mpris.enabled);
+        assert_eq!(config.tcp.port, 9876);
+        assert_eq!(config.tcp.bind_address, "127.0.0.1");
+        assert_eq!(config.viz.fft_size, 4096);
+        assert!(!config.loudness.normalize);
+        assert_eq!(config.loudness.target_lufs, -16.0);
+        assert!(config.discover.api_url.contains("radio-browser"));
+        assert_eq!(
+            config.recognition.backends,
+            vec![RecognizerConfig::Library, RecognizerConfig::Vibra]
+        );
         assert!(config
             .stations
             .stations_toml
             .ends_with("radio/stations.toml"));
     }
+
+    #[test]
+    fn test_recognition_backends() {
+        let config: Config = toml::from_str(
+            r#"
+            [[recognition.backends]]
+            kind = "command"
+            name = "audd"
+            command = ["audd-recognize", "--rate", "{rate}"]
+
+            [[recognition.backends]]
+            kind = "vibra"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.recognition.capture_secs, 10);
+        assert_eq!(config.recognition.auto_min_gap_secs, 45);
+        assert_eq!(
+            config.recognition.backends,
+            vec![
+                RecognizerConfig::Command {
+                    name: "audd".into(),
+                    command: vec!["audd-recognize".into(), "--rate".into(), "{rate}".into()],
+                },
+                RecognizerConfig::Vibra,
+            ]
+        );
+    }
 }
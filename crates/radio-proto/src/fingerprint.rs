@@ -0,0 +1,415 @@
+//! Local known-tracks fingerprint library.
+//!
+//! Landmark fingerprints in the Shazam style: the clip is downmixed to mono
+//! 11025 Hz, the loudest spectral peak in each of a few frequency bands is
+//! picked per STFT frame, peaks that just appeared are kept, and nearby ones
+//! are paired into hashes of (f1, f2, Δt).  A clip matches a track when enough of its hashes line up
+//! at one time offset.
+//!
+//! The library is `fingerprints.tsv`, one track per line, append-only:
+//!
+//!   track  origin  t:hash t:hash …   (t and hash in hex)
+//!
+//! `origin` says where the fingerprint came from — the recognizer that named
+//! a snippet (`vibra`, …) or the file (and chapter start) it was indexed from.
+//! A track can appear on several lines; each adds coverage.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+use tracing::{info, warn};
+
+use crate::recognizer::{PcmClip, RecognizeFuture, Recognizer};
+use crate::songs::RecognitionSource;
+
+/// Sample rate fingerprints are computed at.
+pub const FINGERPRINT_RATE: u32 = 11_025;
+
+const FFT_SIZE: usize = 1024;
+const HOP: usize = 256;
+/// Bin ranges; one peak per band and frame.
+const BANDS: [(usize, usize); 5] = [(10, 20), (20, 40), (40, 80), (80, 160), (160, 512)];
+/// Peaks this many frames after the anchor are paired with it (max 63).
+const TARGET_ZONE: (usize, usize) = (1, 32);
+const FAN_OUT: usize = 5;
+/// Hashes that must agree on one time offset for a match.
+const MIN_ALIGNED: usize = 15;
+
+/// One hash and the frame it anchors at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Landmark {
+    pub t: u32,
+    pub hash: u32,
+}
+
+/// Landmarks of a clip (any rate / channel count).
+pub fn fingerprint(clip: &PcmClip) -> Vec<Landmark> {
+    let samples = mono_resampled(clip);
+    landmarks(&peaks(&samples))
+}
+
+/// Downmix to mono and average down to `FINGERPRINT_RATE`.
+fn mono_resampled(clip: &PcmClip) -> Vec<f32> {
+    let channels = clip.channels.max(1) as usize;
+    let mono: Vec<f32> = clip
+        .data
+        .chunks_exact(2 * channels)
+        .map(|frame| {
+            frame
+                .chunks_exact(2)
+                .map(|s| i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0)
+                .sum::<f32>()
+                / channels as f32
+        })
+        .collect();
+    let step = clip.rate as f64 / FINGERPRINT_RATE as f64;
+    if step <= 1.0 {
+        return mono;
+    }
+    let out_len = (mono.len() as f64 / step) as usize;
+    (0..out_len)
+        .map(|i| {
+            let a = (i as f64 * step) as usize;
+            let b = (((i + 1) as f64 * step) as usize).clamp(a + 1, mono.len());
+            mono[a..b].iter().sum::<f32>() / (b - a) as f32
+        })
+        .collect()
+}
+
+/// Per frame, the bin of each band's loudest peak that stands out from the
+/// frame's other band peaks.
+fn peaks(samples: &[f32]) -> Vec<Vec<u16>> {
+    if samples.len() < FFT_SIZE {
+        return Vec::new();
+    }
+    let fft = FftPlanner::<f32>::new().plan_fft_forward(FFT_SIZE);
+    let window: Vec<f32> = (0..FFT_SIZE)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (FFT_SIZE - 1) as f32).cos())
+        .collect();
+    let mut buf = vec![Complex::new(0.0f32, 0.0); FFT_SIZE];
+    let mut frames = Vec::with_capacity((samples.len() - FFT_SIZE) / HOP + 1);
+    let mut previous: Vec<u16> = Vec::new();
+    for start in (0..=samples.len() - FFT_SIZE).step_by(HOP) {
+        for (i, c) in buf.iter_mut().enumerate() {
+            *c = Complex::new(samples[start + i] * window[i], 0.0);
+        }
+        fft.process(&mut buf);
+        // Only true local maxima count: a band's edge bin is often just the
+        // skirt of a louder tone next door, and would hash the same everywhere.
+        let band_peaks: Vec<(u16, f32)> = BANDS
+            .iter()
+            .filter_map(|&(lo, hi)| {
+                (lo..hi)
+                    .map(|b| (b, buf[b].norm()))
+                    .filter(|&(b, m)| m > buf[b - 1].norm() && m >= buf[b + 1].norm())
+                    .max_by(|x, y| x.1.total_cmp(&y.1))
+                    .map(|(bin, mag)| (bin as u16, (mag + 1e-9).ln()))
+            })
+            .collect();
+        let mean = band_peaks.iter().map(|p| p.1).sum::<f32>() / band_peaks.len().max(1) as f32;
+        // Silence has no landmarks; otherwise keep the peaks at or above the mean.
+        let loud = band_peaks.iter().any(|p| p.1 > -4.0);
+        let current: Vec<u16> = band_peaks
+            .into_iter()
+            .filter(|p| loud && p.1 >= mean)
+            .map(|p| p.0)
+            .collect();
+        // Only onsets: a held note would otherwise pair with itself frame
+        // after frame, and one chance collision would look like a match.
+        frames.push(
+            current
+                .iter()
+                .copied()
+                .filter(|&b| !previous.iter().any(|&p| p.abs_diff(b) <= 1))
+                .collect(),
+        );
+        previous = current;
+    }
+    frames
+}
+
+fn landmarks(frames: &[Vec<u16>]) -> Vec<Landmark> {
+    let mut out = Vec::new();
+    for (t1, anchors) in frames.iter().enumerate() {
+        for &f1 in anchors {
+            let targets = (t1 + TARGET_ZONE.0..=t1 + TARGET_ZONE.1)
+                .filter_map(|t2| frames.get(t2).map(|fs| (t2, fs)))
+                .flat_map(|(t2, fs)| fs.iter().map(move |&f2| (t2 - t1, f2)))
+                .take(FAN_OUT);
+            for (dt, f2) in targets {
+                out.push(Landmark {
+                    t: t1 as u32,
+                    hash: (f1 as u32) << 15 | (f2 as u32) << 6 | dt as u32,
+                });
+            }
+        }
+    }
+    out
+}
+
+// ── Library ───────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct Index {
+    tracks: Vec<String>,
+    origins: HashSet<String>,
+    /// hash → (track index, anchor frame)
+    hashes: HashMap<u32, Vec<(u32, u32)>>,
+}
+
+impl Index {
+    fn insert(&mut self, track: &str, origin: &str, landmarks: &[Landmark]) {
+        let id = match self.tracks.iter().position(|t| t == track) {
+            Some(i) => i,
+            None => {
+                self.tracks.push(track.to_string());
+                self.tracks.len() - 1
+            }
+        } as u32;
+        self.origins.insert(origin.to_string());
+        for l in landmarks {
+            self.hashes.entry(l.hash).or_default().push((id, l.t));
+        }
+    }
+}
+
+/// Fingerprints of known tracks, shared between the `library` recognizer
+/// and the downloads indexer.
+pub struct FingerprintLibrary {
+    path: PathBuf,
+    index: RwLock<Index>,
+}
+
+impl FingerprintLibrary {
+    /// Load `path`; a missing or unreadable file gives an empty library.
+    pub fn load(path: PathBuf) -> Self {
+        let mut index = Index::default();
+        if let Ok(text) = std::fs::read_to_string(&path) {
+            for line in text.lines() {
+                if let Some((track, origin, landmarks)) = parse_line(line) {
+                    index.insert(track, origin, &landmarks);
+                }
+            }
+            info!(
+                "[fingerprint] loaded {} tracks from {}",
+                index.tracks.len(),
+                path.display()
+            );
+        }
+        Self {
+            path,
+            index: RwLock::new(index),
+        }
+    }
+
+    /// Number of distinct tracks known.
+    pub fn len(&self) -> usize {
+        self.index.read().unwrap().tracks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether anything was already indexed from `origin`.
+    pub fn has_origin(&self, origin: &str) -> bool {
+        self.index.read().unwrap().origins.contains(origin)
+    }
+
+    /// Index `landmarks` as `track` and append them to the library file.
+    pub fn add(&self, track: &str, origin: &str, landmarks: &[Landmark]) -> std::io::Result<()> {
+        if landmarks.is_empty() {
+            return Ok(());
+        }
+        let track = clean_field(track);
+        let origin = clean_field(origin);
+        let mut line = format!("{}\t{}\t", track, origin);
+        for (i, l) in landmarks.iter().enumerate() {
+            if i > 0 {
+                line.push(' ');
+            }
+            line.push_str(&format!("{:x}:{:x}", l.t, l.hash));
+        }
+        line.push('\n');
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())?;
+        self.index
+            .write()
+            .unwrap()
+            .insert(&track, &origin, landmarks);
+        Ok(())
+    }
+
+    /// Best matching track and how many hashes agreed, if enough did.
+    pub fn best_match(&self, landmarks: &[Landmark]) -> Option<(String, usize)> {
+        let index = self.index.read().unwrap();
+        let mut votes: HashMap<(u32, i64), usize> = HashMap::new();
+        for l in landmarks {
+            for &(id, t) in index.hashes.get(&l.hash).into_iter().flatten() {
+                *votes.entry((id, t as i64 - l.t as i64)).or_default() += 1;
+            }
+        }
+        let ((id, _), score) = votes.into_iter().max_by_key(|(_, n)| *n)?;
+        (score >= MIN_ALIGNED).then(|| (index.tracks[id as usize].clone(), score))
+    }
+}
+
+fn clean_field(s: &str) -> String {
+    s.replace(['\t', '\n'], " ").replace('\r', "")
+}
+
+fn parse_line(line: &str) -> Option<(&str, &str, Vec<Landmark>)> {
+    let mut cols = line.splitn(3, '\t');
+    let track = cols.next().filter(|t| !t.is_empty())?;
+    let origin = cols.next()?;
+    let landmarks = cols
+        .next()?
+        .split(' ')
+        .filter_map(|pair| {
+            let (t, hash) = pair.split_once(':')?;
+            Some(Landmark {
+                t: u32::from_str_radix(t, 16).ok()?,
+                hash: u32::from_str_radix(hash, 16).ok()?,
+            })
+        })
+        .collect();
+    Some((track, origin, landmarks))
+}
+
+// ── Recognizer ────────────────────────────────────────────────────────────────
+
+/// The `library` backend: matches against `FingerprintLibrary` and learns
+/// every track another backend names.
+pub struct LibraryRecognizer {
+    library: Arc<FingerprintLibrary>,
+}
+
+impl LibraryRecognizer {
+    pub fn new(library: Arc<FingerprintLibrary>) -> Self {
+        Self { library }
+    }
+}
+
+impl Recognizer for LibraryRecognizer {
+    fn source(&self) -> RecognitionSource {
+        RecognitionSource::Library
+    }
+
+    fn recognize<'a>(&'a self, clip: &'a PcmClip) -> RecognizeFuture<'a> {
+        Box::pin(async move {
+            if self.library.is_empty() {
+                return Ok(None);
+            }
+            let landmarks = fingerprint(clip);
+            Ok(self.library.best_match(&landmarks).map(|(track, score)| {
+                info!("[fingerprint] {} ({} aligned hashes)", track, score);
+                track
+            }))
+        })
+    }
+
+    fn learn(&self, clip: &PcmClip, track: &str, source: &RecognitionSource) {
+        if let Err(e) = self.library.add(track, source.label(), &fingerprint(clip)) {
+            warn!("[fingerprint] could not save {:?}: {}", track, e);
+        }
+    }
+}
+
+/// Origin key for an indexed file, or one chapter of it.
+pub fn file_origin(path: &Path, chapter_start: Option<f64>) -> String {
+    match chapter_start {
+        Some(start) => format!("file:{}#{:.0}", path.display(), start),
+        None => format!("file:{}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    /// `secs` of random chords, 44.1 kHz stereo, changing every 250 ms.
+    fn chords(seed: u64, secs: usize) -> Vec<i16> {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        let mut out = Vec::with_capacity(secs * 44_100 * 2);
+        for _ in 0..secs * 4 {
+            let freqs: Vec<f32> = (0..3).map(|_| rng.gen_range(150.0..4000.0)).collect();
+            for i in 0..44_100 / 4 {
+                let t = (out.len() / 2 + i) as f32 / 44_100.0;
+                let s: f32 = freqs
+                    .iter()
+                    .map(|f| (2.0 * std::f32::consts::PI * f * t).sin())
+                    .sum();
+                let v = (s / 3.0 * 12_000.0) as i16;
+                out.extend([v, v]);
+            }
+        }
+        out
+    }
+
+    fn clip(samples: &[i16]) -> PcmClip {
+        PcmClip {
+            data: samples.iter().flat_map(|s| s.to_le_bytes()).collect(),
+            rate: 44_100,
+            channels: 2,
+        }
+    }
+
+    #[test]
+    fn test_snippet_matches_its_track() {
+        let dir = tempfile_dir();
+        let library = FingerprintLibrary::load(dir.join("fingerprints.tsv"));
+        let song = chords(1, 30);
+        let other = chords(2, 30);
+        library
+            .add("A \u{2013} Song", "test", &fingerprint(&clip(&song)))
+            .unwrap();
+        library
+            .add("B \u{2013} Other", "test", &fingerprint(&clip(&other)))
+            .unwrap();
+
+        // 8 s from the middle, not aligned to the STFT hop.
+        let start = (12 * 44_100 + 333) * 2;
+        let snippet = clip(&song[start..start + 8 * 44_100 * 2]);
+        let (track, _) = library.best_match(&fingerprint(&snippet)).unwrap();
+        assert_eq!(track, "A \u{2013} Song");
+
+        let unknown = clip(&chords(3, 8));
+        assert_eq!(library.best_match(&fingerprint(&unknown)), None);
+
+        // Survives a reload from disk.
+        let reloaded = FingerprintLibrary::load(dir.join("fingerprints.tsv"));
+        assert_eq!(reloaded.len(), 2);
+        assert!(reloaded.has_origin("test"));
+        assert_eq!(
+            reloaded.best_match(&fingerprint(&snippet)).unwrap().0,
+            "A \u{2013} Song"
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_silence_has_no_landmarks() {
+        assert!(fingerprint(&clip(&vec![0; 44_100 * 4])).is_empty());
+    }
+
+    fn tempfile_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "r4dio-fingerprint-test-{}-{}",
+            std::process::id(),
+            rand::random::<u32>()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+}
@@ -1,5 +1,10 @@
 pub mod config;
+pub mod fingerprint;
 pub mod platform;
 pub mod protocol;
+pub mod radio_browser;
+pub mod recognizer;
+pub mod song_store;
 pub mod songs;
+pub mod songs_export;
 pub mod state;
@@ -19,13 +19,52 @@ pub enum Command {
     Random,
     TogglePause,
     Volume { value: f32 },
+    /// Gain in dB applied after decoding to level out station loudness
+    /// (`[loudness] normalize`); 0 removes it.
+    SetLoudnessGain { db: f32 },
     SeekRelative { seconds: f64 },
     SeekTo { seconds: f64 },
+    /// Leave the timeshift buffer and resume the current station at the live edge.
+    JumpToLive,
     GetState,
     /// Enable latency telemetry for debugging.
     EnableTelemetry,
     /// Print latency telemetry report to log.
     PrintTelemetryReport,
+    /// Start recording a station's stream to disk.
+    StartRecording { station_idx: usize },
+    /// Finish a station's recording, keeping everything written so far.
+    StopRecording { station_idx: usize },
+    /// Abort a station's recording and discard the segment in progress.
+    CancelRecording { station_idx: usize },
+    /// Record an NTS show from its channel's stream for the show's time window.
+    ScheduleRecording { show: ScheduledShow },
+    /// Drop a scheduled show, finishing its recording if it is running.
+    UnscheduleRecording { channel: usize, start: i64 },
+    /// Stop playback after `minutes`, fading the volume out over the last
+    /// `timers.sleep_fade_secs` seconds.  Replaces any running sleep timer.
+    SetSleepTimer { minutes: u64 },
+    CancelSleepTimer,
+    /// Start `target` at the next occurrence of local wall-clock `time`
+    /// ("HH:MM"; `None` = `timers.alarm_time` from config), ramping the
+    /// volume up from zero.  Replaces any pending alarm.
+    SetAlarm {
+        #[serde(default)]
+        time: Option<String>,
+        target: AlarmTarget,
+    },
+    CancelAlarm,
+    /// Play a station that isn't in the list (a directory search result)
+    /// through the proxy, without adding it.
+    PreviewStation { station: Station },
+    /// Append a station to the list and to `stations.toml`.
+    AddStation { station: Station },
+    /// Replace a station in the list and in `stations.toml`.
+    UpdateStation { station_idx: usize, station: Station },
+    /// Remove a station from the list and from `stations.toml`.
+    DeleteStation { station_idx: usize },
+    /// Insert a copy of a station ("Name (copy)") right after it.
+    DuplicateStation { station_idx: usize },
 }
 
 /// Messages sent from Daemon to TUI (broadcasts)
@@ -60,6 +99,10 @@ pub enum Broadcast {
     Pcm {
         samples: Vec<f32>,
     },
+    /// A local file played through to its end (not stopped or replaced).
+    FileEnded {
+        path: String,
+    },
 }
 
 /// Detailed playback status — reflects actual mpv state
@@ -132,6 +175,9 @@ pub struct DaemonState {
     pub is_playing: bool, // true when Playing
     pub playback_status: PlaybackStatus,
     pub icy_title: Option<String>,
+    /// Files: playback position and length.  Live stations: position within
+    /// the proxy's timeshift buffer and the buffer's length, so
+    /// `duration_secs - time_pos_secs` is how far playback is behind live.
     pub time_pos_secs: Option<f64>,
     pub duration_secs: Option<f64>,
     /// Health of the mpv process as tracked by the daemon.
@@ -140,12 +186,165 @@ pub struct DaemonState {
     /// Whether playback is currently paused (separate from playback_status for clarity).
     #[serde(default)]
     pub is_paused: bool,
+    /// Stream recordings currently running (at most one per station).
+    #[serde(default)]
+    pub recordings: Vec<RecordingStatus>,
+    /// NTS shows marked for recording, including ones being recorded now.
+    #[serde(default)]
+    pub scheduled: Vec<ScheduledShow>,
+    /// Pending sleep timer, if any.
+    #[serde(default)]
+    pub sleep_timer: Option<SleepTimer>,
+    /// Pending wake-up alarm, if any.
+    #[serde(default)]
+    pub alarm: Option<Alarm>,
+    /// Station being previewed (`Command::PreviewStation`); playing it
+    /// leaves `current_station` and `current_file` empty.
+    #[serde(default)]
+    pub preview: Option<Box<Station>>,
+    /// The proxy's upstream for what's playing, once it has one.
+    #[serde(default)]
+    pub upstream: Option<UpstreamStatus>,
+    /// Stream health, refreshed every second while the proxy has streams open.
+    #[serde(default)]
+    pub diagnostics: Diagnostics,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// The proxy's connection to a station's upstream server.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct UpstreamStatus {
+    /// URL being streamed: the station's `url` or one of its `mirrors`.
+    pub url: String,
+    /// Times the upstream was lost and opened again.
+    pub reconnects: u32,
+    /// Lost and not back yet; listeners are held meanwhile.
+    pub reconnecting: bool,
+}
+
+/// Stream health for the diagnostics pane (`H`) and `GET /api/diagnostics`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct Diagnostics {
+    /// One per stream the proxy has open, by station index.
+    pub streams: Vec<StreamDiagnostics>,
+    /// mpv's read-ahead while it plays a station.
+    pub player: Option<PlayerCache>,
+}
+
+/// One stream open in the proxy.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct StreamDiagnostics {
+    /// `None` for the discover preview.
+    pub station_idx: Option<usize>,
+    pub station_name: String,
+    pub url: String,
+    pub content_type: String,
+    /// Format from the ICY headers, refined by ffprobe once some audio is
+    /// buffered.
+    pub codec: Option<String>,
+    pub bitrate_kbps: Option<u32>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+    /// Upstream bytes received over the last few seconds.
+    pub throughput_kbps: f64,
+    /// Live and timeshift subscribers.
+    pub listeners: usize,
+    /// Times a subscriber fell behind the broadcast channel and lost chunks,
+    /// and how many it lost the last time.
+    pub lag_events: u64,
+    pub last_lag_chunks: u64,
+    pub reconnects: u32,
+    pub reconnecting: bool,
+    /// Audio held in the timeshift buffer, and how much it can hold.
+    pub buffered_secs: f64,
+    pub buffer_secs: f64,
+    pub uptime_secs: u64,
+}
+
+/// mpv's demuxer cache for the stream it plays.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct PlayerCache {
+    /// Seconds of audio read ahead of playback.
+    pub cache_secs: Option<f64>,
+    /// Rate mpv reads from the proxy.
+    pub input_kbps: Option<f64>,
+    /// Playback stalled until the cache refills.
+    pub paused_for_cache: bool,
+}
+
+/// A stream recording in progress.  Recordings are split into a new file on
+/// every ICY title change; `segment_path` is the file currently being written.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct RecordingStatus {
+    pub station_idx: usize,
+    pub station_name: String,
+    /// Unix timestamp (seconds) when the recording started.
+    pub started_at: i64,
+    /// File currently being written (still carries its `.part` suffix).
+    pub segment_path: String,
+    /// Number of finished segments already moved into place.
+    pub segments_done: usize,
+}
+
+/// An NTS show marked for recording.  A show is identified by its channel
+/// and start time.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ScheduledShow {
+    /// NTS channel: 0 = NTS 1, 1 = NTS 2.
+    pub channel: usize,
+    pub title: String,
+    /// Unix timestamps (seconds) of the show's slot.
+    pub start: i64,
+    pub end: i64,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub location: String,
+}
+
+impl ScheduledShow {
+    pub fn is_same_slot(&self, channel: usize, start: i64) -> bool {
+        self.channel == channel && self.start == start
+    }
+}
+
+/// A running sleep timer.  Playback fades out over the `fade_secs` before
+/// `ends_at` and is then stopped.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct SleepTimer {
+    /// Unix timestamp (seconds) when playback stops.
+    pub ends_at: i64,
+    pub fade_secs: u64,
+}
+
+/// What an alarm starts playing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlarmTarget {
+    Station { station_idx: usize },
+    File { path: String },
+}
+
+/// A pending wake-up alarm.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Alarm {
+    /// Unix timestamp (seconds) when the alarm fires.
+    pub at: i64,
+    pub target: AlarmTarget,
+    /// Station name or file name, for display.
+    pub label: String,
+    /// Seconds over which the volume ramps up from zero.
+    pub ramp_secs: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct Station {
     pub name: String,
     pub url: String,
+    /// Alternate stream URLs the proxy fails over to when `url` is down
+    #[serde(default)]
+    pub mirrors: Vec<String>,
     /// Optional NTS Infinite Mixtape page URL (metadata lookup source)
     #[serde(default)]
     pub mixtape_url: String,
@@ -0,0 +1,239 @@
+//! Station discovery through a Radio Browser-compatible directory
+//! (<https://api.radio-browser.info>).
+//!
+//! The TUI's discover pane (`b`) sends a query like
+//! `jazz country:france codec:mp3` here: bare words search station names,
+//! `tag:`, `country:` and `codec:` narrow the results.  A chosen result is
+//! turned into a `Station` with `DirectoryStation::to_station` and appended
+//! to `stations.toml`.  The base URL comes from `[discover] api_url`, so any
+//! server speaking the same `/json/stations/search` API works.
+
+use serde::Deserialize;
+
+use crate::protocol::Station;
+
+/// Tags kept per station; directory entries often carry dozens.
+const MAX_TAGS: usize = 8;
+
+/// A directory search: every set field must match.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DirectoryQuery {
+    pub name: String,
+    pub tag: String,
+    pub country: String,
+    pub codec: String,
+}
+
+impl DirectoryQuery {
+    /// Parse `words tag:T country:C codec:X`; bare words are the name.
+    pub fn parse(query: &str) -> Self {
+        let mut q = Self::default();
+        let mut name = Vec::new();
+        for word in query.split_whitespace() {
+            match word.split_once(':') {
+                Some(("tag", v)) => q.tag = v.replace('_', " "),
+                Some(("country", v)) => q.country = v.replace('_', " "),
+                Some(("codec", v)) => q.codec = v.to_string(),
+                _ => name.push(word),
+            }
+        }
+        q.name = name.join(" ");
+        q
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.name.is_empty()
+            && self.tag.is_empty()
+            && self.country.is_empty()
+            && self.codec.is_empty()
+    }
+}
+
+/// One station as the directory returns it.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct DirectoryStation {
+    pub stationuuid: String,
+    pub name: String,
+    pub url: String,
+    /// `url` with playlists (.pls/.m3u) resolved to the stream itself.
+    pub url_resolved: String,
+    pub homepage: String,
+    /// Comma-separated.
+    pub tags: String,
+    pub country: String,
+    pub countrycode: String,
+    /// Region within the country; the directory has no city field.
+    pub state: String,
+    pub language: String,
+    pub codec: String,
+    /// kbps; 0 when unknown.
+    pub bitrate: u32,
+    pub votes: i64,
+}
+
+impl DirectoryStation {
+    /// The URL to play: the resolved stream when the directory found one.
+    pub fn stream_url(&self) -> &str {
+        if self.url_resolved.is_empty() {
+            &self.url
+        } else {
+            &self.url_resolved
+        }
+    }
+
+    /// "MP3 128 kbps · english", or whatever of it is known.
+    pub fn format_label(&self) -> String {
+        let mut parts = Vec::new();
+        match (self.codec.is_empty(), self.bitrate) {
+            (false, 0) => parts.push(self.codec.clone()),
+            (false, kbps) => parts.push(format!("{} {} kbps", self.codec, kbps)),
+            (true, 0) => {}
+            (true, kbps) => parts.push(format!("{} kbps", kbps)),
+        }
+        if !self.language.is_empty() {
+            parts.push(self.language.clone());
+        }
+        parts.join(" · ")
+    }
+
+    /// Map onto a `stations.toml` entry: tags, region (as city) and country
+    /// carry over; codec, bitrate and language become the description.
+    pub fn to_station(&self) -> Station {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.tags.split(',') {
+            let tag = tag.trim().to_lowercase();
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        tags.truncate(MAX_TAGS);
+        Station {
+            name: self.name.trim().to_string(),
+            url: self.stream_url().trim().to_string(),
+            description: self.format_label(),
+            tags,
+            city: self.state.trim().to_string(),
+            country: self.country.trim().to_string(),
+            ..Station::default()
+        }
+    }
+}
+
+/// Search `api_url` (e.g. `https://de1.api.radio-browser.info`), most voted
+/// first, skipping stations the directory marks broken.
+pub async fn search(
+    api_url: &str,
+    query: &DirectoryQuery,
+    limit: usize,
+) -> anyhow::Result<Vec<DirectoryStation>> {
+    let endpoint = format!("{}/json/stations/search", api_url.trim_end_matches('/'));
+    let limit = limit.to_string();
+    let mut params = vec![
+        ("limit", limit.as_str()),
+        ("hidebroken", "true"),
+        ("order", "votes"),
+        ("reverse", "true"),
+    ];
+    for (key, value) in [
+        ("name", &query.name),
+        ("tag", &query.tag),
+        ("country", &query.country),
+        ("codec", &query.codec),
+    ] {
+        if !value.is_empty() {
+            params.push((key, value.as_str()));
+        }
+    }
+    let stations = reqwest::Client::new()
+        .get(&endpoint)
+        .header("User-Agent", concat!("r4dio/", env!("CARGO_PKG_VERSION")))
+        .query(&params)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(stations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const RESULTS: &str = r#"[{
+        "stationuuid": "96202f73-0601-11e8-ae97-52543be04c81",
+        "name": " FIP Jazz ",
+        "url": "http://icecast.radiofrance.fr/fipjazz-midfi.m3u",
+        "url_resolved": "http://icecast.radiofrance.fr/fipjazz-midfi.mp3",
+        "tags": "jazz,Jazz, public radio,,bebop",
+        "country": "France",
+        "countrycode": "FR",
+        "state": "Paris",
+        "language": "french",
+        "codec": "MP3",
+        "bitrate": 128,
+        "votes": 5012,
+        "lastcheckok": 1
+    }]"#;
+
+    #[test]
+    fn test_query_and_mapping() {
+        assert_eq!(
+            DirectoryQuery::parse("fip jazz country:united_kingdom codec:AAC"),
+            DirectoryQuery {
+                name: "fip jazz".into(),
+                country: "united kingdom".into(),
+                codec: "AAC".into(),
+                ..Default::default()
+            }
+        );
+
+        let found: Vec<DirectoryStation> = serde_json::from_str(RESULTS).unwrap();
+        let station = found[0].to_station();
+        assert_eq!(station.name, "FIP Jazz");
+        assert_eq!(
+            station.url,
+            "http://icecast.radiofrance.fr/fipjazz-midfi.mp3"
+        );
+        assert_eq!(station.tags, vec!["jazz", "public radio", "bebop"]);
+        assert_eq!(station.city, "Paris");
+        assert_eq!(station.country, "France");
+        assert_eq!(station.description, "MP3 128 kbps · french");
+    }
+
+    #[tokio::test]
+    async fn test_search_against_mock_directory() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut conn, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            let n = conn.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                RESULTS.len(),
+                RESULTS
+            );
+            conn.write_all(response.as_bytes()).await.unwrap();
+            request
+        });
+
+        let found = search(&api_url, &DirectoryQuery::parse("fip tag:jazz"), 5)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].votes, 5012);
+
+        let request = server.await.unwrap();
+        let path = request.lines().next().unwrap();
+        assert!(path.starts_with("GET /json/stations/search?"), "{}", path);
+        assert!(path.contains("limit=5"), "{}", path);
+        assert!(path.contains("name=fip"), "{}", path);
+        assert!(path.contains("tag=jazz"), "{}", path);
+        assert!(!path.contains("country="), "{}", path);
+    }
+}
@@ -0,0 +1,366 @@
+//! Song-recognition backends.
+//!
+//! A recognition job captures one clip of what was just played — for
+//! stations the last seconds of the proxy's buffer, for files the stretch
+//! before the playback position — and hands it to each configured
+//! `Recognizer` in `[recognition] backends` order until one names the track.
+//! Backends:
+//!
+//! - `library` — the local `FingerprintLibrary`; it learns every track the
+//!   other backends name, so repeats are identified offline.
+//! - `vibra` — Shazam fingerprinting through the vibra CLI.
+//! - `command` — any program that reads raw PCM on stdin and prints JSON,
+//!   either `{"artist": "…", "title": "…"}` or vibra's `{"track": {…}}`.
+//!   Empty output, `{}` or `null` means no match.
+//!
+//! The match is recorded in the VDS with the backend's `RecognitionSource`.
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::Arc;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::{debug, info, warn};
+
+use crate::config::{RecognitionConfig, RecognizerConfig};
+use crate::fingerprint::{FingerprintLibrary, LibraryRecognizer};
+use crate::songs::{vibra_rec_string, RecognitionSource};
+
+/// Raw audio handed to recognizers: interleaved signed 16-bit little-endian.
+#[derive(Debug, Clone)]
+pub struct PcmClip {
+    pub data: Vec<u8>,
+    pub rate: u32,
+    pub channels: u16,
+}
+
+impl PcmClip {
+    pub fn secs(&self) -> f64 {
+        self.data.len() as f64 / (2.0 * self.channels as f64 * self.rate as f64)
+    }
+}
+
+pub type RecognizeFuture<'a> =
+    Pin<Box<dyn Future<Output = anyhow::Result<Option<String>>> + Send + 'a>>;
+
+pub trait Recognizer: Send + Sync {
+    /// Where matches from this backend are recorded.
+    fn source(&self) -> RecognitionSource;
+
+    /// "Artist – Title" (or just the title) for the clip; `Ok(None)` when the
+    /// backend ran fine but didn't know the track.
+    fn recognize<'a>(&'a self, clip: &'a PcmClip) -> RecognizeFuture<'a>;
+
+    /// Called with the clip after `source` (another backend) named it.
+    fn learn(&self, _clip: &PcmClip, _track: &str, _source: &RecognitionSource) {}
+}
+
+/// Backends from config, in priority order.  Vibra is skipped when its
+/// binary can't be found.
+pub fn build_recognizers(
+    config: &RecognitionConfig,
+    library: &Arc<FingerprintLibrary>,
+) -> Vec<Box<dyn Recognizer>> {
+    let mut out: Vec<Box<dyn Recognizer>> = Vec::new();
+    for backend in &config.backends {
+        match backend {
+            RecognizerConfig::Library => {
+                out.push(Box::new(LibraryRecognizer::new(library.clone())))
+            }
+            RecognizerConfig::Vibra => match crate::platform::find_vibra_binary() {
+                Some(path) => out.push(Box::new(VibraRecognizer { path })),
+                None => warn!("[recognize] vibra not found, backend skipped"),
+            },
+            RecognizerConfig::Command { name, command } => {
+                if command.is_empty() {
+                    warn!("[recognize] backend {:?} has no command, skipped", name);
+                    continue;
+                }
+                out.push(Box::new(CommandRecognizer {
+                    name: name.clone(),
+                    command: command.clone(),
+                }));
+            }
+        }
+    }
+    out
+}
+
+/// Run `clip` through `recognizers` in order; the first match wins and the
+/// other backends get to learn it.
+pub async fn recognize_clip(
+    recognizers: &[Box<dyn Recognizer>],
+    clip: &PcmClip,
+) -> Option<(RecognitionSource, String)> {
+    for (i, recognizer) in recognizers.iter().enumerate() {
+        let source = recognizer.source();
+        match recognizer.recognize(clip).await {
+            Ok(Some(track)) => {
+                info!("[recognize] {}: {}", source.label(), track);
+                for (j, other) in recognizers.iter().enumerate() {
+                    if j != i {
+                        other.learn(clip, &track, &source);
+                    }
+                }
+                return Some((source, track));
+            }
+            Ok(None) => info!("[recognize] {}: no match", source.label()),
+            Err(e) => warn!("[recognize] {} failed: {}", source.label(), e),
+        }
+    }
+    None
+}
+
+/// Capture `secs` seconds of `stream_url` with ffmpeg as 44.1 kHz stereo.
+/// A finite body (such as a proxy clip) may yield less.
+pub async fn capture_stream(stream_url: &str, secs: u64) -> anyhow::Result<PcmClip> {
+    info!("[recognize] capturing {}s of {}", secs, stream_url);
+    capture_pcm(&["-i", stream_url, "-t", &secs.to_string()], 44_100, 2).await
+}
+
+/// Decode the `secs` seconds of a local file that end at `end_secs`.
+pub async fn capture_file(path: &Path, end_secs: f64, secs: u64) -> anyhow::Result<PcmClip> {
+    let start = (end_secs - secs as f64).max(0.0);
+    let len = (end_secs - start).max(1.0);
+    info!(
+        "[recognize] decoding {:.1}s–{:.1}s of {}",
+        start,
+        start + len,
+        path.display()
+    );
+    decode_file_range(path, start, len, 44_100, 2).await
+}
+
+/// `len` seconds of a local file from `start`, at `rate` / `channels`.
+pub async fn decode_file_range(
+    path: &Path,
+    start: f64,
+    len: f64,
+    rate: u32,
+    channels: u16,
+) -> anyhow::Result<PcmClip> {
+    let input = path.to_string_lossy();
+    capture_pcm(
+        &[
+            "-ss",
+            &format!("{:.3}", start),
+            "-i",
+            &input,
+            "-t",
+            &format!("{:.3}", len),
+        ],
+        rate,
+        channels,
+    )
+    .await
+}
+
+async fn capture_pcm(input_args: &[&str], rate: u32, channels: u16) -> anyhow::Result<PcmClip> {
+    let ffmpeg =
+        crate::platform::find_ffmpeg_binary().ok_or_else(|| anyhow::anyhow!("ffmpeg not found"))?;
+    let output = tokio::process::Command::new(ffmpeg)
+        .arg("-nostdin")
+        .args(input_args)
+        .args([
+            "-vn",
+            "-ar",
+            &rate.to_string(),
+            "-ac",
+            &channels.to_string(),
+        ])
+        .args(["-f", "s16le", "pipe:1"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await?;
+    if output.stdout.is_empty() {
+        anyhow::bail!("ffmpeg captured no audio ({})", output.status);
+    }
+    Ok(PcmClip {
+        data: output.stdout,
+        rate,
+        channels,
+    })
+}
+
+// ── Backends ──────────────────────────────────────────────────────────────────
+
+pub struct VibraRecognizer {
+    path: PathBuf,
+}
+
+impl Recognizer for VibraRecognizer {
+    fn source(&self) -> RecognitionSource {
+        RecognitionSource::Vibra
+    }
+
+    fn recognize<'a>(&'a self, clip: &'a PcmClip) -> RecognizeFuture<'a> {
+        Box::pin(async move {
+            let secs = (clip.secs().round() as u64).max(1).to_string();
+            let args = [
+                "--recognize".to_string(),
+                "--seconds".to_string(),
+                secs,
+                "--rate".to_string(),
+                clip.rate.to_string(),
+                "--channels".to_string(),
+                clip.channels.to_string(),
+                "--bits".to_string(),
+                "16".to_string(),
+            ];
+            let stdout = run_with_pcm(&self.path, &args, clip).await?;
+            if stdout.trim().is_empty() {
+                anyhow::bail!("vibra produced no output");
+            }
+            let json: serde_json::Value = serde_json::from_str(stdout.trim())
+                .map_err(|e| anyhow::anyhow!("vibra JSON parse: {} (raw: {})", e, stdout.trim()))?;
+            Ok(vibra_rec_string(&json))
+        })
+    }
+}
+
+pub struct CommandRecognizer {
+    name: String,
+    /// Program and arguments; `{rate}`, `{channels}` and `{seconds}` in the
+    /// arguments are filled in from the clip.
+    command: Vec<String>,
+}
+
+impl Recognizer for CommandRecognizer {
+    fn source(&self) -> RecognitionSource {
+        RecognitionSource::Command(self.name.clone())
+    }
+
+    fn recognize<'a>(&'a self, clip: &'a PcmClip) -> RecognizeFuture<'a> {
+        Box::pin(async move {
+            let args: Vec<String> = self.command[1..]
+                .iter()
+                .map(|a| {
+                    a.replace("{rate}", &clip.rate.to_string())
+                        .replace("{channels}", &clip.channels.to_string())
+                        .replace("{seconds}", &format!("{:.0}", clip.secs()))
+                })
+                .collect();
+            let stdout = run_with_pcm(&PathBuf::from(&self.command[0]), &args, clip).await?;
+            parse_command_output(&stdout)
+        })
+    }
+}
+
+/// Run `program`, feed it the clip on stdin and return its stdout.
+async fn run_with_pcm(
+    program: &PathBuf,
+    args: &[String],
+    clip: &PcmClip,
+) -> anyhow::Result<String> {
+    debug!("[recognize] running {} {:?}", program.display(), args);
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let mut stdin = child.stdin.take().expect("stdin piped");
+    let mut stdout = child.stdout.take().expect("stdout piped");
+    let mut stderr = child.stderr.take().expect("stderr piped");
+    // Feed and drain concurrently: a backend may answer (and exit) before
+    // reading the whole clip, or fill its stdout pipe while we still write.
+    let write = async {
+        // A backend that stops reading early closes the pipe; not an error.
+        let _ = stdin.write_all(&clip.data).await;
+        drop(stdin);
+    };
+    let mut out = String::new();
+    let mut err = String::new();
+    let (_, out_res, err_res) = tokio::join!(
+        write,
+        stdout.read_to_string(&mut out),
+        stderr.read_to_string(&mut err)
+    );
+    out_res?;
+    err_res?;
+    let status = child.wait().await?;
+    if !status.success() {
+        anyhow::bail!("{} exited {}: {}", program.display(), status, err.trim());
+    }
+    Ok(out)
+}
+
+/// Track from a command backend's JSON output.
+fn parse_command_output(stdout: &str) -> anyhow::Result<Option<String>> {
+    let stdout = stdout.trim();
+    if stdout.is_empty() {
+        return Ok(None);
+    }
+    let json: serde_json::Value = serde_json::from_str(stdout)
+        .map_err(|e| anyhow::anyhow!("JSON parse: {} (raw: {})", e, stdout))?;
+    if json.get("track").is_some() {
+        return Ok(vibra_rec_string(&json));
+    }
+    let field = |k: &str| {
+        json.get(k)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    };
+    Ok(match (field("artist"), field("title")) {
+        (Some(artist), Some(title)) => Some(format!("{} \u{2013} {}", artist, title)),
+        (None, Some(title)) => Some(title.to_string()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command_output() {
+        assert_eq!(parse_command_output("").unwrap(), None);
+        assert_eq!(parse_command_output("null").unwrap(), None);
+        assert_eq!(parse_command_output("{}").unwrap(), None);
+        assert_eq!(
+            parse_command_output(r#"{"artist": "Can", "title": "Vitamin C"}"#).unwrap(),
+            Some("Can \u{2013} Vitamin C".to_string())
+        );
+        assert_eq!(
+            parse_command_output(r#"{"track": {"title": "Halleluhwah"}}"#).unwrap(),
+            Some("Halleluhwah".to_string())
+        );
+        assert!(parse_command_output("not json").is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_recognizer_chain() {
+        let clip = PcmClip {
+            data: vec![0; 44_100 * 4],
+            rate: 44_100,
+            channels: 2,
+        };
+        let sh = |name: &str, script: &str| -> Box<dyn Recognizer> {
+            Box::new(CommandRecognizer {
+                name: name.into(),
+                command: vec!["sh".into(), "-c".into(), script.into()],
+            })
+        };
+        let chain = vec![
+            sh("broken", "exit 3"),
+            sh("unknown", "cat >/dev/null; echo '{}'"),
+            sh(
+                "counter",
+                r#"n=$(wc -c | tr -d ' '); echo "{\"artist\": \"A\", \"title\": \"$n bytes\"}""#,
+            ),
+            sh("never", "echo '{\"title\": \"not reached\"}'"),
+        ];
+        let (source, track) = recognize_clip(&chain, &clip).await.unwrap();
+        assert_eq!(source, RecognitionSource::Command("counter".into()));
+        assert_eq!(track, "A \u{2013} 176400 bytes");
+    }
+}
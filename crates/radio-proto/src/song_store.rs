@@ -0,0 +1,500 @@
+//! The songs database: every recognition row, searchable and editable.
+//!
+//! Rows live in memory, in the order they were recognized, and on disk as an
+//! append-only log (`songs.jsonl`): one JSON object per change, either
+//! `{"put": row}` (insert, or replace the row with that `job_id`) or
+//! `{"delete": job_id}`.  Replaying the log rebuilds the store; it is
+//! compacted to one `put` per row when it grows to twice that.  A truncated
+//! last line (a crash mid-write) is skipped, and the log is compacted right
+//! away so the next entry doesn't land on the end of it.
+//!
+//! A token index over station, ICY title, NTS show/tags and the recognized
+//! track answers `search`: every query word must prefix some word of the row.
+//!
+//! The first `open` imports the old `songs.vds` file; after that the VDS is
+//! no longer read or written.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::songs::{load_vds, RecognitionResult, RecognitionSource, VdsPatch};
+
+/// Recognitions of the same track on the same station this close together
+/// are duplicates (a repeated `i`, or auto-ID and `i` on one track).
+pub const DUPLICATE_WINDOW_MINS: i64 = 20;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum LogEntry {
+    Put(RecognitionResult),
+    Delete(String),
+}
+
+pub struct SongStore {
+    path: PathBuf,
+    rows: Vec<RecognitionResult>,
+    by_id: HashMap<String, usize>,
+    /// Lowercased word → job_ids of the rows containing it.
+    index: BTreeMap<String, HashSet<String>>,
+    /// Entries in the log file, for compaction.
+    log_len: usize,
+}
+
+impl SongStore {
+    /// Open the store at `path`, importing `legacy_vds` the first time.
+    pub fn open(path: &Path, legacy_vds: &Path) -> anyhow::Result<Self> {
+        let mut store = Self {
+            path: path.to_path_buf(),
+            rows: Vec::new(),
+            by_id: HashMap::new(),
+            index: BTreeMap::new(),
+            log_len: 0,
+        };
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                let mut skipped = false;
+                for (n, line) in content.lines().enumerate() {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    match serde_json::from_str::<LogEntry>(line) {
+                        Ok(LogEntry::Put(row)) => store.put_row(row),
+                        Ok(LogEntry::Delete(job_id)) => {
+                            store.remove_row(&job_id);
+                        }
+                        Err(e) => {
+                            warn!("[songs] {}:{}: skipped: {}", path.display(), n + 1, e);
+                            skipped = true;
+                        }
+                    }
+                    store.log_len += 1;
+                }
+                if skipped || store.log_len > 2 * store.rows.len() + 100 {
+                    store.compact()?;
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                for row in load_vds(&legacy_vds.to_path_buf(), usize::MAX) {
+                    store.put_row(row);
+                }
+                if !store.rows.is_empty() {
+                    store.compact()?;
+                    info!(
+                        "[songs] imported {} rows from {}",
+                        store.rows.len(),
+                        legacy_vds.display()
+                    );
+                }
+            }
+            Err(e) => return Err(e.into()),
+        }
+        Ok(store)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Every row, oldest first.
+    pub fn rows(&self) -> &[RecognitionResult] {
+        &self.rows
+    }
+
+    /// The newest `limit` rows, oldest first.
+    pub fn recent(&self, limit: usize) -> Vec<RecognitionResult> {
+        self.rows[self.rows.len().saturating_sub(limit)..].to_vec()
+    }
+
+    pub fn get(&self, job_id: &str) -> Option<&RecognitionResult> {
+        self.by_id.get(job_id).map(|&i| &self.rows[i])
+    }
+
+    /// Add a row, or replace the row with the same `job_id`.
+    pub fn insert(&mut self, row: RecognitionResult) -> anyhow::Result<()> {
+        self.append(&LogEntry::Put(row.clone()))?;
+        self.put_row(row);
+        Ok(())
+    }
+
+    /// Fill in the columns `patch` has; false if there's no such row.
+    pub fn patch(&mut self, job_id: &str, patch: &VdsPatch) -> anyhow::Result<bool> {
+        self.update(job_id, |row| patch.apply(row))
+    }
+
+    /// Change a row in place; false if there's no such row.
+    pub fn update(
+        &mut self,
+        job_id: &str,
+        edit: impl FnOnce(&mut RecognitionResult),
+    ) -> anyhow::Result<bool> {
+        let Some(mut row) = self.get(job_id).cloned() else {
+            return Ok(false);
+        };
+        edit(&mut row);
+        self.insert(row)?;
+        Ok(true)
+    }
+
+    /// Correct a row's track by hand.
+    pub fn correct(&mut self, job_id: &str, track: &str) -> anyhow::Result<bool> {
+        let track = track.trim().to_string();
+        self.update(job_id, |row| {
+            row.vibra_rec = Some(track).filter(|t| !t.is_empty());
+            row.rec_source = row
+                .vibra_rec
+                .as_ref()
+                .map(|_| RecognitionSource::Manual.label().to_string());
+        })
+    }
+
+    /// False if there's no such row.
+    pub fn delete(&mut self, job_id: &str) -> anyhow::Result<bool> {
+        if !self.by_id.contains_key(job_id) {
+            return Ok(false);
+        }
+        self.append(&LogEntry::Delete(job_id.to_string()))?;
+        self.remove_row(job_id);
+        Ok(true)
+    }
+
+    /// Fold the rows in `drop` into `keep`: columns `keep` lacks are taken
+    /// from them, then they're deleted.  Returns how many were merged.
+    pub fn merge(&mut self, keep: &str, drop: &[String]) -> anyhow::Result<usize> {
+        let others: Vec<RecognitionResult> = drop
+            .iter()
+            .filter(|id| id.as_str() != keep)
+            .filter_map(|id| self.get(id).cloned())
+            .collect();
+        if others.is_empty() || !self.by_id.contains_key(keep) {
+            return Ok(0);
+        }
+        self.update(keep, |row| {
+            for other in &others {
+                fill(&mut row.station, &other.station);
+                fill(&mut row.icy_info, &other.icy_info);
+                fill(&mut row.nts_show, &other.nts_show);
+                fill(&mut row.nts_tag, &other.nts_tag);
+                fill(&mut row.nts_url, &other.nts_url);
+                if row.vibra_rec.is_none() {
+                    row.vibra_rec = other.vibra_rec.clone();
+                    row.rec_source = other.rec_source.clone();
+                }
+            }
+        })?;
+        for other in &others {
+            self.delete(&other.job_id)?;
+        }
+        Ok(others.len())
+    }
+
+    /// Groups of duplicate rows, oldest first in each: the same track on the
+    /// same station, each within `DUPLICATE_WINDOW_MINS` of the one before.
+    pub fn duplicates(&self) -> Vec<Vec<String>> {
+        let window = Duration::minutes(DUPLICATE_WINDOW_MINS);
+        let mut groups: Vec<Vec<String>> = Vec::new();
+        // (station, track) → index in `groups` of its latest group, and the
+        // time of that group's newest row.
+        let mut open: HashMap<(Option<String>, String), (usize, _)> = HashMap::new();
+        for row in &self.rows {
+            let (Some(track), Some(ts)) = (track_key(row), row.timestamp) else {
+                continue;
+            };
+            let key = (row.station.clone(), track);
+            match open.get_mut(&key) {
+                Some((group, last)) if ts - *last <= window => {
+                    groups[*group].push(row.job_id.clone());
+                    *last = ts;
+                }
+                _ => {
+                    open.insert(key, (groups.len(), ts));
+                    groups.push(vec![row.job_id.clone()]);
+                }
+            }
+        }
+        groups.retain(|g| g.len() > 1);
+        groups
+    }
+
+    /// Merge every group of `duplicates` into its oldest row.  Returns how
+    /// many rows were merged away.
+    pub fn dedupe(&mut self) -> anyhow::Result<usize> {
+        let mut merged = 0;
+        for group in self.duplicates() {
+            merged += self.merge(&group[0], &group[1..])?;
+        }
+        if merged > 0 {
+            self.compact()?;
+        }
+        Ok(merged)
+    }
+
+    /// Rows matching every word of `query` (as word prefixes), newest first.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<RecognitionResult> {
+        let mut ids: Option<HashSet<&String>> = None;
+        for term in tokens(query) {
+            let matching: HashSet<&String> = self
+                .index
+                .range(term.clone()..)
+                .take_while(|(word, _)| word.starts_with(&term))
+                .flat_map(|(_, ids)| ids)
+                .collect();
+            ids = Some(match ids {
+                Some(ids) => ids.intersection(&matching).copied().collect(),
+                None => matching,
+            });
+        }
+        let mut found: Vec<usize> = match ids {
+            Some(ids) => ids
+                .iter()
+                .filter_map(|id| self.by_id.get(*id))
+                .copied()
+                .collect(),
+            None => (0..self.rows.len()).collect(),
+        };
+        found.sort_unstable_by(|a, b| b.cmp(a));
+        found
+            .into_iter()
+            .take(limit)
+            .map(|i| self.rows[i].clone())
+            .collect()
+    }
+
+    fn put_row(&mut self, row: RecognitionResult) {
+        if let Some(&i) = self.by_id.get(&row.job_id) {
+            let old = std::mem::replace(&mut self.rows[i], row);
+            self.index_row(&old, false);
+            let row = self.rows[i].clone();
+            self.index_row(&row, true);
+        } else {
+            self.index_row(&row, true);
+            self.by_id.insert(row.job_id.clone(), self.rows.len());
+            self.rows.push(row);
+        }
+    }
+
+    fn remove_row(&mut self, job_id: &str) -> Option<RecognitionResult> {
+        let i = self.by_id.remove(job_id)?;
+        let row = self.rows.remove(i);
+        self.index_row(&row, false);
+        for idx in self.by_id.values_mut() {
+            if *idx > i {
+                *idx -= 1;
+            }
+        }
+        Some(row)
+    }
+
+    fn index_row(&mut self, row: &RecognitionResult, add: bool) {
+        let fields = [
+            &row.station,
+            &row.icy_info,
+            &row.nts_show,
+            &row.nts_tag,
+            &row.vibra_rec,
+        ];
+        for word in fields.into_iter().flatten().flat_map(|f| tokens(f)) {
+            if add {
+                self.index
+                    .entry(word)
+                    .or_default()
+                    .insert(row.job_id.clone());
+            } else if let Some(ids) = self.index.get_mut(&word) {
+                ids.remove(&row.job_id);
+                if ids.is_empty() {
+                    self.index.remove(&word);
+                }
+            }
+        }
+    }
+
+    fn append(&mut self, entry: &LogEntry) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut f = std::fs::OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)?;
+        // Never continue a line left unfinished by a crash.
+        let len = f.metadata()?.len();
+        let mut last = [b'\n'];
+        if len > 0 {
+            f.seek(SeekFrom::Start(len - 1))?;
+            f.read_exact(&mut last)?;
+        }
+        if last[0] != b'\n' {
+            writeln!(f)?;
+        }
+        writeln!(f, "{}", serde_json::to_string(entry)?)?;
+        self.log_len += 1;
+        if self.log_len > 2 * self.rows.len() + 100 {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite the log as one `put` per row.
+    fn compact(&mut self) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = String::new();
+        for row in &self.rows {
+            out.push_str(&serde_json::to_string(&LogEntry::Put(row.clone()))?);
+            out.push('\n');
+        }
+        let tmp = self.path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, out)?;
+        std::fs::rename(&tmp, &self.path)?;
+        self.log_len = self.rows.len();
+        Ok(())
+    }
+}
+
+fn fill(field: &mut Option<String>, from: &Option<String>) {
+    if field.is_none() {
+        *field = from.clone();
+    }
+}
+
+/// What makes two rows the same track: the recognized track, else the ICY
+/// title, compared by words.
+fn track_key(row: &RecognitionResult) -> Option<String> {
+    let track = row.vibra_rec.as_ref().or(row.icy_info.as_ref())?;
+    let words = tokens(track);
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// Lowercased alphanumeric words.
+fn tokens(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn row(job_id: &str, mins: i64, station: &str, track: Option<&str>) -> RecognitionResult {
+        RecognitionResult {
+            job_id: job_id.into(),
+            timestamp: Some(
+                Local.with_ymd_and_hms(2026, 10, 16, 20, 0, 0).unwrap() + Duration::minutes(mins),
+            ),
+            station: Some(station.into()),
+            vibra_rec: track.map(Into::into),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_log_replay_and_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("songs.jsonl");
+        let vds = dir.path().join("songs.vds");
+        let mut store = SongStore::open(&path, &vds).unwrap();
+        store.insert(row("a", 0, "NTS 1", None)).unwrap();
+        store
+            .insert(row("b", 5, "FIP", Some("Can \u{2013} Vitamin C")))
+            .unwrap();
+        store.insert(row("c", 9, "FIP", Some("Junk"))).unwrap();
+        let patch = VdsPatch {
+            icy_info: Some("Nina Simone - Sinnerman".into()),
+            ..Default::default()
+        };
+        assert!(store.patch("a", &patch).unwrap());
+        assert!(store.correct("b", "Can \u{2013} Mother Sky").unwrap());
+        assert!(store.delete("c").unwrap());
+        assert!(!store.delete("c").unwrap());
+
+        let store = SongStore::open(&path, &vds).unwrap();
+        assert_eq!(store.len(), 2);
+        let b = store.get("b").unwrap();
+        assert_eq!(b.vibra_rec.as_deref(), Some("Can \u{2013} Mother Sky"));
+        assert_eq!(b.sources(), vec![RecognitionSource::Manual]);
+
+        let ids = |rows: Vec<RecognitionResult>| -> Vec<String> {
+            rows.into_iter().map(|r| r.job_id).collect()
+        };
+        assert_eq!(ids(store.search("sinner", 10)), ["a"]);
+        assert_eq!(ids(store.search("can sky fip", 10)), ["b"]);
+        assert!(store.search("vitamin", 10).is_empty());
+        assert!(store.search("junk", 10).is_empty());
+        assert_eq!(ids(store.search("", 10)), ["b", "a"]);
+    }
+
+    #[test]
+    fn test_truncated_last_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("songs.jsonl");
+        let vds = dir.path().join("songs.vds");
+        let mut store = SongStore::open(&path, &vds).unwrap();
+        store.insert(row("a", 0, "FIP", None)).unwrap();
+        store.insert(row("b", 5, "FIP", None)).unwrap();
+        // A crash halfway through writing "b".
+        let content = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &content[..content.len() - 10]).unwrap();
+
+        let mut store = SongStore::open(&path, &vds).unwrap();
+        assert_eq!(store.len(), 1);
+        store.insert(row("c", 9, "NTS 1", None)).unwrap();
+        let store = SongStore::open(&path, &vds).unwrap();
+        assert!(store.get("a").is_some() && store.get("c").is_some());
+        assert_eq!(store.len(), 2);
+
+        // Appending to a log that doesn't end in a newline starts a new line.
+        let content = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &content[..content.len() - 1]).unwrap();
+        let mut store = store;
+        store.insert(row("d", 12, "NTS 1", None)).unwrap();
+        let store = SongStore::open(&path, &vds).unwrap();
+        assert_eq!(store.len(), 3);
+    }
+
+    #[test]
+    fn test_vds_migration_and_dedupe() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("songs.jsonl");
+        let vds = dir.path().join("songs.vds");
+        std::fs::write(
+            &vds,
+            "job_id\ttimestamp\tstation\ticy_info\tnts_show\tnts_tag\tnts_url\tvibra_rec\trec_source\n\
+             a\t2026-10-16T20:00:00\tNTS 1\t\tMorning Show\t\t\tCan \u{2013} Vitamin C\t\n\
+             b\t2026-10-16T20:04:00\tNTS 1\tCan - Vitamin C\t\t\t\tCan \u{2013} Vitamin C\tlibrary\n\
+             c\t2026-10-16T20:30:00\tNTS 1\t\t\t\t\tCan \u{2013} Vitamin C\t\n\
+             d\t2026-10-16T20:05:00\tFIP\t\t\t\t\tCan \u{2013} Vitamin C\t\n",
+        )
+        .unwrap();
+        let mut store = SongStore::open(&path, &vds).unwrap();
+        assert_eq!(store.len(), 4);
+        assert!(path.exists());
+
+        assert_eq!(
+            store.duplicates(),
+            vec![vec!["a".to_string(), "b".to_string()]]
+        );
+        assert_eq!(store.dedupe().unwrap(), 1);
+        let a = store.get("a").unwrap();
+        assert_eq!(a.icy_info.as_deref(), Some("Can - Vitamin C"));
+        assert_eq!(a.nts_show.as_deref(), Some("Morning Show"));
+        assert_eq!(a.rec_source, None);
+
+        // The VDS is only read the first time.
+        std::fs::write(&vds, "").unwrap();
+        let store = SongStore::open(&path, &vds).unwrap();
+        assert_eq!(store.len(), 3);
+    }
+}
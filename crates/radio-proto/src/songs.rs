@@ -1,26 +1,29 @@
-//! Song recognition and VDS (Vibra Data Store) persistence.
+//! Song recognition rows and the legacy VDS (Vibra Data Store) reader.
 //!
-//! ## New async pipeline (fire-and-forget, patch-in-place)
+//! ## Async pipeline (fire-and-forget, patch-in-place)
 //!
 //! On `i` press:
 //!   1. Generate a `job_id` (hash of timestamp+station).
-//!   2. Write an empty VDS row immediately with job_id, timestamp, station, icy_info.
-//!   3. Spawn three concurrent tasks that each `patch_vds_by_job_id` when done:
-//!      a. vibra  — ffmpeg captures 10 s raw PCM from stream, pipes to vibra (1 attempt)
+//!   2. Insert an empty row into the `SongStore` with job_id, timestamp, station, icy_info.
+//!   3. Spawn three concurrent tasks whose `VdsPatch`es are applied by job_id:
+//!      a. recognizer — ffmpeg clip of the stream through `[recognition] backends`
 //!      b. ICY    — already available, patched immediately
 //!      c. NTS    — async API call (NTS 1/2 only)
 //!
 //! ## VDS schema (tab-separated)
 //!
-//!   job_id  timestamp  station  icy_info  nts_show  nts_tag  nts_url  vibra_rec
+//!   job_id  timestamp  station  icy_info  nts_show  nts_tag  nts_url  vibra_rec  rec_source
 //!
-//! All fields except job_id and timestamp may be empty strings.
+//! All fields except job_id and timestamp may be empty strings.  `vibra_rec`
+//! holds the matched track from whichever backend `rec_source` names; rows
+//! written before there were other backends have no `rec_source` and mean vibra.
+//! Rows now live in `song_store`; `load_vds` remains for the one-time import.
 
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::OnceLock;
-use tracing::{debug, info, warn};
+use tracing::{info, warn};
 
 // ── Public types ──────────────────────────────────────────────────────────────
 
@@ -30,14 +33,35 @@ pub enum RecognitionSource {
     Vibra,
     Icy,
     Nts,
+    /// The local fingerprint library.
+    Library,
+    /// An external `command` recognizer, by its configured name.
+    Command(String),
+    /// Corrected by hand in the Songs pane.
+    Manual,
 }
 
 impl RecognitionSource {
-    pub fn label(&self) -> &'static str {
+    pub fn label(&self) -> &str {
         match self {
             Self::Vibra => "vibra",
             Self::Icy => "icy",
             Self::Nts => "nts",
+            Self::Library => "library",
+            Self::Command(name) => name,
+            Self::Manual => "manual",
+        }
+    }
+
+    /// Inverse of `label`, for the VDS `rec_source` column.
+    pub fn from_label(label: &str) -> Self {
+        match label {
+            "vibra" => Self::Vibra,
+            "icy" => Self::Icy,
+            "nts" => Self::Nts,
+            "library" => Self::Library,
+            "manual" => Self::Manual,
+            name => Self::Command(name.to_string()),
         }
     }
 }
@@ -57,8 +81,11 @@ pub struct RecognitionResult {
     pub nts_tag: Option<String>,
     /// NTS show URL.
     pub nts_url: Option<String>,
-    /// Best track info from vibra: "Artist – Title" or just "Title".
+    /// Best track info from a recognizer: "Artist – Title" or just "Title".
     pub vibra_rec: Option<String>,
+    /// Label of the recognizer that filled `vibra_rec`; `None` means vibra.
+    #[serde(default)]
+    pub rec_source: Option<String>,
 }
 
 impl RecognitionResult {
@@ -87,7 +114,11 @@ impl RecognitionResult {
             .map(|s| !s.is_empty())
             .unwrap_or(false)
         {
-            v.push(RecognitionSource::Vibra);
+            v.push(
+                self.rec_source
+                    .as_deref()
+                    .map_or(RecognitionSource::Vibra, RecognitionSource::from_label),
+            );
         }
         if self
             .nts_show
@@ -133,114 +164,7 @@ pub fn make_job_id(ts: &DateTime<Local>, station: Option<&str>) -> String {
     format!("{:016x}", h.finish())
 }
 
-// ── Vibra recognition (ffmpeg capture) ───────────────────────────────────────
-
-/// Capture 10 s of `stream_url` via ffmpeg (raw PCM to stdout), pipe to vibra
-/// for fingerprinting.  One attempt, no retries.
-///
-/// Returns the raw vibra JSON on success.
-pub async fn recognize_via_vibra(stream_url: &str) -> Option<serde_json::Value> {
-    let vibra = crate::platform::find_vibra_binary()?;
-    let ffmpeg = crate::platform::find_ffmpeg_binary()?;
-
-    info!("[vibra] Starting capture: url={}", stream_url);
-    match try_vibra_ffmpeg(&vibra, &ffmpeg, stream_url).await {
-        Ok(json) => {
-            info!("[vibra] Recognition succeeded");
-            Some(json)
-        }
-        Err(e) => {
-            warn!("[vibra] Recognition failed: {}", e);
-            None
-        }
-    }
-}
-
-async fn try_vibra_ffmpeg(
-    vibra: &PathBuf,
-    ffmpeg: &PathBuf,
-    stream_url: &str,
-) -> anyhow::Result<serde_json::Value> {
-    // Spawn ffmpeg: connect to stream, decode to raw PCM (s16le, 44100 Hz, stereo) on stdout.
-    // pipe:1 routes the output to stdout. -t 10 records exactly 10 seconds.
-    info!(
-        "[vibra] Spawning ffmpeg: {} -i {} -t 10 -vn -ar 44100 -ac 2 -f s16le pipe:1",
-        ffmpeg.display(),
-        stream_url
-    );
-
-    let mut ffmpeg_proc = std::process::Command::new(ffmpeg)
-        .args([
-            "-i", stream_url, "-t", "10", "-vn", "-ar", "44100", "-ac", "2", "-f", "s16le",
-            "pipe:1",
-        ])
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::null())
-        .spawn()?;
-
-    let ffmpeg_pid = ffmpeg_proc.id();
-    info!("[vibra] ffmpeg spawned with PID: {:?}", ffmpeg_pid);
-
-    let ffmpeg_stdout = ffmpeg_proc
-        .stdout
-        .take()
-        .ok_or_else(|| anyhow::anyhow!("ffmpeg stdout unavailable"))?;
-
-    // Run vibra in a blocking thread: reads raw PCM from stdin (ffmpeg stdout), outputs JSON.
-    // ffmpeg outputs 44100 Hz stereo 16-bit signed little-endian (s16le).
-    let vibra_path = vibra.clone();
-    info!(
-        "[vibra] Spawning vibra: {} --recognize --seconds 10 --rate 44100 --channels 2 --bits 16",
-        vibra_path.display()
-    );
-
-    let output = tokio::task::spawn_blocking(move || {
-        let result = std::process::Command::new(&vibra_path)
-            .args([
-                "--recognize",
-                "--seconds",
-                "10",
-                "--rate",
-                "44100",
-                "--channels",
-                "2",
-                "--bits",
-                "16",
-            ])
-            .stdin(std::process::Stdio::from(ffmpeg_stdout))
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .output();
-        if let Ok(ref out) = result {
-            info!("[vibra] vibra exited with status: {:?}", out.status.code());
-        }
-        result
-    })
-    .await??;
-
-    // Wait for ffmpeg to finish (should already be done after 10s + vibra read)
-    let ffmpeg_exit = ffmpeg_proc.wait()?;
-    debug!("[vibra] ffmpeg exited: {:?}", ffmpeg_exit.code());
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("vibra exited {}: {}", output.status, stderr.trim());
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    if stdout.trim().is_empty() {
-        anyhow::bail!("vibra produced no output");
-    }
-
-    let json: serde_json::Value = serde_json::from_str(stdout.trim())
-        .map_err(|e| anyhow::anyhow!("vibra JSON parse: {} (raw: {})", e, stdout.trim()))?;
-
-    if json.get("track").is_none() {
-        anyhow::bail!("vibra: no track in response: {}", stdout.trim());
-    }
-
-    Ok(json)
-}
+// ── Vibra output ──────────────────────────────────────────────────────────────
 
 /// Parse vibra JSON → "Artist – Title" string (or just "Title").
 pub fn vibra_rec_string(json: &serde_json::Value) -> Option<String> {
@@ -509,100 +433,6 @@ pub fn parse_icy(icy: &str) -> (Option<String>, Option<String>) {
 
 // ── VDS persistence ───────────────────────────────────────────────────────────
 
-const VDS_HEADER: &str =
-    "job_id\ttimestamp\tstation\ticy_info\tnts_show\tnts_tag\tnts_url\tvibra_rec\n";
-
-/// Write an initial (possibly partial) VDS row.
-/// Called immediately on `i` press with whatever is known at that moment.
-pub async fn append_to_vds(path: &PathBuf, result: &RecognitionResult) -> anyhow::Result<()> {
-    let exists = path.exists();
-    if let Some(parent) = path.parent() {
-        tokio::fs::create_dir_all(parent).await?;
-    }
-    let mut f = tokio::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(path)
-        .await?;
-    use tokio::io::AsyncWriteExt;
-    if !exists {
-        f.write_all(VDS_HEADER.as_bytes()).await?;
-    }
-    f.write_all(encode_row(result).as_bytes()).await?;
-    info!("[vds] Wrote initial row job_id={}", result.job_id);
-    Ok(())
-}
-
-/// Patch an existing VDS row identified by `job_id`, updating only the
-/// columns that are `Some` in `patch`. Other columns are left unchanged.
-pub async fn patch_vds_by_job_id(
-    path: &PathBuf,
-    job_id: &str,
-    patch: VdsPatch,
-) -> anyhow::Result<()> {
-    let content = tokio::fs::read_to_string(path).await.unwrap_or_default();
-
-    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
-    let mut found = false;
-
-    for line in lines.iter_mut() {
-        // Skip header
-        if line.starts_with("job_id\t") {
-            continue;
-        }
-        let cols: Vec<&str> = line.splitn(9, '\t').collect();
-        if cols.first().map(|c| *c == job_id).unwrap_or(false) {
-            found = true;
-            // Parse the existing row
-            let get = |i: usize| cols.get(i).copied().unwrap_or("").to_string();
-            let mut r = RecognitionResult {
-                job_id: get(0),
-                timestamp: parse_ts(&get(1)),
-                station: nn(&get(2)),
-                icy_info: nn(&get(3)),
-                nts_show: nn(&get(4)),
-                nts_tag: nn(&get(5)),
-                nts_url: nn(&get(6)),
-                vibra_rec: nn(&get(7)),
-            };
-            // Apply patch
-            if let Some(v) = patch.icy_info {
-                r.icy_info = Some(v);
-            }
-            if let Some(v) = patch.nts_show {
-                r.nts_show = Some(v);
-            }
-            if let Some(v) = patch.nts_tag {
-                r.nts_tag = Some(v);
-            }
-            if let Some(v) = patch.nts_url {
-                r.nts_url = Some(v);
-            }
-            if let Some(v) = patch.vibra_rec {
-                r.vibra_rec = Some(v);
-            }
-            *line = encode_row(&r).trim_end_matches('\n').to_string();
-            info!(
-                "[vds] Patched job_id={}: icy={:?} nts={:?} vibra={:?}",
-                job_id, r.icy_info, r.nts_show, r.vibra_rec
-            );
-            break;
-        }
-    }
-
-    if !found {
-        warn!(
-            "[vds] patch_vds_by_job_id: job_id={} not found in file",
-            job_id
-        );
-        return Ok(());
-    }
-
-    let new_content = lines.join("\n") + "\n";
-    tokio::fs::write(path, new_content).await?;
-    Ok(())
-}
-
 /// Fields that can be patched after initial write.
 #[derive(Debug, Default)]
 pub struct VdsPatch {
@@ -611,29 +441,26 @@ pub struct VdsPatch {
     pub nts_tag: Option<String>,
     pub nts_url: Option<String>,
     pub vibra_rec: Option<String>,
+    pub rec_source: Option<String>,
 }
 
-fn encode_row(r: &RecognitionResult) -> String {
-    let ts = r
-        .timestamp
-        .as_ref()
-        .map(|t| t.format("%Y-%m-%dT%H:%M:%S").to_string())
-        .unwrap_or_default();
-    format!(
-        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
-        vds_esc(&r.job_id),
-        ts,
-        vds_esc(r.station.as_deref().unwrap_or("")),
-        vds_esc(r.icy_info.as_deref().unwrap_or("")),
-        vds_esc(r.nts_show.as_deref().unwrap_or("")),
-        vds_esc(r.nts_tag.as_deref().unwrap_or("")),
-        vds_esc(r.nts_url.as_deref().unwrap_or("")),
-        vds_esc(r.vibra_rec.as_deref().unwrap_or("")),
-    )
-}
-
-fn vds_esc(s: &str) -> String {
-    s.replace('\t', " ").replace('\n', " ").replace('\r', "")
+impl VdsPatch {
+    /// Set the columns this patch has; the rest are left unchanged.
+    pub fn apply(&self, r: &mut RecognitionResult) {
+        let fields = [
+            (&mut r.icy_info, &self.icy_info),
+            (&mut r.nts_show, &self.nts_show),
+            (&mut r.nts_tag, &self.nts_tag),
+            (&mut r.nts_url, &self.nts_url),
+            (&mut r.vibra_rec, &self.vibra_rec),
+            (&mut r.rec_source, &self.rec_source),
+        ];
+        for (field, value) in fields {
+            if let Some(v) = value {
+                *field = Some(v.clone());
+            }
+        }
+    }
 }
 
 fn parse_ts(s: &str) -> Option<DateTime<Local>> {
@@ -672,7 +499,7 @@ pub fn load_vds(path: &PathBuf, limit: usize) -> Vec<RecognitionResult> {
 }
 
 fn parse_vds_row(line: &str) -> Option<RecognitionResult> {
-    let cols: Vec<&str> = line.splitn(9, '\t').collect();
+    let cols: Vec<&str> = line.splitn(10, '\t').collect();
     if cols.len() < 3 {
         return None;
     }
@@ -685,6 +512,7 @@ fn parse_vds_row(line: &str) -> Option<RecognitionResult> {
         nts_tag: cols.get(5).and_then(|s| nn(s)),
         nts_url: cols.get(6).and_then(|s| nn(s)),
         vibra_rec: cols.get(7).and_then(|s| nn(s)),
+        rec_source: cols.get(8).and_then(|s| nn(s)),
     })
 }
 
@@ -694,6 +522,31 @@ fn parse_vds_row(line: &str) -> Option<RecognitionResult> {
 mod tests {
     use super::*;
 
+    /// A `songs.vds` line, as the old writer produced it.
+    fn encode_row(r: &RecognitionResult) -> String {
+        let ts = r
+            .timestamp
+            .as_ref()
+            .map(|t| t.format("%Y-%m-%dT%H:%M:%S").to_string())
+            .unwrap_or_default();
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            vds_esc(&r.job_id),
+            ts,
+            vds_esc(r.station.as_deref().unwrap_or("")),
+            vds_esc(r.icy_info.as_deref().unwrap_or("")),
+            vds_esc(r.nts_show.as_deref().unwrap_or("")),
+            vds_esc(r.nts_tag.as_deref().unwrap_or("")),
+            vds_esc(r.nts_url.as_deref().unwrap_or("")),
+            vds_esc(r.vibra_rec.as_deref().unwrap_or("")),
+            vds_esc(r.rec_source.as_deref().unwrap_or("")),
+        )
+    }
+
+    fn vds_esc(s: &str) -> String {
+        s.replace(['\t', '\n'], " ").replace('\r', "")
+    }
+
     #[test]
     fn test_parse_icy_artist_title() {
         let (title, artist) = parse_icy("The Beatles - Hey Jude");
@@ -745,6 +598,14 @@ mod tests {
         assert!(srcs.contains(&RecognitionSource::Vibra));
         assert!(srcs.contains(&RecognitionSource::Icy));
         assert!(!srcs.contains(&RecognitionSource::Nts));
+
+        let r = RecognitionResult {
+            vibra_rec: Some("A \u{2013} B".into()),
+            rec_source: Some("audd".into()),
+            ..Default::default()
+        };
+        assert_eq!(r.sources(), vec![RecognitionSource::Command("audd".into())]);
+        assert_eq!(r.source_label(), "audd");
     }
 
     #[test]
@@ -758,6 +619,7 @@ mod tests {
             nts_tag: Some("Jazz, Soul".into()),
             nts_url: Some("https://www.nts.live/shows/morning".into()),
             vibra_rec: Some("Artist \u{2013} Track".into()),
+            rec_source: Some("audd".into()),
         };
         let encoded = encode_row(&r);
         let decoded = parse_vds_row(encoded.trim()).unwrap();
@@ -765,5 +627,11 @@ mod tests {
         assert_eq!(decoded.station.as_deref(), Some("NTS 1"));
         assert_eq!(decoded.icy_info.as_deref(), Some("Artist - Track"));
         assert_eq!(decoded.vibra_rec.as_deref(), Some("Artist \u{2013} Track"));
+        assert_eq!(decoded.rec_source.as_deref(), Some("audd"));
+
+        // Rows from before the rec_source column.
+        let old = parse_vds_row("id\t\tFIP\t\t\t\t\tA \u{2013} B").unwrap();
+        assert_eq!(old.rec_source, None);
+        assert_eq!(old.sources(), vec![RecognitionSource::Vibra]);
     }
 }
@@ -0,0 +1,492 @@
+//! Songs history export: songs database rows as CSV, JSON, M3U, a Markdown
+//! tracklist or a CUE sheet, optionally filtered by date, station and source.
+//!
+//! The TUI exports from the Songs pane (`x`, with a spec like
+//! `week.md since:7d station:nts`); the HTTP API serves the same output from
+//! `GET /api/songs/export`.  Rows are exported oldest first.
+//!
+//! CSV and JSON carry every matching row; the tracklist formats (M3U,
+//! Markdown, CUE) leave out rows nothing was identified for.  CUE track
+//! offsets count from the first exported track, so the sheet lines up with a
+//! recording of the session that started when it played.
+
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::Station;
+use crate::songs::{parse_icy, RecognitionResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    M3u,
+    #[serde(alias = "md")]
+    Markdown,
+    Cue,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::M3u => "m3u",
+            Self::Markdown => "md",
+            Self::Cue => "cue",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Json => "application/json",
+            Self::M3u => "audio/x-mpegurl; charset=utf-8",
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Cue => "application/x-cue; charset=utf-8",
+        }
+    }
+
+    /// Format for a file extension (`csv`, `json`, `m3u`/`m3u8`, `md`, `cue`).
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "m3u" | "m3u8" => Some(Self::M3u),
+            "md" | "markdown" => Some(Self::Markdown),
+            "cue" => Some(Self::Cue),
+            _ => None,
+        }
+    }
+}
+
+/// Which rows to export.  Dates are local and inclusive.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SongsFilter {
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+    /// Case-insensitive substring of the station (or file) name.
+    pub station: Option<String>,
+    /// A source label, as in the Songs pane badge: `vibra`, `library`, `icy`,
+    /// `nts` or a command backend's name.
+    pub source: Option<String>,
+}
+
+impl SongsFilter {
+    pub fn matches(&self, row: &RecognitionResult) -> bool {
+        let day = row.timestamp.map(|t| t.date_naive());
+        if self
+            .since
+            .is_some_and(|since| day.is_none_or(|d| d < since))
+        {
+            return false;
+        }
+        if self
+            .until
+            .is_some_and(|until| day.is_none_or(|d| d > until))
+        {
+            return false;
+        }
+        if let Some(station) = &self.station {
+            let wanted = station.to_lowercase();
+            if !row
+                .station
+                .as_deref()
+                .is_some_and(|s| s.to_lowercase().contains(&wanted))
+            {
+                return false;
+            }
+        }
+        if let Some(source) = &self.source {
+            if !row
+                .sources()
+                .iter()
+                .any(|s| s.label().eq_ignore_ascii_case(source))
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// A day for `since:`/`until:`: `YYYY-MM-DD`, `today`, or `Nd` (N days ago).
+pub fn parse_day(s: &str, today: NaiveDate) -> anyhow::Result<NaiveDate> {
+    if s.eq_ignore_ascii_case("today") {
+        return Ok(today);
+    }
+    if let Some(days) = s.strip_suffix('d').and_then(|n| n.parse::<i64>().ok()) {
+        return Ok(today - Duration::days(days));
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("bad date {:?} (YYYY-MM-DD, today or 7d)", s))
+}
+
+/// Parse a Songs pane export spec: a file name plus `since:`, `until:`,
+/// `station:` and `source:` terms, e.g. `week.md since:7d station:nts`.
+/// The format comes from the file name's extension.
+pub fn parse_spec(
+    spec: &str,
+    today: NaiveDate,
+) -> anyhow::Result<(String, ExportFormat, SongsFilter)> {
+    let mut filter = SongsFilter::default();
+    let mut name = Vec::new();
+    for term in spec.split_whitespace() {
+        match term.split_once(':') {
+            Some(("since", v)) => filter.since = Some(parse_day(v, today)?),
+            Some(("until", v)) => filter.until = Some(parse_day(v, today)?),
+            Some(("station", v)) => filter.station = Some(v.to_string()),
+            Some(("source", v)) => filter.source = Some(v.to_string()),
+            _ => name.push(term),
+        }
+    }
+    let name = name.join(" ");
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        anyhow::bail!("invalid file name {:?}", name);
+    }
+    let format = std::path::Path::new(&name)
+        .extension()
+        .and_then(|e| ExportFormat::from_extension(&e.to_string_lossy()))
+        .ok_or_else(|| anyhow::anyhow!("{:?}: use .csv, .json, .m3u, .md or .cue", name))?;
+    Ok((name, format, filter))
+}
+
+/// One exported row: the VDS columns with the track split into artist and
+/// title.
+#[derive(Debug, Serialize)]
+struct ExportRow<'a> {
+    timestamp: Option<String>,
+    station: Option<&'a str>,
+    artist: Option<String>,
+    title: Option<String>,
+    /// Labels of the sources that contributed, e.g. "vibra+icy".
+    source: String,
+    icy: Option<&'a str>,
+    show: Option<&'a str>,
+    tags: Option<&'a str>,
+    url: Option<&'a str>,
+}
+
+impl<'a> ExportRow<'a> {
+    fn new(r: &'a RecognitionResult) -> Self {
+        let (artist, title) = match track(r) {
+            Some(t) => artist_title(&t),
+            None => (None, None),
+        };
+        Self {
+            timestamp: r
+                .timestamp
+                .map(|t| t.format("%Y-%m-%dT%H:%M:%S%:z").to_string()),
+            station: r.station.as_deref(),
+            artist,
+            title,
+            source: r.source_label(),
+            icy: r.icy_info.as_deref(),
+            show: r.nts_show.as_deref(),
+            tags: r.nts_tag.as_deref(),
+            url: r.nts_url.as_deref(),
+        }
+    }
+}
+
+/// The identified track, recognizer match before ICY title.
+fn track(r: &RecognitionResult) -> Option<String> {
+    Some(r.display()).filter(|d| d != "?")
+}
+
+/// Split "Artist – Title" (recognizers) or "Artist - Title" (ICY).
+fn artist_title(track: &str) -> (Option<String>, Option<String>) {
+    match track.split_once(" \u{2013} ") {
+        Some((artist, title)) => (
+            Some(artist.trim().to_string()),
+            Some(title.trim().to_string()),
+        ),
+        None => {
+            let (title, artist) = parse_icy(track);
+            (artist, title)
+        }
+    }
+}
+
+/// Render the rows of `rows` that `filter` keeps.  M3U entries point at the
+/// NTS show, else the stream they were heard on (looked up in `stations`).
+pub fn export(
+    rows: &[RecognitionResult],
+    filter: &SongsFilter,
+    format: ExportFormat,
+    stations: &[Station],
+) -> anyhow::Result<String> {
+    let rows: Vec<&RecognitionResult> = rows.iter().filter(|r| filter.matches(r)).collect();
+    Ok(match format {
+        ExportFormat::Csv => to_csv(&rows),
+        ExportFormat::Json => {
+            let rows: Vec<ExportRow> = rows.iter().map(|r| ExportRow::new(r)).collect();
+            serde_json::to_string_pretty(&rows)? + "\n"
+        }
+        ExportFormat::M3u => to_m3u(&rows, stations),
+        ExportFormat::Markdown => to_markdown(&rows),
+        ExportFormat::Cue => to_cue(&rows),
+    })
+}
+
+fn to_csv(rows: &[&RecognitionResult]) -> String {
+    let mut out = String::from("timestamp,station,artist,title,source,icy,show,tags,url\r\n");
+    for r in rows {
+        let row = ExportRow::new(r);
+        let cols = [
+            row.timestamp.as_deref(),
+            row.station,
+            row.artist.as_deref(),
+            row.title.as_deref(),
+            Some(row.source.as_str()),
+            row.icy,
+            row.show,
+            row.tags,
+            row.url,
+        ];
+        let cols: Vec<String> = cols.iter().map(|c| csv_field(c.unwrap_or(""))).collect();
+        out.push_str(&cols.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// RFC 4180 quoting.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn to_m3u(rows: &[&RecognitionResult], stations: &[Station]) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    for r in rows {
+        let Some(track) = track(r) else { continue };
+        let location = r.nts_url.clone().or_else(|| {
+            let name = r.station.as_deref()?;
+            stations
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.url.clone())
+        });
+        // An entry needs a location; tracks heard in files have none.
+        let Some(location) = location else { continue };
+        // ICY-style "Artist - Title" is what players and importers expect.
+        let title = match artist_title(&track) {
+            (Some(artist), Some(title)) => format!("{} - {}", artist, title),
+            _ => track,
+        };
+        out.push_str(&format!("#EXTINF:-1,{}\n{}\n", title, location));
+    }
+    out
+}
+
+fn to_markdown(rows: &[&RecognitionResult]) -> String {
+    let tracks: Vec<(&RecognitionResult, String)> =
+        rows.iter().filter_map(|r| Some((*r, track(r)?))).collect();
+    let days: Vec<NaiveDate> = tracks
+        .iter()
+        .filter_map(|(r, _)| r.timestamp.map(|t| t.date_naive()))
+        .collect();
+    let mut out = match (days.first(), days.last()) {
+        (Some(first), Some(last)) if first != last => {
+            format!("# Songs \u{2014} {} to {}\n", first, last)
+        }
+        (Some(day), _) => format!("# Songs \u{2014} {}\n", day),
+        _ => "# Songs\n".to_string(),
+    };
+    let mut day: Option<NaiveDate> = None;
+    let mut first = true;
+    for (r, track) in &tracks {
+        let this_day = r.timestamp.map(|t| t.date_naive());
+        if first || this_day != day {
+            out.push_str(&match this_day {
+                Some(d) => format!("\n## {}\n\n", d),
+                None => "\n## Undated\n\n".to_string(),
+            });
+            day = this_day;
+            first = false;
+        }
+        let mut line = String::from("- ");
+        if let Some(t) = r.timestamp {
+            line.push_str(&format!("{} ", t.format("%H:%M")));
+        }
+        line.push_str(&format!("**{}**", md_escape(track)));
+        if let Some(station) = &r.station {
+            line.push_str(&format!(" \u{b7} {}", md_escape(station)));
+        }
+        match (&r.nts_show, &r.nts_url) {
+            (Some(show), Some(url)) => {
+                line.push_str(&format!(" \u{b7} [{}]({})", md_escape(show), url))
+            }
+            (Some(show), None) => line.push_str(&format!(" \u{b7} {}", md_escape(show))),
+            _ => {}
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+fn md_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '\\' | '*' | '_' | '[' | ']' | '`') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn to_cue(rows: &[&RecognitionResult]) -> String {
+    let tracks: Vec<(DateTime<Local>, &RecognitionResult, String)> = rows
+        .iter()
+        .filter_map(|r| Some((r.timestamp?, *r, track(r)?)))
+        .collect();
+    let mut out = String::new();
+    let Some((start, ..)) = tracks.first() else {
+        return out;
+    };
+    out.push_str(&format!("REM DATE {}\n", start.format("%Y-%m-%d")));
+    out.push_str(&format!(
+        "REM COMMENT \"r4dio songs from {}\"\n",
+        start.format("%H:%M:%S")
+    ));
+    out.push_str("TITLE \"r4dio session\"\n");
+    out.push_str("FILE \"r4dio.mp3\" MP3\n");
+    for (n, (ts, r, track)) in tracks.iter().enumerate() {
+        let (artist, title) = artist_title(track);
+        let secs = (*ts - *start).num_seconds().max(0);
+        out.push_str(&format!("  TRACK {:02} AUDIO\n", n + 1));
+        out.push_str(&format!(
+            "    TITLE \"{}\"\n",
+            cue_escape(title.as_deref().unwrap_or(track))
+        ));
+        if let Some(performer) = artist.as_deref().or(r.station.as_deref()) {
+            out.push_str(&format!("    PERFORMER \"{}\"\n", cue_escape(performer)));
+        }
+        out.push_str(&format!(
+            "    INDEX 01 {:02}:{:02}:00\n",
+            secs / 60,
+            secs % 60
+        ));
+    }
+    out
+}
+
+/// CUE strings can't hold double quotes.
+fn cue_escape(s: &str) -> String {
+    s.replace('"', "'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn row(ts: &str, station: &str, track: Option<&str>, icy: Option<&str>) -> RecognitionResult {
+        let naive = chrono::NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S").unwrap();
+        RecognitionResult {
+            job_id: ts.into(),
+            timestamp: Local.from_local_datetime(&naive).single(),
+            station: Some(station.into()),
+            icy_info: icy.map(Into::into),
+            vibra_rec: track.map(Into::into),
+            ..Default::default()
+        }
+    }
+
+    fn history() -> Vec<RecognitionResult> {
+        vec![
+            row(
+                "2026-10-09 22:10:00",
+                "NTS 1",
+                Some("Can \u{2013} Vitamin C"),
+                None,
+            ),
+            row(
+                "2026-10-10 14:03:00",
+                "FIP",
+                None,
+                Some("Nina Simone - Sinnerman"),
+            ),
+            row("2026-10-10 14:09:30", "FIP", None, None),
+            row(
+                "2026-10-10 14:12:05",
+                "FIP",
+                Some("Air, \"Sexy\" Boy"),
+                None,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_filter() {
+        let rows = history();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let (name, format, filter) = parse_spec("fip week.md since:7d station:fip", today).unwrap();
+        assert_eq!(name, "fip week.md");
+        assert_eq!(format, ExportFormat::Markdown);
+        assert_eq!(filter.since, NaiveDate::from_ymd_opt(2026, 10, 9));
+        assert_eq!(rows.iter().filter(|r| filter.matches(r)).count(), 3);
+
+        let filter = SongsFilter {
+            until: NaiveDate::from_ymd_opt(2026, 10, 9),
+            ..Default::default()
+        };
+        assert_eq!(rows.iter().filter(|r| filter.matches(r)).count(), 1);
+        let filter = SongsFilter {
+            source: Some("icy".into()),
+            ..Default::default()
+        };
+        assert_eq!(rows.iter().filter(|r| filter.matches(r)).count(), 1);
+
+        assert!(parse_spec("songs.txt", today).is_err());
+        assert!(parse_spec("week.csv since:yesterday", today).is_err());
+    }
+
+    #[test]
+    fn test_formats() {
+        let rows = history();
+        let all = SongsFilter::default();
+        let stations = vec![Station {
+            name: "FIP".into(),
+            url: "https://icecast.radiofrance.fr/fip-hifi.aac".into(),
+            ..Default::default()
+        }];
+
+        let csv = export(&rows, &all, ExportFormat::Csv, &stations).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[2].contains(",FIP,Nina Simone,Sinnerman,icy,"));
+        assert!(lines[4].contains(",\"Air, \"\"Sexy\"\" Boy\",vibra,"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&export(&rows, &all, ExportFormat::Json, &stations).unwrap())
+                .unwrap();
+        assert_eq!(json[0]["artist"], "Can");
+        assert_eq!(json[0]["title"], "Vitamin C");
+
+        let m3u = export(&rows, &all, ExportFormat::M3u, &stations).unwrap();
+        assert_eq!(
+            m3u,
+            "#EXTM3U\n#EXTINF:-1,Nina Simone - Sinnerman\nhttps://icecast.radiofrance.fr/fip-hifi.aac\n\
+             #EXTINF:-1,Air, \"Sexy\" Boy\nhttps://icecast.radiofrance.fr/fip-hifi.aac\n"
+        );
+
+        let md = export(&rows, &all, ExportFormat::Markdown, &stations).unwrap();
+        assert!(md.starts_with("# Songs \u{2014} 2026-10-09 to 2026-10-10\n\n## 2026-10-09\n"));
+        assert!(md.contains("\n## 2026-10-10\n\n- 14:03 **Nina Simone - Sinnerman** \u{b7} FIP\n"));
+
+        let cue = export(&rows, &all, ExportFormat::Cue, &stations).unwrap();
+        assert!(cue.contains("  TRACK 01 AUDIO\n    TITLE \"Vitamin C\"\n    PERFORMER \"Can\"\n    INDEX 01 00:00:00\n"));
+        // 15 h 53 m after the first track; the sheet has three tracks.
+        assert!(cue.contains("  TRACK 02 AUDIO\n    TITLE \"Sinnerman\"\n    PERFORMER \"Nina Simone\"\n    INDEX 01 953:00:00\n"));
+        assert!(cue.contains("TITLE \"Air, 'Sexy' Boy\"\n    PERFORMER \"FIP\""));
+        assert!(!cue.contains("TRACK 04"));
+    }
+}
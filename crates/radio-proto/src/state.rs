@@ -1,9 +1,39 @@
-use crate::protocol::{DaemonState, MpvHealth, PlaybackStatus, Station};
+use crate::protocol::{
+    Alarm, AlarmTarget, DaemonState, Diagnostics, MpvHealth, PlaybackStatus, RecordingStatus,
+    ScheduledShow, SleepTimer, Station, UpstreamStatus,
+};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// A change in the station list's shape.  Everything that refers to a
+/// station by index (current station, alarm, proxy streams) is renumbered
+/// through `apply`.  Running recordings keep their index for their whole
+/// life, so a change that `moves` one is refused.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ListChange {
+    Inserted(usize),
+    Removed(usize),
+}
+
+impl ListChange {
+    /// Where the station at `idx` is after the change; `None` if removed.
+    pub fn apply(self, idx: usize) -> Option<usize> {
+        match self {
+            Self::Inserted(at) if idx >= at => Some(idx + 1),
+            Self::Removed(at) if idx == at => None,
+            Self::Removed(at) if idx > at => Some(idx - 1),
+            _ => Some(idx),
+        }
+    }
+
+    /// True when the station at `idx` is renumbered or removed.
+    pub fn moves(self, idx: usize) -> bool {
+        self.apply(idx) != Some(idx)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersistentState {
     pub last_station_idx: Option<usize>,
@@ -41,6 +71,13 @@ impl StateManager {
             time_pos_secs: None,
             duration_secs: None,
             mpv_health: MpvHealth::Absent,
+            recordings: Vec::new(),
+            scheduled: Vec::new(),
+            sleep_timer: None,
+            alarm: None,
+            preview: None,
+            upstream: None,
+            diagnostics: Diagnostics::default(),
         };
 
         Self {
@@ -49,6 +86,15 @@ impl StateManager {
         }
     }
 
+    /// Holder for snapshots of a remote core (`r4dio --attach`): starts
+    /// empty and never reads or writes the state file.
+    pub fn mirror() -> Self {
+        Self {
+            state: Arc::new(RwLock::new(DaemonState::default())),
+            state_file: PathBuf::new(),
+        }
+    }
+
     pub fn arc(&self) -> Arc<RwLock<DaemonState>> {
         Arc::clone(&self.state)
     }
@@ -62,6 +108,7 @@ impl StateManager {
             let mut state = self.state.write().await;
             state.current_station = Some(idx);
             state.current_file = None;
+            state.preview = None;
             state.is_playing = true;
             state.playback_status = PlaybackStatus::Connecting;
             state.icy_title = None; // clear stale ICY from previous station
@@ -77,6 +124,7 @@ impl StateManager {
             let mut state = self.state.write().await;
             state.current_station = None;
             state.current_file = Some(path);
+            state.preview = None;
             state.is_playing = true;
             state.playback_status = PlaybackStatus::Connecting;
             state.icy_title = None;
@@ -94,6 +142,7 @@ impl StateManager {
             state.playback_status = PlaybackStatus::Idle;
             state.icy_title = None;
             state.current_file = None;
+            state.preview = None;
             state.time_pos_secs = None;
             state.duration_secs = None;
             state.rev += 1;
@@ -101,6 +150,73 @@ impl StateManager {
         self.save().await
     }
 
+    /// Play `station` as a preview: not in the list, so no current station.
+    pub async fn set_previewing(&self, station: Station) {
+        let mut state = self.state.write().await;
+        state.current_station = None;
+        state.current_file = None;
+        state.preview = Some(Box::new(station));
+        state.is_playing = true;
+        state.playback_status = PlaybackStatus::Connecting;
+        state.icy_title = None;
+        state.time_pos_secs = None;
+        state.duration_secs = None;
+        state.rev += 1;
+    }
+
+    /// Append a station to the list; returns its index.
+    pub async fn add_station(&self, station: Station) -> usize {
+        let mut state = self.state.write().await;
+        state.stations.push(station);
+        state.rev += 1;
+        state.stations.len() - 1
+    }
+
+    /// Replace the station at `idx` (an edit from the station editor).
+    pub async fn update_station(&self, idx: usize, station: Station) {
+        let mut state = self.state.write().await;
+        for rec in state.recordings.iter_mut().filter(|r| r.station_idx == idx) {
+            rec.station_name = station.name.clone();
+        }
+        if let Some(slot) = state.stations.get_mut(idx) {
+            *slot = station;
+            state.rev += 1;
+        }
+    }
+
+    /// Insert a station at `idx`, after the ones before it.
+    pub async fn insert_station(&self, idx: usize, station: Station) -> anyhow::Result<()> {
+        {
+            let mut state = self.state.write().await;
+            let idx = idx.min(state.stations.len());
+            state.stations.insert(idx, station);
+            renumber(&mut state, ListChange::Inserted(idx));
+        }
+        self.save().await
+    }
+
+    /// Remove the station at `idx`.  The caller stops its playback first;
+    /// here it just stops being the current station.
+    pub async fn remove_station(&self, idx: usize) -> anyhow::Result<Option<Station>> {
+        let removed = {
+            let mut state = self.state.write().await;
+            if idx >= state.stations.len() {
+                return Ok(None);
+            }
+            let removed = state.stations.remove(idx);
+            renumber(&mut state, ListChange::Removed(idx));
+            removed
+        };
+        self.save().await?;
+        Ok(Some(removed))
+    }
+
+    /// Replace the whole state with a snapshot received from a remote core.
+    /// Not persisted: the remote core owns its state file.
+    pub async fn replace_state(&self, state: DaemonState) {
+        *self.state.write().await = state;
+    }
+
     pub async fn set_playback_status(&self, status: PlaybackStatus) {
         let mut state = self.state.write().await;
         state.is_playing = matches!(status, PlaybackStatus::Playing | PlaybackStatus::Paused);
@@ -130,6 +246,18 @@ impl StateManager {
         state.rev += 1;
     }
 
+    pub async fn set_upstream(&self, upstream: Option<UpstreamStatus>) {
+        let mut state = self.state.write().await;
+        state.upstream = upstream;
+        state.rev += 1;
+    }
+
+    pub async fn set_diagnostics(&self, diagnostics: Diagnostics) {
+        let mut state = self.state.write().await;
+        state.diagnostics = diagnostics;
+        state.rev += 1;
+    }
+
     pub async fn set_timeline(&self, time_pos_secs: Option<f64>, duration_secs: Option<f64>) {
         let mut state = self.state.write().await;
         state.time_pos_secs = time_pos_secs;
@@ -137,6 +265,44 @@ impl StateManager {
         state.rev += 1;
     }
 
+    /// Insert or replace the recording entry for `status.station_idx`.
+    pub async fn set_recording(&self, status: RecordingStatus) {
+        let mut state = self.state.write().await;
+        match state
+            .recordings
+            .iter_mut()
+            .find(|r| r.station_idx == status.station_idx)
+        {
+            Some(existing) => *existing = status,
+            None => state.recordings.push(status),
+        }
+        state.rev += 1;
+    }
+
+    pub async fn clear_recording(&self, station_idx: usize) {
+        let mut state = self.state.write().await;
+        state.recordings.retain(|r| r.station_idx != station_idx);
+        state.rev += 1;
+    }
+
+    pub async fn set_scheduled(&self, shows: Vec<ScheduledShow>) {
+        let mut state = self.state.write().await;
+        state.scheduled = shows;
+        state.rev += 1;
+    }
+
+    pub async fn set_sleep_timer(&self, timer: Option<SleepTimer>) {
+        let mut state = self.state.write().await;
+        state.sleep_timer = timer;
+        state.rev += 1;
+    }
+
+    pub async fn set_alarm(&self, alarm: Option<Alarm>) {
+        let mut state = self.state.write().await;
+        state.alarm = alarm;
+        state.rev += 1;
+    }
+
     pub async fn next_station(&self) -> anyhow::Result<()> {
         let stations_len = {
             let state = self.state.read().await;
@@ -286,6 +452,8 @@ struct TomlStation {
     name: String,
     url: String,
     #[serde(default)]
+    mirrors: Vec<String>,
+    #[serde(default)]
     mixtape_url: String,
     #[serde(default)]
     network: String,
@@ -299,6 +467,19 @@ struct TomlStation {
     country: String,
 }
 
+fn renumber(state: &mut DaemonState, change: ListChange) {
+    state.current_station = state.current_station.and_then(|i| change.apply(i));
+    if let Some(alarm) = state.alarm.as_mut() {
+        if let AlarmTarget::Station { station_idx } = &mut alarm.target {
+            match change.apply(*station_idx) {
+                Some(idx) => *station_idx = idx,
+                None => state.alarm = None,
+            }
+        }
+    }
+    state.rev += 1;
+}
+
 pub fn load_stations_from_toml(path: &std::path::Path) -> anyhow::Result<Vec<Station>> {
     let content = std::fs::read_to_string(path)?;
     parse_stations_from_toml_str(&content)
@@ -312,6 +493,7 @@ pub fn parse_stations_from_toml_str(content: &str) -> anyhow::Result<Vec<Station
         .map(|s| Station {
             name: s.name,
             url: s.url,
+            mirrors: s.mirrors,
             mixtape_url: s.mixtape_url,
             network: s.network,
             description: s.description,
@@ -322,3 +504,441 @@ pub fn parse_stations_from_toml_str(content: &str) -> anyhow::Result<Vec<Station
         .collect();
     Ok(stations)
 }
+
+/// A `[[station]]` table in the hand-written style of `stations.toml`:
+/// aligned keys, empty fields left out.
+pub fn station_to_toml(station: &Station) -> String {
+    let quote = |s: &str| toml::Value::String(s.to_string()).to_string();
+    let mut out = String::from("[[station]]\n");
+    let mut field = |key: &str, value: String| {
+        out.push_str(&format!("{:<11} = {}\n", key, value));
+    };
+    field("name", quote(&station.name));
+    field("url", quote(&station.url));
+    let list = |items: &[String]| {
+        let items: Vec<String> = items.iter().map(|t| quote(t)).collect();
+        format!("[{}]", items.join(", "))
+    };
+    if !station.mirrors.is_empty() {
+        field("mirrors", list(&station.mirrors));
+    }
+    for (key, value) in [
+        ("mixtape_url", &station.mixtape_url),
+        ("network", &station.network),
+        ("description", &station.description),
+    ] {
+        if !value.is_empty() {
+            field(key, quote(value));
+        }
+    }
+    if !station.tags.is_empty() {
+        field("tags", list(&station.tags));
+    }
+    for (key, value) in [("city", &station.city), ("country", &station.country)] {
+        if !value.is_empty() {
+            field(key, quote(value));
+        }
+    }
+    out
+}
+
+/// Keys of a `[[station]]` table, in the order `station_to_toml` writes them.
+const STATION_KEYS: [&str; 9] = [
+    "name",
+    "url",
+    "mirrors",
+    "mixtape_url",
+    "network",
+    "description",
+    "tags",
+    "city",
+    "country",
+];
+
+/// Check a station before it goes into the list: a name that no other
+/// station has (stars, history and queues refer to stations by name) and
+/// http(s) URLs.  `idx` is the station being edited, if any.
+pub fn validate_station(
+    station: &Station,
+    stations: &[Station],
+    idx: Option<usize>,
+) -> anyhow::Result<()> {
+    let name = station.name.trim();
+    if name.is_empty() {
+        anyhow::bail!("name is empty");
+    }
+    let taken = stations
+        .iter()
+        .enumerate()
+        .any(|(i, s)| Some(i) != idx && s.name.trim().eq_ignore_ascii_case(name));
+    if taken {
+        anyhow::bail!("there is already a station named {:?}", name);
+    }
+    let urls = [("url", &station.url), ("mixtape_url", &station.mixtape_url)]
+        .into_iter()
+        .chain(station.mirrors.iter().map(|m| ("mirror", m)));
+    for (field, url) in urls {
+        if url.trim().is_empty() {
+            if field == "url" {
+                anyhow::bail!("url is empty");
+            }
+            continue;
+        }
+        match reqwest::Url::parse(url.trim()) {
+            Ok(u) if matches!(u.scheme(), "http" | "https") => {}
+            Ok(u) => anyhow::bail!("{} must be http or https, not {}", field, u.scheme()),
+            Err(e) => anyhow::bail!("{} {:?} is not a URL: {}", field, url, e),
+        }
+    }
+    Ok(())
+}
+
+/// "Name (copy)", or "Name (copy 2)" and up when that's taken too.
+pub fn copy_name(name: &str, stations: &[Station]) -> String {
+    let taken = |n: &str| stations.iter().any(|s| s.name.eq_ignore_ascii_case(n));
+    let mut candidate = format!("{} (copy)", name);
+    let mut n = 2;
+    while taken(&candidate) {
+        candidate = format!("{} (copy {})", name, n);
+        n += 1;
+    }
+    candidate
+}
+
+/// Append `station` to the TOML station file at `path`.
+pub fn append_station_to_toml(
+    path: &std::path::Path,
+    existing: &[Station],
+    station: &Station,
+) -> anyhow::Result<()> {
+    edit_stations_toml(path, existing, |tables| {
+        tables.push(station_table(station)?);
+        Ok(())
+    })
+}
+
+/// Insert `copy` right after `original` in the TOML station file.
+pub fn insert_station_after_in_toml(
+    path: &std::path::Path,
+    existing: &[Station],
+    original: &Station,
+    copy: &Station,
+) -> anyhow::Result<()> {
+    edit_stations_toml(path, existing, |tables| {
+        let i = find_station_table(tables, original)?;
+        // `ArrayOfTables` can only push, so rebuild it in the new order.
+        let mut all: Vec<toml_edit::Table> = tables.iter().cloned().collect();
+        all.insert(i + 1, station_table(copy)?);
+        tables.clear();
+        for table in all {
+            tables.push(table);
+        }
+        Ok(())
+    })
+}
+
+/// Rewrite the fields of `old` that `new` changes, in place: untouched
+/// fields, the key order, alignment and comments stay as they are.
+pub fn update_station_in_toml(
+    path: &std::path::Path,
+    existing: &[Station],
+    old: &Station,
+    new: &Station,
+) -> anyhow::Result<()> {
+    edit_stations_toml(path, existing, |tables| {
+        let i = find_station_table(tables, old)?;
+        let table = tables.get_mut(i).expect("index from find_station_table");
+        let fresh = station_table(new)?;
+        for key in STATION_KEYS {
+            match (table.get_mut(key), fresh.get_key_value(key)) {
+                (Some(item), Some((_, value))) => {
+                    if field_text(item) != field_text(value) {
+                        let decor = item.as_value().map(|v| v.decor().clone());
+                        *item = value.clone();
+                        if let (Some(decor), Some(v)) = (decor, item.as_value_mut()) {
+                            *v.decor_mut() = decor;
+                        }
+                    }
+                }
+                (Some(_), None) => {
+                    table.remove(key);
+                }
+                (None, Some((k, value))) => {
+                    table.insert_formatted(k, value.clone());
+                }
+                (None, None) => {}
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Remove `station` from the TOML station file.  A comment above it (a
+/// section heading) moves to the station that follows.
+pub fn remove_station_from_toml(
+    path: &std::path::Path,
+    existing: &[Station],
+    station: &Station,
+) -> anyhow::Result<()> {
+    edit_stations_toml(path, existing, |tables| {
+        let i = find_station_table(tables, station)?;
+        let prefix = |t: &toml_edit::Table| {
+            t.decor()
+                .prefix()
+                .and_then(|p| p.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        let removed = prefix(tables.get(i).expect("index from find_station_table"));
+        tables.remove(i);
+        if removed.contains('#') {
+            if let Some(next) = tables.get_mut(i) {
+                let own = prefix(next);
+                let merged = if own.contains('#') {
+                    removed + &own
+                } else {
+                    removed
+                };
+                next.decor_mut().set_prefix(merged);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Apply `edit` to the `[[station]]` tables of the file at `path` and
+/// write it back, keeping everything else (comments included).  When the
+/// file doesn't exist yet it is started with `existing`, so a list loaded
+/// from elsewhere (m3u, bundled file) isn't lost on the next start.
+fn edit_stations_toml(
+    path: &std::path::Path,
+    existing: &[Station],
+    edit: impl FnOnce(&mut toml_edit::ArrayOfTables) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut content = String::new();
+            if !existing.is_empty() {
+                content.push_str("# radio-tui station list\n");
+            }
+            for s in existing {
+                content.push('\n');
+                content.push_str(&station_to_toml(s));
+            }
+            content
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let mut doc: toml_edit::DocumentMut = content.parse()?;
+    let tables = doc
+        .entry("station")
+        .or_insert(toml_edit::Item::ArrayOfTables(Default::default()))
+        .as_array_of_tables_mut()
+        .ok_or_else(|| anyhow::anyhow!("`station` in {} is not [[station]]", path.display()))?;
+    edit(tables)?;
+    let content = doc.to_string();
+    // Don't leave a file that no longer parses.
+    parse_stations_from_toml_str(&content)?;
+    let tmp = path.with_extension("toml.tmp");
+    std::fs::write(&tmp, &content)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Index of the table whose name and url are `station`'s.
+fn find_station_table(
+    tables: &toml_edit::ArrayOfTables,
+    station: &Station,
+) -> anyhow::Result<usize> {
+    tables
+        .iter()
+        .position(|t| {
+            t.get("name").and_then(|v| v.as_str()) == Some(station.name.as_str())
+                && t.get("url").and_then(|v| v.as_str()) == Some(station.url.as_str())
+        })
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "{:?} is not in the station file (edited since it was loaded?)",
+                station.name
+            )
+        })
+}
+
+/// `station` as a table laid out like `station_to_toml`.  It has no
+/// document position, so it is written right after the table before it.
+fn station_table(station: &Station) -> anyhow::Result<toml_edit::Table> {
+    let doc: toml_edit::DocumentMut = station_to_toml(station).parse()?;
+    let parsed = doc
+        .get("station")
+        .and_then(toml_edit::Item::as_array_of_tables)
+        .and_then(|t| t.get(0))
+        .ok_or_else(|| anyhow::anyhow!("station_to_toml wrote no table"))?;
+    let mut table = toml_edit::Table::new();
+    for key in STATION_KEYS {
+        if let Some((k, item)) = parsed.get_key_value(key) {
+            table.insert_formatted(k, item.clone());
+        }
+    }
+    table.decor_mut().set_prefix("\n");
+    Ok(table)
+}
+
+/// A string or string-array field's content, ignoring how it's written.
+fn field_text(item: &toml_edit::Item) -> Option<Vec<&str>> {
+    match item.as_value()? {
+        toml_edit::Value::String(s) => Some(vec![s.value().as_str()]),
+        toml_edit::Value::Array(a) => a.iter().map(|v| v.as_str()).collect(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_station_to_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("radio").join("stations.toml");
+        let first = Station {
+            name: "NTS 1".into(),
+            url: "https://stream-relay-geo.ntslive.net/stream".into(),
+            ..Station::default()
+        };
+        let added = Station {
+            name: "Radio \"Quoted\"".into(),
+            url: "http://example.com/live.mp3".into(),
+            description: "MP3 128 kbps".into(),
+            tags: vec!["jazz".into(), "public radio".into()],
+            city: "Paris".into(),
+            country: "France".into(),
+            ..Station::default()
+        };
+
+        // A missing file starts from the stations already loaded.
+        append_station_to_toml(&path, std::slice::from_ref(&first), &added).unwrap();
+        assert_eq!(
+            load_stations_from_toml(&path).unwrap(),
+            vec![first.clone(), added.clone()]
+        );
+
+        // An existing file keeps its comments and gains one table.
+        std::fs::write(
+            &path,
+            "# mine\n[[station]]\nname = \"A\"\nurl = \"http://a\"",
+        )
+        .unwrap();
+        append_station_to_toml(&path, &[], &added).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# mine\n"));
+        assert!(content.contains("tags        = [\"jazz\", \"public radio\"]\n"));
+        assert_eq!(load_stations_from_toml(&path).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_edit_stations_toml_keeps_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stations.toml");
+        std::fs::write(
+            &path,
+            r#"# my stations
+
+# ── Chile ──
+
+[[station]]
+name        = "Valentin"
+url         = "https://a.example/stream"  # the 128k one
+city        = "Valparaiso"
+
+# ── Australia ──
+
+[[station]]
+name        = "FBI"
+url         = "https://b.example/stream"
+tags        = ["indie"]
+
+[[station]]
+name        = "PBS"
+url         = "https://c.example/stream"
+"#,
+        )
+        .unwrap();
+        let stations = load_stations_from_toml(&path).unwrap();
+
+        // Edit: only changed fields are rewritten; comments stay.
+        let mut edited = stations[0].clone();
+        edited.city = String::new();
+        edited.country = "Chile".into();
+        edited.tags = vec!["culture".into()];
+        edited.mirrors = vec!["https://a2.example/stream".into()];
+        update_station_in_toml(&path, &stations, &stations[0], &edited).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("url         = \"https://a.example/stream\"  # the 128k one\n"));
+        assert!(content.contains("mirrors     = [\"https://a2.example/stream\"]\n"));
+        assert!(!content.contains("Valparaiso"));
+        assert!(content.contains("country     = \"Chile\"\n"));
+        let stations = load_stations_from_toml(&path).unwrap();
+        assert_eq!(stations[0], edited);
+
+        // Duplicate: the copy goes right after its original.
+        let copy = Station {
+            name: copy_name("FBI", &stations),
+            ..stations[1].clone()
+        };
+        insert_station_after_in_toml(&path, &stations, &stations[1], &copy).unwrap();
+        let stations = load_stations_from_toml(&path).unwrap();
+        let names: Vec<&str> = stations.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Valentin", "FBI", "FBI (copy)", "PBS"]);
+
+        // Delete: a section heading moves to the next station.
+        remove_station_from_toml(&path, &stations, &stations[1]).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# my stations\n"));
+        assert!(content.contains("# ── Australia ──\n\n[[station]]\nname        = \"FBI (copy)\""));
+        let names: Vec<String> = load_stations_from_toml(&path)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, ["Valentin", "FBI (copy)", "PBS"]);
+    }
+
+    #[test]
+    fn test_validate_and_renumber() {
+        let stations = vec![
+            Station {
+                name: "FBI".into(),
+                url: "https://b.example/stream".into(),
+                ..Station::default()
+            },
+            Station {
+                name: "PBS".into(),
+                url: "https://c.example/stream".into(),
+                ..Station::default()
+            },
+        ];
+        assert!(validate_station(&stations[0], &stations, Some(0)).is_ok());
+        let mut s = stations[0].clone();
+        assert!(validate_station(&s, &stations, None).is_err());
+        s.name = "pbs".into();
+        assert!(validate_station(&s, &stations, Some(0)).is_err());
+        s.name = "RRR".into();
+        s.url = "ftp://r.example".into();
+        assert!(validate_station(&s, &stations, None).is_err());
+        s.url = "http://r.example/live".into();
+        assert!(validate_station(&s, &stations, None).is_ok());
+        s.mirrors = vec!["r.example/backup".into()];
+        assert!(validate_station(&s, &stations, None).is_err());
+        assert_eq!(copy_name("FBI", &stations), "FBI (copy)");
+
+        assert_eq!(ListChange::Removed(2).apply(1), Some(1));
+        assert_eq!(ListChange::Removed(2).apply(2), None);
+        assert_eq!(ListChange::Removed(2).apply(5), Some(4));
+        assert_eq!(ListChange::Inserted(2).apply(2), Some(3));
+        assert_eq!(ListChange::Inserted(2).apply(1), Some(1));
+    }
+}
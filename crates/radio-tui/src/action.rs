@@ -1,6 +1,8 @@
 //! Action enum — all user-initiated intents and internal events.
 
-use radio_proto::protocol::Command;
+use radio_proto::protocol::{Command, Station};
+
+use crate::queue::QueueItem;
 
 /// Unique identifier for a focusable component.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -14,6 +16,9 @@ pub enum ComponentId {
     LogPanel,
     HelpOverlay,
     ScopePanel,
+    QueuePanel,
+    DiscoverPanel,
+    DiagnosticsPanel,
 }
 
 /// Context for star operations — identifies which item type is being starred.
@@ -41,8 +46,25 @@ pub enum Action {
     Volume(f32),
     SeekRelative(f64),
     SeekTo(f64),
+    /// Leave the timeshift buffer and return the station to live.
+    JumpToLive,
     Mute, // toggle mute (save/restore volume)
 
+    // ── Queue / playlists ────────────────────────────────────────────────────
+    /// Append a file or station to the play queue.
+    Enqueue(QueueItem),
+    /// Play queue entry n now, taking it out of the queue.
+    QueuePlay(usize),
+    /// Move queue entry n by the given number of places.
+    QueueMove(usize, isize),
+    QueueRemove(usize),
+    QueueClear,
+    QueueShuffle,
+    /// Save the queue as a playlist (name, `.m3u` for M3U).
+    SavePlaylist(String),
+    /// Append a saved playlist (file name) to the queue.
+    LoadPlaylist(String),
+
     // ── Navigation ───────────────────────────────────────────────────────────
     FocusNext,
     FocusPrev,
@@ -81,6 +103,30 @@ pub enum Action {
 
     // ── Scope ─────────────────────────────────────────────────────────────────
     ToggleScope,
+
+    // ── Station discovery ─────────────────────────────────────────────────────
+    /// Show/hide the discover pane (Radio workspace).
+    ToggleDiscover,
+    /// Search the station directory (see `radio_proto::radio_browser::DirectoryQuery::parse`).
+    DiscoverSearch(String),
+    /// Play a directory result without adding it.
+    PreviewStation(Station),
+    /// Add a directory result to the list and `stations.toml`.
+    AddStation(Station),
+
+    // ── Diagnostics ───────────────────────────────────────────────────────────
+    /// Show/hide the stream diagnostics pane (Radio workspace).
+    ToggleDiagnostics,
+
+    // ── Station editing ───────────────────────────────────────────────────────
+    /// Save an edited station (original index, new fields).
+    UpdateStation(usize, Station),
+    /// Delete a station; asks to press again to confirm.
+    DeleteStation(usize),
+    /// Insert a copy of a station right after it.
+    DuplicateStation(usize),
+    /// Probe every station and mark the dead ones (`station_check`).
+    CheckStations,
     
     // ── VU Meter ───────────────────────────────────────────────────────────────
     CycleVuMeterStyle,
@@ -88,9 +134,38 @@ pub enum Action {
     // ── Song recognition ─────────────────────────────────────────────────────
     /// Trigger song recognition (vibra + icy + nts pipeline).
     RecognizeSong,
+    /// Export the songs history: a file name plus filter terms (see
+    /// `radio_proto::songs_export::parse_spec`).
+    ExportSongs(String),
+    /// Search the songs database from the Songs pane (empty = no search).
+    SearchSongs(String),
+    /// Correct a songs row's track by job_id (recorded as a manual row).
+    CorrectSong(String, String),
+    /// Delete a songs row by job_id.
+    DeleteSong(String),
+    /// Merge repeated detections of the same track into single rows.
+    MergeDuplicateSongs,
+    /// Toggle auto-ID: recognize on track boundaries and on a cadence.
+    ToggleAutoIdentify,
     /// Toggle passive background polling for station now-playing annotations.
     ToggleAutoPolling,
 
+    // ── Recording ────────────────────────────────────────────────────────────
+    /// Start/stop recording the playing station to downloads_dir.
+    ToggleRecording,
+    /// Stop a recording and discard the segment in progress.
+    CancelRecording,
+    /// Schedule (or unschedule) an upcoming NTS show for recording:
+    /// (channel, index into that channel's upcoming list).
+    ToggleShowRecording(usize, usize),
+
+    // ── Sleep timer / alarm ──────────────────────────────────────────────────
+    /// Step the sleep timer through its presets, then off.
+    CycleSleepTimer,
+    /// Set an alarm for the selected station/file at the configured time,
+    /// or cancel the pending one.
+    ToggleAlarm,
+
     // ── UI toggles ───────────────────────────────────────────────────────────
     ToggleLogs,
     ToggleHelp,
@@ -31,29 +31,46 @@ use tokio::io::AsyncWriteExt;
 use tokio::sync::{broadcast, mpsc, Mutex as TokioMutex};
 use tracing::{debug, info, warn};
 
-use radio_proto::protocol::{Command, DaemonState, MpvHealth, Station};
-use radio_proto::state::StateManager;
+use radio_proto::config::{DiscoverConfig, LoudnessConfig, RecognitionConfig, RecognizerConfig};
+use radio_proto::fingerprint::FingerprintLibrary;
+use radio_proto::protocol::{
+    AlarmTarget, Command, DaemonState, MpvHealth, PlaybackStatus, ScheduledShow, Station,
+};
+use radio_proto::state::{ListChange, StateManager};
 
 use crate::core::DaemonEvent;
+use crate::icy::IcyDemuxer;
+use crate::remote::{DownloadInfo, NtsChannelInfo, RemoteRequest, StarsSnapshot, MAX_STARS};
+use crate::station_check::{self, StationCheck};
+use crate::stream_playlist::{fetch_playlist_target, is_playlist_url, looks_hls_url};
 use crate::BroadcastMessage;
 
+use radio_proto::radio_browser::{self, DirectoryQuery, DirectoryStation};
+use radio_proto::recognizer::{
+    build_recognizers, capture_file, capture_stream, recognize_clip, PcmClip, Recognizer,
+};
+use radio_proto::song_store::SongStore;
 use radio_proto::songs::{
-    append_to_vds, load_vds, make_job_id, recognize_via_nts, recognize_via_nts_mixtape,
-    recognize_via_vibra, vibra_rec_string, RecognitionResult, VdsPatch,
+    make_job_id, recognize_via_nts, recognize_via_nts_mixtape, RecognitionResult,
+    RecognitionSource, VdsPatch,
 };
+use radio_proto::songs_export;
 
 use crate::{
     action::{Action, ComponentId, StarContext, Workspace},
     app_state::{
-        AppState, DownloadStatus, FileChapter, FileMetadata, LocalFileEntry, NtsChannel, NtsShow,
-        RandomHistoryEntry, TickerEntry,
+        AppState, DiscoverResults, DownloadStatus, FileChapter, FileMetadata, LocalFileEntry,
+        NtsChannel, NtsShow, RandomHistoryEntry, TickerEntry,
     },
     component::Component,
     components::{
-        file_list::FileList, file_meta::FileMeta, header::Header, help_overlay::HelpOverlay,
-        icy_ticker::IcyTicker, log_panel::LogPanel, nts_panel::NtsPanel, scope_panel::ScopePanel,
+        diagnostics_panel::DiagnosticsPanel, discover_panel::DiscoverPanel, file_list::FileList,
+        file_meta::FileMeta, header::Header, help_overlay::HelpOverlay, icy_ticker::IcyTicker,
+        log_panel::LogPanel, nts_panel::NtsPanel, queue_panel::QueuePanel, scope_panel::ScopePanel,
         songs_ticker::SongsTicker, station_list::StationList,
     },
+    loudness::{self, LearnedLoudness},
+    queue::{self, QueueItem},
     widgets::{
         status_bar::{self, InputMode},
         toast::{Severity, ToastManager},
@@ -76,10 +93,20 @@ enum AppMessage {
     RecognitionPatch(String, VdsPatch),
     /// Vibra recognition succeeded.
     RecognitionComplete(String, String), // job_id, display string
-    /// Vibra recognition produced no match (or stream_url was absent).
+    /// Vibra recognition produced no match (or there was no audio to capture).
     RecognitionNoMatch,
     /// Fires after a 1-second delay to start the next queued recognition job.
     RecognitionQueueNext,
+    /// An auto-ID recognition finished: (station_name, icy_title, match).
+    AutoIdentified(
+        Option<String>,
+        Option<String>,
+        Option<(RecognitionSource, String)>,
+    ),
+    /// A station directory search finished: (query, results or error).
+    DiscoverResults(String, Result<Vec<DirectoryStation>, String>),
+    /// A station health check finished, in station order.
+    StationChecks(Vec<StationCheck>),
     /// Real-time audio RMS level from daemon (dBFS).
     AudioLevel(f32),
     /// Raw PCM chunk (mono f32 normalised -1..1, 44100 Hz) for scope display.
@@ -100,6 +127,12 @@ enum AppMessage {
         total: usize,
         elapsed_ms: u128,
     },
+    /// Query or action from the HTTP API.
+    Remote(RemoteRequest),
+    /// A local file played to its end.
+    FileEnded(String),
+    /// SIGTERM/SIGINT in headless mode: leave the loop as `q` would.
+    Quit,
 }
 
 const STREAM_PCM_RATE_HZ: usize = 44_100;
@@ -120,6 +153,12 @@ const MIN_POLL_INTERVAL_SECS: u64 = 10;
 const NTS_POLL_TASK_TIMEOUT_SECS: u64 = 25; // Increased from 12s - mixtape lookups need more time
 const NTS_MIN_STAGGER_MS: u64 = 100; // Minimum delay between NTS requests
 const NTS_MAX_STAGGER_MS: u64 = 200; // Maximum delay between NTS requests (random to appear natural)
+/// Sleep timer lengths `z` steps through before switching it off.
+const SLEEP_PRESETS_MINS: [u64; 5] = [15, 30, 45, 60, 90];
+/// Audio (above the silence gate) a station must play before its loudness is
+/// learned, and how often the running measurement is saved after that.
+const LOUDNESS_MIN_SECS: f32 = 20.0;
+const LOUDNESS_LEARN_INTERVAL: Duration = Duration::from_secs(30);
 
 // Non-NTS ICY polling tuning (now configurable via config.toml):
 //
@@ -186,6 +225,33 @@ struct MixtapePollJob {
     mixtape_url: String,
 }
 
+/// Rows of the songs database kept in `state.songs_history`.
+const SONGS_HISTORY_MAX: usize = 500;
+
+/// (station_name, audio, icy_title, nts_ch, nts_mixtape_url)
+type RecognitionRequest = (
+    Option<String>,
+    Option<RecognitionAudio>,
+    Option<String>,
+    Option<usize>,
+    Option<String>,
+);
+
+/// What a recognition job listens to: the audio just before `i` was pressed.
+#[derive(Debug, Clone)]
+enum RecognitionAudio {
+    /// A station's proxy buffer, ending `behind` seconds before live as of
+    /// `pressed_at`.  `upstream` is captured directly when nothing is buffered.
+    Station {
+        idx: usize,
+        behind: f64,
+        pressed_at: std::time::Instant,
+        upstream: String,
+    },
+    /// A local file, ending at playback position `pos`.
+    File { path: PathBuf, pos: f64 },
+}
+
 // ── Persistence serde structs ─────────────────────────────────────────────────
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
@@ -227,6 +293,9 @@ struct PaneAreas {
     nts_panel: Rect,   // whichever NTS panel is currently shown
     nts_overlay: Rect, // hover overlay on top of station list (may be default/zero when hidden)
     file_meta: Rect,
+    queue_panel: Rect,
+    discover_panel: Rect,
+    diagnostics_panel: Rect,
     log_panel: Rect,
     scope: Rect, // scope panel in header (may be default/zero when hidden)
 }
@@ -237,13 +306,22 @@ pub struct App {
     // ── Paths ─────────────────────────────────────────────────────────────────
     icy_log_path: PathBuf,
     songs_csv_path: PathBuf,
-    songs_vds_path: PathBuf,
+    /// The songs database (`songs.jsonl`); `state.songs_history` mirrors its
+    /// newest rows for the Songs pane.
+    songs: SongStore,
+    /// Songs pane search; `state.song_search` holds its results.
+    songs_query: String,
     tui_log_path: PathBuf,
     stars_path: PathBuf,
     random_history_path: PathBuf,
+    queue_path: PathBuf,
+    playlists_dir: PathBuf,
+    /// Songs history exports (`x` in the Songs pane).
+    exports_dir: PathBuf,
     recent_path: PathBuf,
     file_positions_path: PathBuf,
     ui_state_path: PathBuf,
+    loudness_path: PathBuf,
 
     // ── Shared state (passed read-only to components) ─────────────────────────
     pub state: AppState,
@@ -257,6 +335,9 @@ pub struct App {
     nts_panel_ch1: NtsPanel,
     nts_panel_ch2: NtsPanel,
     file_meta: FileMeta,
+    queue_panel: QueuePanel,
+    discover_panel: DiscoverPanel,
+    diagnostics_panel: DiagnosticsPanel,
     log_panel: LogPanel,
     help_overlay: HelpOverlay,
     scope_panel: ScopePanel,
@@ -275,6 +356,17 @@ pub struct App {
     pending_resume_file: Option<(String, f64)>,
     jump_from_station: Option<Option<usize>>,
 
+    // ── Loudness normalization ────────────────────────────────────────────────
+    loudness_config: LoudnessConfig,
+    /// Learned integrated loudness per station name (`loudness.json`).
+    learned_loudness: HashMap<String, LearnedLoudness>,
+    /// Station the loudness meter is measuring, with what was learned for it
+    /// before this session (the session's measurement is merged into that).
+    loudness_session: Option<(String, Option<LearnedLoudness>)>,
+    loudness_learned_at: std::time::Instant,
+    /// First `D` on a station: delete it if pressed again before this expires.
+    pending_station_delete: Option<(usize, std::time::Instant)>,
+
     /// Whether to quit on next iteration.
     should_quit: bool,
 
@@ -299,19 +391,25 @@ pub struct App {
 
     // ── Recognition safety queue ──────────────────────────────────────────────
     /// Pending recognition jobs
-    /// (station_name, stream_url, icy_title, nts_ch, nts_mixtape_url). Max 3.
+    /// (station_name, audio, icy_title, nts_ch, nts_mixtape_url). Max 3.
     /// Cleared automatically when the station changes.
-    recognize_queue: std::collections::VecDeque<(
-        Option<String>,
-        Option<String>,
-        Option<String>,
-        Option<usize>,
-        Option<String>,
-    )>,
-    /// True while a recognition job is in flight (vibra running).
+    recognize_queue: std::collections::VecDeque<RecognitionRequest>,
+    /// True while a recognition job is in flight (capture + recognizers).
     recognize_in_flight: bool,
     /// Station name that the current queue belongs to; used to detect station changes.
     recognize_active_station: Option<String>,
+    /// `[recognition] backends`, in priority order.
+    recognizers: std::sync::Arc<Vec<Box<dyn Recognizer>>>,
+    /// Known-tracks library, when the `library` backend is configured; fed
+    /// from `downloads_dir` at startup and after each download.
+    fingerprint_library: Option<std::sync::Arc<FingerprintLibrary>>,
+    recognition_capture_secs: u64,
+    /// Auto-ID schedule; runs while `state.auto_identify` is on (`I`).
+    auto_id: crate::auto_id::AutoId,
+    /// True while an auto-ID recognition is in flight.
+    auto_id_in_flight: bool,
+    /// `[discover]`: the station directory searched from the discover pane.
+    discover_config: DiscoverConfig,
 
     // ── Download safety queue ─────────────────────────────────────────────────
     /// Pending downloads (url, display_name). Executed one at a time.
@@ -346,13 +444,18 @@ impl App {
     pub fn new(
         icy_log_path: PathBuf,
         songs_csv_path: PathBuf,
-        songs_vds_path: PathBuf,
+        songs: SongStore,
         tui_log_path: PathBuf,
         stars_path: PathBuf,
         random_history_path: PathBuf,
+        queue_path: PathBuf,
+        playlists_dir: PathBuf,
+        exports_dir: PathBuf,
         recent_path: PathBuf,
         file_positions_path: PathBuf,
         ui_state_path: PathBuf,
+        loudness_path: PathBuf,
+        fingerprints_path: PathBuf,
         downloads_dir: PathBuf,
         cmd_tx: mpsc::Sender<DaemonEvent>,
         state_manager: std::sync::Arc<StateManager>,
@@ -360,15 +463,24 @@ impl App {
         poll_interval_secs: u64,
         poll_max_concurrency: usize,
         poll_max_jobs_per_cycle: usize,
+        scope_fft_size: usize,
+        scope_peak_hold_ms: u64,
+        loudness_config: LoudnessConfig,
+        recognition_config: &RecognitionConfig,
+        discover_config: DiscoverConfig,
     ) -> Self {
         let icy_history = load_icy_log(&icy_log_path);
-        let songs_history = load_vds(&songs_vds_path, 200);
+        let songs_history = songs.recent(SONGS_HISTORY_MAX);
         let files = load_local_files(&downloads_dir);
         let (station_stars, file_stars) = load_stars(&stars_path);
         let random_history = load_random_history(&random_history_path);
+        let play_queue = queue::load_queue(&queue_path);
+        let playlists = queue::list_playlists(&playlists_dir);
         let recent = load_recent_state(&recent_path);
         let file_positions = load_file_positions(&file_positions_path);
         let ui_state = load_ui_session_state(&ui_state_path);
+        let learned_loudness = loudness::load_learned(&loudness_path);
+        let fingerprint_library = std::sync::Arc::new(FingerprintLibrary::load(fingerprints_path));
 
         let mut file_metadata_cache: HashMap<String, FileMetadata> = HashMap::new();
         // Pre-probe files that are already in cache (will be picked up in refresh)
@@ -385,6 +497,8 @@ impl App {
             files: files.clone(),
             file_metadata_cache,
             file_positions: file_positions.clone(),
+            queue: play_queue,
+            playlists,
             icy_history: icy_history.clone(),
             last_known_icy: None,
             songs_history: songs_history.clone(),
@@ -394,6 +508,8 @@ impl App {
             nts_ch1_error: None,
             nts_ch2_error: None,
             station_poll_titles: HashMap::new(),
+            station_checks: HashMap::new(),
+            checking_stations: false,
             workspace: Workspace::Radio,
             input_mode: InputMode::Normal,
             last_nonzero_volume: 0.7,
@@ -413,13 +529,17 @@ impl App {
             station_hint: crate::intent::RenderHint::Normal,
             downloads_dir: downloads_dir.clone(),
             icy_log_path: icy_log_path.clone(),
+            discover: DiscoverResults::default(),
             songs_csv_path: songs_csv_path.clone(),
-            songs_vds_path: songs_vds_path.clone(),
+            song_search: None,
             tui_log_path: tui_log_path.clone(),
             random_history,
             pcm_ring: std::collections::VecDeque::new(),
             pcm_pending: std::collections::VecDeque::new(),
             pcm_pending_started: false,
+            loudness: loudness::LoudnessMeter::default(),
+            loudness_gain_db: None,
+            auto_identify: false,
             download_statuses: HashMap::new(),
         };
 
@@ -438,6 +558,7 @@ impl App {
             "icy" | "icyticker" => wm.focus_set(ComponentId::IcyTicker),
             "songs" | "songsticker" => wm.focus_set(ComponentId::SongsTicker),
             "meta" | "filemeta" => wm.focus_set(ComponentId::FileMeta),
+            "queue" => wm.focus_set(ComponentId::QueuePanel),
             "filelist" => wm.focus_set(ComponentId::FileList),
             _ => {}
         };
@@ -451,13 +572,18 @@ impl App {
         let mut app = Self {
             icy_log_path,
             songs_csv_path,
-            songs_vds_path,
+            songs,
+            songs_query: String::new(),
             tui_log_path,
             stars_path,
             random_history_path,
+            queue_path,
+            playlists_dir,
+            exports_dir,
             recent_path,
             file_positions_path,
             ui_state_path,
+            loudness_path,
             state,
             header: Header::new(),
             station_list: StationList::new(),
@@ -467,9 +593,12 @@ impl App {
             nts_panel_ch1: NtsPanel::new(0),
             nts_panel_ch2: NtsPanel::new(1),
             file_meta: FileMeta::new(),
+            queue_panel: QueuePanel::new(),
+            discover_panel: DiscoverPanel::new(),
+            diagnostics_panel: DiagnosticsPanel::new(),
             log_panel: LogPanel::new(),
             help_overlay: HelpOverlay::new(),
-            scope_panel: ScopePanel::default(),
+            scope_panel: ScopePanel::new(scope_fft_size, scope_peak_hold_ms),
             wm,
             cmd_tx,
             state_manager,
@@ -483,6 +612,11 @@ impl App {
                 .clone()
                 .map(|p| (p, ui_state.last_file_pos.max(0.0))),
             jump_from_station: None,
+            loudness_config,
+            learned_loudness,
+            loudness_session: None,
+            loudness_learned_at: std::time::Instant::now(),
+            pending_station_delete: None,
             should_quit: false,
             pane_areas: PaneAreas::default(),
             toast: ToastManager::new(),
@@ -492,6 +626,22 @@ impl App {
             recognize_queue: std::collections::VecDeque::new(),
             recognize_in_flight: false,
             recognize_active_station: None,
+            recognizers: std::sync::Arc::new(build_recognizers(
+                recognition_config,
+                &fingerprint_library,
+            )),
+            fingerprint_library: recognition_config
+                .backends
+                .contains(&RecognizerConfig::Library)
+                .then_some(fingerprint_library),
+            recognition_capture_secs: recognition_config.capture_secs,
+            auto_id: crate::auto_id::AutoId::new(
+                recognition_config.auto_interval_secs,
+                recognition_config.auto_min_gap_secs,
+                recognition_config.capture_secs,
+            ),
+            auto_id_in_flight: false,
+            discover_config,
             download_queue: std::collections::VecDeque::new(),
             download_in_flight: false,
             intent_pause: crate::intent::IntentState::new(false),
@@ -540,7 +690,8 @@ impl App {
 
     pub async fn run(
         mut self,
-        mut broadcast_rx: broadcast::Receiver<BroadcastMessage>,
+        broadcast_rx: broadcast::Receiver<BroadcastMessage>,
+        remote_rx: mpsc::Receiver<RemoteRequest>,
     ) -> anyhow::Result<()> {
         debug!("run(): enabling raw mode");
         enable_raw_mode()?;
@@ -552,6 +703,48 @@ impl App {
         let mut terminal = Terminal::new(backend)?;
         debug!("run(): terminal created, size={:?}", terminal.size());
 
+        let result = self
+            .event_loop(
+                Some(&mut terminal),
+                broadcast_rx,
+                remote_rx,
+                std::future::pending(),
+            )
+            .await;
+
+        // ── Teardown ──────────────────────────────────────────────────────────
+        disable_raw_mode()?;
+        execute!(
+            terminal.backend_mut(),
+            LeaveAlternateScreen,
+            DisableMouseCapture
+        )?;
+        terminal.show_cursor()?;
+
+        result
+    }
+
+    /// The same event loop without a terminal: no input, no drawing.  Polling,
+    /// NTS schedules, recognition and remote requests all keep running.  Used
+    /// by `r4dio --headless`; returns once `shutdown` completes and the loop
+    /// has saved the session state.
+    pub async fn run_headless(
+        mut self,
+        broadcast_rx: broadcast::Receiver<BroadcastMessage>,
+        remote_rx: mpsc::Receiver<RemoteRequest>,
+        shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+    ) -> anyhow::Result<()> {
+        self.event_loop(None, broadcast_rx, remote_rx, shutdown)
+            .await
+    }
+
+    async fn event_loop(
+        &mut self,
+        mut terminal: Option<&mut Terminal<CrosstermBackend<io::Stdout>>>,
+        mut broadcast_rx: broadcast::Receiver<BroadcastMessage>,
+        mut remote_rx: mpsc::Receiver<RemoteRequest>,
+        shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+    ) -> anyhow::Result<()> {
         let (tx, mut rx) = mpsc::channel::<AppMessage>(1024);
         self.recognition_tx = Some(tx.clone());
 
@@ -560,17 +753,19 @@ impl App {
         self.push_log("r4dio started".to_string());
 
         // ── Background task: keyboard/mouse events ────────────────────────────
-        let event_tx = tx.clone();
-        tokio::task::spawn_blocking(move || loop {
-            match event::read() {
-                Ok(ev) => {
-                    if event_tx.blocking_send(AppMessage::Event(ev)).is_err() {
-                        break;
+        if terminal.is_some() {
+            let event_tx = tx.clone();
+            tokio::task::spawn_blocking(move || loop {
+                match event::read() {
+                    Ok(ev) => {
+                        if event_tx.blocking_send(AppMessage::Event(ev)).is_err() {
+                            break;
+                        }
                     }
+                    Err(_) => break,
                 }
-                Err(_) => break,
-            }
-        });
+            });
+        }
 
         // ── Background task: broadcast receiver (DaemonCore → AppMessage) ──────
         let bc_tx = tx.clone();
@@ -588,6 +783,7 @@ impl App {
                             BroadcastMessage::Log(s) => AppMessage::Log(s),
                             BroadcastMessage::AudioLevel(rms) => AppMessage::AudioLevel(rms),
                             BroadcastMessage::PcmChunk(chunk) => AppMessage::PcmChunk(chunk),
+                            BroadcastMessage::FileEnded(path) => AppMessage::FileEnded(path),
                         };
                         if bc_tx.send(app_msg).await.is_err() {
                             break;
@@ -604,6 +800,23 @@ impl App {
             }
         });
 
+        // ── Background task: remote requests (HTTP API → AppMessage) ───────────
+        let remote_tx = tx.clone();
+        tokio::spawn(async move {
+            while let Some(req) = remote_rx.recv().await {
+                if remote_tx.send(AppMessage::Remote(req)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        // ── Background task: shutdown signal → Quit ───────────────────────────
+        let quit_tx = tx.clone();
+        tokio::spawn(async move {
+            shutdown.await;
+            let _ = quit_tx.send(AppMessage::Quit).await;
+        });
+
         // ── Periodic timers ───────────────────────────────────────────────────
         let mut files_refresh = tokio::time::interval(Duration::from_secs(5));
         files_refresh.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
@@ -644,10 +857,19 @@ impl App {
             info!("[poll] auto polling disabled at startup");
         }
 
+        if let Some(library) = self.fingerprint_library.clone() {
+            tokio::spawn(crate::fingerprint_index::index_dir(
+                library,
+                self.state.downloads_dir.clone(),
+            ));
+        }
+
         loop {
             // Draw only when something changed (PCM accumulation doesn't need a redraw)
-            if needs_redraw {
-                terminal.draw(|f| self.draw(f))?;
+            if let Some(terminal) = terminal.as_deref_mut() {
+                if needs_redraw {
+                    terminal.draw(|f| self.draw(f))?;
+                }
             }
             needs_redraw = false;
 
@@ -691,6 +913,7 @@ impl App {
                 _ = files_refresh.tick() => {
                     let new_files = load_local_files(&self.state.downloads_dir);
                     self.state.files = new_files;
+                    self.state.playlists = queue::list_playlists(&self.playlists_dir);
                     // Background-index a few files per tick
                     self.index_file_metadata_chunk(8);
                     // Re-sync the file list component.
@@ -764,7 +987,7 @@ impl App {
                     }
                 }
 
-                _ = meter_tick.tick() => {
+                _ = meter_tick.tick(), if terminal.is_some() => {
                     needs_redraw = self.handle_message(AppMessage::MeterTick).await;
                 }
             }
@@ -774,16 +997,7 @@ impl App {
             }
         }
 
-        // ── Teardown ──────────────────────────────────────────────────────────
         self.save_ui_session_state();
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        terminal.show_cursor()?;
-
         Ok(())
     }
 
@@ -833,6 +1047,10 @@ impl App {
         let alpha_spread = (1.0 - (-elapsed / 8.0_f32).exp()).min(0.08);
         self.state.meter_spread_db += alpha_spread * (deviation - self.state.meter_spread_db);
         self.state.meter_spread_db = self.state.meter_spread_db.max(2.0);
+
+        if self.state.auto_identify {
+            self.auto_id.push_level(rms_db, now);
+        }
     }
 
     // ── Message handler ───────────────────────────────────────────────────────
@@ -1063,56 +1281,16 @@ impl App {
                     result.job_id, result.station
                 );
                 // Add placeholder row to in-memory history immediately
-                self.state.songs_history.push(result.clone());
-                if self.state.songs_history.len() > 500 {
-                    self.state.songs_history.remove(0);
-                }
-                // Write initial row to VDS file
-                let vds_path = self.songs_vds_path.clone();
-                tokio::spawn(async move {
-                    if let Err(e) = append_to_vds(&vds_path, &result).await {
-                        warn!("[vds] Initial write error: {}", e);
-                    }
-                });
+                self.record_song(result);
             }
 
             AppMessage::RecognitionPatch(job_id, patch) => {
                 info!("[app] Recognition patch job_id={}", job_id);
-                // Update in-memory history
-                if let Some(entry) = self
-                    .state
-                    .songs_history
-                    .iter_mut()
-                    .rev()
-                    .find(|e| e.job_id == job_id)
-                {
-                    if let Some(v) = &patch.icy_info {
-                        entry.icy_info = Some(v.clone());
-                    }
-                    if let Some(v) = &patch.nts_show {
-                        entry.nts_show = Some(v.clone());
-                    }
-                    if let Some(v) = &patch.nts_tag {
-                        entry.nts_tag = Some(v.clone());
-                    }
-                    if let Some(v) = &patch.nts_url {
-                        entry.nts_url = Some(v.clone());
-                    }
-                    if let Some(v) = &patch.vibra_rec {
-                        entry.vibra_rec = Some(v.clone());
-                    }
+                match self.songs.patch(&job_id, &patch) {
+                    Ok(true) => self.refresh_songs(),
+                    Ok(false) => warn!("[songs] patch: job_id={} not found", job_id),
+                    Err(e) => warn!("[songs] patch error: {}", e),
                 }
-                // Patch VDS file on disk
-                let vds_path = self.songs_vds_path.clone();
-                let job_id_owned = job_id.clone();
-                tokio::spawn(async move {
-                    if let Err(e) =
-                        radio_proto::songs::patch_vds_by_job_id(&vds_path, &job_id_owned, patch)
-                            .await
-                    {
-                        warn!("[vds] Patch error: {}", e);
-                    }
-                });
             }
 
             AppMessage::RecognitionComplete(job_id, rec_display) => {
@@ -1138,7 +1316,7 @@ impl App {
             }
 
             AppMessage::RecognitionNoMatch => {
-                info!("[app] Recognition: no match from vibra");
+                info!("[app] Recognition: no match from any backend");
                 self.recognize_in_flight = false;
                 self.toast.resolve_spinner(
                     crate::widgets::toast::Severity::Warning,
@@ -1157,7 +1335,7 @@ impl App {
             }
             AppMessage::RecognitionQueueNext => {
                 // Pop the next queued recognition job and start it.
-                if let Some((station_name, stream_url, icy_title, nts_ch, nts_mixtape_url)) =
+                if let Some((station_name, audio, icy_title, nts_ch, nts_mixtape_url)) =
                     self.recognize_queue.pop_front()
                 {
                     info!(
@@ -1167,7 +1345,7 @@ impl App {
                     self.recognize_in_flight = true;
                     self.spawn_recognition_job(
                         station_name,
-                        stream_url,
+                        audio,
                         icy_title,
                         nts_ch,
                         nts_mixtape_url,
@@ -1175,6 +1353,71 @@ impl App {
                     self.toast.spinner("identifying…");
                 }
             }
+            AppMessage::AutoIdentified(station_name, icy_title, result) => {
+                self.auto_id_in_flight = false;
+                let Some((source, track)) = result else {
+                    info!("[auto-id] no match");
+                    return false;
+                };
+                // Only a different track than the station's last identified
+                // row is news; the rest would just repeat it in the songs database.
+                let last = self
+                    .state
+                    .songs_history
+                    .iter()
+                    .rev()
+                    .filter(|e| e.station == station_name)
+                    .find_map(|e| e.vibra_rec.as_deref());
+                if last == Some(track.as_str()) {
+                    info!("[auto-id] still {:?}", track);
+                    return false;
+                }
+                info!("[auto-id] {:?} via {}", track, source.label());
+                let now = chrono::Local::now();
+                self.record_song(RecognitionResult {
+                    job_id: make_job_id(&now, station_name.as_deref()),
+                    timestamp: Some(now),
+                    station: station_name,
+                    icy_info: icy_title,
+                    vibra_rec: Some(track.clone()),
+                    rec_source: Some(source.label().to_string()),
+                    ..Default::default()
+                });
+                self.toast.info(format!("auto-ID: {}", track));
+            }
+            AppMessage::DiscoverResults(query, result) => {
+                // A newer search supersedes this one.
+                if query != self.state.discover.query {
+                    return false;
+                }
+                self.state.discover.searching = false;
+                match result {
+                    Ok(stations) => {
+                        info!("[discover] {:?}: {} stations", query, stations.len());
+                        self.state.discover.stations = stations;
+                    }
+                    Err(e) => {
+                        warn!("[discover] {:?}: {}", query, e);
+                        self.state.discover.stations.clear();
+                        self.state.discover.error = Some(e);
+                    }
+                }
+            }
+
+            AppMessage::StationChecks(checks) => {
+                self.state.checking_stations = false;
+                let dead = checks.iter().filter(|c| c.is_dead()).count();
+                info!("[check] {} stations, {} dead", checks.len(), dead);
+                let summary = format!("{} of {} stations dead", dead, checks.len());
+                if dead == 0 {
+                    self.toast.success(summary);
+                } else {
+                    self.toast.warning(summary);
+                }
+                self.state.station_checks =
+                    checks.into_iter().map(|c| (c.name.clone(), c)).collect();
+            }
+
             AppMessage::AudioLevel(rms_db) => {
                 // Keep mpv-lavfi RMS for debug bulbs on all sources.
                 self.state.mpv_audio_level = rms_db;
@@ -1234,6 +1477,7 @@ impl App {
                             if let Some(s) = self.state.pcm_pending.pop_front() {
                                 hold = s;
                                 consumed += 1;
+                                self.state.loudness.push(s);
                                 let sf = s as f64;
                                 sum_sq += sf * sf;
                                 self.state.pcm_ring.push_back(s);
@@ -1263,6 +1507,19 @@ impl App {
                             self.update_audio_trackers(rms_db);
                         }
                     }
+                    if self.loudness_config.normalize
+                        && self.loudness_learned_at.elapsed() >= LOUDNESS_LEARN_INTERVAL
+                    {
+                        self.loudness_learned_at = std::time::Instant::now();
+                        let had_gain = self.state.loudness_gain_db.is_some();
+                        if let Some(learned) = self.learn_loudness() {
+                            // A station heard for the first time gets its gain
+                            // once there's a measurement; later ones at start.
+                            if !had_gain {
+                                self.apply_loudness_gain(Some(learned)).await;
+                            }
+                        }
+                    }
                 } else {
                     self.state.pcm_pending.clear();
                     self.state.pcm_pending_started = false;
@@ -1305,12 +1562,20 @@ impl App {
                 }
 
                 self.state.peak_last_update = now;
+                if self.state.auto_identify {
+                    self.maybe_auto_identify(now);
+                }
             }
 
             AppMessage::DownloadComplete { url, result } => {
                 self.download_in_flight = false;
                 match result {
-                    Ok(_) => {
+                    Ok(path) => {
+                        if let Some(library) = self.fingerprint_library.clone() {
+                            tokio::spawn(async move {
+                                crate::fingerprint_index::index_file(&library, &path).await;
+                            });
+                        }
                         self.state
                             .download_statuses
                             .insert(url, DownloadStatus::Downloaded);
@@ -1336,16 +1601,144 @@ impl App {
                     self.start_download(next_url, next_display);
                 }
             }
+
+            AppMessage::Remote(req) => {
+                self.handle_remote(req).await;
+            }
+
+            AppMessage::Quit => {
+                self.should_quit = true;
+            }
+
+            AppMessage::FileEnded(path) => {
+                // Played through: start from the top next time.
+                self.state.file_positions.remove(&path);
+                if self.last_file_path.as_deref() == Some(path.as_str()) {
+                    self.last_file_pos = 0.0;
+                }
+                let _ = save_file_positions(&self.file_positions_path, &self.state.file_positions);
+                if !self.state.queue.is_empty() {
+                    self.play_next_queued().await;
+                }
+            }
         }
         true
     }
 
+    // ── Remote requests ───────────────────────────────────────────────────────
+
+    async fn handle_remote(&mut self, req: RemoteRequest) {
+        match req {
+            RemoteRequest::Stars(reply) => {
+                let _ = reply.send(Ok(StarsSnapshot {
+                    stations: self.state.station_stars.clone(),
+                    files: self.state.file_stars.clone(),
+                }));
+            }
+            RemoteRequest::SetStationStars { name, stars, reply } => {
+                let result = if stars > MAX_STARS {
+                    Err(format!("stars must be 0–{}", MAX_STARS))
+                } else if let Some(st) = self
+                    .state
+                    .daemon_state
+                    .stations
+                    .iter()
+                    .find(|s| s.name.eq_ignore_ascii_case(&name))
+                {
+                    let name = st.name.clone();
+                    self.dispatch(Action::SetStar(stars, StarContext::Station(name)))
+                        .await;
+                    Ok(())
+                } else {
+                    Err(format!("no station named {:?}", name))
+                };
+                let _ = reply.send(result);
+            }
+            RemoteRequest::SetFileStars { path, stars, reply } => {
+                let result = if stars > MAX_STARS {
+                    Err(format!("stars must be 0–{}", MAX_STARS))
+                } else if !std::path::Path::new(&path).is_file() {
+                    Err(format!("no such file: {}", path))
+                } else {
+                    self.dispatch(Action::SetStar(stars, StarContext::File(path)))
+                        .await;
+                    Ok(())
+                };
+                let _ = reply.send(result);
+            }
+            RemoteRequest::Recognize(reply) => {
+                let state = &self.state.daemon_state;
+                let result = if state.current_station.is_none()
+                    && state.current_file.is_none()
+                    && state.icy_title.is_none()
+                {
+                    Err("nothing playing — can't identify".to_string())
+                } else {
+                    self.dispatch(Action::RecognizeSong).await;
+                    Ok(())
+                };
+                let _ = reply.send(result);
+            }
+            RemoteRequest::Songs {
+                limit,
+                query,
+                reply,
+            } => {
+                let songs = self.songs.search(query.as_deref().unwrap_or(""), limit);
+                let _ = reply.send(Ok(songs));
+            }
+            RemoteRequest::ExportSongs {
+                filter,
+                format,
+                reply,
+            } => {
+                let _ = reply.send(
+                    songs_export::export(
+                        self.songs.rows(),
+                        &filter,
+                        format,
+                        &self.state.daemon_state.stations,
+                    )
+                    .map_err(|e| e.to_string()),
+                );
+            }
+            RemoteRequest::Nts(reply) => {
+                let _ = reply.send(Ok(vec![
+                    NtsChannelInfo::new(
+                        0,
+                        self.state.nts_ch1.as_ref(),
+                        self.state.nts_ch1_error.as_ref(),
+                    ),
+                    NtsChannelInfo::new(
+                        1,
+                        self.state.nts_ch2.as_ref(),
+                        self.state.nts_ch2_error.as_ref(),
+                    ),
+                ]));
+            }
+            RemoteRequest::PollTitles(reply) => {
+                let _ = reply.send(Ok(self.state.station_poll_titles.clone()));
+            }
+            RemoteRequest::Downloads(reply) => {
+                let downloads = self
+                    .state
+                    .download_statuses
+                    .iter()
+                    .map(|(url, status)| DownloadInfo::new(url, status))
+                    .collect();
+                let _ = reply.send(Ok(downloads));
+            }
+        }
+    }
+
     // ── Daemon state update ───────────────────────────────────────────────────
 
     async fn on_state_updated(&mut self, new_state: DaemonState) {
         let was_empty = self.state.daemon_state.stations.is_empty();
         let prev_station = self.state.daemon_state.current_station;
         let prev_file = self.state.daemon_state.current_file.clone();
+        let prev_recordings = std::mem::take(&mut self.state.daemon_state.recordings);
+        let prev_alarm = self.state.daemon_state.alarm.take();
 
         // Preserve NTS city overrides
         let nts1_city = self
@@ -1400,6 +1793,7 @@ impl App {
             self.state.pcm_ring.clear();
             self.state.pcm_pending.clear();
             self.state.pcm_pending_started = false;
+            self.start_loudness_session().await;
         }
 
         // Clear last_known_icy when the station changes — the new station's
@@ -1433,6 +1827,40 @@ impl App {
             self.prev_mpv_health = new_health;
         }
 
+        // ── Recording transition toasts ───────────────────────────────────────
+        for rec in &self.state.daemon_state.recordings {
+            if !prev_recordings
+                .iter()
+                .any(|p| p.station_idx == rec.station_idx)
+            {
+                self.toast
+                    .success(format!("● recording {}", rec.station_name));
+            }
+        }
+        for rec in &prev_recordings {
+            if !self
+                .state
+                .daemon_state
+                .recordings
+                .iter()
+                .any(|r| r.station_idx == rec.station_idx)
+            {
+                self.toast
+                    .info(format!("recording stopped: {}", rec.station_name));
+            }
+        }
+
+        // ── Alarm toasts ──────────────────────────────────────────────────────
+        if let Some(alarm) = &self.state.daemon_state.alarm {
+            if prev_alarm.as_ref() != Some(alarm) {
+                let at = chrono::DateTime::from_timestamp(alarm.at, 0)
+                    .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+                    .unwrap_or_default();
+                self.toast
+                    .success(format!("alarm {} → {}", at, alarm.label));
+            }
+        }
+
         // ── Intent confirmation ───────────────────────────────────────────────
         self.intent_pause
             .on_confirmed(self.state.daemon_state.is_playing);
@@ -1799,9 +2227,15 @@ impl App {
                     self.wm.focus_nth(3);
                     return vec![];
                 }
+                KeyCode::Char('5') => {
+                    self.wm.focus_nth(4);
+                    return vec![];
+                }
                 KeyCode::Char('!') => return vec![Action::ToggleNts(0)],
                 KeyCode::Char('@') => return vec![Action::ToggleNts(1)],
                 KeyCode::Char('o') => return vec![Action::ToggleScope],
+                KeyCode::Char('b') => return vec![Action::ToggleDiscover],
+                KeyCode::Char('H') => return vec![Action::ToggleDiagnostics],
                 KeyCode::Char('v') => return vec![Action::CycleVuMeterStyle],
                 KeyCode::Char('_') | KeyCode::Char('|') => return vec![Action::ToggleFullWidth],
                 KeyCode::Char('K') => {
@@ -1811,7 +2245,16 @@ impl App {
                 KeyCode::Char('J') => return vec![Action::JumpToCurrent],
                 KeyCode::Char('c') => return vec![Action::ToggleCollapse],
                 // Song recognition — global, works from any pane
-                KeyCode::Char('i') | KeyCode::Char('I') => return vec![Action::RecognizeSong],
+                KeyCode::Char('i') => return vec![Action::RecognizeSong],
+                KeyCode::Char('I') => return vec![Action::ToggleAutoIdentify],
+                // The NTS pane uses w to mark upcoming shows instead.
+                KeyCode::Char('w') if self.wm.focused() != Some(ComponentId::NtsPanel) => {
+                    return vec![Action::ToggleRecording]
+                }
+                KeyCode::Char('W') => return vec![Action::CancelRecording],
+                KeyCode::Char('e') => return vec![Action::JumpToLive],
+                KeyCode::Char('z') => return vec![Action::CycleSleepTimer],
+                KeyCode::Char('Z') => return vec![Action::ToggleAlarm],
                 _ => {}
             }
         }
@@ -1832,6 +2275,9 @@ impl App {
                 }
             }
             Some(ComponentId::FileMeta) => self.file_meta.handle_key(key, s),
+            Some(ComponentId::QueuePanel) => self.queue_panel.handle_key(key, s),
+            Some(ComponentId::DiscoverPanel) => self.discover_panel.handle_key(key, s),
+            Some(ComponentId::DiagnosticsPanel) => self.diagnostics_panel.handle_key(key, s),
             Some(ComponentId::LogPanel) => self.log_panel.handle_key(key, s),
             Some(ComponentId::HelpOverlay) => self.help_overlay.handle_key(key, s),
             Some(ComponentId::ScopePanel) => {
@@ -1937,6 +2383,23 @@ impl App {
         if hit(areas.file_meta, col, row) {
             click_pane!(ComponentId::FileMeta, self.file_meta, areas.file_meta);
         }
+        if hit(areas.queue_panel, col, row) {
+            click_pane!(ComponentId::QueuePanel, self.queue_panel, areas.queue_panel);
+        }
+        if hit(areas.discover_panel, col, row) {
+            click_pane!(
+                ComponentId::DiscoverPanel,
+                self.discover_panel,
+                areas.discover_panel
+            );
+        }
+        if hit(areas.diagnostics_panel, col, row) {
+            click_pane!(
+                ComponentId::DiagnosticsPanel,
+                self.diagnostics_panel,
+                areas.diagnostics_panel
+            );
+        }
         if hit(areas.log_panel, col, row) {
             click_pane!(ComponentId::LogPanel, self.log_panel, areas.log_panel);
         }
@@ -1958,6 +2421,9 @@ impl App {
             out.extend(self.nts_panel_ch1.on_action(&action, s));
             out.extend(self.nts_panel_ch2.on_action(&action, s));
             out.extend(self.file_meta.on_action(&action, s));
+            out.extend(self.queue_panel.on_action(&action, s));
+            out.extend(self.discover_panel.on_action(&action, s));
+            out.extend(self.diagnostics_panel.on_action(&action, s));
             out.extend(self.log_panel.on_action(&action, s));
             out.extend(self.help_overlay.on_action(&action, s));
             out
@@ -2019,9 +2485,14 @@ impl App {
                 self.send_cmd(Command::TogglePause).await;
             }
             Action::Next => {
-                self.jump_from_station = Some(self.state.daemon_state.current_station);
-                self.intent_station.set_intent(None); // unknown target
-                self.send_cmd(Command::Next).await;
+                // In Files, n works through the play queue first.
+                if self.wm.workspace == Workspace::Files && !self.state.queue.is_empty() {
+                    self.play_next_queued().await;
+                } else {
+                    self.jump_from_station = Some(self.state.daemon_state.current_station);
+                    self.intent_station.set_intent(None); // unknown target
+                    self.send_cmd(Command::Next).await;
+                }
             }
             Action::Prev => {
                 self.jump_from_station = Some(self.state.daemon_state.current_station);
@@ -2058,6 +2529,9 @@ impl App {
             Action::SeekTo(pos) => {
                 self.send_cmd(Command::SeekTo { seconds: pos }).await;
             }
+            Action::JumpToLive => {
+                self.send_cmd(Command::JumpToLive).await;
+            }
             Action::Mute => {
                 let current = self.state.daemon_state.volume;
                 let new_vol = if current < 0.01 {
@@ -2068,6 +2542,128 @@ impl App {
                 self.send_cmd(Command::Volume { value: new_vol }).await;
             }
 
+            // ── Queue / playlists ─────────────────────────────────────────────
+            Action::Enqueue(item) => {
+                self.toast.info(format!("queued {}", item.label()));
+                self.state.queue.push(item);
+                self.save_queue();
+            }
+            Action::QueuePlay(idx) => {
+                if idx < self.state.queue.len() {
+                    let item = self.state.queue.remove(idx);
+                    self.save_queue();
+                    self.play_queue_item(item).await;
+                }
+            }
+            Action::QueueMove(idx, delta) => {
+                queue::move_item(&mut self.state.queue, idx, delta);
+                self.save_queue();
+            }
+            Action::QueueRemove(idx) => {
+                if idx < self.state.queue.len() {
+                    self.state.queue.remove(idx);
+                    self.save_queue();
+                }
+            }
+            Action::QueueClear => {
+                self.state.queue.clear();
+                self.save_queue();
+                self.toast.info("queue cleared");
+            }
+            Action::QueueShuffle => {
+                use rand::seq::SliceRandom;
+                self.state.queue.shuffle(&mut rand::thread_rng());
+                self.save_queue();
+            }
+            Action::SavePlaylist(name) => {
+                let saved = queue::playlist_file_name(&name).and_then(|file_name| {
+                    queue::save_playlist(
+                        &self.playlists_dir,
+                        &file_name,
+                        &self.state.queue,
+                        &self.state.daemon_state.stations,
+                    )
+                    .map(|_| file_name)
+                });
+                match saved {
+                    Ok(file_name) => {
+                        info!("[queue] saved playlist {}", file_name);
+                        self.toast.success(format!("saved playlist {}", file_name));
+                        self.state.playlists = queue::list_playlists(&self.playlists_dir);
+                    }
+                    Err(e) => self.toast.error(format!("can't save playlist: {}", e)),
+                }
+            }
+            Action::ExportSongs(spec) => {
+                let today = chrono::Local::now().date_naive();
+                let exported = songs_export::parse_spec(&spec, today).and_then(
+                    |(file_name, format, filter)| {
+                        let content = songs_export::export(
+                            self.songs.rows(),
+                            &filter,
+                            format,
+                            &self.state.daemon_state.stations,
+                        )?;
+                        std::fs::create_dir_all(&self.exports_dir)?;
+                        let path = self.exports_dir.join(file_name);
+                        std::fs::write(&path, content)?;
+                        Ok(path)
+                    },
+                );
+                match exported {
+                    Ok(path) => {
+                        info!("[songs] exported {}", path.display());
+                        self.toast.success(format!("exported {}", path.display()));
+                    }
+                    Err(e) => self.toast.error(format!("can't export songs: {}", e)),
+                }
+            }
+            Action::SearchSongs(query) => {
+                self.songs_query = query;
+                self.refresh_songs();
+            }
+            Action::CorrectSong(job_id, track) => match self.songs.correct(&job_id, &track) {
+                Ok(_) => {
+                    info!("[songs] corrected {} to {:?}", job_id, track);
+                    self.refresh_songs();
+                }
+                Err(e) => self.toast.error(format!("can't save correction: {}", e)),
+            },
+            Action::DeleteSong(job_id) => match self.songs.delete(&job_id) {
+                Ok(_) => {
+                    info!("[songs] deleted {}", job_id);
+                    self.toast.info("song deleted");
+                    self.refresh_songs();
+                }
+                Err(e) => self.toast.error(format!("can't delete song: {}", e)),
+            },
+            Action::MergeDuplicateSongs => match self.songs.dedupe() {
+                Ok(0) => self.toast.info("no duplicate songs"),
+                Ok(n) => {
+                    info!("[songs] merged {} duplicates", n);
+                    self.toast.success(format!("merged {} duplicate songs", n));
+                    self.refresh_songs();
+                }
+                Err(e) => self.toast.error(format!("can't merge songs: {}", e)),
+            },
+            Action::LoadPlaylist(file_name) => {
+                match queue::load_playlist(
+                    &self.playlists_dir,
+                    &file_name,
+                    &self.state.daemon_state.stations,
+                ) {
+                    Ok(items) => {
+                        self.toast
+                            .info(format!("queued {} from {}", items.len(), file_name));
+                        self.state.queue.extend(items);
+                        self.save_queue();
+                    }
+                    Err(e) => self
+                        .toast
+                        .error(format!("can't load playlist {}: {}", file_name, e)),
+                }
+            }
+
             // ── Navigation ────────────────────────────────────────────────────
             Action::FocusNext => {
                 self.wm.focus_next();
@@ -2122,35 +2718,174 @@ impl App {
                 self.wm.toggle_scope();
             }
 
-            // ── VU Meter ───────────────────────────────────────────────────────
-            Action::CycleVuMeterStyle => {
-                let new_style = self.header.cycle_meter_style();
-                let style_name = match new_style {
-                    crate::components::vu_meter::MeterStyle::Studio => "Studio",
-                    crate::components::vu_meter::MeterStyle::Led => "LED",
-                    crate::components::vu_meter::MeterStyle::Analog => "Analog",
+            // ── Station discovery ─────────────────────────────────────────────
+            Action::ToggleDiscover => {
+                self.wm.toggle_discover();
+                if self.wm.radio_right_pane == RightPane::Discover {
+                    self.wm.focus_set(ComponentId::DiscoverPanel);
+                }
+            }
+            Action::ToggleDiagnostics => {
+                self.wm.toggle_diagnostics();
+                if self.wm.radio_right_pane == RightPane::Diagnostics {
+                    self.wm.focus_set(ComponentId::DiagnosticsPanel);
+                }
+            }
+            Action::DiscoverSearch(query) => {
+                let Some(tx) = self.recognition_tx.clone() else {
+                    return;
                 };
-                self.toast.info(format!("VU meter: {}", style_name));
+                self.state.discover.query = query.clone();
+                self.state.discover.searching = true;
+                self.state.discover.error = None;
+                let api_url = self.discover_config.api_url.clone();
+                let limit = self.discover_config.limit;
+                tokio::spawn(async move {
+                    let result =
+                        radio_browser::search(&api_url, &DirectoryQuery::parse(&query), limit)
+                            .await
+                            .map_err(|e| e.to_string());
+                    let _ = tx.send(AppMessage::DiscoverResults(query, result)).await;
+                });
+            }
+            Action::PreviewStation(station) => {
+                self.toast.info(format!("previewing {}", station.name));
+                self.send_cmd(Command::PreviewStation { station }).await;
+            }
+            Action::AddStation(station) => {
+                let stations = &self.state.daemon_state.stations;
+                if stations.iter().any(|s| s.url == station.url) {
+                    self.toast
+                        .warning(format!("{} is already in the list", station.name));
+                } else {
+                    self.toast
+                        .success(format!("added {} to stations.toml", station.name));
+                    self.send_cmd(Command::AddStation { station }).await;
+                }
             }
 
-            // ── Stars ─────────────────────────────────────────────────────────
-            Action::SetStar(n, ctx) => match ctx {
-                StarContext::Station(name) => {
-                    if n == 0 {
-                        self.state.station_stars.remove(&name);
-                    } else {
-                        self.state.station_stars.insert(name.clone(), n);
-                    }
-                    let _ = save_stars(
-                        &self.stars_path,
-                        &self.state.station_stars,
-                        &self.state.file_stars,
-                    );
-                    if n == 0 {
-                        self.toast.info(format!("unstarred {}", name));
-                    } else {
-                        self.toast
-                            .success(format!("{} {}", "✹".repeat(n as usize), name));
+            // ── Station editing ───────────────────────────────────────────────
+            Action::UpdateStation(idx, station) => {
+                let Some(old) = self.state.daemon_state.stations.get(idx).cloned() else {
+                    return;
+                };
+                if old.name != station.name {
+                    // Stars and recency are keyed by name; carry them over.
+                    if let Some(n) = self.state.station_stars.remove(&old.name) {
+                        self.state.station_stars.insert(station.name.clone(), n);
+                        let _ = save_stars(
+                            &self.stars_path,
+                            &self.state.station_stars,
+                            &self.state.file_stars,
+                        );
+                    }
+                    if let Some(ts) = self.state.recent_station.remove(&old.name) {
+                        self.state.recent_station.insert(station.name.clone(), ts);
+                        let _ = save_recent_state(
+                            &self.recent_path,
+                            &RecentState {
+                                recent_station: self.state.recent_station.clone(),
+                                recent_file: self.state.recent_file.clone(),
+                            },
+                        );
+                    }
+                }
+                self.toast.success(format!("saved {}", station.name));
+                self.send_cmd(Command::UpdateStation {
+                    station_idx: idx,
+                    station,
+                })
+                .await;
+            }
+            Action::DeleteStation(idx) => {
+                let Some(name) = self.station_name(idx) else {
+                    return;
+                };
+                if let Some(blocker) = self.recording_blocking(ListChange::Removed(idx)) {
+                    self.toast.error(format!(
+                        "stop the recording of {} before deleting a station",
+                        blocker
+                    ));
+                    return;
+                }
+                let now = std::time::Instant::now();
+                let confirmed = self.pending_station_delete.take().is_some_and(|(i, at)| {
+                    i == idx && now.duration_since(at) < Duration::from_secs(3)
+                });
+                if confirmed {
+                    self.toast.success(format!("deleted {}", name));
+                    self.send_cmd(Command::DeleteStation { station_idx: idx })
+                        .await;
+                } else {
+                    self.pending_station_delete = Some((idx, now));
+                    self.toast
+                        .warning(format!("press D again to delete {}", name));
+                }
+            }
+            Action::CheckStations => {
+                let Some(tx) = self.recognition_tx.clone() else {
+                    return;
+                };
+                if self.state.checking_stations {
+                    self.toast.info("a station check is already running");
+                    return;
+                }
+                let stations = self.state.daemon_state.stations.clone();
+                self.state.checking_stations = true;
+                self.toast
+                    .info(format!("checking {} stations…", stations.len()));
+                let options = station_check::CheckOptions::default();
+                tokio::spawn(async move {
+                    let checks =
+                        station_check::check_all(&stations, options.jobs, options.timeout).await;
+                    let _ = tx.send(AppMessage::StationChecks(checks)).await;
+                });
+            }
+            Action::DuplicateStation(idx) => {
+                let Some(name) = self.station_name(idx) else {
+                    return;
+                };
+                if let Some(blocker) = self.recording_blocking(ListChange::Inserted(idx + 1)) {
+                    self.toast.error(format!(
+                        "stop the recording of {} before duplicating a station",
+                        blocker
+                    ));
+                    return;
+                }
+                self.toast.info(format!("duplicated {}", name));
+                self.send_cmd(Command::DuplicateStation { station_idx: idx })
+                    .await;
+            }
+
+            // ── VU Meter ───────────────────────────────────────────────────────
+            Action::CycleVuMeterStyle => {
+                let new_style = self.header.cycle_meter_style();
+                let style_name = match new_style {
+                    crate::components::vu_meter::MeterStyle::Studio => "Studio",
+                    crate::components::vu_meter::MeterStyle::Led => "LED",
+                    crate::components::vu_meter::MeterStyle::Analog => "Analog",
+                };
+                self.toast.info(format!("VU meter: {}", style_name));
+            }
+
+            // ── Stars ─────────────────────────────────────────────────────────
+            Action::SetStar(n, ctx) => match ctx {
+                StarContext::Station(name) => {
+                    if n == 0 {
+                        self.state.station_stars.remove(&name);
+                    } else {
+                        self.state.station_stars.insert(name.clone(), n);
+                    }
+                    let _ = save_stars(
+                        &self.stars_path,
+                        &self.state.station_stars,
+                        &self.state.file_stars,
+                    );
+                    if n == 0 {
+                        self.toast.info(format!("unstarred {}", name));
+                    } else {
+                        self.toast
+                            .success(format!("{} {}", "✹".repeat(n as usize), name));
                     }
                 }
                 StarContext::File(path) => {
@@ -2184,79 +2919,8 @@ impl App {
             Action::RecognizeSong => {
                 info!("[app] RecognizeSong action triggered");
 
-                let station = self
-                    .state
-                    .daemon_state
-                    .current_station
-                    .and_then(|i| self.state.daemon_state.stations.get(i))
-                    .cloned();
-                let station_name = station.as_ref().map(|s| s.name.clone());
-                let stream_url = station.as_ref().map(|s| s.url.clone());
-
-                // Best-effort ICY resolution — three tiers, in order of freshness:
-                //
-                // 1. daemon_state.icy_title  — live value from the latest StateUpdated
-                //    or IcyUpdated message.  Most up-to-date but can be None if the
-                //    ICY hasn't arrived yet (fresh daemon start, station just switched).
-                //
-                // 2. last_known_icy           — sticky field updated by IcyUpdated only,
-                //    never overwritten by StateUpdated.  Survives transient None states
-                //    as long as the station hasn't changed.
-                //
-                // 3. icy_history (session)    — most recent entry tagged to this station
-                //    recorded during this session (has station tag, unlike log-loaded
-                //    entries).  Covers the case where daemon dedup prevented a
-                //    re-broadcast but the title is in recent history.
-                let icy_title = self
-                    .state
-                    .daemon_state
-                    .icy_title
-                    .clone()
-                    .or_else(|| {
-                        // Tier 2: last_known_icy for the current station
-                        let name = station_name.as_deref()?;
-                        self.last_known_icy
-                            .as_ref()
-                            .filter(|(st, _)| st.as_str() == name)
-                            .map(|(_, t)| t.clone())
-                    })
-                    .or_else(|| {
-                        // Tier 3: most recent icy_history entry for this station
-                        let name = station_name.as_deref()?;
-                        self.state
-                            .icy_history
-                            .iter()
-                            .rev()
-                            .find(|e| e.station.as_deref() == Some(name))
-                            .map(|e| e.raw.clone())
-                    });
-
-                info!(
-                    "[app] Recognition context: station={:?}, icy={:?}",
-                    station_name, icy_title
-                );
-
-                let nts_ch = station_name.as_deref().and_then(|n| {
-                    if n.eq_ignore_ascii_case("nts 1") {
-                        Some(0)
-                    } else if n.eq_ignore_ascii_case("nts 2") {
-                        Some(1)
-                    } else {
-                        None
-                    }
-                });
-
-                let nts_mixtape_url = station.as_ref().and_then(|s| {
-                    if !s.name.starts_with("NTS:") {
-                        return None;
-                    }
-                    let u = s.mixtape_url.trim();
-                    if u.is_empty() {
-                        None
-                    } else {
-                        Some(u.to_string())
-                    }
-                });
+                let (station_name, audio, icy_title, nts_ch, nts_mixtape_url) =
+                    self.recognition_request();
 
                 if station_name.is_none() && icy_title.is_none() {
                     warn!("[app] Cannot start recognition: nothing playing");
@@ -2273,7 +2937,7 @@ impl App {
                         if self.recognize_queue.len() < 3 {
                             self.recognize_queue.push_back((
                                 station_name,
-                                stream_url,
+                                audio,
                                 icy_title,
                                 nts_ch,
                                 nts_mixtape_url,
@@ -2291,7 +2955,7 @@ impl App {
                         self.recognize_active_station = station_name.clone();
                         self.spawn_recognition_job(
                             station_name,
-                            stream_url,
+                            audio,
                             icy_title,
                             nts_ch,
                             nts_mixtape_url,
@@ -2301,6 +2965,120 @@ impl App {
                 }
             }
 
+            // ── Recording ─────────────────────────────────────────────────────
+            Action::ToggleRecording => {
+                let Some(idx) = self.state.daemon_state.current_station else {
+                    self.toast.warning("no station playing — nothing to record");
+                    return;
+                };
+                if self.state.recording_for(idx).is_some() {
+                    self.send_cmd(Command::StopRecording { station_idx: idx })
+                        .await;
+                } else {
+                    self.send_cmd(Command::StartRecording { station_idx: idx })
+                        .await;
+                }
+            }
+            Action::CancelRecording => {
+                // Prefer the current station's recording, else the newest one.
+                let target = self
+                    .state
+                    .daemon_state
+                    .current_station
+                    .and_then(|idx| self.state.recording_for(idx))
+                    .or_else(|| {
+                        self.state
+                            .daemon_state
+                            .recordings
+                            .iter()
+                            .max_by_key(|r| r.started_at)
+                    })
+                    .map(|r| r.station_idx);
+                match target {
+                    Some(idx) => {
+                        self.send_cmd(Command::CancelRecording { station_idx: idx })
+                            .await;
+                    }
+                    None => self.toast.info("no recording to cancel"),
+                }
+            }
+            Action::ToggleShowRecording(channel, i) => {
+                let nts = if channel == 0 {
+                    &self.state.nts_ch1
+                } else {
+                    &self.state.nts_ch2
+                };
+                let Some(show) = nts.as_ref().and_then(|ch| ch.upcoming.get(i)) else {
+                    return;
+                };
+                let show = scheduled_show(channel, show);
+                let already = self
+                    .state
+                    .daemon_state
+                    .scheduled
+                    .iter()
+                    .any(|s| s.is_same_slot(channel, show.start));
+                if already {
+                    self.toast.info(format!("won't record {}", show.title));
+                    self.send_cmd(Command::UnscheduleRecording {
+                        channel,
+                        start: show.start,
+                    })
+                    .await;
+                } else {
+                    let at = chrono::DateTime::from_timestamp(show.start, 0)
+                        .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+                        .unwrap_or_default();
+                    self.toast
+                        .success(format!("● will record {} at {}", show.title, at));
+                    self.send_cmd(Command::ScheduleRecording { show }).await;
+                }
+            }
+
+            // ── Sleep timer / alarm ───────────────────────────────────────────
+            Action::CycleSleepTimer => {
+                let remaining_mins = self.state.daemon_state.sleep_timer.as_ref().map(|t| {
+                    ((t.ends_at - chrono::Local::now().timestamp()).max(0) as u64).div_ceil(60)
+                });
+                // Off → first preset; otherwise the next preset above what's left.
+                let next = SLEEP_PRESETS_MINS
+                    .iter()
+                    .copied()
+                    .find(|&m| remaining_mins.is_none_or(|left| m > left));
+                match next {
+                    Some(minutes) => {
+                        self.toast.info(format!("sleep in {} min", minutes));
+                        self.send_cmd(Command::SetSleepTimer { minutes }).await;
+                    }
+                    None => {
+                        self.toast.info("sleep timer off");
+                        self.send_cmd(Command::CancelSleepTimer).await;
+                    }
+                }
+            }
+            Action::ToggleAlarm => {
+                if self.state.daemon_state.alarm.is_some() {
+                    self.toast.info("alarm cancelled");
+                    self.send_cmd(Command::CancelAlarm).await;
+                    return;
+                }
+                let target = match self.wm.workspace {
+                    Workspace::Radio => self
+                        .station_list
+                        .selected_station_idx()
+                        .map(|station_idx| AlarmTarget::Station { station_idx }),
+                    Workspace::Files => self.file_list.selected_path().map(|p| AlarmTarget::File {
+                        path: p.to_string_lossy().to_string(),
+                    }),
+                };
+                let Some(target) = target else {
+                    self.toast.warning("select a station or file to wake up to");
+                    return;
+                };
+                self.send_cmd(Command::SetAlarm { time: None, target })
+                    .await;
+            }
+
             // ── UI toggles ────────────────────────────────────────────────────
             Action::ToggleLogs => {
                 self.wm.show_log_panel = !self.wm.show_log_panel;
@@ -2323,6 +3101,17 @@ impl App {
             Action::ToggleKeys => {
                 self.wm.show_keys_bar = !self.wm.show_keys_bar;
             }
+            Action::ToggleAutoIdentify => {
+                self.state.auto_identify = !self.state.auto_identify;
+                if self.state.auto_identify {
+                    self.auto_id.reset();
+                    info!("[auto-id] enabled");
+                    self.toast.info("auto-ID: on");
+                } else {
+                    info!("[auto-id] disabled");
+                    self.toast.info("auto-ID: off");
+                }
+            }
             Action::ToggleAutoPolling => {
                 self.auto_polling_enabled = !self.auto_polling_enabled;
                 if self.auto_polling_enabled {
@@ -2485,6 +3274,7 @@ impl App {
                 self.wm.workspace,
                 self.state.mpv_audio_level,
                 self.auto_polling_enabled,
+                &self.state.daemon_state,
             );
         }
 
@@ -2519,6 +3309,11 @@ impl App {
     fn draw_radio(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         use ratatui::widgets::Borders;
 
+        // Files-only pane: keep it out of mouse hit-testing.
+        self.pane_areas.queue_panel = Rect::default();
+        self.pane_areas.discover_panel = Rect::default();
+        self.pane_areas.diagnostics_panel = Rect::default();
+
         let right_maximized = self.wm.radio_right_maximized;
         let has_overlay = self.state.nts_hover_channel.is_some()
             && matches!(self.wm.radio_right_pane, RightPane::Tickers);
@@ -2669,6 +3464,56 @@ impl App {
                 }
                 self.pane_areas.nts_panel = right_area;
             }
+            RightPane::Discover => {
+                let discover_collapsed = self.wm.is_collapsed(ComponentId::DiscoverPanel);
+                let discover_focused = self.wm.focused() == Some(ComponentId::DiscoverPanel);
+                if discover_collapsed {
+                    use crate::widgets::pane_chrome::draw_collapsed_pane;
+                    let summary = self.discover_panel.collapse_summary(&self.state);
+                    draw_collapsed_pane(
+                        frame,
+                        right_area,
+                        "discover",
+                        summary.as_deref(),
+                        discover_focused,
+                    );
+                } else {
+                    self.discover_panel.borders = Borders::ALL;
+                    self.discover_panel
+                        .draw(frame, right_area, discover_focused, &self.state);
+                }
+                self.pane_areas.icy_ticker = Rect::default();
+                self.pane_areas.songs_ticker = Rect::default();
+                self.pane_areas.nts_panel = Rect::default();
+                self.pane_areas.discover_panel = right_area;
+            }
+            RightPane::Diagnostics => {
+                let diagnostics_collapsed = self.wm.is_collapsed(ComponentId::DiagnosticsPanel);
+                let diagnostics_focused = self.wm.focused() == Some(ComponentId::DiagnosticsPanel);
+                if diagnostics_collapsed {
+                    use crate::widgets::pane_chrome::draw_collapsed_pane;
+                    let summary = self.diagnostics_panel.collapse_summary(&self.state);
+                    draw_collapsed_pane(
+                        frame,
+                        right_area,
+                        "diagnostics",
+                        summary.as_deref(),
+                        diagnostics_focused,
+                    );
+                } else {
+                    self.diagnostics_panel.borders = Borders::ALL;
+                    self.diagnostics_panel.draw(
+                        frame,
+                        right_area,
+                        diagnostics_focused,
+                        &self.state,
+                    );
+                }
+                self.pane_areas.icy_ticker = Rect::default();
+                self.pane_areas.songs_ticker = Rect::default();
+                self.pane_areas.nts_panel = Rect::default();
+                self.pane_areas.diagnostics_panel = right_area;
+            }
             RightPane::Scope => {
                 // Handled by early-return scope layout above; unreachable here.
                 unreachable!("RightPane::Scope should have returned early")
@@ -2719,9 +3564,13 @@ impl App {
     fn draw_files(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         use ratatui::widgets::Borders;
 
-        // Files focus ring: FileList=1, FileMeta=2, IcyTicker=3, SongsTicker=4
-        self.icy_ticker.number_key = Some('3');
-        self.songs_ticker.number_key = Some('4');
+        // Radio-only panes: keep them out of mouse hit-testing.
+        self.pane_areas.discover_panel = Rect::default();
+        self.pane_areas.diagnostics_panel = Rect::default();
+
+        // Files focus ring: FileList=1, FileMeta=2, QueuePanel=3, IcyTicker=4, SongsTicker=5
+        self.icy_ticker.number_key = Some('4');
+        self.songs_ticker.number_key = Some('5');
 
         let right_maximized = self.wm.files_right_maximized;
 
@@ -2754,10 +3603,12 @@ impl App {
 
         // Right column: determine collapse state for each pane
         let meta_collapsed = self.wm.is_collapsed(ComponentId::FileMeta);
+        let queue_collapsed = self.wm.is_collapsed(ComponentId::QueuePanel);
         let icy_collapsed = self.wm.is_collapsed(ComponentId::IcyTicker);
         let songs_collapsed = self.wm.is_collapsed(ComponentId::SongsTicker);
 
         let meta_focused = self.wm.focused() == Some(ComponentId::FileMeta);
+        let queue_focused = self.wm.focused() == Some(ComponentId::QueuePanel);
         let icy_focused = self.wm.focused() == Some(ComponentId::IcyTicker);
         let songs_focused = self.wm.focused() == Some(ComponentId::SongsTicker);
 
@@ -2767,17 +3618,22 @@ impl App {
                 if meta_collapsed {
                     Constraint::Length(1)
                 } else {
-                    Constraint::Percentage(50)
+                    Constraint::Percentage(35)
                 },
-                if icy_collapsed {
+                if queue_collapsed {
                     Constraint::Length(1)
                 } else {
                     Constraint::Percentage(25)
                 },
+                if icy_collapsed {
+                    Constraint::Length(1)
+                } else {
+                    Constraint::Percentage(20)
+                },
                 if songs_collapsed {
                     Constraint::Length(1)
                 } else {
-                    Constraint::Percentage(25)
+                    Constraint::Percentage(20)
                 },
             ])
             .split(right_area);
@@ -2792,25 +3648,40 @@ impl App {
                 .draw(frame, rows[0], meta_focused, &self.state);
         }
 
+        if queue_collapsed {
+            use crate::widgets::pane_chrome::draw_collapsed_pane;
+            let summary = self.queue_panel.collapse_summary(&self.state);
+            draw_collapsed_pane(frame, rows[1], "queue", summary.as_deref(), queue_focused);
+        } else {
+            // Omit top border if meta is expanded above (shares bottom/top edge)
+            self.queue_panel.borders = if meta_collapsed {
+                Borders::ALL
+            } else {
+                Borders::LEFT | Borders::BOTTOM | Borders::RIGHT
+            };
+            self.queue_panel
+                .draw(frame, rows[1], queue_focused, &self.state);
+        }
+
         if icy_collapsed {
             use crate::widgets::pane_chrome::draw_collapsed_pane;
             let summary = self.icy_ticker.collapse_summary(&self.state);
-            draw_collapsed_pane(frame, rows[1], "icy", summary.as_deref(), icy_focused);
+            draw_collapsed_pane(frame, rows[2], "icy", summary.as_deref(), icy_focused);
         } else {
-            // Omit top border if meta is expanded above (shares bottom/top edge)
-            self.icy_ticker.borders = if meta_collapsed {
+            // Omit top border if the queue is expanded above
+            self.icy_ticker.borders = if queue_collapsed {
                 Borders::ALL
             } else {
                 Borders::LEFT | Borders::BOTTOM | Borders::RIGHT
             };
             self.icy_ticker
-                .draw(frame, rows[1], icy_focused, &self.state);
+                .draw(frame, rows[2], icy_focused, &self.state);
         }
 
         if songs_collapsed {
             use crate::widgets::pane_chrome::draw_collapsed_pane;
             let summary = self.songs_ticker.collapse_summary(&self.state);
-            draw_collapsed_pane(frame, rows[2], "songs", summary.as_deref(), songs_focused);
+            draw_collapsed_pane(frame, rows[3], "songs", summary.as_deref(), songs_focused);
         } else {
             // Omit top border if icy is expanded above
             self.songs_ticker.borders = if icy_collapsed {
@@ -2819,12 +3690,142 @@ impl App {
                 Borders::LEFT | Borders::BOTTOM | Borders::RIGHT
             };
             self.songs_ticker
-                .draw(frame, rows[2], songs_focused, &self.state);
+                .draw(frame, rows[3], songs_focused, &self.state);
         }
 
         self.pane_areas.file_meta = rows[0];
-        self.pane_areas.icy_ticker = rows[1];
-        self.pane_areas.songs_ticker = rows[2];
+        self.pane_areas.queue_panel = rows[1];
+        self.pane_areas.icy_ticker = rows[2];
+        self.pane_areas.songs_ticker = rows[3];
+    }
+
+    // ── Queue ─────────────────────────────────────────────────────────────────
+
+    fn save_queue(&self) {
+        if let Err(e) = queue::save_queue(&self.queue_path, &self.state.queue) {
+            warn!(
+                "[queue] failed to save {}: {}",
+                self.queue_path.display(),
+                e
+            );
+        }
+    }
+
+    /// Take entries off the head of the queue until one plays.
+    async fn play_next_queued(&mut self) {
+        while !self.state.queue.is_empty() {
+            let item = self.state.queue.remove(0);
+            if self.play_queue_item(item).await {
+                break;
+            }
+        }
+        self.save_queue();
+    }
+
+    /// Start a queue entry.  False if it's gone: a deleted file or a station
+    /// no longer in the list.
+    async fn play_queue_item(&mut self, item: QueueItem) -> bool {
+        let label = item.label();
+        match item {
+            QueueItem::File(path) => {
+                if !std::path::Path::new(&path).exists() {
+                    warn!("[queue] {} no longer exists, skipped", path);
+                    self.toast.warning(format!("skipped missing {}", label));
+                    return false;
+                }
+                info!("[queue] playing {}", path);
+                self.last_file_path = Some(path.clone());
+                let pos = self.state.file_position_for(&path);
+                self.send_cmd(Command::PlayFileAt {
+                    path,
+                    start_secs: pos,
+                })
+                .await;
+                true
+            }
+            QueueItem::Station(name) => {
+                let Some(idx) = self
+                    .state
+                    .daemon_state
+                    .stations
+                    .iter()
+                    .position(|s| s.name == name)
+                else {
+                    warn!("[queue] station {:?} not found, skipped", name);
+                    self.toast
+                        .warning(format!("skipped unknown station {}", label));
+                    return false;
+                };
+                info!("[queue] playing station {}", name);
+                self.jump_from_station = Some(self.state.daemon_state.current_station);
+                self.intent_station.set_intent(Some(idx));
+                self.send_cmd(Command::Play { station_idx: idx }).await;
+                true
+            }
+        }
+    }
+
+    // ── Loudness ──────────────────────────────────────────────────────────────
+
+    /// The source changed: learn from the station that was playing, restart
+    /// the meter and set the new station's gain.
+    async fn start_loudness_session(&mut self) {
+        if self.loudness_config.normalize {
+            self.learn_loudness();
+        }
+        self.state.loudness.reset();
+        self.loudness_learned_at = std::time::Instant::now();
+        let ds = &self.state.daemon_state;
+        self.loudness_session = ds
+            .current_station
+            .filter(|_| ds.current_file.is_none())
+            .and_then(|i| ds.stations.get(i))
+            .map(|st| {
+                (
+                    st.name.clone(),
+                    self.learned_loudness.get(&st.name).copied(),
+                )
+            });
+        if self.loudness_config.normalize {
+            let learned = self.loudness_session.as_ref().and_then(|(_, l)| *l);
+            self.apply_loudness_gain(learned).await;
+        }
+    }
+
+    /// Merge the running measurement into the session station's learned
+    /// loudness and save it.  `None` until enough audio has been measured.
+    fn learn_loudness(&mut self) -> Option<LearnedLoudness> {
+        let (name, before) = self.loudness_session.clone()?;
+        let secs = self.state.loudness.measured_secs();
+        if secs < LOUDNESS_MIN_SECS {
+            return None;
+        }
+        let learned = LearnedLoudness::merge(before, self.state.loudness.integrated()?, secs);
+        debug!(
+            "[loudness] {}: {:.1} LUFS over {:.0}s",
+            name, learned.lufs, learned.secs
+        );
+        self.learned_loudness.insert(name, learned);
+        if let Err(e) = loudness::save_learned(&self.loudness_path, &self.learned_loudness) {
+            warn!(
+                "[loudness] failed to save {}: {}",
+                self.loudness_path.display(),
+                e
+            );
+        }
+        Some(learned)
+    }
+
+    /// Ask the core for the gain that brings `learned` to the target level
+    /// (none for files and stations not measured yet).
+    async fn apply_loudness_gain(&mut self, learned: Option<LearnedLoudness>) {
+        let cfg = &self.loudness_config;
+        let gain = learned.map(|l| l.gain_db(cfg.target_lufs, cfg.max_gain_db));
+        self.state.loudness_gain_db = gain;
+        self.send_cmd(Command::SetLoudnessGain {
+            db: gain.unwrap_or(0.0),
+        })
+        .await;
     }
 
     // ── Helpers ───────────────────────────────────────────────────────────────
@@ -2833,6 +3834,24 @@ impl App {
         let _ = self.cmd_tx.send(DaemonEvent::ClientCommand(cmd)).await;
     }
 
+    fn station_name(&self, idx: usize) -> Option<String> {
+        self.state
+            .daemon_state
+            .stations
+            .get(idx)
+            .map(|s| s.name.clone())
+    }
+
+    /// A running recording `change` would renumber; the core refuses those.
+    fn recording_blocking(&self, change: ListChange) -> Option<String> {
+        self.state
+            .daemon_state
+            .recordings
+            .iter()
+            .find(|r| change.moves(r.station_idx))
+            .map(|r| r.station_name.clone())
+    }
+
     fn push_log(&mut self, msg: String) {
         self.state.logs.push(msg);
         if self.state.logs.len() > 500 {
@@ -2929,17 +3948,180 @@ impl App {
     /// Maximum file metadata cache entries to prevent unbounded growth
     const MAX_METADATA_CACHE_SIZE: usize = 1000;
 
+    /// Add a row to the songs database.
+    fn record_song(&mut self, result: RecognitionResult) {
+        if let Err(e) = self.songs.insert(result) {
+            warn!("[songs] write error: {}", e);
+        }
+        self.refresh_songs();
+    }
+
+    /// Re-read the Songs pane's rows (and search results) after a change.
+    fn refresh_songs(&mut self) {
+        self.state.songs_history = self.songs.recent(SONGS_HISTORY_MAX);
+        self.state.song_search = (!self.songs_query.is_empty())
+            .then(|| self.songs.search(&self.songs_query, SONGS_HISTORY_MAX));
+    }
+
+    /// Auto-ID: start a quiet recognition when the schedule says so.  Skipped
+    /// while any recognition runs or nothing is playing; only matches are
+    /// recorded (see `AppMessage::AutoIdentified`).
+    fn maybe_auto_identify(&mut self, now: std::time::Instant) {
+        let ds = &self.state.daemon_state;
+        let source = ds
+            .current_file
+            .clone()
+            .or_else(|| ds.current_station.map(|idx| format!("station:{}", idx)));
+        self.auto_id.set_source(source.as_deref(), now);
+        if source.is_none()
+            || ds.playback_status != PlaybackStatus::Playing
+            || self.recognize_in_flight
+            || self.auto_id_in_flight
+            || !self.auto_id.due(now)
+        {
+            return;
+        }
+        let (station_name, audio, icy_title, _, _) = self.recognition_request();
+        let (Some(audio), Some(tx)) = (audio, self.recognition_tx.clone()) else {
+            return;
+        };
+        self.auto_id_in_flight = true;
+        let recognizers = self.recognizers.clone();
+        let capture_secs = self.recognition_capture_secs;
+        tokio::spawn(async move {
+            debug!("[auto-id] identifying {:?}", audio);
+            let result = match capture_recent(&audio, capture_secs).await {
+                Ok(clip) => recognize_clip(&recognizers, &clip).await,
+                Err(e) => {
+                    warn!("[auto-id] capture failed: {}", e);
+                    None
+                }
+            };
+            let _ = tx
+                .send(AppMessage::AutoIdentified(station_name, icy_title, result))
+                .await;
+        });
+    }
+
+    /// What a recognition would listen to right now, and the context rows
+    /// get: (station_name, audio, icy_title, nts_ch, nts_mixtape_url).
+    fn recognition_request(&self) -> RecognitionRequest {
+        let station = self
+            .state
+            .daemon_state
+            .current_station
+            .and_then(|i| self.state.daemon_state.stations.get(i))
+            .cloned();
+        let file = self.state.daemon_state.current_file.clone();
+        // Files are tagged with their name in the songs history.
+        let station_name = station.as_ref().map(|s| s.name.clone()).or_else(|| {
+            file.as_deref().map(|f| {
+                std::path::Path::new(f)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| f.to_string())
+            })
+        });
+        let time_pos = self.state.daemon_state.time_pos_secs;
+        let audio = match (&file, &station) {
+            (Some(path), _) => time_pos.map(|pos| RecognitionAudio::File {
+                path: PathBuf::from(path),
+                pos,
+            }),
+            (None, Some(s)) => Some(RecognitionAudio::Station {
+                idx: self.state.daemon_state.current_station.unwrap_or_default(),
+                // Stations report their place in the timeshift buffer.
+                behind: match (time_pos, self.state.daemon_state.duration_secs) {
+                    (Some(pos), Some(len)) => (len - pos).max(0.0),
+                    _ => 0.0,
+                },
+                pressed_at: std::time::Instant::now(),
+                upstream: s.url.clone(),
+            }),
+            (None, None) => None,
+        };
+
+        // Best-effort ICY resolution — three tiers, in order of freshness:
+        //
+        // 1. daemon_state.icy_title  — live value from the latest StateUpdated
+        //    or IcyUpdated message.  Most up-to-date but can be None if the
+        //    ICY hasn't arrived yet (fresh daemon start, station just switched).
+        //
+        // 2. last_known_icy           — sticky field updated by IcyUpdated only,
+        //    never overwritten by StateUpdated.  Survives transient None states
+        //    as long as the station hasn't changed.
+        //
+        // 3. icy_history (session)    — most recent entry tagged to this station
+        //    recorded during this session (has station tag, unlike log-loaded
+        //    entries).  Covers the case where daemon dedup prevented a
+        //    re-broadcast but the title is in recent history.
+        let icy_title = self
+            .state
+            .daemon_state
+            .icy_title
+            .clone()
+            .or_else(|| {
+                // Tier 2: last_known_icy for the current station
+                let name = station_name.as_deref()?;
+                self.last_known_icy
+                    .as_ref()
+                    .filter(|(st, _)| st.as_str() == name)
+                    .map(|(_, t)| t.clone())
+            })
+            .or_else(|| {
+                // Tier 3: most recent icy_history entry for this station
+                let name = station_name.as_deref()?;
+                self.state
+                    .icy_history
+                    .iter()
+                    .rev()
+                    .find(|e| e.station.as_deref() == Some(name))
+                    .map(|e| e.raw.clone())
+            });
+
+        info!(
+            "[app] Recognition context: station={:?}, icy={:?}",
+            station_name, icy_title
+        );
+
+        let nts_ch = station_name.as_deref().and_then(|n| {
+            if n.eq_ignore_ascii_case("nts 1") {
+                Some(0)
+            } else if n.eq_ignore_ascii_case("nts 2") {
+                Some(1)
+            } else {
+                None
+            }
+        });
+
+        let nts_mixtape_url = station.as_ref().and_then(|s| {
+            if !s.name.starts_with("NTS:") {
+                return None;
+            }
+            let u = s.mixtape_url.trim();
+            if u.is_empty() {
+                None
+            } else {
+                Some(u.to_string())
+            }
+        });
+
+        (station_name, audio, icy_title, nts_ch, nts_mixtape_url)
+    }
+
     /// Spawn an async recognition job (fire-and-forget, patch-in-place).
     ///
     /// 1. Immediately sends `RecognitionStarted` with initial row (job_id + station + icy).
     /// 2. Spawns three concurrent tasks:
     ///    a. ICY patch — immediate if icy_title is Some.
     ///    b. NTS patch — async API call (NTS 1/2 or NTS Infinite Mixtape).
-    ///    c. vibra patch — silent mpv 10s capture + vibra fingerprint.
+    ///    c. recognizer patch — the last `capture_secs` of what was playing
+    ///    (proxy buffer or file), run through the `[recognition] backends`
+    ///    in order (vibra by default).
     fn spawn_recognition_job(
         &mut self,
         station_name: Option<String>,
-        stream_url: Option<String>,
+        audio: Option<RecognitionAudio>,
         icy_title: Option<String>,
         nts_ch: Option<usize>,
         nts_mixtape_url: Option<String>,
@@ -2952,8 +4134,8 @@ impl App {
         let now = chrono::Local::now();
         let job_id = make_job_id(&now, station_name.as_deref());
         info!(
-            "[app] Spawning recognition job_id={} station={:?} icy={:?} nts_ch={:?} nts_mixtape_url={:?} url={:?}",
-            job_id, station_name, icy_title, nts_ch, nts_mixtape_url, stream_url
+            "[app] Spawning recognition job_id={} station={:?} icy={:?} nts_ch={:?} nts_mixtape_url={:?} audio={:?}",
+            job_id, station_name, icy_title, nts_ch, nts_mixtape_url, audio
         );
 
         // Initial row — sent immediately so the UI shows something right away
@@ -3020,32 +4202,38 @@ impl App {
             });
         }
 
-        // ── Task C: vibra patch (async, ~10s) ────────────────────────────────
-        if let Some(url) = stream_url {
+        // ── Task C: recognizer patch (async, ~capture_secs) ──────────────────
+        if let Some(audio) = audio {
+            let recognizers = self.recognizers.clone();
+            let capture_secs = self.recognition_capture_secs;
             tokio::spawn(async move {
-                info!("[recognition] vibra task started for url={}", url);
-                let vibra_result = recognize_via_vibra(&url).await;
-                if let Some(json) = vibra_result {
-                    let rec_str = vibra_rec_string(&json);
-                    info!("[recognition] vibra result: {:?}", rec_str);
-                    let display = rec_str.clone().unwrap_or_else(|| "?".to_string());
+                info!("[recognition] capture task started for {:?}", audio);
+                let result = match capture_recent(&audio, capture_secs).await {
+                    Ok(clip) => recognize_clip(&recognizers, &clip).await,
+                    Err(e) => {
+                        warn!("[recognition] capture failed: {}", e);
+                        None
+                    }
+                };
+                if let Some((source, track)) = result {
                     let patch = VdsPatch {
-                        vibra_rec: rec_str,
+                        vibra_rec: Some(track.clone()),
+                        rec_source: Some(source.label().to_string()),
                         ..Default::default()
                     };
                     let _ = tx4
                         .send(AppMessage::RecognitionPatch(job_id4.clone(), patch))
                         .await;
                     let _ = tx4
-                        .send(AppMessage::RecognitionComplete(job_id4, display))
+                        .send(AppMessage::RecognitionComplete(job_id4, track))
                         .await;
                 } else {
-                    warn!("[recognition] vibra returned nothing");
+                    warn!("[recognition] no backend recognized the clip");
                     let _ = tx4.send(AppMessage::RecognitionNoMatch).await;
                 }
             });
         } else {
-            // No stream URL — vibra can't run.  The spinner was already shown;
+            // Nothing playing — nothing to capture.  The spinner was already shown;
             // dismiss it immediately so it doesn't hang indefinitely.
             let tx_no_url = tx.clone();
             tokio::spawn(async move {
@@ -3097,6 +4285,7 @@ impl App {
                 Some(ComponentId::IcyTicker) => "icyticker".to_string(),
                 Some(ComponentId::SongsTicker) => "songsticker".to_string(),
                 Some(ComponentId::FileMeta) => "filemeta".to_string(),
+                Some(ComponentId::QueuePanel) => "queue".to_string(),
                 Some(ComponentId::NtsPanel) => "ntspanel".to_string(),
                 _ => "stationlist".to_string(),
             },
@@ -3155,13 +4344,7 @@ impl App {
 
     /// Get the currently selected song entry from songs ticker
     fn get_selected_song_entry(&self) -> Option<radio_proto::songs::RecognitionResult> {
-        // Get the selected index from songs_ticker
-        let selected = self.songs_ticker.selected;
-        if selected < self.state.songs_history.len() {
-            Some(self.state.songs_history[selected].clone())
-        } else {
-            None
-        }
+        self.songs_ticker.selected_entry(&self.state).cloned()
     }
 
     /// Start an NTS download immediately (no queue check — caller is responsible).
@@ -3838,6 +5021,8 @@ async fn poll_non_nts_station_icy(
     }
 }
 
+/// Read `resp` through an `IcyDemuxer` until a `StreamTitle` turns up or
+/// `max_blocks` metadata intervals went by; returns it and the bytes read.
 async fn read_icy_stream_title(
     resp: &mut reqwest::Response,
     metaint: usize,
@@ -3849,154 +5034,26 @@ async fn read_icy_stream_title(
     }
 
     let deadline = std::time::Instant::now() + timeout_total;
-    let mut buf: Vec<u8> = Vec::with_capacity((metaint + 1024).min(128 * 1024));
-    let mut cursor = 0usize;
-
-    for _ in 0..max_blocks {
-        let required_for_len = cursor + metaint + 1;
-        while buf.len() < required_for_len {
-            let now = std::time::Instant::now();
-            if now >= deadline {
-                return Err("timed out waiting for ICY metadata length byte".to_string());
-            }
-            let remain = deadline.saturating_duration_since(now);
-            let next = tokio::time::timeout(remain, resp.chunk())
-                .await
-                .map_err(|_| "timed out waiting for stream chunk".to_string())?
-                .map_err(|e| e.to_string())?;
-
-            match next {
-                Some(chunk) => buf.extend_from_slice(&chunk),
-                None => return Err("stream ended before ICY metadata block".to_string()),
-            }
-        }
-
-        let len_byte = buf[cursor + metaint] as usize;
-        let meta_len = len_byte * 16;
-        if meta_len > 16 * 255 {
-            return Err(format!("metadata block too large: {}", meta_len));
-        }
-
-        let block_total = metaint + 1 + meta_len;
-        let required_total = cursor + block_total;
-        while buf.len() < required_total {
-            let now = std::time::Instant::now();
-            if now >= deadline {
-                return Err("timed out waiting for ICY metadata payload".to_string());
-            }
-            let remain = deadline.saturating_duration_since(now);
-            let next = tokio::time::timeout(remain, resp.chunk())
-                .await
-                .map_err(|_| "timed out waiting for stream chunk".to_string())?
-                .map_err(|e| e.to_string())?;
-            match next {
-                Some(chunk) => buf.extend_from_slice(&chunk),
-                None => return Err("stream ended before ICY metadata payload".to_string()),
-            }
-        }
-
-        if meta_len > 0 {
-            let meta_start = cursor + metaint + 1;
-            let meta_end = meta_start + meta_len;
-            let meta = &buf[meta_start..meta_end];
-            if let Some(title) = parse_stream_title(meta) {
-                return Ok((Some(title), required_total));
-            }
-        }
-
-        cursor = required_total;
-    }
-
-    Ok((None, cursor))
-}
-
-fn parse_stream_title(meta: &[u8]) -> Option<String> {
-    let text = String::from_utf8_lossy(meta)
-        .trim_matches(char::from(0))
-        .trim()
-        .to_string();
-    if text.is_empty() {
-        return None;
-    }
-
-    if let Some(start) = text.find("StreamTitle='") {
-        let rest = &text[start + "StreamTitle='".len()..];
-        if let Some(end) = rest.find("';") {
-            let title = rest[..end].trim();
-            if !title.is_empty() {
-                return Some(title.to_string());
-            }
-        }
-    }
-
-    if let Some(start) = text.find("StreamTitle=\"") {
-        let rest = &text[start + "StreamTitle=\"".len()..];
-        if let Some(end) = rest.find("\";") {
-            let title = rest[..end].trim();
-            if !title.is_empty() {
-                return Some(title.to_string());
-            }
-        }
-    }
-
-    None
-}
-
-async fn fetch_playlist_target(
-    client: &reqwest::Client,
-    url: &str,
-) -> Result<Option<String>, String> {
-    let body = client
-        .get(url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .error_for_status()
-        .map_err(|e| e.to_string())?
-        .text()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if url.to_ascii_lowercase().ends_with(".pls") {
-        for line in body.lines() {
-            let l = line.trim();
-            if l.to_ascii_lowercase().starts_with("file") {
-                if let Some((_, v)) = l.split_once('=') {
-                    return resolve_relative_url(url, v.trim()).map(Some);
-                }
-            }
-        }
-        return Ok(None);
-    }
-
-    for line in body.lines() {
-        let l = line.trim();
-        if l.is_empty() || l.starts_with('#') {
-            continue;
+    let mut demuxer = IcyDemuxer::new(Some(metaint));
+    let mut audio = Vec::new();
+    let mut bytes_read = 0usize;
+    while bytes_read < max_blocks * (metaint + 1) {
+        let remain = deadline.saturating_duration_since(std::time::Instant::now());
+        let next = tokio::time::timeout(remain, resp.chunk())
+            .await
+            .map_err(|_| "timed out waiting for ICY metadata".to_string())?
+            .map_err(|e| e.to_string())?;
+        let Some(chunk) = next else {
+            return Err("stream ended before ICY metadata block".to_string());
+        };
+        bytes_read += chunk.len();
+        audio.clear();
+        if let Some((_, title)) = demuxer.feed(&chunk, &mut audio).into_iter().next() {
+            return Ok((Some(title), bytes_read));
         }
-        return resolve_relative_url(url, l).map(Some);
     }
-    Ok(None)
-}
-
-fn resolve_relative_url(base: &str, candidate: &str) -> Result<String, String> {
-    if candidate.starts_with("http://") || candidate.starts_with("https://") {
-        return Ok(candidate.to_string());
-    }
-    let base_url = reqwest::Url::parse(base).map_err(|e| e.to_string())?;
-    base_url
-        .join(candidate)
-        .map(|u| u.to_string())
-        .map_err(|e| e.to_string())
-}
-
-fn is_playlist_url(url: &str) -> bool {
-    let l = url.to_ascii_lowercase();
-    l.ends_with(".m3u") || l.ends_with(".m3u8") || l.ends_with(".pls")
-}
 
-fn looks_hls_url(url: &str) -> bool {
-    url.to_ascii_lowercase().contains(".m3u8")
+    Ok((None, bytes_read))
 }
 
 fn adaptive_icy_blocks(metaint: usize) -> usize {
@@ -4012,6 +5069,34 @@ fn adaptive_icy_blocks(metaint: usize) -> usize {
 
 // ── NTS fetch ─────────────────────────────────────────────────────────────────
 
+/// The last `secs` seconds the user heard.  Stations take them from the
+/// proxy's buffer and only reconnect upstream (capturing what plays next)
+/// when the proxy holds too little, e.g. right after tuning in.
+async fn capture_recent(audio: &RecognitionAudio, secs: u64) -> anyhow::Result<PcmClip> {
+    match audio {
+        RecognitionAudio::Station {
+            idx,
+            behind,
+            pressed_at,
+            upstream,
+        } => {
+            // Queued presses run later; keep ending where the press was.
+            let behind = behind + pressed_at.elapsed().as_secs_f64();
+            let url = crate::proxy::clip_url(*idx, secs, behind);
+            match capture_stream(&url, secs).await {
+                Ok(clip) if clip.secs() >= secs as f64 / 2.0 => return Ok(clip),
+                Ok(clip) => info!(
+                    "[recognition] proxy holds only {:.1}s, capturing upstream",
+                    clip.secs()
+                ),
+                Err(e) => info!("[recognition] no proxy clip ({}), capturing upstream", e),
+            }
+            capture_stream(upstream, secs).await
+        }
+        RecognitionAudio::File { path, pos } => capture_file(path, *pos, secs).await,
+    }
+}
+
 async fn fetch_nts_channel(ch_idx: usize) -> anyhow::Result<NtsChannel> {
     let resp: serde_json::Value = reqwest::get("https://www.nts.live/api/v2/live")
         .await?
@@ -4041,6 +5126,24 @@ async fn fetch_nts_channel(ch_idx: usize) -> anyhow::Result<NtsChannel> {
     })
 }
 
+/// Snapshot of an NTS show for the core's recording schedule.
+fn scheduled_show(channel: usize, show: &NtsShow) -> ScheduledShow {
+    let location = if show.location_long.is_empty() {
+        show.location_short.clone()
+    } else {
+        show.location_long.clone()
+    };
+    ScheduledShow {
+        channel,
+        title: show.broadcast_title.clone(),
+        start: show.start.timestamp(),
+        end: show.end.timestamp(),
+        genres: show.genres.clone(),
+        description: show.description.clone(),
+        location,
+    }
+}
+
 fn parse_nts_show(obj: &serde_json::Value) -> anyhow::Result<NtsShow> {
     let broadcast_title = obj["broadcast_title"]
         .as_str()
@@ -4218,7 +5321,7 @@ pub fn search_matches(query: &str, text: &str) -> bool {
     true
 }
 
-fn load_local_files(dir: &PathBuf) -> Vec<LocalFileEntry> {
+pub(crate) fn load_local_files(dir: &PathBuf) -> Vec<LocalFileEntry> {
     let Ok(rd) = std::fs::read_dir(dir) else {
         return Vec::new();
     };
@@ -4279,11 +5382,12 @@ fn is_playable_audio_path(path: &std::path::Path) -> bool {
                 | "mkv"
                 | "mp4"
                 | "m4b"
+                | "ts"
         )
     )
 }
 
-fn probe_file_metadata(path: &std::path::Path) -> Option<FileMetadata> {
+pub(crate) fn probe_file_metadata(path: &std::path::Path) -> Option<FileMetadata> {
     // Use ffprobe / ffmpeg to extract metadata via a simple JSON call
     // This mirrors the logic in the old main.rs
     let ffprobe_bin = radio_proto::platform::find_ffprobe_binary()
@@ -6,11 +6,15 @@
 use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
 
-use radio_proto::protocol::{DaemonState, PlaybackStatus};
+use radio_proto::protocol::{DaemonState, PlaybackStatus, RecordingStatus};
+use radio_proto::radio_browser::DirectoryStation;
 use radio_proto::songs::RecognitionResult;
 
 use crate::action::Workspace;
 use crate::intent::RenderHint;
+use crate::loudness::LoudnessMeter;
+use crate::queue::QueueItem;
+use crate::station_check::StationCheck;
 use crate::widgets::status_bar::InputMode;
 
 /// Data about the currently playing file (position, duration, etc.)
@@ -98,6 +102,17 @@ pub struct RandomHistoryEntry {
     pub saved_at_epoch: i64,
 }
 
+/// Results of the last station directory search (discover pane).
+#[derive(Debug, Clone, Default)]
+pub struct DiscoverResults {
+    /// The query these results answer.
+    pub query: String,
+    pub stations: Vec<DirectoryStation>,
+    /// A search is running.
+    pub searching: bool,
+    pub error: Option<String>,
+}
+
 /// The full shared state of the application.
 /// Components read this; only the App event-loop writes to it.
 pub struct AppState {
@@ -116,13 +131,19 @@ pub struct AppState {
     pub files: Vec<LocalFileEntry>,
     pub file_metadata_cache: HashMap<String, FileMetadata>,
     pub file_positions: HashMap<String, f64>,
+    /// Play queue, head first (persisted to queue.json).
+    pub queue: Vec<QueueItem>,
+    /// Saved playlist file names in the playlists dir.
+    pub playlists: Vec<String>,
 
     // ── ICY / songs ticker ──────────────────────────────────────────────────
     pub icy_history: Vec<TickerEntry>,
     /// Sticky ICY title that persists across transient None states (station_name, title).
     pub last_known_icy: Option<(String, String)>,
-    /// Songs history loaded from songs.vds (newest last).
+    /// Newest rows of the songs database (newest last).
     pub songs_history: Vec<RecognitionResult>,
+    /// Songs pane search results (newest first); None when not searching.
+    pub song_search: Option<Vec<RecognitionResult>>,
     /// When the station list cursor is on an NTS station, this holds the channel
     /// index (0 = NTS 1, 1 = NTS 2). None when not hovering an NTS row.
     pub nts_hover_channel: Option<usize>,
@@ -133,9 +154,15 @@ pub struct AppState {
     pub nts_ch1_error: Option<String>,
     pub nts_ch2_error: Option<String>,
 
+    // ── Station discovery ───────────────────────────────────────────────────
+    pub discover: DiscoverResults,
+
     // ── Passive station annotations ─────────────────────────────────────────
     /// Last polled "now playing" label per station name.
     pub station_poll_titles: HashMap<String, String>,
+    /// Last health check per station name (`X`).
+    pub station_checks: HashMap<String, StationCheck>,
+    pub checking_stations: bool,
 
     // ── UI mode ─────────────────────────────────────────────────────────────
     pub workspace: Workspace,
@@ -180,6 +207,13 @@ pub struct AppState {
     pub pcm_pending: VecDeque<f32>,
     /// True once enough samples are buffered to start stable scope/VU playback.
     pub pcm_pending_started: bool,
+    /// EBU R128 loudness of the station PCM since the source last changed.
+    pub loudness: LoudnessMeter,
+    /// Normalization gain applied to the current station; `None` when
+    /// normalization is off or the station hasn't been measured yet.
+    pub loudness_gain_db: Option<f32>,
+    /// Auto-ID is on (`I`); shown as a header badge.
+    pub auto_identify: bool,
 
     // ── Intent render hints ──────────────────────────────────────────────────
     /// How to render the pause/play icon.
@@ -193,7 +227,6 @@ pub struct AppState {
     pub downloads_dir: PathBuf,
     pub icy_log_path: PathBuf,
     pub songs_csv_path: PathBuf,
-    pub songs_vds_path: PathBuf,
     pub tui_log_path: PathBuf,
     pub random_history: Vec<RandomHistoryEntry>,
 
@@ -224,6 +257,14 @@ impl AppState {
             .map(|s| s.name.as_str())
     }
 
+    /// Running recording for a station index, if any.
+    pub fn recording_for(&self, station_idx: usize) -> Option<&RecordingStatus> {
+        self.daemon_state
+            .recordings
+            .iter()
+            .find(|r| r.station_idx == station_idx)
+    }
+
     /// Stars for a station by name.
     pub fn station_stars_for(&self, name: &str) -> u8 {
         self.station_stars.get(name).copied().unwrap_or(0)
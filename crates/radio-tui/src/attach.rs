@@ -0,0 +1,270 @@
+//! `r4dio --attach host:port`: drive a remote r4dio's core from this TUI.
+//!
+//! No local `DaemonCore` runs.  The link task stands in for it: commands the
+//! App sends as `DaemonEvent::ClientCommand` go over TCP (see `socket.rs`),
+//! and the remote's broadcasts come back as the same `BroadcastMessage`s the
+//! core would send, with state snapshots written into a mirror
+//! `StateManager`.  The VU meter and scope are fed from `Broadcast::Pcm` and
+//! `Broadcast::AudioLevel` exactly as from a local core.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, mpsc};
+use tracing::{error, info, warn};
+
+use radio_proto::protocol::{Broadcast, Message, PROTOCOL_VERSION};
+use radio_proto::state::StateManager;
+
+use crate::core::DaemonEvent;
+use crate::socket::read_message;
+use crate::BroadcastMessage;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Add the default port to a bare host.
+pub fn normalize_addr(addr: &str) -> String {
+    let has_port = addr
+        .rsplit_once(':')
+        .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok());
+    if has_port {
+        addr.to_string()
+    } else {
+        format!("{}:{}", addr, radio_proto::platform::DAEMON_TCP_PORT)
+    }
+}
+
+/// Keep a link to the core at `addr` open, reconnecting when it drops, until
+/// `DaemonEvent::Shutdown` arrives.
+pub fn start(
+    addr: String,
+    state_manager: Arc<StateManager>,
+    mut event_rx: mpsc::Receiver<DaemonEvent>,
+    broadcast_tx: broadcast::Sender<BroadcastMessage>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let log = |message: String| {
+            let _ = broadcast_tx.send(BroadcastMessage::Log(message));
+        };
+        loop {
+            let end = match TcpStream::connect(&addr).await {
+                Ok(stream) => {
+                    info!("[attach] connected to {}", addr);
+                    log(format!("attached to {}", addr));
+                    session(stream, &state_manager, &mut event_rx, &broadcast_tx).await
+                }
+                Err(e) => Err(e.into()),
+            };
+            match end {
+                Ok(LinkEnd::Quit) => return,
+                Ok(LinkEnd::Closed) => {
+                    warn!("[attach] {} closed the connection", addr);
+                    log(format!("{} closed the connection, reconnecting", addr));
+                }
+                Ok(LinkEnd::Incompatible(version)) => {
+                    let message = format!(
+                        "{} speaks protocol v{}, this r4dio speaks v{}; not attaching",
+                        addr, version, PROTOCOL_VERSION
+                    );
+                    error!("[attach] {}", message);
+                    log(message);
+                    wait_for_shutdown(&mut event_rx).await;
+                    return;
+                }
+                Err(e) => {
+                    warn!("[attach] {}: {}", addr, e);
+                    log(format!("cannot reach {}: {}", addr, e));
+                }
+            }
+            if !wait_to_reconnect(&mut event_rx).await {
+                return;
+            }
+        }
+    })
+}
+
+enum LinkEnd {
+    /// The App is shutting down.
+    Quit,
+    /// The remote closed the connection.
+    Closed,
+    /// The remote speaks another protocol version.
+    Incompatible(u32),
+}
+
+async fn session(
+    stream: TcpStream,
+    state_manager: &StateManager,
+    event_rx: &mut mpsc::Receiver<DaemonEvent>,
+    broadcast_tx: &broadcast::Sender<BroadcastMessage>,
+) -> anyhow::Result<LinkEnd> {
+    stream.set_nodelay(true)?;
+    let (mut read_half, mut write_half) = stream.into_split();
+    let mut read_buf = Vec::new();
+
+    match read_message(&mut read_half, &mut read_buf).await? {
+        Some(Message::Broadcast(Broadcast::Hello {
+            protocol_version,
+            state,
+            ..
+        })) => {
+            if protocol_version != PROTOCOL_VERSION {
+                return Ok(LinkEnd::Incompatible(protocol_version));
+            }
+            state_manager.replace_state(state).await;
+            let _ = broadcast_tx.send(BroadcastMessage::StateUpdated);
+        }
+        Some(_) => anyhow::bail!("expected Hello as the first message"),
+        None => return Ok(LinkEnd::Closed),
+    }
+
+    loop {
+        tokio::select! {
+            msg = read_message(&mut read_half, &mut read_buf) => match msg? {
+                Some(Message::Broadcast(broadcast)) => {
+                    if let Some(msg) = apply_broadcast(broadcast, state_manager).await {
+                        let _ = broadcast_tx.send(msg);
+                    }
+                }
+                Some(Message::Command(_)) => {}
+                None => return Ok(LinkEnd::Closed),
+            },
+            event = event_rx.recv() => match event {
+                Some(DaemonEvent::ClientCommand(cmd)) => {
+                    write_half.write_all(&Message::Command(cmd).encode()?).await?;
+                }
+                Some(DaemonEvent::Shutdown) | None => return Ok(LinkEnd::Quit),
+                Some(_) => {}
+            },
+        }
+    }
+}
+
+/// Turn a remote broadcast into what a local core would have sent.
+async fn apply_broadcast(
+    broadcast: Broadcast,
+    state_manager: &StateManager,
+) -> Option<BroadcastMessage> {
+    match broadcast {
+        Broadcast::Hello { state, .. } | Broadcast::State { data: state } => {
+            state_manager.replace_state(state).await;
+            Some(BroadcastMessage::StateUpdated)
+        }
+        Broadcast::Icy { title } => Some(BroadcastMessage::IcyUpdated(title)),
+        Broadcast::Log { message } | Broadcast::Error { message } => {
+            Some(BroadcastMessage::Log(message))
+        }
+        Broadcast::AudioLevel { rms_db } => Some(BroadcastMessage::AudioLevel(rms_db)),
+        Broadcast::Pcm { samples } => Some(BroadcastMessage::PcmChunk(Arc::new(samples))),
+        Broadcast::FileEnded { path } => Some(BroadcastMessage::FileEnded(path)),
+    }
+}
+
+/// Sleep before reconnecting; commands sent meanwhile are dropped.  Returns
+/// false if the App shut down while waiting.
+async fn wait_to_reconnect(event_rx: &mut mpsc::Receiver<DaemonEvent>) -> bool {
+    let delay = tokio::time::sleep(RECONNECT_DELAY);
+    tokio::pin!(delay);
+    loop {
+        tokio::select! {
+            _ = &mut delay => return true,
+            event = event_rx.recv() => match event {
+                Some(DaemonEvent::Shutdown) | None => return false,
+                Some(DaemonEvent::ClientCommand(cmd)) => {
+                    warn!("[attach] not connected, dropping {:?}", cmd);
+                }
+                Some(_) => {}
+            },
+        }
+    }
+}
+
+async fn wait_for_shutdown(event_rx: &mut mpsc::Receiver<DaemonEvent>) {
+    while let Some(event) = event_rx.recv().await {
+        if matches!(event, DaemonEvent::Shutdown) {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use radio_proto::protocol::{Command, PlaybackStatus, Station};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_normalize_addr() {
+        assert_eq!(normalize_addr("mediabox"), "mediabox:9876");
+        assert_eq!(normalize_addr("mediabox:7000"), "mediabox:7000");
+        assert_eq!(normalize_addr("10.0.0.5:9876"), "10.0.0.5:9876");
+    }
+
+    #[tokio::test]
+    async fn test_attach_to_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let remote = Arc::new(StateManager::new(
+            dir.path().join("state.json"),
+            vec![Station {
+                name: "FIP".into(),
+                ..Default::default()
+            }],
+        ));
+        remote.set_playing(0).await.unwrap();
+        let (remote_event_tx, mut remote_event_rx) = mpsc::channel(16);
+        let (remote_broadcast_tx, _) = broadcast::channel(16);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(crate::socket::serve(
+            listener,
+            remote.clone(),
+            remote_event_tx,
+            remote_broadcast_tx.clone(),
+        ));
+
+        let mirror = Arc::new(StateManager::mirror());
+        let (event_tx, event_rx) = mpsc::channel(16);
+        let (broadcast_tx, mut broadcast_rx) = broadcast::channel(16);
+        let link = start(addr, mirror.clone(), event_rx, broadcast_tx);
+
+        // Hello fills the mirror.
+        loop {
+            if let Ok(BroadcastMessage::StateUpdated) = broadcast_rx.recv().await {
+                break;
+            }
+        }
+        assert_eq!(mirror.get_state().await.stations[0].name, "FIP");
+        assert_eq!(mirror.get_state().await.current_station, Some(0));
+
+        // Commands reach the remote core.
+        event_tx
+            .send(DaemonEvent::ClientCommand(Command::TogglePause))
+            .await
+            .unwrap();
+        match remote_event_rx.recv().await {
+            Some(DaemonEvent::ClientCommand(Command::TogglePause)) => {}
+            _ => panic!("expected TogglePause"),
+        }
+
+        // Remote broadcasts come back as local ones.
+        remote.set_playback_status(PlaybackStatus::Paused).await;
+        let _ = remote_broadcast_tx.send(BroadcastMessage::StateUpdated);
+        let _ = remote_broadcast_tx.send(BroadcastMessage::PcmChunk(Arc::new(vec![0.5; 4])));
+        let mut got_pcm = false;
+        while !got_pcm {
+            match broadcast_rx.recv().await.unwrap() {
+                BroadcastMessage::PcmChunk(samples) => {
+                    assert_eq!(*samples, vec![0.5; 4]);
+                    got_pcm = true;
+                }
+                _ => continue,
+            }
+        }
+        assert!(mirror.get_state().await.is_paused);
+
+        event_tx.send(DaemonEvent::Shutdown).await.unwrap();
+        link.await.unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+//! Auto-ID: identifies what's playing without pressing `i`.
+//!
+//! While on (`I`), the App feeds every meter RMS reading into `AutoId` and
+//! asks it on each meter tick whether to run a recognition.  It says yes
+//! `capture_secs` after a track boundary (so the clip holds only the new
+//! track), or once `auto_interval_secs` pass without one; never more often
+//! than `auto_min_gap_secs`.
+//!
+//! A boundary is a gap of near-silence followed by sound, a lasting shift in
+//! level between the last second and the last ~15, or a change of station
+//! or file.  Matches are only added to the songs database when they differ
+//! from the station's previous identified row, so it reads as a tracklist.
+
+use std::time::{Duration, Instant};
+
+/// Below this the stream counts as silent.
+const SILENCE_DB: f32 = -50.0;
+/// Silence must last this long to count as a gap between tracks.
+const SILENCE_MIN: Duration = Duration::from_millis(1_000);
+/// Short (τ 1 s) vs long (τ 15 s) level difference that marks a new track.
+const SHIFT_DB: f32 = 8.0;
+/// The levels must come back within this before another shift counts.
+const SHIFT_REARM_DB: f32 = 3.0;
+const FAST_TAU_SECS: f32 = 1.0;
+const SLOW_TAU_SECS: f32 = 15.0;
+/// Spare time after `capture_secs` so the proxy has the clip buffered.
+const CAPTURE_SLACK: Duration = Duration::from_secs(2);
+
+/// Finds track boundaries in a stream of RMS readings.
+#[derive(Debug, Default)]
+struct BoundaryDetector {
+    fast_db: Option<f32>,
+    slow_db: f32,
+    silent_since: Option<Instant>,
+    shifted: bool,
+    last: Option<Instant>,
+}
+
+impl BoundaryDetector {
+    /// Feed one RMS reading (dBFS); true on a boundary.
+    fn push(&mut self, rms_db: f32, now: Instant) -> bool {
+        let dt = self
+            .last
+            .map(|t| now.duration_since(t).as_secs_f32().min(0.5))
+            .unwrap_or(0.0);
+        self.last = Some(now);
+
+        if rms_db < SILENCE_DB {
+            self.silent_since.get_or_insert(now);
+            // Silence itself isn't a level change; keep the averages as they were.
+            return false;
+        }
+        let gap = self
+            .silent_since
+            .take()
+            .is_some_and(|since| now.duration_since(since) >= SILENCE_MIN);
+
+        let Some(fast) = self.fast_db.as_mut() else {
+            self.fast_db = Some(rms_db);
+            self.slow_db = rms_db;
+            return false;
+        };
+        *fast += (1.0 - (-dt / FAST_TAU_SECS).exp()) * (rms_db - *fast);
+        self.slow_db += (1.0 - (-dt / SLOW_TAU_SECS).exp()) * (rms_db - self.slow_db);
+
+        let diff = (*fast - self.slow_db).abs();
+        let shift = !self.shifted && diff >= SHIFT_DB;
+        if shift {
+            self.shifted = true;
+        } else if diff < SHIFT_REARM_DB {
+            self.shifted = false;
+        }
+        gap || shift
+    }
+}
+
+/// Auto-ID scheduling: when to recognize next.
+#[derive(Debug)]
+pub struct AutoId {
+    interval: Duration,
+    min_gap: Duration,
+    capture: Duration,
+    detector: BoundaryDetector,
+    /// The station or file being watched.
+    source: Option<String>,
+    last_run: Option<Instant>,
+    /// Recognize at this time, after a boundary.
+    pending: Option<Instant>,
+}
+
+impl AutoId {
+    pub fn new(interval_secs: u64, min_gap_secs: u64, capture_secs: u64) -> Self {
+        Self {
+            interval: Duration::from_secs(interval_secs.max(min_gap_secs)),
+            min_gap: Duration::from_secs(min_gap_secs),
+            capture: Duration::from_secs(capture_secs),
+            detector: BoundaryDetector::default(),
+            source: None,
+            last_run: None,
+            pending: None,
+        }
+    }
+
+    /// Forget the schedule, so the next `due` identifies right away.
+    pub fn reset(&mut self) {
+        self.detector = BoundaryDetector::default();
+        self.source = None;
+        self.last_run = None;
+        self.pending = None;
+    }
+
+    /// Feed one RMS reading of what's playing.
+    pub fn push_level(&mut self, rms_db: f32, now: Instant) {
+        if self.detector.push(rms_db, now) {
+            self.boundary(now);
+        }
+    }
+
+    /// Note what's playing; a change is a boundary.
+    pub fn set_source(&mut self, source: Option<&str>, now: Instant) {
+        if self.source.as_deref() != source {
+            self.source = source.map(str::to_string);
+            self.detector = BoundaryDetector::default();
+            if self.last_run.is_some() {
+                self.boundary(now);
+            }
+        }
+    }
+
+    fn boundary(&mut self, now: Instant) {
+        self.pending = Some(now + self.capture + CAPTURE_SLACK);
+    }
+
+    /// True when a recognition should start now; the caller must start one.
+    pub fn due(&mut self, now: Instant) -> bool {
+        let due = match self.last_run {
+            None => true,
+            Some(last) => {
+                let since = now.duration_since(last);
+                since >= self.min_gap
+                    && (since >= self.interval || self.pending.is_some_and(|at| now >= at))
+            }
+        };
+        if due {
+            self.last_run = Some(now);
+            self.pending = None;
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `secs` of a steady level at the 50 Hz meter rate.
+    fn feed(d: &mut BoundaryDetector, t: &mut Instant, rms_db: f32, secs: f32) -> usize {
+        let mut hits = 0;
+        for _ in 0..(secs * 50.0) as usize {
+            *t += Duration::from_millis(20);
+            hits += d.push(rms_db, *t) as usize;
+        }
+        hits
+    }
+
+    #[test]
+    fn test_boundaries() {
+        let mut d = BoundaryDetector::default();
+        let mut t = Instant::now();
+        assert_eq!(feed(&mut d, &mut t, -18.0, 30.0), 0);
+        // A short dip is not a gap; two seconds of silence is.
+        assert_eq!(feed(&mut d, &mut t, -70.0, 0.5), 0);
+        assert_eq!(feed(&mut d, &mut t, -18.0, 5.0), 0);
+        assert_eq!(feed(&mut d, &mut t, -70.0, 2.0), 0);
+        assert_eq!(feed(&mut d, &mut t, -18.0, 30.0), 1);
+        // A much quieter track is one boundary, however long it plays.
+        assert_eq!(feed(&mut d, &mut t, -32.0, 60.0), 1);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let t = Instant::now();
+        let mut auto = AutoId::new(180, 45, 10);
+        assert!(auto.due(t));
+        assert!(!auto.due(t + Duration::from_secs(1)));
+        // A boundary fires capture + slack later, but not inside the min gap.
+        auto.set_source(Some("other station"), t + Duration::from_secs(5));
+        assert!(!auto.due(t + Duration::from_secs(20)));
+        assert!(auto.due(t + Duration::from_secs(45)));
+        assert!(!auto.due(t + Duration::from_secs(100)));
+        assert!(auto.due(t + Duration::from_secs(225)));
+    }
+}
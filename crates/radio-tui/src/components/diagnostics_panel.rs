@@ -0,0 +1,296 @@
+//! DiagnosticsPanel — health of the proxy's open streams (Radio workspace, `H`).
+//!
+//! One block per stream, the one playing marked ▶:
+//!   format    codec · bitrate · sample rate · channels (headers, then ffprobe)
+//!   received  upstream throughput and subscribers
+//!   buffer    timeshift buffer fill
+//!   upstream  reconnects, and whether it is reconnecting now
+//!   lag       times a subscriber fell behind the broadcast channel
+//! followed by mpv's cache while a station plays.
+//!
+//! Keybindings (when focused):
+//!   j/k ↑↓   — scroll
+//!   y        — copy the diagnostics as JSON
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyEventKind, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Borders, Paragraph},
+    Frame,
+};
+
+use radio_proto::protocol::{PlayerCache, StreamDiagnostics};
+
+use crate::{
+    action::{Action, ComponentId},
+    app_state::AppState,
+    component::Component,
+    theme::{C_CONNECTING, C_ERROR, C_MUTED, C_PLAYING, C_PRIMARY, C_SECONDARY},
+    widgets::pane_chrome::pane_chrome_borders,
+};
+
+const LABEL_WIDTH: usize = 10;
+
+pub struct DiagnosticsPanel {
+    pub scroll_offset: usize,
+    pub borders: Borders,
+    /// Dynamic pane number hint (set by app.rs before draw).
+    pub number_key: Option<char>,
+    /// Lines drawn last time, to bound scrolling.
+    line_count: usize,
+}
+
+impl DiagnosticsPanel {
+    pub fn new() -> Self {
+        Self {
+            scroll_offset: 0,
+            borders: Borders::ALL,
+            number_key: Some('2'),
+            line_count: 0,
+        }
+    }
+
+    fn scroll_up(&mut self, n: usize) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(n);
+    }
+
+    fn scroll_down(&mut self, n: usize) {
+        self.scroll_offset = (self.scroll_offset + n).min(self.line_count.saturating_sub(1));
+    }
+}
+
+fn row(label: &str, value: String, color: ratatui::style::Color) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!("   {:<w$}", label, w = LABEL_WIDTH),
+            Style::default().fg(C_SECONDARY),
+        ),
+        Span::styled(value, Style::default().fg(color)),
+    ])
+}
+
+/// "mp3 · 128 kbps · 44.1 kHz · stereo", or whatever of it is known.
+fn format_label(d: &StreamDiagnostics) -> String {
+    let mut parts = Vec::new();
+    if let Some(codec) = &d.codec {
+        parts.push(codec.clone());
+    }
+    if let Some(kbps) = d.bitrate_kbps {
+        parts.push(format!("{} kbps", kbps));
+    }
+    if let Some(hz) = d.sample_rate {
+        parts.push(format!("{} kHz", hz as f64 / 1000.0));
+    }
+    match d.channels {
+        Some(1) => parts.push("mono".to_string()),
+        Some(2) => parts.push("stereo".to_string()),
+        Some(n) => parts.push(format!("{} channels", n)),
+        None => {}
+    }
+    if parts.is_empty() {
+        "unknown".to_string()
+    } else {
+        parts.join(" · ")
+    }
+}
+
+fn uptime_label(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+fn stream_lines(d: &StreamDiagnostics, playing: bool) -> Vec<Line<'static>> {
+    let marker = if playing {
+        Span::styled(" ▶ ", Style::default().fg(C_PLAYING))
+    } else {
+        Span::raw("   ")
+    };
+    let name = if d.station_name.is_empty() {
+        "(preview)".to_string()
+    } else {
+        d.station_name.clone()
+    };
+    let mut lines = vec![
+        Line::from(vec![
+            marker,
+            Span::styled(
+                name,
+                Style::default().fg(C_PRIMARY).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("  up {}", uptime_label(d.uptime_secs)),
+                Style::default().fg(C_MUTED),
+            ),
+        ]),
+        row("url", d.url.clone(), C_MUTED),
+        row("format", format_label(d), C_PRIMARY),
+    ];
+    if !d.content_type.is_empty() {
+        lines.push(row("type", d.content_type.clone(), C_MUTED));
+    }
+
+    // Well short of the declared bitrate means the upstream can't keep up.
+    let starved = d
+        .bitrate_kbps
+        .is_some_and(|kbps| d.uptime_secs > 10 && d.throughput_kbps < kbps as f64 * 0.8);
+    lines.push(row(
+        "received",
+        format!(
+            "{:.0} kbps · {} listener{}",
+            d.throughput_kbps,
+            d.listeners,
+            if d.listeners == 1 { "" } else { "s" }
+        ),
+        if starved { C_CONNECTING } else { C_PRIMARY },
+    ));
+    lines.push(row(
+        "buffer",
+        format!("{:.1} / {:.0} s", d.buffered_secs, d.buffer_secs),
+        C_PRIMARY,
+    ));
+    let (upstream, color) = if d.reconnecting {
+        ("reconnecting…".to_string(), C_ERROR)
+    } else if d.reconnects > 0 {
+        (format!("{} reconnects", d.reconnects), C_CONNECTING)
+    } else {
+        ("connected".to_string(), C_PRIMARY)
+    };
+    lines.push(row("upstream", upstream, color));
+    lines.push(if d.lag_events == 0 {
+        row("lag", "none".to_string(), C_PRIMARY)
+    } else {
+        row(
+            "lag",
+            format!(
+                "{} events, last dropped {} chunks",
+                d.lag_events, d.last_lag_chunks
+            ),
+            C_CONNECTING,
+        )
+    });
+    lines
+}
+
+fn player_lines(cache: &PlayerCache) -> Vec<Line<'static>> {
+    let mut ahead = match cache.cache_secs {
+        Some(secs) => format!("{:.1} s ahead", secs),
+        None => "no cache".to_string(),
+    };
+    if let Some(kbps) = cache.input_kbps {
+        ahead.push_str(&format!(" · reading {:.0} kbps", kbps));
+    }
+    let mut lines = vec![
+        Line::from(Span::styled(
+            " mpv",
+            Style::default().fg(C_PRIMARY).add_modifier(Modifier::BOLD),
+        )),
+        row("cache", ahead, C_PRIMARY),
+    ];
+    if cache.paused_for_cache {
+        lines.push(row(
+            "stalled",
+            "waiting for the cache to fill".to_string(),
+            C_ERROR,
+        ));
+    }
+    lines
+}
+
+impl Component for DiagnosticsPanel {
+    fn id(&self) -> ComponentId {
+        ComponentId::DiagnosticsPanel
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, state: &AppState) -> Vec<Action> {
+        if key.kind == KeyEventKind::Release {
+            return vec![];
+        }
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_up(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_down(1),
+            KeyCode::PageUp => self.scroll_up(10),
+            KeyCode::PageDown => self.scroll_down(10),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll_offset = 0,
+            KeyCode::Char('y') => {
+                if let Ok(json) = serde_json::to_string_pretty(&state.daemon_state.diagnostics) {
+                    return vec![Action::CopyToClipboard(json)];
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn handle_mouse(&mut self, event: MouseEvent, _area: Rect, _state: &AppState) -> Vec<Action> {
+        match event.kind {
+            MouseEventKind::ScrollUp => self.scroll_up(1),
+            MouseEventKind::ScrollDown => self.scroll_down(1),
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn on_action(&mut self, _action: &Action, _state: &AppState) -> Vec<Action> {
+        vec![]
+    }
+
+    fn collapse_summary(&self, state: &AppState) -> Option<String> {
+        let streams = &state.daemon_state.diagnostics.streams;
+        let reconnecting = streams.iter().filter(|d| d.reconnecting).count();
+        Some(match reconnecting {
+            0 => format!("{} streams open", streams.len()),
+            n => format!("{} streams open, {} reconnecting", streams.len(), n),
+        })
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect, focused: bool, state: &AppState) {
+        if area.height == 0 {
+            return;
+        }
+
+        let block =
+            pane_chrome_borders("diagnostics", self.number_key, focused, None, self.borders);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let daemon = &state.daemon_state;
+        let diagnostics = &daemon.diagnostics;
+        if diagnostics.streams.is_empty() && diagnostics.player.is_none() {
+            self.line_count = 0;
+            frame.render_widget(
+                Paragraph::new(Span::styled(
+                    "  no streams open — play a station",
+                    Style::default().fg(C_MUTED),
+                )),
+                inner,
+            );
+            return;
+        }
+
+        let mut lines: Vec<Line> = Vec::new();
+        for d in &diagnostics.streams {
+            let playing = daemon.is_playing
+                && match d.station_idx {
+                    Some(idx) => daemon.preview.is_none() && daemon.current_station == Some(idx),
+                    None => daemon.preview.is_some(),
+                };
+            lines.extend(stream_lines(d, playing));
+            lines.push(Line::from(""));
+        }
+        if let Some(cache) = &diagnostics.player {
+            lines.extend(player_lines(cache));
+        }
+
+        self.line_count = lines.len();
+        self.scroll_offset = self.scroll_offset.min(lines.len().saturating_sub(1));
+        frame.render_widget(
+            Paragraph::new(lines).scroll((self.scroll_offset as u16, 0)),
+            inner,
+        );
+    }
+}
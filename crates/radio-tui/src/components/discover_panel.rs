@@ -0,0 +1,263 @@
+//! DiscoverPanel — station directory search (Radio workspace, `b`).
+//!
+//! Searches a Radio Browser-compatible directory (`[discover] api_url`) and
+//! lists the results, most voted first.  Each row shows:
+//!   name  ·  codec bitrate · language  ·  country  ·  tags
+//!
+//! Keybindings (when focused):
+//!   / or s   — search: words match the name; tag:T country:C codec:X narrow it
+//!   j/k ↑↓   — navigate
+//!   Enter    — preview the selected station through the proxy
+//!   a        — add it to the station list and stations.toml
+//!   y        — copy its stream URL
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyEventKind, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Borders, Paragraph},
+    Frame,
+};
+
+use crate::{
+    action::{Action, ComponentId},
+    app_state::AppState,
+    component::Component,
+    theme::{C_LOCATION, C_MUTED, C_PLAYING, C_PRIMARY, C_SECONDARY, C_SELECTION_BG, C_TAG},
+    widgets::{
+        filter_input::{FilterAction, FilterInput},
+        pane_chrome::pane_chrome_borders,
+    },
+};
+
+pub struct DiscoverPanel {
+    pub selected: usize,
+    pub scroll_offset: usize,
+    search_input: FilterInput,
+    pub borders: Borders,
+    /// Dynamic pane number hint (set by app.rs before draw).
+    pub number_key: Option<char>,
+}
+
+impl DiscoverPanel {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            scroll_offset: 0,
+            search_input: FilterInput::new("search stations… (jazz country:france codec:mp3)"),
+            borders: Borders::ALL,
+            number_key: Some('2'),
+        }
+    }
+
+    fn select_up(&mut self, n: usize) {
+        self.selected = self.selected.saturating_sub(n);
+    }
+
+    fn select_down(&mut self, n: usize, len: usize) {
+        self.selected = (self.selected + n).min(len.saturating_sub(1));
+    }
+}
+
+impl Component for DiscoverPanel {
+    fn id(&self) -> ComponentId {
+        ComponentId::DiscoverPanel
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, state: &AppState) -> Vec<Action> {
+        if key.kind == KeyEventKind::Release {
+            return vec![];
+        }
+
+        if self.search_input.is_active() {
+            return match self.search_input.handle_key(key) {
+                FilterAction::Confirmed => {
+                    let query = self.search_input.text().trim().to_string();
+                    self.search_input.deactivate();
+                    self.selected = 0;
+                    self.scroll_offset = 0;
+                    vec![Action::CloseFilter, Action::DiscoverSearch(query)]
+                }
+                FilterAction::Cancelled => vec![Action::CloseFilter],
+                FilterAction::Changed(_) | FilterAction::None => vec![],
+            };
+        }
+
+        let results = &state.discover.stations;
+        let len = results.len();
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.select_up(1),
+            KeyCode::Down | KeyCode::Char('j') => self.select_down(1, len),
+            KeyCode::PageUp => self.select_up(10),
+            KeyCode::PageDown => self.select_down(10, len),
+            KeyCode::Home | KeyCode::Char('g') => self.selected = 0,
+            KeyCode::End | KeyCode::Char('G') => self.selected = len.saturating_sub(1),
+
+            KeyCode::Char('/') | KeyCode::Char('s') => {
+                self.search_input.activate();
+                self.search_input.set_value(&state.discover.query);
+                return vec![Action::OpenFilter];
+            }
+
+            KeyCode::Enter => {
+                if let Some(found) = results.get(self.selected) {
+                    return vec![Action::PreviewStation(found.to_station())];
+                }
+            }
+            KeyCode::Char('a') => {
+                if let Some(found) = results.get(self.selected) {
+                    return vec![Action::AddStation(found.to_station())];
+                }
+            }
+            KeyCode::Char('y') => {
+                if let Some(found) = results.get(self.selected) {
+                    return vec![Action::CopyToClipboard(found.stream_url().to_string())];
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn handle_mouse(&mut self, event: MouseEvent, area: Rect, state: &AppState) -> Vec<Action> {
+        let len = state.discover.stations.len();
+        match event.kind {
+            MouseEventKind::ScrollUp => self.select_up(1),
+            MouseEventKind::ScrollDown => self.select_down(1, len),
+            MouseEventKind::Down(ratatui::crossterm::event::MouseButton::Left) => {
+                let rel_row = event.row.saturating_sub(area.y + 1) as usize;
+                let target = self.scroll_offset + rel_row;
+                if target < len {
+                    self.selected = target;
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn on_action(&mut self, action: &Action, _state: &AppState) -> Vec<Action> {
+        if let Action::ClearFilter = action {
+            self.search_input.clear();
+            self.search_input.deactivate();
+        }
+        vec![]
+    }
+
+    fn collapse_summary(&self, state: &AppState) -> Option<String> {
+        let found = &state.discover;
+        (!found.query.is_empty())
+            .then(|| format!("{} results for \"{}\"", found.stations.len(), found.query))
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect, focused: bool, state: &AppState) {
+        if area.height == 0 {
+            return;
+        }
+
+        let block = pane_chrome_borders("discover", self.number_key, focused, None, self.borders);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let found = &state.discover;
+        let total = found.stations.len();
+        let height = inner.height as usize;
+
+        let status = if found.searching {
+            Some("  searching…".to_string())
+        } else if let Some(e) = &found.error {
+            Some(format!("  search failed: {}", e))
+        } else if total == 0 && found.query.is_empty() {
+            Some("  press / to search the station directory".to_string())
+        } else if total == 0 {
+            Some(format!("  no stations match \"{}\"", found.query))
+        } else {
+            None
+        };
+        if let Some(msg) = status {
+            frame.render_widget(
+                Paragraph::new(Span::styled(msg, Style::default().fg(C_MUTED))),
+                inner,
+            );
+        } else {
+            self.selected = self.selected.min(total - 1);
+            if self.selected < self.scroll_offset {
+                self.scroll_offset = self.selected;
+            } else if self.selected >= self.scroll_offset + height {
+                self.scroll_offset = self.selected.saturating_sub(height.saturating_sub(1));
+            }
+
+            let stations = &state.daemon_state.stations;
+            let previewing = state.daemon_state.preview.as_ref().map(|s| s.url.as_str());
+            let lines: Vec<Line> = found
+                .stations
+                .iter()
+                .enumerate()
+                .skip(self.scroll_offset)
+                .take(height)
+                .map(|(i, entry)| {
+                    let row_style = if i == self.selected && focused {
+                        Style::default()
+                            .fg(C_PRIMARY)
+                            .bg(C_SELECTION_BG)
+                            .add_modifier(Modifier::BOLD)
+                    } else if i == self.selected {
+                        Style::default().fg(C_PRIMARY)
+                    } else {
+                        Style::default().fg(C_SECONDARY)
+                    };
+                    let url = entry.stream_url();
+                    let marker = if previewing == Some(url) {
+                        Span::styled(" ▶ ", Style::default().fg(C_PLAYING))
+                    } else if stations.iter().any(|s| s.url == url) {
+                        Span::styled(" ✓ ", Style::default().fg(C_PLAYING))
+                    } else {
+                        Span::raw("   ")
+                    };
+                    let mut spans = vec![
+                        marker,
+                        Span::styled(entry.name.trim().to_string(), row_style),
+                    ];
+                    let format = entry.format_label();
+                    if !format.is_empty() {
+                        spans.push(Span::styled(
+                            format!("  {}", format),
+                            Style::default().fg(C_MUTED),
+                        ));
+                    }
+                    if !entry.country.is_empty() {
+                        spans.push(Span::styled(
+                            format!("  {}", entry.country),
+                            Style::default().fg(C_LOCATION),
+                        ));
+                    }
+                    let tags: Vec<&str> = entry
+                        .tags
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .take(3)
+                        .collect();
+                    if !tags.is_empty() {
+                        spans.push(Span::styled(
+                            format!("  {}", tags.join(" · ")),
+                            Style::default().fg(C_TAG),
+                        ));
+                    }
+                    Line::from(spans)
+                })
+                .collect();
+            frame.render_widget(Paragraph::new(lines), inner);
+        }
+
+        if self.search_input.is_active() {
+            let bar = Rect {
+                y: inner.y + inner.height.saturating_sub(1),
+                height: 1,
+                ..inner
+            };
+            self.search_input.draw(frame, bar);
+        }
+    }
+}
@@ -20,6 +20,7 @@ use crate::{
     app_state::{AppState, FileMetadata, LocalFileEntry},
     component::Component,
     intent::RenderHint,
+    queue::QueueItem,
     theme::{
         C_ACCENT, C_BADGE_ERR, C_BADGE_PENDING, C_CONNECTING, C_LOCATION, C_MUTED, C_PLAYING,
         C_PRIMARY, C_SECONDARY, C_SELECTION_BG, C_STARS,
@@ -377,6 +378,12 @@ impl Component for FileList {
 
             KeyCode::Char('r') => return vec![Action::Random],
             KeyCode::Char('R') => return vec![Action::RandomBack],
+            KeyCode::Char('a') => {
+                if let Some(f) = self.list.selected_item() {
+                    let path = f.path.to_string_lossy().to_string();
+                    return vec![Action::Enqueue(QueueItem::File(path))];
+                }
+            }
             KeyCode::Char('y') => {
                 if let Some(f) = self.list.selected_item() {
                     let text = f.path.to_string_lossy().to_string();
@@ -1,7 +1,9 @@
 //! Header component — 2-row top bar.
 //!
-//! Row 1: now-playing station/file, ICY/show title, location, health badge.
-//! Row 2: VU meter (left half) | seek bar + position (right half, file only).
+//! Row 1: now-playing station/file, ICY/show title, location, health and
+//!        recording badges.
+//! Row 2: VU meter (left half) | seek bar + position (right half; files, and
+//!        stations with a timeshift buffer).
 //!
 //! Not focusable; draws to a 2-row area.
 
@@ -14,7 +16,7 @@ use ratatui::{
     Frame,
 };
 
-use radio_proto::protocol::{MpvHealth, PlaybackStatus};
+use radio_proto::protocol::{MpvHealth, PlaybackStatus, Station};
 
 use crate::{
     action::{Action, ComponentId},
@@ -201,13 +203,73 @@ fn build_row1(state: &AppState) -> Line<'static> {
         _ => None,
     };
 
-    if let Some(path) = ds.current_file.as_ref() {
+    let mut line = if let Some(path) = ds.current_file.as_ref() {
         build_file_row(state, path, health_span)
+    } else if let Some(station) = ds.preview.as_deref() {
+        build_preview_row(state, station, health_span)
     } else if let Some(idx) = ds.current_station {
         build_station_row(state, idx, health_span)
     } else {
         idle_line()
+    };
+    if let Some(upstream) = upstream_span(state) {
+        line.spans.push(upstream);
+    }
+    if let Some(rec) = recording_span(state) {
+        line.spans.push(rec);
+    }
+    if state.auto_identify {
+        line.spans.push(Span::styled(
+            " [auto-ID]",
+            Style::default().fg(C_TAG).add_modifier(Modifier::BOLD),
+        ));
     }
+    line
+}
+
+/// "[reconnecting…]" while the proxy has lost the upstream, then "[mirror]"
+/// when it's streaming a fallback URL and "[⟳ 3]" once it has reconnected.
+fn upstream_span(state: &AppState) -> Option<Span<'static>> {
+    let ds = &state.daemon_state;
+    let upstream = ds.upstream.as_ref()?;
+    if upstream.reconnecting {
+        return Some(Span::styled(
+            " [reconnecting…]",
+            Style::default().fg(C_BADGE_PENDING),
+        ));
+    }
+    let primary = ds
+        .preview
+        .as_deref()
+        .or_else(|| ds.current_station.and_then(|i| ds.stations.get(i)))
+        .map(|s| s.url.as_str());
+    let mut label = String::new();
+    if primary.is_some_and(|url| url != upstream.url) {
+        label.push_str(" [mirror]");
+    }
+    if upstream.reconnects > 0 {
+        label.push_str(&format!(" [⟳ {}]", upstream.reconnects));
+    }
+    (!label.is_empty()).then(|| Span::styled(label, Style::default().fg(C_MUTED)))
+}
+
+/// "● REC 12:34" badge while any stream recording is running.  Names the
+/// station when it isn't the one playing, and counts when there are several.
+fn recording_span(state: &AppState) -> Option<Span<'static>> {
+    let recs = &state.daemon_state.recordings;
+    let oldest = recs.iter().min_by_key(|r| r.started_at)?;
+    let elapsed = (chrono::Local::now().timestamp() - oldest.started_at).max(0) as f64;
+    let label = if recs.len() > 1 {
+        format!(" ● REC ×{} {}", recs.len(), fmt_clock(elapsed))
+    } else if state.daemon_state.current_station == Some(oldest.station_idx) {
+        format!(" ● REC {}", fmt_clock(elapsed))
+    } else {
+        format!(" ● REC {} {}", oldest.station_name, fmt_clock(elapsed))
+    };
+    Some(Span::styled(
+        label,
+        Style::default().fg(C_ACCENT).add_modifier(Modifier::BOLD),
+    ))
 }
 
 fn build_file_row(
@@ -392,6 +454,50 @@ fn build_station_row(
     Line::from(spans)
 }
 
+/// A station previewed from the discover pane: not in the list, so no
+/// stars, NTS or poll data — just its name, format and live ICY title.
+fn build_preview_row(
+    state: &AppState,
+    station: &Station,
+    health_span: Option<Span<'static>>,
+) -> Line<'static> {
+    let ds = &state.daemon_state;
+    let (icon, icon_color): (&str, Color) = match ds.playback_status {
+        PlaybackStatus::Playing => ("▶", C_PLAYING),
+        PlaybackStatus::Paused => ("⏸", C_CONNECTING),
+        PlaybackStatus::Connecting => ("◔", C_CONNECTING),
+        PlaybackStatus::Error => ("⛔", C_ACCENT),
+        PlaybackStatus::Idle => ("■", C_MUTED),
+    };
+
+    let mut spans: Vec<Span> = vec![
+        Span::raw(" "),
+        Span::styled(icon, Style::default().fg(icon_color)),
+        Span::raw(" "),
+        Span::styled("[preview] ", Style::default().fg(C_TAG)),
+        Span::styled(
+            station.name.clone(),
+            Style::default()
+                .fg(title_text_color(state))
+                .add_modifier(Modifier::BOLD),
+        ),
+    ];
+    if !station.description.is_empty() {
+        spans.push(Span::styled(
+            format!("  {}", station.description),
+            Style::default().fg(C_MUTED),
+        ));
+    }
+    if let Some(title) = ds.icy_title.as_ref().filter(|t| !t.is_empty()) {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(title.clone(), Style::default().fg(C_NETWORK)));
+    }
+    if let Some(hs) = health_span {
+        spans.push(hs);
+    }
+    Line::from(spans)
+}
+
 fn idle_line() -> Line<'static> {
     Line::from(vec![
         Span::raw(" "),
@@ -414,7 +520,9 @@ fn draw_row2(frame: &mut Frame, area: Rect, state: &AppState, meter_style: Meter
         PlaybackStatus::Playing | PlaybackStatus::Paused | PlaybackStatus::Connecting
     );
     let has_file = ds.current_file.is_some();
-    let has_seek = has_file && ds.time_pos_secs.is_some();
+    // Stations only report a timeline while the proxy's timeshift buffer is up.
+    let has_timeshift = !has_file && ds.current_station.is_some() && ds.duration_secs.is_some();
+    let has_seek = (has_file || has_timeshift) && ds.time_pos_secs.is_some();
 
     let (meter_area, seek_area) = if has_seek && area.width >= 20 {
         let meter_w = (area.width * 4) / 10;
@@ -431,14 +539,41 @@ fn draw_row2(frame: &mut Frame, area: Rect, state: &AppState, meter_style: Meter
     // Draw VU meter using the new component
     vu_meter::draw_vu_meter(frame, meter_area, state, is_playing, meter_style);
 
-    // Draw seek bar (file only)
+    // Draw seek bar (file) or timeshift bar (station)
     if let Some(seek_area) = seek_area {
         if let Some(pos) = ds.time_pos_secs {
-            draw_seek_bar(frame, seek_area, pos, ds.duration_secs.unwrap_or(0.0));
+            let duration = ds.duration_secs.unwrap_or(0.0);
+            if has_timeshift {
+                draw_timeshift_bar(frame, seek_area, pos, duration);
+            } else {
+                draw_seek_bar(frame, seek_area, pos, duration);
+            }
         }
     }
 }
 
+/// Station variant of the seek bar: the bar spans the buffered window and
+/// the label reads "-03:12 behind live", or "live" at the edge.
+fn draw_timeshift_bar(frame: &mut Frame, area: Rect, pos: f64, window: f64) {
+    let w = area.width as usize;
+    let behind = (window - pos).max(0.0);
+    // Matches the core's live threshold: a second or two of drift is still live.
+    let (label, label_color) = if behind < 2.0 {
+        (" live".to_string(), C_PLAYING)
+    } else {
+        (format!(" -{} behind live", fmt_clock(behind)), C_CONNECTING)
+    };
+    let bar_w = w.saturating_sub(label.chars().count() + 1);
+    let bar = smooth_bar(pos, window, bar_w);
+
+    let line = Line::from(vec![
+        Span::raw(" "),
+        Span::styled(bar, Style::default().fg(C_MUTED)),
+        Span::styled(label, Style::default().fg(label_color)),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
 fn draw_seek_bar(frame: &mut Frame, area: Rect, pos: f64, duration: f64) {
     let w = area.width as usize;
     let label = format!(
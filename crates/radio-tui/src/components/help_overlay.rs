@@ -80,7 +80,7 @@ impl Component for HelpOverlay {
             return;
         }
 
-        let popup = centered_rect(68, 34, area);
+        let popup = centered_rect(68, 48, area);
 
         let help_lines: Vec<Line> = vec![
             Line::from(Span::styled(
@@ -95,11 +95,19 @@ impl Component for HelpOverlay {
             help_row("enter", "play/stop selected station or file"),
             help_row("space", "toggle pause/play"),
             help_row("← / →  or  - / +", "volume down / up"),
-            help_row(", / .", "seek file ±30s (Shift = ±5m)"),
+            help_row(", / .", "seek file or rewind station ±30s (Shift = ±5m)"),
+            help_row("e", "jump back to live (timeshifted station)"),
             help_row("n / P / r / R", "next / prev / random / random back"),
             help_row("m", "mute"),
             help_row("i", "identify song"),
+            help_row("I", "toggle auto-ID (identify each new track)"),
             help_row("d", "download NTS show"),
+            help_row("x  (Songs pane)", "export history (week.md since:7d …)"),
+            help_row("/ E D M  (Songs pane)", "search / correct / delete / merge dupes"),
+            help_row("w / W", "record station start/stop / cancel"),
+            help_row("w  (NTS pane)", "record / unrecord selected upcoming show"),
+            help_row("z", "sleep timer: 15/30/45/60/90 min, off"),
+            help_row("Z", "wake-up alarm for selected station/file (or cancel)"),
             Line::from(""),
             Line::from(Span::styled(
                 " navigation & panes",
@@ -109,10 +117,12 @@ impl Component for HelpOverlay {
             help_row("pg up / pg dn", "jump 10 rows"),
             help_row("home / end  or  g / G", "jump first / last"),
             help_row("tab / shift-tab", "focus next / previous pane"),
-            help_row("1 / 2 / 3 / 4", "focus pane slot"),
+            help_row("1 … 5", "focus pane slot"),
             help_row("f", "switch Radio ↔ Files workspace"),
             help_row("! / @", "toggle NTS 1 / NTS 2 panel"),
             help_row("o", "toggle scope panel"),
+            help_row("b", "toggle discover panel (search the station directory)"),
+            help_row("H", "toggle stream diagnostics panel"),
             help_row("_  or  |", "toggle right pane full width"),
             Line::from(""),
             Line::from(Span::styled(
@@ -123,6 +133,8 @@ impl Component for HelpOverlay {
             help_row("s / S", "cycle sort forward / backward"),
             help_row("*", "cycle stars on selected item"),
             help_row("y", "copy selected url/text/path"),
+            help_row("A E C D  (stations)", "new / edit / copy / delete station"),
+            help_row("X  (stations)", "check every station, mark dead ones"),
             help_row("J", "jump to current playing item"),
             help_row("c", "collapse focused pane"),
             help_row("K / L", "toggle keys bar / log panel"),
@@ -130,12 +142,24 @@ impl Component for HelpOverlay {
             help_row("?", "toggle this help overlay"),
             help_row("q / Ctrl+C", "quit"),
             Line::from(""),
+            Line::from(Span::styled(
+                " queue",
+                Style::default().fg(C_MUTED).add_modifier(Modifier::BOLD),
+            )),
+            help_row("a", "add selected file/station to the queue"),
+            help_row("n  (Files)", "play next in queue (auto at end of file)"),
+            help_row("shift-↑ / ↓", "move queue entry (queue pane)"),
+            help_row("x / X / s", "remove / clear / shuffle (queue pane)"),
+            help_row("S / l", "save queue as playlist / load a playlist"),
+            Line::from(""),
             Line::from(Span::styled(
                 " scope (when focused)",
                 Style::default().fg(C_MUTED).add_modifier(Modifier::BOLD),
             )),
+            help_row("s", "cycle scope / spectrum / spectrogram"),
             help_row("↑ / ↓ / ← / →", "adjust scale/samples (Shift = coarse)"),
-            help_row("esc", "reset scope scale + sample window"),
+            help_row("", "spectrum: dB range / FFT window"),
+            help_row("esc", "reset scale, window and range"),
             Line::from(""),
             Line::from(Span::styled(
                 " press ? or esc to close",
@@ -1,3 +1,5 @@
+pub mod diagnostics_panel;
+pub mod discover_panel;
 pub mod file_list;
 pub mod file_meta;
 pub mod header;
@@ -5,6 +7,7 @@ pub mod help_overlay;
 pub mod icy_ticker;
 pub mod log_panel;
 pub mod nts_panel;
+pub mod queue_panel;
 pub mod scope_panel;
 pub mod songs_ticker;
 pub mod station_list;
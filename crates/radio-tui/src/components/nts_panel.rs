@@ -1,7 +1,8 @@
 //! NtsPanel component — NTS live schedule panel.
 //!
 //! Shows current show + upcoming schedule for one NTS channel.
-//! Supports scrolling through content taller than the panel.
+//! Supports scrolling through content taller than the panel.  j/k move a
+//! cursor over the upcoming shows; `w` marks the selected one for recording.
 
 use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyEventKind, MouseEvent, MouseEventKind};
 use ratatui::{
@@ -16,7 +17,9 @@ use crate::{
     action::{Action, ComponentId},
     app_state::{AppState, NtsChannel, NtsShow},
     component::Component,
-    theme::{C_ACCENT, C_FILTER_BG, C_LOCATION, C_MUTED, C_PRIMARY, C_SECONDARY, C_TAG},
+    theme::{
+        C_ACCENT, C_FILTER_BG, C_LOCATION, C_MUTED, C_PRIMARY, C_SECONDARY, C_SELECTION_BG, C_TAG,
+    },
     widgets::pane_chrome::pane_chrome_borders,
 };
 use ratatui::widgets::Borders;
@@ -34,6 +37,11 @@ pub struct NtsPanel {
     pub borders: Borders,
     /// Dynamic pane number hint (set by app.rs before draw).
     pub number_key: Option<char>,
+    /// Cursor into `NtsChannel.upcoming`.
+    pub selected: usize,
+    /// Scroll the cursor into view on the next draw (set when it moves, so
+    /// paging through the description isn't undone).
+    follow_selection: bool,
 }
 
 impl NtsPanel {
@@ -46,6 +54,8 @@ impl NtsPanel {
             scroll_x: 0,
             borders: Borders::ALL,
             number_key,
+            selected: 0,
+            follow_selection: false,
         }
     }
 
@@ -67,8 +77,17 @@ impl NtsPanel {
 
     // ── Full-pane (right-panel) content ──────────────────────────────────────
 
-    fn build_lines(&self, ch: &NtsChannel, area_width: u16) -> Vec<Line<'static>> {
+    /// Build the full-pane lines.  `cursor` highlights an upcoming show; the
+    /// second return value is the line index of that show.
+    fn build_lines(
+        &self,
+        ch: &NtsChannel,
+        area_width: u16,
+        cursor: Option<usize>,
+        state: &AppState,
+    ) -> (Vec<Line<'static>>, Option<usize>) {
         let mut lines: Vec<Line<'static>> = Vec::new();
+        let mut cursor_line = None;
         let now = &ch.now;
 
         // Live / Replay indicator + show title
@@ -149,9 +168,9 @@ impl NtsPanel {
                 " upcoming".to_string(),
                 Style::default().fg(C_MUTED).add_modifier(Modifier::BOLD),
             )));
-            for show in ch.upcoming.iter() {
+            for (i, show) in ch.upcoming.iter().enumerate() {
                 let mut spans = vec![
-                    Span::raw("   "),
+                    record_marker(self.is_scheduled(show, state), "  "),
                     Span::styled(fmt_time_range(show), Style::default().fg(C_SECONDARY)),
                     Span::raw("  "),
                     Span::styled(show.broadcast_title.clone(), Style::default().fg(C_PRIMARY)),
@@ -174,11 +193,25 @@ impl NtsPanel {
                         Style::default().fg(C_MUTED),
                     ));
                 }
-                lines.push(Line::from(spans));
+                let mut line = Line::from(spans);
+                if cursor == Some(i) {
+                    line = line.style(Style::default().bg(C_SELECTION_BG));
+                    cursor_line = Some(lines.len());
+                }
+                lines.push(line);
             }
         }
 
-        lines
+        (lines, cursor_line)
+    }
+
+    fn is_scheduled(&self, show: &NtsShow, state: &AppState) -> bool {
+        let start = show.start.timestamp();
+        state
+            .daemon_state
+            .scheduled
+            .iter()
+            .any(|s| s.is_same_slot(self.channel, start))
     }
 
     // ── Compact popup (hover overlay) ────────────────────────────────────────
@@ -274,7 +307,12 @@ impl NtsPanel {
 
     /// Build lines for the RIGHT column: upcoming schedule (next N, one line each).
     /// Format: `HH:MM – HH:MM  Title  location`
-    fn build_right_lines(&self, ch: &NtsChannel, _col_width: u16) -> Vec<Line<'static>> {
+    fn build_right_lines(
+        &self,
+        ch: &NtsChannel,
+        _col_width: u16,
+        state: &AppState,
+    ) -> Vec<Line<'static>> {
         let mut lines: Vec<Line<'static>> = Vec::new();
 
         lines.push(Line::from(Span::styled(
@@ -293,6 +331,7 @@ impl NtsPanel {
         for show in ch.upcoming.iter().take(COMPACT_UPCOMING) {
             let time_str = fmt_time_range(show);
             let mut spans = vec![
+                record_marker(self.is_scheduled(show, state), ""),
                 Span::styled(time_str, Style::default().fg(C_SECONDARY)),
                 Span::raw("  "),
                 Span::styled(show.broadcast_title.clone(), Style::default().fg(C_PRIMARY)),
@@ -311,11 +350,16 @@ impl NtsPanel {
 
     /// Returns the number of inner rows needed to display the compact popup
     /// given an inner width. Used by app.rs to size the overlay before drawing.
-    pub fn compact_content_height(&self, ch: &NtsChannel, inner_width: u16) -> u16 {
+    pub fn compact_content_height(
+        &self,
+        ch: &NtsChannel,
+        inner_width: u16,
+        state: &AppState,
+    ) -> u16 {
         // Split the same way draw_compact does: left 60%, right 40% (min widths applied)
         let (left_w, right_w) = compact_col_widths(inner_width);
         let left_h = self.build_left_lines(&ch.now, left_w).len();
-        let right_h = self.build_right_lines(ch, right_w).len();
+        let right_h = self.build_right_lines(ch, right_w, state).len();
         left_h.max(right_h) as u16
     }
 
@@ -324,7 +368,7 @@ impl NtsPanel {
     pub fn compact_content_height_for_state(&self, state: &AppState, overlay_width: u16) -> u16 {
         let inner_width = overlay_width.saturating_sub(2);
         if let Some(ch) = self.channel_data(state) {
-            self.compact_content_height(ch, inner_width)
+            self.compact_content_height(ch, inner_width, state)
         } else {
             1
         }
@@ -375,7 +419,7 @@ impl NtsPanel {
         let right_area = cols[2];
 
         let left_lines = self.build_left_lines(&ch.now, left_w);
-        let right_lines = self.build_right_lines(ch, right_w);
+        let right_lines = self.build_right_lines(ch, right_w, state);
 
         frame.render_widget(
             Paragraph::new(left_lines)
@@ -397,16 +441,29 @@ impl Component for NtsPanel {
         ComponentId::NtsPanel
     }
 
-    fn handle_key(&mut self, key: KeyEvent, _state: &AppState) -> Vec<Action> {
+    fn handle_key(&mut self, key: KeyEvent, state: &AppState) -> Vec<Action> {
         if key.kind == KeyEventKind::Release {
             return vec![];
         }
+        let upcoming = self.channel_data(state).map_or(0, |ch| ch.upcoming.len());
         match key.code {
             KeyCode::Up | KeyCode::Char('k') => {
-                self.scroll = self.scroll.saturating_sub(1);
+                if self.selected > 0 {
+                    self.selected -= 1;
+                    self.follow_selection = true;
+                } else {
+                    // Already on the first show: reveal the "now" block above it.
+                    self.scroll = 0;
+                }
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                self.scroll += 1;
+                if self.selected + 1 < upcoming {
+                    self.selected += 1;
+                }
+                self.follow_selection = true;
+            }
+            KeyCode::Char('w') if upcoming > 0 => {
+                return vec![Action::ToggleShowRecording(self.channel, self.selected)];
             }
             KeyCode::Left | KeyCode::Char('h') => {
                 self.scroll_x = self.scroll_x.saturating_sub(4);
@@ -423,6 +480,7 @@ impl Component for NtsPanel {
             KeyCode::Home | KeyCode::Char('g') => {
                 self.scroll = 0;
                 self.scroll_x = 0;
+                self.selected = 0;
             }
             _ => {}
         }
@@ -456,6 +514,7 @@ impl Component for NtsPanel {
             Action::ToggleNts(ch) if *ch == self.channel => {
                 self.scroll = 0; // reset scroll when toggled
                 self.scroll_x = 0;
+                self.selected = 0;
             }
             _ => {}
         }
@@ -502,7 +561,21 @@ impl Component for NtsPanel {
             return;
         };
 
-        let lines = self.build_lines(ch, inner.width);
+        // The schedule refreshes under the cursor; keep it on a real row.
+        self.selected = self.selected.min(ch.upcoming.len().saturating_sub(1));
+        let cursor = focused.then_some(self.selected);
+        let (lines, cursor_line) = self.build_lines(ch, inner.width, cursor, state);
+
+        if std::mem::take(&mut self.follow_selection) {
+            if let Some(line) = cursor_line {
+                let height = inner.height as usize;
+                if line < self.scroll {
+                    self.scroll = line;
+                } else if line >= self.scroll + height {
+                    self.scroll = line + 1 - height;
+                }
+            }
+        }
 
         // Clamp scroll
         let max_scroll = lines.len().saturating_sub(inner.height as usize);
@@ -537,6 +610,16 @@ fn compact_col_widths(inner_width: u16) -> (u16, u16) {
     (left_w, right_w)
 }
 
+/// Leading cell of an upcoming-show row: a red dot when it is scheduled for
+/// recording, otherwise `pad`.
+fn record_marker(scheduled: bool, pad: &'static str) -> Span<'static> {
+    if scheduled {
+        Span::styled("● ", Style::default().fg(C_ACCENT))
+    } else {
+        Span::raw(pad)
+    }
+}
+
 fn fmt_time_range(show: &NtsShow) -> String {
     let start = show.start.format("%H:%M").to_string();
     let end = show.end.format("%H:%M").to_string();
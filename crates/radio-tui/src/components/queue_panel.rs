@@ -0,0 +1,295 @@
+//! QueuePanel — the play queue and saved playlists (Files workspace).
+//!
+//! Keybindings (when focused):
+//!   j/k ↑↓        — navigate
+//!   Shift+↑/↓     — move the selected entry up / down
+//!   Enter         — play the selected entry now
+//!   x / Delete    — remove the selected entry
+//!   X             — clear the queue
+//!   s             — shuffle the queue
+//!   S             — save the queue as a playlist (name ending .m3u for M3U)
+//!   l             — show saved playlists; Enter appends one to the queue
+
+use ratatui::crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind,
+};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Borders, Paragraph},
+    Frame,
+};
+
+use crate::{
+    action::{Action, ComponentId},
+    app_state::AppState,
+    component::Component,
+    queue::QueueItem,
+    theme::{C_LOCATION, C_MUTED, C_PRIMARY, C_SECONDARY, C_SELECTION_BG},
+    widgets::{
+        filter_input::{FilterAction, FilterInput},
+        pane_chrome::pane_chrome_borders,
+    },
+};
+
+pub struct QueuePanel {
+    pub selected: usize,
+    pub scroll_offset: usize,
+    /// Showing saved playlists instead of the queue.
+    browsing_playlists: bool,
+    playlist_selected: usize,
+    /// Playlist name prompt for `S`.
+    name_input: FilterInput,
+    pub borders: Borders,
+    /// Dynamic pane number hint (set by app.rs before draw).
+    pub number_key: Option<char>,
+}
+
+impl QueuePanel {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            scroll_offset: 0,
+            browsing_playlists: false,
+            playlist_selected: 0,
+            name_input: FilterInput::new("save queue as… (name.m3u for M3U)"),
+            borders: Borders::ALL,
+            number_key: Some('3'),
+        }
+    }
+
+    fn len(&self, state: &AppState) -> usize {
+        if self.browsing_playlists {
+            state.playlists.len()
+        } else {
+            state.queue.len()
+        }
+    }
+
+    fn cursor(&mut self) -> &mut usize {
+        if self.browsing_playlists {
+            &mut self.playlist_selected
+        } else {
+            &mut self.selected
+        }
+    }
+
+    fn select_up(&mut self, n: usize) {
+        let cursor = self.cursor();
+        *cursor = cursor.saturating_sub(n);
+    }
+
+    fn select_down(&mut self, n: usize, len: usize) {
+        let cursor = self.cursor();
+        *cursor = (*cursor + n).min(len.saturating_sub(1));
+    }
+}
+
+impl Component for QueuePanel {
+    fn id(&self) -> ComponentId {
+        ComponentId::QueuePanel
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, state: &AppState) -> Vec<Action> {
+        if key.kind == KeyEventKind::Release {
+            return vec![];
+        }
+
+        if self.name_input.is_active() {
+            return match self.name_input.handle_key(key) {
+                FilterAction::Confirmed => {
+                    let name = self.name_input.text().trim().to_string();
+                    self.name_input.clear();
+                    if name.is_empty() {
+                        vec![Action::CloseFilter]
+                    } else {
+                        vec![Action::CloseFilter, Action::SavePlaylist(name)]
+                    }
+                }
+                FilterAction::Cancelled => vec![Action::CloseFilter],
+                FilterAction::Changed(_) | FilterAction::None => vec![],
+            };
+        }
+
+        let len = self.len(state);
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Up if shift && !self.browsing_playlists && self.selected > 0 => {
+                self.selected -= 1;
+                return vec![Action::QueueMove(self.selected + 1, -1)];
+            }
+            KeyCode::Down if shift && !self.browsing_playlists && self.selected + 1 < len => {
+                self.selected += 1;
+                return vec![Action::QueueMove(self.selected - 1, 1)];
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.select_up(1),
+            KeyCode::Down | KeyCode::Char('j') => self.select_down(1, len),
+            KeyCode::PageUp => self.select_up(10),
+            KeyCode::PageDown => self.select_down(10, len),
+            KeyCode::Home | KeyCode::Char('g') => *self.cursor() = 0,
+            KeyCode::End | KeyCode::Char('G') => *self.cursor() = len.saturating_sub(1),
+
+            KeyCode::Char('l') => {
+                self.browsing_playlists = !self.browsing_playlists;
+                self.playlist_selected = 0;
+            }
+            KeyCode::Esc if self.browsing_playlists => self.browsing_playlists = false,
+
+            KeyCode::Enter if self.browsing_playlists => {
+                if let Some(name) = state.playlists.get(self.playlist_selected) {
+                    self.browsing_playlists = false;
+                    return vec![Action::LoadPlaylist(name.clone())];
+                }
+            }
+            KeyCode::Enter if self.selected < len => {
+                return vec![Action::QueuePlay(self.selected)];
+            }
+            KeyCode::Char('x') | KeyCode::Delete
+                if !self.browsing_playlists && self.selected < len =>
+            {
+                return vec![Action::QueueRemove(self.selected)];
+            }
+            KeyCode::Char('X') if !self.browsing_playlists => return vec![Action::QueueClear],
+            KeyCode::Char('s') if !self.browsing_playlists => return vec![Action::QueueShuffle],
+            KeyCode::Char('S') if !state.queue.is_empty() => {
+                self.browsing_playlists = false;
+                self.name_input.activate();
+                return vec![Action::OpenFilter];
+            }
+            KeyCode::Char('y') if !self.browsing_playlists => {
+                if let Some(QueueItem::File(path)) = state.queue.get(self.selected) {
+                    return vec![Action::CopyToClipboard(path.clone())];
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn handle_mouse(&mut self, event: MouseEvent, area: Rect, state: &AppState) -> Vec<Action> {
+        let len = self.len(state);
+        match event.kind {
+            MouseEventKind::ScrollUp => self.select_up(1),
+            MouseEventKind::ScrollDown => self.select_down(1, len),
+            MouseEventKind::Down(ratatui::crossterm::event::MouseButton::Left) => {
+                let rel_row = event.row.saturating_sub(area.y + 1) as usize;
+                let target = self.scroll_offset + rel_row;
+                if target < len {
+                    *self.cursor() = target;
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn on_action(&mut self, action: &Action, _state: &AppState) -> Vec<Action> {
+        if let Action::ClearFilter = action {
+            self.name_input.clear();
+            self.name_input.deactivate();
+        }
+        vec![]
+    }
+
+    fn collapse_summary(&self, state: &AppState) -> Option<String> {
+        state
+            .queue
+            .first()
+            .map(|next| format!("{} queued · next: {}", state.queue.len(), next.label()))
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect, focused: bool, state: &AppState) {
+        if area.height == 0 {
+            return;
+        }
+
+        let title = if self.browsing_playlists {
+            "playlists"
+        } else {
+            "queue"
+        };
+        let block = pane_chrome_borders(title, self.number_key, focused, None, self.borders);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let labels: Vec<(String, Option<&str>)> = if self.browsing_playlists {
+            state.playlists.iter().map(|n| (n.clone(), None)).collect()
+        } else {
+            state
+                .queue
+                .iter()
+                .map(|item| match item {
+                    QueueItem::File(_) => (item.label(), None),
+                    QueueItem::Station(_) => (item.label(), Some("station")),
+                })
+                .collect()
+        };
+
+        let total = labels.len();
+        let height = inner.height as usize;
+        if total == 0 {
+            let msg = if self.browsing_playlists {
+                "  no saved playlists — press S in the queue to save one"
+            } else {
+                "  queue is empty — press a on a file or station to add it"
+            };
+            frame.render_widget(
+                Paragraph::new(Span::styled(msg, Style::default().fg(C_MUTED))),
+                inner,
+            );
+        } else {
+            let selected = {
+                let cursor = self.cursor();
+                *cursor = (*cursor).min(total - 1);
+                *cursor
+            };
+            if selected < self.scroll_offset {
+                self.scroll_offset = selected;
+            } else if selected >= self.scroll_offset + height {
+                self.scroll_offset = selected.saturating_sub(height.saturating_sub(1));
+            }
+
+            let lines: Vec<Line> = labels
+                .iter()
+                .enumerate()
+                .skip(self.scroll_offset)
+                .take(height)
+                .map(|(i, (label, kind))| {
+                    let row_style = if i == selected && focused {
+                        Style::default()
+                            .fg(C_PRIMARY)
+                            .bg(C_SELECTION_BG)
+                            .add_modifier(Modifier::BOLD)
+                    } else if i == selected {
+                        Style::default().fg(C_PRIMARY)
+                    } else {
+                        Style::default().fg(C_SECONDARY)
+                    };
+                    let mut spans = vec![Span::styled(
+                        format!(" {:>3}  ", i + 1),
+                        Style::default().fg(C_MUTED),
+                    )];
+                    spans.push(Span::styled(label.clone(), row_style));
+                    if let Some(kind) = kind {
+                        spans.push(Span::styled(
+                            format!("  {}", kind),
+                            Style::default().fg(C_LOCATION),
+                        ));
+                    }
+                    Line::from(spans)
+                })
+                .collect();
+            frame.render_widget(Paragraph::new(lines), inner);
+        }
+
+        if self.name_input.is_active() {
+            let bar = Rect {
+                y: inner.y + inner.height.saturating_sub(1),
+                height: 1,
+                ..inner
+            };
+            self.name_input.draw(frame, bar);
+        }
+    }
+}
@@ -1,5 +1,9 @@
 use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
 use ratatui::{
+    buffer::Buffer,
     layout::Rect,
     style::{Color, Style},
     symbols::Marker,
@@ -8,21 +12,64 @@ use ratatui::{
 };
 
 use crate::app_state::AppState;
+use crate::scope::spectrum::{self, Analyzer, PeakHold};
 use crate::scope::{oscilloscope::Oscilloscope, DataSet, DisplayMode, GraphConfig, Matrix};
+use crate::theme::{C_BG, C_MUTED, C_PRIMARY};
 
 /// Default number of PCM samples displayed per scope frame.
 /// At 44100 Hz: 4096 samples ≈ 93 ms of audio.
 pub const SCOPE_SAMPLES: usize = 4096;
 
+/// Default dB span of the spectrum and spectrogram (top is 0 dBFS).
+const RANGE_DB: f32 = 70.0;
+/// Spectrogram scroll rate: one column per step.
+const SPECTROGRAM_STEP: Duration = Duration::from_millis(50);
+
+const BAR_LOW: (u8, u8, u8) = (0, 200, 180);
+const BAR_HIGH: (u8, u8, u8) = (255, 184, 80);
+const EIGHTHS: [&str; 9] = [" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+
+/// What the scope pane shows; `s` cycles through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeMode {
+    Oscilloscope,
+    /// Log-frequency bars with peak-hold markers.
+    Spectrum,
+    /// Scrolling waterfall of the same bands, newest column on the right.
+    Spectrogram,
+}
+
+impl ScopeMode {
+    fn next(self) -> Self {
+        match self {
+            ScopeMode::Oscilloscope => ScopeMode::Spectrum,
+            ScopeMode::Spectrum => ScopeMode::Spectrogram,
+            ScopeMode::Spectrogram => ScopeMode::Oscilloscope,
+        }
+    }
+}
+
 pub struct ScopePanel {
+    mode: ScopeMode,
     oscilloscope: Oscilloscope,
     graph_cfg: GraphConfig,
     matrix: Matrix,
+    analyzer: Analyzer,
+    /// FFT window from `[viz] fft_size`, restored by Esc.
+    default_fft_size: usize,
+    range_db: f32,
+    peaks: PeakHold,
+    /// Spectrogram columns, oldest first; each holds one level per half-cell.
+    history: VecDeque<Vec<f32>>,
+    last_column: Instant,
 }
 
-impl Default for ScopePanel {
-    fn default() -> Self {
+impl ScopePanel {
+    /// `fft_size` and `peak_hold_ms` come from `[viz]` in config.toml.
+    pub fn new(fft_size: usize, peak_hold_ms: u64) -> Self {
+        let analyzer = Analyzer::new(fft_size);
         Self {
+            mode: ScopeMode::Oscilloscope,
             oscilloscope: Oscilloscope::default(),
             graph_cfg: GraphConfig {
                 // scale=1.0: full PCM range (-1..1) fits exactly in display.
@@ -38,17 +85,39 @@ impl Default for ScopePanel {
                 axis_color: Color::Rgb(40, 40, 40),
             },
             matrix: vec![Vec::new()],
+            default_fft_size: analyzer.size(),
+            analyzer,
+            range_db: RANGE_DB,
+            peaks: PeakHold::new(Duration::from_millis(peak_hold_ms)),
+            history: VecDeque::new(),
+            last_column: Instant::now(),
         }
     }
-}
 
-impl ScopePanel {
     /// Handle scope-tui-style keys.
     ///
+    /// s                  — cycle oscilloscope / spectrum / spectrogram
+    ///
+    /// Oscilloscope:
     /// Up / Down          — scale ± 0.01 (× 10 with Shift)
     /// Left / Right       — samples ± 25  (× 10 with Shift)
     /// Esc                — reset to defaults
+    ///
+    /// Spectrum / spectrogram:
+    /// Up / Down          — dB range ± 5 (× 4 with Shift)
+    /// Left / Right       — halve / double the FFT window
+    /// Esc                — reset to defaults
     pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Char('s') {
+            self.mode = self.mode.next();
+            self.history.clear();
+            return;
+        }
+        if self.mode != ScopeMode::Oscilloscope {
+            self.handle_spectrum_key(key);
+            return;
+        }
+
         let shift = key.modifiers.contains(KeyModifiers::SHIFT);
         let magnitude: f64 = if shift { 10.0 } else { 1.0 };
 
@@ -86,7 +155,40 @@ impl ScopePanel {
         }
     }
 
+    fn handle_spectrum_key(&mut self, key: KeyEvent) {
+        let step = if key.modifiers.contains(KeyModifiers::SHIFT) {
+            20.0
+        } else {
+            5.0
+        };
+        match key.code {
+            KeyCode::Up => self.range_db = (self.range_db + step).min(120.0),
+            KeyCode::Down => self.range_db = (self.range_db - step).max(20.0),
+            KeyCode::Right => self.set_fft_size(self.analyzer.size() * 2),
+            KeyCode::Left => self.set_fft_size(self.analyzer.size() / 2),
+            KeyCode::Esc => {
+                self.range_db = RANGE_DB;
+                self.set_fft_size(self.default_fft_size);
+            }
+            _ => {}
+        }
+    }
+
+    fn set_fft_size(&mut self, size: usize) {
+        if spectrum::clamp_fft_size(size) != self.analyzer.size() {
+            self.analyzer = Analyzer::new(size);
+        }
+    }
+
     pub fn draw(&mut self, frame: &mut Frame, area: Rect, state: &AppState) {
+        match self.mode {
+            ScopeMode::Oscilloscope => self.draw_oscilloscope(frame, area, state),
+            ScopeMode::Spectrum => self.draw_spectrum(frame.buffer_mut(), area, state),
+            ScopeMode::Spectrogram => self.draw_spectrogram(frame.buffer_mut(), area, state),
+        }
+    }
+
+    fn draw_oscilloscope(&mut self, frame: &mut Frame, area: Rect, state: &AppState) {
         let n = self.graph_cfg.samples as usize;
         let ring = &state.pcm_ring;
 
@@ -128,3 +230,114 @@ impl ScopePanel {
         frame.render_widget(chart, area);
     }
 }
+
+// ── Spectrum / spectrogram ────────────────────────────────────────────────────
+
+impl ScopePanel {
+    /// 0..1 position of `db` within the display range.
+    fn level_frac(&self, db: f32) -> f32 {
+        ((db + self.range_db) / self.range_db).clamp(0.0, 1.0)
+    }
+
+    fn draw_spectrum(&mut self, buf: &mut Buffer, area: Rect, state: &AppState) {
+        buf.set_style(area, Style::default().bg(C_BG));
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        let levels = self.analyzer.bands(&state.pcm_ring, area.width as usize);
+        let peaks = if self.peaks.enabled() {
+            self.peaks.update(&levels, Instant::now()).to_vec()
+        } else {
+            Vec::new()
+        };
+
+        let h = area.height as usize;
+        for (i, &db) in levels.iter().enumerate() {
+            let x = area.x + i as u16;
+            let eighths = (self.level_frac(db) * (h * 8) as f32).round() as usize;
+            for row in 0..h {
+                let y = area.bottom() - 1 - row as u16;
+                let fill = eighths.saturating_sub(row * 8).min(8);
+                let color = lerp_rgb(BAR_LOW, BAR_HIGH, row as f32 / h as f32);
+                if fill > 0 {
+                    buf[(x, y)].set_symbol(EIGHTHS[fill]).set_fg(color);
+                }
+            }
+            if let Some(&peak) = peaks.get(i) {
+                let peak_row = (self.level_frac(peak) * h as f32) as usize;
+                if peak > -self.range_db && peak_row * 8 >= eighths && peak_row < h {
+                    let y = area.bottom() - 1 - peak_row as u16;
+                    buf[(x, y)].set_symbol("▔").set_fg(C_PRIMARY);
+                }
+            }
+        }
+        self.draw_label(buf, area);
+    }
+
+    fn draw_spectrogram(&mut self, buf: &mut Buffer, area: Rect, state: &AppState) {
+        buf.set_style(area, Style::default().bg(C_BG));
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        // Two bands per cell, via upper half-blocks.
+        let n_bands = area.height as usize * 2;
+        if self.history.back().is_some_and(|c| c.len() != n_bands) {
+            self.history.clear();
+        }
+        if self.last_column.elapsed() >= SPECTROGRAM_STEP || self.history.is_empty() {
+            self.last_column = Instant::now();
+            let column = self.analyzer.bands(&state.pcm_ring, n_bands);
+            self.history.push_back(column);
+        }
+        while self.history.len() > area.width as usize {
+            self.history.pop_front();
+        }
+
+        let x0 = area.right() - self.history.len() as u16;
+        for (i, column) in self.history.iter().enumerate() {
+            let x = x0 + i as u16;
+            for row in 0..area.height {
+                let band = (area.height - 1 - row) as usize * 2;
+                let y = area.y + row;
+                buf[(x, y)]
+                    .set_symbol("▀")
+                    .set_fg(heat(self.level_frac(column[band + 1])))
+                    .set_bg(heat(self.level_frac(column[band])));
+            }
+        }
+        self.draw_label(buf, area);
+    }
+
+    /// FFT window and dB range, top right.
+    fn draw_label(&self, buf: &mut Buffer, area: Rect) {
+        let label = format!(" fft {} · {:.0} dB ", self.analyzer.size(), self.range_db);
+        let width = label.chars().count() as u16;
+        if area.width > width {
+            buf.set_string(
+                area.right() - width,
+                area.y,
+                label,
+                Style::default().fg(C_MUTED).bg(C_BG),
+            );
+        }
+    }
+}
+
+fn lerp_rgb(a: (u8, u8, u8), b: (u8, u8, u8), t: f32) -> Color {
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Color::Rgb(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
+/// Spectrogram colour ramp: background → deep teal → teal → amber → white.
+fn heat(t: f32) -> Color {
+    const STOPS: [(u8, u8, u8); 5] = [
+        (18, 18, 18),
+        (0, 70, 100),
+        BAR_LOW,
+        BAR_HIGH,
+        (255, 245, 230),
+    ];
+    let pos = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let i = (pos as usize).min(STOPS.len() - 2);
+    lerp_rgb(STOPS[i], STOPS[i + 1], pos - i as f32)
+}
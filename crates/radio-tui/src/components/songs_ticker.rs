@@ -1,4 +1,4 @@
-//! SongsTicker — songs.vds recognition history panel.
+//! SongsTicker — the songs database panel.
 //!
 //! Displays recognised songs (newest at top).  Each row shows:
 //!   [source badge]  HH:MM  Artist – Title  ·  station  ·  show
@@ -6,7 +6,11 @@
 //! Keybindings (when focused):
 //!   i        — trigger song recognition (vibra + ICY + NTS pipeline)
 //!   y        — copy display text to clipboard
-//!   /        — open filter
+//!   x        — export the history (prompts for a file name and filters)
+//!   E        — correct the selected row's track
+//!   D / Del  — delete the selected row
+//!   M        — merge repeated detections of the same track
+//!   /        — search the whole database (word prefixes)
 //!   Esc      — clear filter text (first press) / close filter (second press)
 //!   j/k ↑↓   — navigate
 //!   Enter    — open show URL if present
@@ -38,13 +42,19 @@ use ratatui::widgets::Borders;
 const C_VIBRA: Color = Color::Rgb(180, 120, 220); // purple
 const C_ICY: Color = Color::Rgb(80, 160, 220); // blue
 const C_NTS: Color = Color::Rgb(220, 80, 80); // red
+const C_MANUAL: Color = Color::Rgb(120, 200, 120); // green
 
 pub struct SongsTicker {
     pub selected: usize,
     pub scroll_offset: usize,
     pub filter_input: FilterInput,
     pub filter: String,
-    last_visible: Vec<usize>,
+    /// Export prompt for `x`: file name plus `since:`/`until:`/`station:`/`source:`.
+    export_input: FilterInput,
+    /// Correction prompt for `E`, and the job_id of the row being corrected.
+    edit_input: FilterInput,
+    editing: Option<String>,
+    visible_len: usize,
     pub borders: Borders,
     /// Dynamic pane number hint (set by app.rs before draw).
     pub number_key: Option<char>,
@@ -57,7 +67,10 @@ impl SongsTicker {
             scroll_offset: 0,
             filter_input: FilterInput::new("search songs…"),
             filter: String::new(),
-            last_visible: Vec::new(),
+            export_input: FilterInput::new("export songs as… (week.md since:7d station:nts)"),
+            edit_input: FilterInput::new("correct track… (Artist – Title)"),
+            editing: None,
+            visible_len: 0,
             borders: Borders::ALL,
             number_key: Some('3'),
         }
@@ -67,32 +80,13 @@ impl SongsTicker {
         self.filter_input.is_active()
     }
 
-    fn visible_indices(&self, state: &AppState) -> Vec<usize> {
-        let q = self.filter.to_lowercase();
-        // newest first
-        (0..state.songs_history.len())
-            .rev()
-            .filter(|&i| {
-                if q.is_empty() {
-                    return true;
-                }
-                let e = &state.songs_history[i];
-                let mut text = e.display().to_lowercase();
-                if let Some(s) = e.station.as_deref() {
-                    text.push(' ');
-                    text.push_str(&s.to_lowercase());
-                }
-                if let Some(s) = e.nts_show.as_deref() {
-                    text.push(' ');
-                    text.push_str(&s.to_lowercase());
-                }
-                if let Some(s) = e.icy_info.as_deref() {
-                    text.push(' ');
-                    text.push_str(&s.to_lowercase());
-                }
-                q.split_whitespace().all(|term| text.contains(term))
-            })
-            .collect()
+    /// Rows to show, newest first: the search results while searching,
+    /// otherwise the recent history.
+    fn visible<'a>(&self, state: &'a AppState) -> Vec<&'a RecognitionResult> {
+        match &state.song_search {
+            Some(found) if !self.filter.is_empty() => found.iter().collect(),
+            _ => state.songs_history.iter().rev().collect(),
+        }
     }
 
     fn select_up(&mut self, n: usize) {
@@ -103,9 +97,24 @@ impl SongsTicker {
         self.selected = (self.selected + n).min(max.saturating_sub(1));
     }
 
-    fn selected_entry<'a>(&self, state: &'a AppState) -> Option<&'a RecognitionResult> {
-        let idx = self.last_visible.get(self.selected)?;
-        state.songs_history.get(*idx)
+    /// The filter or export prompt, on the pane's last row.
+    fn draw_input(&mut self, frame: &mut Frame, inner: Rect) {
+        let bar = Rect {
+            y: inner.y + inner.height.saturating_sub(1),
+            height: 1,
+            ..inner
+        };
+        if self.export_input.is_active() {
+            self.export_input.draw(frame, bar);
+        } else if self.edit_input.is_active() {
+            self.edit_input.draw(frame, bar);
+        } else if self.filter_input.is_active() {
+            self.filter_input.draw(frame, bar);
+        }
+    }
+
+    pub fn selected_entry<'a>(&self, state: &'a AppState) -> Option<&'a RecognitionResult> {
+        self.visible(state).get(self.selected).copied()
     }
 }
 
@@ -119,6 +128,42 @@ impl Component for SongsTicker {
             return vec![];
         }
 
+        if self.export_input.is_active() {
+            return match self.export_input.handle_key(key) {
+                FilterAction::Confirmed => {
+                    let spec = self.export_input.text().trim().to_string();
+                    self.export_input.clear();
+                    if spec.is_empty() {
+                        vec![Action::CloseFilter]
+                    } else {
+                        vec![Action::CloseFilter, Action::ExportSongs(spec)]
+                    }
+                }
+                FilterAction::Cancelled => vec![Action::CloseFilter],
+                FilterAction::Changed(_) | FilterAction::None => vec![],
+            };
+        }
+
+        if self.edit_input.is_active() {
+            return match self.edit_input.handle_key(key) {
+                FilterAction::Confirmed => {
+                    let track = self.edit_input.text().trim().to_string();
+                    self.edit_input.clear();
+                    match self.editing.take() {
+                        Some(job_id) => {
+                            vec![Action::CloseFilter, Action::CorrectSong(job_id, track)]
+                        }
+                        None => vec![Action::CloseFilter],
+                    }
+                }
+                FilterAction::Cancelled => {
+                    self.editing = None;
+                    vec![Action::CloseFilter]
+                }
+                FilterAction::Changed(_) | FilterAction::None => vec![],
+            };
+        }
+
         if self.filter_input.is_active() {
             match key.code {
                 KeyCode::Up => {
@@ -126,7 +171,7 @@ impl Component for SongsTicker {
                     return vec![];
                 }
                 KeyCode::Down => {
-                    let m = self.last_visible.len();
+                    let m = self.visible_len;
                     self.select_down(1, m);
                     return vec![];
                 }
@@ -134,15 +179,15 @@ impl Component for SongsTicker {
             }
             match self.filter_input.handle_key(key) {
                 FilterAction::Changed(q) => {
-                    self.filter = q;
+                    self.filter = q.clone();
                     self.selected = 0;
                     self.scroll_offset = 0;
-                    return vec![];
+                    return vec![Action::SearchSongs(q)];
                 }
                 FilterAction::Cancelled => {
                     self.filter.clear();
                     self.selected = 0;
-                    return vec![Action::CloseFilter];
+                    return vec![Action::CloseFilter, Action::SearchSongs(String::new())];
                 }
                 FilterAction::Confirmed | FilterAction::None => return vec![],
             }
@@ -151,12 +196,12 @@ impl Component for SongsTicker {
         match key.code {
             KeyCode::Up | KeyCode::Char('k') => self.select_up(1),
             KeyCode::Down | KeyCode::Char('j') => {
-                let m = self.last_visible.len();
+                let m = self.visible_len;
                 self.select_down(1, m);
             }
             KeyCode::PageUp => self.select_up(10),
             KeyCode::PageDown => {
-                let m = self.last_visible.len();
+                let m = self.visible_len;
                 self.select_down(10, m);
             }
             KeyCode::Home | KeyCode::Char('g') => {
@@ -164,7 +209,7 @@ impl Component for SongsTicker {
                 self.scroll_offset = 0;
             }
             KeyCode::End | KeyCode::Char('G') => {
-                self.selected = self.last_visible.len().saturating_sub(1);
+                self.selected = self.visible_len.saturating_sub(1);
             }
 
             KeyCode::Char('/') => {
@@ -182,6 +227,30 @@ impl Component for SongsTicker {
                 return vec![Action::Download];
             }
 
+            KeyCode::Char('x') => {
+                self.export_input.activate();
+                return vec![Action::OpenFilter];
+            }
+
+            KeyCode::Char('E') => {
+                if let Some(e) = self.selected_entry(state) {
+                    self.editing = Some(e.job_id.clone());
+                    self.edit_input.activate();
+                    self.edit_input.set_value(&e.display());
+                    return vec![Action::OpenFilter];
+                }
+            }
+
+            KeyCode::Char('D') | KeyCode::Delete => {
+                if let Some(e) = self.selected_entry(state) {
+                    return vec![Action::DeleteSong(e.job_id.clone())];
+                }
+            }
+
+            KeyCode::Char('M') => {
+                return vec![Action::MergeDuplicateSongs];
+            }
+
             KeyCode::Char('y') => {
                 if let Some(e) = self.selected_entry(state) {
                     let text = e.display();
@@ -208,13 +277,13 @@ impl Component for SongsTicker {
         match event.kind {
             MouseEventKind::ScrollUp => self.select_up(1),
             MouseEventKind::ScrollDown => {
-                let m = self.last_visible.len();
+                let m = self.visible_len;
                 self.select_down(1, m);
             }
             MouseEventKind::Down(ratatui::crossterm::event::MouseButton::Left) => {
                 let rel_row = event.row.saturating_sub(area.y) as usize;
                 let target = self.scroll_offset + rel_row;
-                if target < self.last_visible.len() {
+                if target < self.visible_len {
                     self.selected = target;
                 }
             }
@@ -225,10 +294,19 @@ impl Component for SongsTicker {
 
     fn on_action(&mut self, action: &Action, _state: &AppState) -> Vec<Action> {
         if let Action::ClearFilter = action {
+            let searching = !self.filter.is_empty();
             self.filter.clear();
             self.filter_input.clear();
             self.filter_input.deactivate();
+            self.export_input.clear();
+            self.export_input.deactivate();
+            self.edit_input.clear();
+            self.edit_input.deactivate();
+            self.editing = None;
             self.selected = 0;
+            if searching {
+                return vec![Action::SearchSongs(String::new())];
+            }
         }
         vec![]
     }
@@ -246,8 +324,8 @@ impl Component for SongsTicker {
         let inner = block.inner(area);
         frame.render_widget(block, area);
 
-        let visible = self.visible_indices(state);
-        self.last_visible = visible.clone();
+        let visible = self.visible(state);
+        self.visible_len = visible.len();
         let total = visible.len();
         let height = inner.height as usize;
 
@@ -261,14 +339,7 @@ impl Component for SongsTicker {
                 Paragraph::new(Span::styled(msg, Style::default().fg(C_MUTED))),
                 inner,
             );
-            if self.filter_input.is_active() {
-                let bar = Rect {
-                    y: inner.y + inner.height.saturating_sub(1),
-                    height: 1,
-                    ..inner
-                };
-                self.filter_input.draw(frame, bar);
-            }
+            self.draw_input(frame, inner);
             return;
         }
 
@@ -286,12 +357,10 @@ impl Component for SongsTicker {
             .skip(self.scroll_offset)
             .take(height)
             .enumerate()
-            .map(|(view_i, &orig_idx)| {
+            .map(|(view_i, &entry)| {
                 let abs_i = self.scroll_offset + view_i;
-                let entry = &state.songs_history[orig_idx];
                 let is_selected = abs_i == self.selected;
-                let is_newest = orig_idx == state.songs_history.len().saturating_sub(1)
-                    && self.filter.is_empty();
+                let is_newest = self.filter.is_empty() && abs_i == 0;
 
                 let row_style = if is_selected && focused {
                     Style::default()
@@ -383,15 +452,7 @@ impl Component for SongsTicker {
             .collect();
 
         frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), inner);
-
-        if self.filter_input.is_active() {
-            let bar = Rect {
-                y: inner.y + inner.height.saturating_sub(1),
-                height: 1,
-                ..inner
-            };
-            self.filter_input.draw(frame, bar);
-        }
+        self.draw_input(frame, inner);
     }
 }
 
@@ -417,7 +478,20 @@ fn source_badge_str(entry: &RecognitionResult) -> String {
 fn source_color(entry: &RecognitionResult) -> Color {
     use radio_proto::songs::RecognitionSource;
     let srcs = entry.sources();
-    if srcs.contains(&RecognitionSource::Vibra) {
+    if srcs.contains(&RecognitionSource::Manual) {
+        return C_MANUAL;
+    }
+    if srcs
+        .iter()
+        .any(|s| {
+            matches!(
+                s,
+                RecognitionSource::Vibra
+                    | RecognitionSource::Library
+                    | RecognitionSource::Command(_)
+            )
+        })
+    {
         return C_VIBRA;
     }
     if srcs.contains(&RecognitionSource::Nts) {
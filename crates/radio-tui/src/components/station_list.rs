@@ -1,4 +1,9 @@
 //! StationList component — left pane in Radio workspace.
+//!
+//! Besides playing, it edits the list: `A` new station, `E` edit, `C`
+//! duplicate, `D` delete (see `widgets::station_form`).  Changes go to the
+//! core, which writes them back to `stations.toml`.  `X` checks every
+//! station (`station_check`) and marks the dead ones ✗ with the reason.
 
 use ratatui::crossterm::event::{
     KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind,
@@ -12,6 +17,7 @@ use ratatui::{
 };
 
 use radio_proto::protocol::{PlaybackStatus, Station};
+use radio_proto::state::copy_name;
 use ratatui::widgets::Borders;
 use std::time::Instant;
 
@@ -20,6 +26,7 @@ use crate::{
     app_state::AppState,
     component::Component,
     intent::RenderHint,
+    queue::QueueItem,
     theme::{
         C_BADGE_ERR, C_BADGE_PENDING, C_CONNECTING, C_LOCATION, C_MUTED, C_NETWORK, C_PLAYING,
         C_PRIMARY, C_SECONDARY, C_SELECTION_BG, C_STARS, C_TAG,
@@ -28,6 +35,7 @@ use crate::{
         filter_input::{FilterAction, FilterInput},
         pane_chrome::pane_chrome_borders,
         scrollable_list::ScrollableList,
+        station_form::{FormAction, StationForm},
     },
 };
 
@@ -97,6 +105,11 @@ pub struct StationList {
     pub borders: Borders,
     /// Track last click (row index, time) for double-click detection.
     last_click: Option<(usize, Instant)>,
+    /// Station editor (`A` / `E`), drawn over the list while open.
+    editor: StationForm,
+    /// Select this station once it shows up in the list (after an add,
+    /// rename or duplicate round-trips through the core).
+    select_on_sync: Option<String>,
 }
 
 impl StationList {
@@ -109,6 +122,8 @@ impl StationList {
             jump_from_station: None,
             borders: Borders::ALL,
             last_click: None,
+            editor: StationForm::new(),
+            select_on_sync: None,
         }
     }
 
@@ -117,6 +132,13 @@ impl StationList {
         let stations = state.daemon_state.stations.clone();
         self.list.set_items(stations);
         self.apply_sort(state);
+        if let Some(name) = &self.select_on_sync {
+            let stations = &state.daemon_state.stations;
+            if let Some(idx) = stations.iter().position(|s| &s.name == name) {
+                self.list.set_selected_by_original(idx);
+                self.select_on_sync = None;
+            }
+        }
     }
 
     fn apply_sort(&mut self, state: &AppState) {
@@ -330,6 +352,15 @@ impl StationList {
             spans.push(Span::styled(location, Style::default().fg(C_LOCATION)));
         }
 
+        if let Some(error) = state
+            .station_checks
+            .get(&station.name)
+            .and_then(|c| c.error.as_ref())
+        {
+            spans.push(Span::styled("  ✗ ", Style::default().fg(C_BADGE_ERR)));
+            spans.push(Span::styled(error.clone(), Style::default().fg(C_MUTED)));
+        }
+
         if let Some(show) = state.station_poll_titles.get(&station.name) {
             let s = show.trim();
             if !s.is_empty() {
@@ -379,6 +410,22 @@ impl Component for StationList {
             return vec![];
         }
 
+        if self.editor.is_active() {
+            let stations = &state.daemon_state.stations;
+            return match self.editor.handle_key(key, stations) {
+                FormAction::Save(target, station) => {
+                    self.select_on_sync = Some(station.name.clone());
+                    let save = match target {
+                        Some(idx) => Action::UpdateStation(idx, *station),
+                        None => Action::AddStation(*station),
+                    };
+                    vec![Action::CloseFilter, save]
+                }
+                FormAction::Cancelled => vec![Action::CloseFilter],
+                FormAction::None => vec![],
+            };
+        }
+
         // Filter mode input
         if self.filter_input.is_active() {
             match key.code {
@@ -487,6 +534,12 @@ impl Component for StationList {
                 }
             }
 
+            KeyCode::Char('a') => {
+                if let Some(st) = self.list.selected_item() {
+                    return vec![Action::Enqueue(QueueItem::Station(st.name.clone()))];
+                }
+            }
+
             KeyCode::Char('n') => {
                 self.jump_from_station = Some(state.daemon_state.current_station);
                 return vec![Action::Next];
@@ -506,6 +559,36 @@ impl Component for StationList {
                 }
             }
 
+            KeyCode::Char('A') => {
+                self.editor.open(None, &Station::default());
+                return vec![Action::OpenFilter];
+            }
+            KeyCode::Char('E') => {
+                if let (Some(idx), Some(st)) = (
+                    self.list.selected_original_index(),
+                    self.list.selected_item(),
+                ) {
+                    self.editor.open(Some(idx), st);
+                    return vec![Action::OpenFilter];
+                }
+            }
+            KeyCode::Char('C') => {
+                if let (Some(idx), Some(st)) = (
+                    self.list.selected_original_index(),
+                    self.list.selected_item(),
+                ) {
+                    let stations = &state.daemon_state.stations;
+                    self.select_on_sync = Some(copy_name(&st.name, stations));
+                    return vec![Action::DuplicateStation(idx)];
+                }
+            }
+            KeyCode::Char('D') | KeyCode::Delete => {
+                if let Some(idx) = self.list.selected_original_index() {
+                    return vec![Action::DeleteStation(idx)];
+                }
+            }
+            KeyCode::Char('X') => return vec![Action::CheckStations],
+
             _ => {}
         }
 
@@ -559,6 +642,7 @@ impl Component for StationList {
                 self.list.set_filter("");
                 self.filter_input.clear();
                 self.filter_input.deactivate();
+                self.editor.close();
             }
             _ => {}
         }
@@ -665,6 +749,10 @@ impl Component for StationList {
             };
             self.filter_input.draw(frame, filter_area);
         }
+
+        if self.editor.is_active() {
+            self.editor.draw(frame, inner);
+        }
     }
 }
 
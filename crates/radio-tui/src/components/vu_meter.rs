@@ -8,6 +8,7 @@
 //! - Three visual presets: Studio (classic), LED (discrete), Analog (needle)
 //! - Volume-scaled display (respects system volume)
 //! - Smooth sub-cell precision (1/8th blocks)
+//! - EBU R128 momentary / short-term / integrated loudness readout
 
 use ratatui::{
     layout::Rect,
@@ -18,6 +19,7 @@ use ratatui::{
 };
 
 use crate::app_state::AppState;
+use crate::theme::{C_MUTED, C_SECONDARY};
 use std::sync::Mutex;
 
 // ═════════════════════════════════════════════════════════════════════════════
@@ -751,8 +753,51 @@ pub fn draw_vu_meter(
         (-90.0, -90.0, -90.0)
     };
 
-    let meter_line = build_meter(vu_db, peak_db, instant_db, area.width as usize, style);
+    // Loudness readout on the right when there's room and PCM to measure.
+    let readout = is_playing
+        .then(|| loudness_readout(state))
+        .flatten()
+        .filter(|line| area.width as usize >= line.width() + 24);
+    let meter_width = area.width as usize - readout.as_ref().map_or(0, |l| l.width());
+
+    let meter_line = build_meter(vu_db, peak_db, instant_db, meter_width, style);
     frame.render_widget(Paragraph::new(meter_line), area);
+    if let Some(line) = readout {
+        let width = line.width() as u16;
+        let readout_area = Rect {
+            x: area.right() - width,
+            width,
+            ..area
+        };
+        frame.render_widget(Paragraph::new(line), readout_area);
+    }
+}
+
+/// "M -14.2 S -15.0 I -16.1 LUFS", plus the normalization gain when one is
+/// applied.  `None` until the meter has a momentary reading.
+fn loudness_readout(state: &AppState) -> Option<Line<'static>> {
+    let meter = &state.loudness;
+    let momentary = meter.momentary()?;
+    let value = |v: Option<f32>| match v {
+        Some(v) if v > -70.0 => format!("{:>5.1}", v),
+        _ => "   --".to_string(),
+    };
+    let label = Style::default().fg(C_MUTED);
+    let number = Style::default().fg(C_SECONDARY);
+    let mut spans = vec![
+        Span::styled("  M ", label),
+        Span::styled(value(Some(momentary)), number),
+        Span::styled(" S ", label),
+        Span::styled(value(meter.short_term()), number),
+        Span::styled(" I ", label),
+        Span::styled(value(meter.integrated()), number),
+        Span::styled(" LUFS", label),
+    ];
+    if let Some(gain) = state.loudness_gain_db {
+        spans.push(Span::styled(format!(" {:+.1}dB", gain), label));
+    }
+    spans.push(Span::raw(" "));
+    Some(Line::from(spans))
 }
 
 /// Get a simple meter line without full state (for testing/custom use).
@@ -12,13 +12,23 @@
 /// connection we send `observe_property` for core-idle, pause, icy-title,
 /// time-pos, and duration.  mpv pushes a `property-change` event whenever any
 /// of those values change.  We no longer poll; the 10-second heartbeat tick
-/// only checks process liveness.
+/// only checks process liveness.  A 1-second timeline tick keeps the
+/// "behind live" position moving while a timeshifted station is paused,
+/// drives the sleep timer fade and alarm ramp, and refreshes the stream
+/// diagnostics (the one place mpv is still asked for a property: its cache).
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Instant;
 
 use radio_proto::config::Config;
-use radio_proto::protocol::{Command, MpvHealth, PlaybackStatus, Station};
+use radio_proto::protocol::{
+    Alarm, AlarmTarget, Command, DaemonState, Diagnostics, MpvHealth, PlaybackStatus,
+    ScheduledShow, SleepTimer, Station,
+};
 use radio_proto::state::{
-    load_stations_from_m3u, load_stations_from_toml, parse_m3u_from_str, StateManager,
+    copy_name, insert_station_after_in_toml, load_stations_from_m3u, load_stations_from_toml,
+    parse_m3u_from_str, remove_station_from_toml, update_station_in_toml, validate_station,
+    ListChange, StateManager,
 };
 use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, error, info, warn};
@@ -27,6 +37,10 @@ use crate::mpv::{
     MpvDriver, MpvEvent, MpvHandle, OBS_AUDIO_LEVEL, OBS_CORE_IDLE, OBS_DURATION, OBS_ICY_TITLE,
     OBS_PAUSE, OBS_TIME_POS,
 };
+use crate::proxy::ProxyState;
+use crate::recorder::{self, Recording, StopMode};
+use crate::schedule::{self, Schedule};
+use crate::timers;
 use crate::BroadcastMessage;
 
 // ── DaemonEvent ───────────────────────────────────────────────────────────────
@@ -38,13 +52,53 @@ pub enum DaemonEvent {
     ClientCommand(Command),
     /// Heartbeat — check process liveness.
     HeartbeatTick,
+    /// Refresh the timeshift timeline (it moves even when mpv is paused).
+    TimelineTick,
+    /// The proxy saw a new ICY title on one of its streams.
+    StreamTitle,
     /// Raw mpv unsolicited event (forwarded from reader task).
     MpvEvent(MpvEvent),
     /// Shutdown requested.
-    #[allow(dead_code)]
     Shutdown,
 }
 
+// ── Timeshift ─────────────────────────────────────────────────────────────────
+
+/// Below this many seconds behind, a station counts as live and is played
+/// from the plain proxy URL.
+const TIMESHIFT_LIVE_EPSILON: f64 = 2.0;
+
+/// Where station playback sits relative to the live edge of the proxy's
+/// timeshift buffer.
+#[derive(Debug, Clone, Copy)]
+struct TimeshiftPos {
+    /// How far behind live the proxy was asked to start.
+    behind_at_load: f64,
+    /// First `time-pos` mpv reported for this load, and when.  Later
+    /// positions are measured against it so connect latency doesn't count.
+    anchor: Option<(Instant, f64)>,
+}
+
+impl TimeshiftPos {
+    fn new(behind_at_load: f64) -> Self {
+        Self {
+            behind_at_load,
+            anchor: None,
+        }
+    }
+
+    /// Seconds behind live: wall-clock time since the anchor minus the audio
+    /// played since then.  Grows while paused, holds steady while playing.
+    fn behind(&self, time_pos: Option<f64>) -> f64 {
+        match (self.anchor, time_pos) {
+            (Some((at, pos0)), Some(pos)) => {
+                (self.behind_at_load + at.elapsed().as_secs_f64() - (pos - pos0)).max(0.0)
+            }
+            _ => self.behind_at_load,
+        }
+    }
+}
+
 // ── DaemonCore ────────────────────────────────────────────────────────────────
 
 pub struct DaemonCore {
@@ -82,6 +136,21 @@ pub struct DaemonCore {
     current_station_via_proxy: bool,
     /// Direct URL to fallback to if proxy fails (when current_station_via_proxy is true).
     current_station_direct_url: Option<String>,
+    /// Shared stream proxy (served on PROXY_PORT, tapped by the recorder).
+    proxy: ProxyState,
+    /// Running stream recordings keyed by station index.
+    recordings: HashMap<usize, Recording>,
+    /// NTS shows marked for recording (persisted to `schedule.toml`).
+    schedule: Schedule,
+    /// Scheduled shows whose recording failed to start, so retries on later
+    /// heartbeats don't repeat the error toast.  Keyed by (channel, start).
+    schedule_failed: HashSet<(usize, i64)>,
+    /// Timeshift position of the current station; `None` unless it plays via the proxy.
+    timeshift: Option<TimeshiftPos>,
+    /// Volume ramp of an alarm that just fired: (fired at, ramp seconds).
+    alarm_ramp: Option<(Instant, u64)>,
+    /// Loudness normalization gain (dB) in mpv's filter chain; set by the App.
+    loudness_gain_db: f32,
 }
 
 impl DaemonCore {
@@ -96,6 +165,15 @@ impl DaemonCore {
             stations,
         ));
 
+        let proxy = ProxyState::new(
+            state_manager.clone(),
+            std::time::Duration::from_secs(config.proxy.timeshift_minutes * 60),
+            config.proxy.hls_max_bandwidth,
+        );
+
+        let schedule = Schedule::load(radio_proto::platform::data_dir().join("schedule.toml"));
+        state_manager.set_scheduled(schedule.shows().to_vec()).await;
+
         let initial_volume = state_manager.get_state().await.volume;
         let mut mpv_driver = MpvDriver::new();
         mpv_driver.last_volume = initial_volume;
@@ -122,6 +200,13 @@ impl DaemonCore {
             last_source: (None, None),
             current_station_via_proxy: false,
             current_station_direct_url: None,
+            proxy,
+            recordings: HashMap::new(),
+            schedule,
+            schedule_failed: HashSet::new(),
+            timeshift: None,
+            alarm_ramp: None,
+            loudness_gain_db: 0.0,
         })
     }
 
@@ -130,6 +215,11 @@ impl DaemonCore {
         Arc::clone(&self.state_manager)
     }
 
+    /// Clone of the stream proxy state (for `proxy::start_server`).
+    pub fn proxy_state(&self) -> ProxyState {
+        self.proxy.clone()
+    }
+
     /// Run the core event loop.  Returns when a `Shutdown` event is received
     /// or the event channel is closed (TUI exited).
     pub async fn run(mut self, mut event_rx: mpsc::Receiver<DaemonEvent>) -> anyhow::Result<()> {
@@ -146,6 +236,27 @@ impl DaemonCore {
             }
         });
 
+        let timeline_tx = self.mpv_event_tx.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                if timeline_tx.send(DaemonEvent::TimelineTick).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let title_changed = self.proxy.title_changed();
+        let title_tx = self.mpv_event_tx.clone();
+        tokio::spawn(async move {
+            loop {
+                title_changed.notified().await;
+                if title_tx.send(DaemonEvent::StreamTitle).await.is_err() {
+                    break;
+                }
+            }
+        });
+
         loop {
             let evt = event_rx.recv().await;
             match evt {
@@ -196,6 +307,26 @@ impl DaemonCore {
                     if self.intend_playing && !self.obs_pause {
                         self.maybe_update_status().await;
                     }
+
+                    self.check_schedule().await;
+                }
+
+                Some(DaemonEvent::TimelineTick) => {
+                    if self.timeshift.is_some() {
+                        self.publish_timeline().await;
+                        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+                    }
+                    let upstream_changed = self.publish_upstream().await;
+                    if self.publish_diagnostics().await || upstream_changed {
+                        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+                    }
+                    // Replayed audio reaches older titles as it plays.
+                    self.publish_icy().await;
+                    self.check_timers().await;
+                }
+
+                Some(DaemonEvent::StreamTitle) => {
+                    self.publish_icy().await;
                 }
             }
         }
@@ -218,9 +349,7 @@ impl DaemonCore {
                         self.obs_core_idle = val;
                         self.maybe_update_status().await;
                         // push timeline immediately too
-                        self.state_manager
-                            .set_timeline(self.obs_time_pos, self.obs_duration)
-                            .await;
+                        self.publish_timeline().await;
                         let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
                     }
                 }
@@ -232,47 +361,31 @@ impl DaemonCore {
                         self.maybe_update_status().await;
                     }
                 }
-                OBS_ICY_TITLE | 6 => {
+                // Stations playing through the proxy take their title from
+                // it (`publish_icy`); mpv's is only used on a direct URL.
+                OBS_ICY_TITLE | 6 if !self.current_station_via_proxy => {
                     // Both obs id 3 (metadata/by-key/icy-title) and id 6 (icy-title direct)
                     let raw_val = match data {
                         serde_json::Value::String(s) => Some(s.clone()),
                         serde_json::Value::Null => None,
                         _ => data.as_str().map(|s| s.to_string()),
                     };
-                    // Filter trivial values
-                    let val = raw_val.and_then(|t| {
-                        let trimmed = t.trim().trim_matches('-').trim().to_string();
-                        if trimmed.is_empty() {
-                            None
-                        } else {
-                            Some(t)
-                        }
-                    });
-                    if val != self.obs_icy_title {
-                        info!("mpv: icy-title {:?} → {:?}", self.obs_icy_title, val);
-                        self.obs_icy_title = val.clone();
-                        self.state_manager.set_icy_title(val.clone()).await;
-                        if val != self.last_icy {
-                            self.last_icy = val.clone();
-                            let _ = self.broadcast_tx.send(BroadcastMessage::IcyUpdated(val));
-                        }
-                    }
+                    self.set_icy_title(raw_val, "mpv").await;
                 }
                 OBS_TIME_POS => {
                     let val = if data.is_null() { None } else { data.as_f64() };
                     self.obs_time_pos = val;
-                    self.state_manager
-                        .set_timeline(self.obs_time_pos, self.obs_duration)
-                        .await;
+                    if let (Some(ts), Some(pos)) = (self.timeshift.as_mut(), val) {
+                        ts.anchor.get_or_insert((Instant::now(), pos));
+                    }
+                    self.publish_timeline().await;
                     let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
                 }
                 OBS_DURATION => {
                     let val = if data.is_null() { None } else { data.as_f64() };
                     if val != self.obs_duration {
                         self.obs_duration = val;
-                        self.state_manager
-                            .set_timeline(self.obs_time_pos, self.obs_duration)
-                            .await;
+                        self.publish_timeline().await;
                         let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
                     }
                 }
@@ -327,9 +440,10 @@ impl DaemonCore {
                         );
                         // Reset proxy flag before attempting fallback
                         self.current_station_via_proxy = false;
+                        self.timeshift = None;
                         // Attempt to play direct URL
                         if let Some(handle) = self.mpv_handle.clone() {
-                            let volume = self.state_manager.get_state().await.volume;
+                            let volume = self.output_volume(&self.state_manager.get_state().await);
                             if let Err(e) = handle.load_stream(&direct_url, volume).await {
                                 error!("mpv: fallback to direct URL failed: {}", e);
                                 self.intend_playing = false;
@@ -348,7 +462,7 @@ impl DaemonCore {
                                 let url = direct_url.clone();
                                 let new_handle = tokio::spawn(async move {
                                     loop {
-                                        if let Err(e) = run_vu_ffmpeg(&url, &tx).await {
+                                        if let Err(e) = run_vu_ffmpeg(&url, false, &tx).await {
                                             debug!("VU ffmpeg exited: {e}");
                                         }
                                         tokio::time::sleep(std::time::Duration::from_secs(2)).await;
@@ -371,6 +485,16 @@ impl DaemonCore {
                         self.connecting_since = None;
                     }
                 }
+                // A file that plays out leaves mpv idle: stop waiting for
+                // audio and tell the App, which moves on to the queue.
+                let mut ended_file = None;
+                if reason == "eof" && self.intend_playing {
+                    if let (None, Some(path)) = &self.last_source {
+                        info!("[queue] file ended: {}", path);
+                        self.intend_playing = false;
+                        ended_file = Some(path.clone());
+                    }
+                }
                 // Clear ICY on stream end
                 if self.obs_icy_title.is_some() {
                     self.obs_icy_title = None;
@@ -386,6 +510,9 @@ impl DaemonCore {
                 self.state_manager.set_timeline(None, None).await;
                 self.obs_core_idle = Some(true);
                 self.maybe_update_status().await;
+                if let Some(path) = ended_file {
+                    let _ = self.broadcast_tx.send(BroadcastMessage::FileEnded(path));
+                }
             }
             Some("start-file") => {
                 let playlist_entry_id = evt
@@ -395,6 +522,11 @@ impl DaemonCore {
                     .unwrap_or(0);
                 info!("mpv: start-file playlist_entry_id={}", playlist_entry_id);
                 self.connecting_since = None;
+                // Stale time-pos events from the previous file may have set
+                // the anchor; re-anchor on the new file's first position.
+                if let Some(ts) = self.timeshift.as_mut() {
+                    ts.anchor = None;
+                }
                 self.obs_core_idle = Some(true); // will flip to false when audio flows
                 self.maybe_update_status().await;
             }
@@ -403,10 +535,11 @@ impl DaemonCore {
                 // Wait 50ms before re-observing so mpv has settled on the new file,
                 // then re-register observations so mpv pushes current values immediately.
                 if let Some(h) = self.mpv_handle.clone() {
+                    let gain_db = self.loudness_gain_db;
                     tokio::spawn(async move {
                         tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
                         h.observe_all_properties().await;
-                        h.set_audio_filter().await;
+                        h.set_audio_filter(gain_db).await;
                     });
                 }
             }
@@ -528,9 +661,10 @@ impl DaemonCore {
 
             // Register property observations + audio filter on the fresh handle.
             let h_clone = handle.clone();
+            let gain_db = self.loudness_gain_db;
             tokio::spawn(async move {
                 h_clone.observe_all_properties().await;
-                h_clone.set_audio_filter().await;
+                h_clone.set_audio_filter(gain_db).await;
             });
 
             // Audio observer (lavfi) — only used for local file playback.
@@ -564,14 +698,41 @@ impl DaemonCore {
             Command::Random => self.random().await?,
             Command::TogglePause => self.toggle_pause().await?,
             Command::Volume { value } => self.set_volume(value).await?,
+            Command::SetLoudnessGain { db } => self.set_loudness_gain(db).await,
             Command::SeekRelative { seconds } => self.seek_relative(seconds).await?,
             Command::SeekTo { seconds } => self.seek_to(seconds).await?,
+            Command::JumpToLive => self.jump_to_live().await?,
             Command::GetState => {
                 // State will be broadcast automatically
             }
             Command::EnableTelemetry | Command::PrintTelemetryReport => {
                 // Telemetry commands handled elsewhere; no-op in core
             }
+            Command::StartRecording { station_idx } => self.start_recording(station_idx).await?,
+            Command::StopRecording { station_idx } => {
+                self.stop_recording(station_idx, StopMode::Finish)
+            }
+            Command::CancelRecording { station_idx } => {
+                self.stop_recording(station_idx, StopMode::Discard)
+            }
+            Command::ScheduleRecording { show } => self.schedule_recording(show).await,
+            Command::UnscheduleRecording { channel, start } => {
+                self.unschedule_recording(channel, start).await
+            }
+            Command::SetSleepTimer { minutes } => self.set_sleep_timer(minutes).await,
+            Command::CancelSleepTimer => self.cancel_sleep_timer().await,
+            Command::SetAlarm { time, target } => self.set_alarm(time, target).await?,
+            Command::CancelAlarm => self.cancel_alarm().await,
+            Command::PreviewStation { station } => self.preview_station(station).await?,
+            Command::AddStation { station } => self.add_station(station).await?,
+            Command::UpdateStation {
+                station_idx,
+                station,
+            } => self.update_station(station_idx, station).await?,
+            Command::DeleteStation { station_idx } => self.delete_station(station_idx).await?,
+            Command::DuplicateStation { station_idx } => {
+                self.duplicate_station(station_idx).await?
+            }
         }
         Ok(())
     }
@@ -579,7 +740,7 @@ impl DaemonCore {
     async fn play_station(&mut self, idx: usize) -> anyhow::Result<()> {
         let (station, volume) = {
             let state = self.state_manager.get_state().await;
-            (state.stations.get(idx).cloned(), state.volume)
+            (state.stations.get(idx).cloned(), self.output_volume(&state))
         };
 
         if let Some(station) = station {
@@ -607,122 +768,250 @@ impl DaemonCore {
             // Reset proxy tracking before attempting playback
             self.current_station_via_proxy = false;
             self.current_station_direct_url = Some(station.url.clone());
+            self.timeshift = None;
 
-            match self.ensure_mpv_handle().await {
-                Some(handle) => {
-                    let mut stream_url = station.url.clone();
-                    let wants_proxy = !station.url.to_ascii_lowercase().contains(".m3u8");
-                    let mut used_proxy = false;
-                    if wants_proxy {
-                        let proxy_url = crate::proxy::proxy_url(idx);
-                        info!("Attempting to load '{}' via proxy: {}", station.name, proxy_url);
-                        match handle.load_stream(&proxy_url, volume).await {
-                            Ok(()) => {
-                                info!("Successfully loaded proxy stream for '{}'", station.name);
-                                stream_url = proxy_url;
-                                used_proxy = true;
-                                // Track that we're using proxy so we can fallback if mpv fails later
-                                self.current_station_via_proxy = true;
-                            }
-                            Err(e) => {
-                                error!(
-                                    "Failed to load proxy stream '{}': {}. Falling back to direct URL: {}",
-                                    station.name, e, station.url
-                                );
-                                if let Err(e2) = handle.load_stream(&stream_url, volume).await {
-                                    error!(
-                                        "Failed to load direct stream '{}': {}. Marking as error.",
-                                        station.name, e2
-                                    );
-                                    self.intend_playing = false;
-                                    self.current_station_via_proxy = false;
-                                    self.current_station_direct_url = None;
-                                    self.state_manager
-                                        .set_playback_status(PlaybackStatus::Error)
-                                        .await;
-                                    let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
-                                    return Ok(());
-                                }
-                                self.current_station_via_proxy = false;
-                            }
-                        }
-                    } else if let Err(e) = handle.load_stream(&stream_url, volume).await {
-                        error!("Failed to load direct HLS stream '{}': {}", station.name, e);
-                        self.intend_playing = false;
-                        self.current_station_via_proxy = false;
-                        self.current_station_direct_url = None;
-                        self.state_manager
-                            .set_playback_status(PlaybackStatus::Error)
-                            .await;
-                        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
-                        return Ok(());
+            self.load_station_stream(&station, crate::proxy::proxy_url(idx), volume)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Load a station into mpv through `proxy_url`, falling back to its own
+    /// URL, and start the meter/scope taps.
+    async fn load_station_stream(&mut self, station: &Station, proxy_url: String, volume: f32) {
+        match self.ensure_mpv_handle().await {
+            Some(handle) => {
+                let mut stream_url = station.url.clone();
+                let mut used_proxy = false;
+                info!("Attempting to load '{}' via proxy: {}", station.name, proxy_url);
+                match handle.load_stream(&proxy_url, volume).await {
+                    Ok(()) => {
+                        info!("Successfully loaded proxy stream for '{}'", station.name);
+                        stream_url = proxy_url;
+                        used_proxy = true;
+                        // Track that we're using proxy so we can fallback if mpv fails later
+                        self.current_station_via_proxy = true;
+                        self.timeshift = Some(TimeshiftPos::new(0.0));
                     }
-                    if used_proxy {
-                        info!(
-                            "Playing '{}' via shared proxy: {}",
-                            station.name, stream_url
+                    Err(e) => {
+                        error!(
+                            "Failed to load proxy stream '{}': {}. Falling back to direct URL: {}",
+                            station.name, e, station.url
                         );
-                    } else if wants_proxy {
-                        info!("Playing '{}' direct URL (proxy unavailable)", station.name);
-                    } else {
-                        info!("Playing '{}' direct URL (HLS stream)", station.name);
+                        if let Err(e2) = handle.load_stream(&stream_url, volume).await {
+                            error!(
+                                "Failed to load direct stream '{}': {}. Marking as error.",
+                                station.name, e2
+                            );
+                            self.intend_playing = false;
+                            self.current_station_via_proxy = false;
+                            self.current_station_direct_url = None;
+                            self.state_manager
+                                .set_playback_status(PlaybackStatus::Error)
+                                .await;
+                            let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+                            return;
+                        }
+                        self.current_station_via_proxy = false;
                     }
-
-                    // Spawn mpv lavfi observer for debug RMS bulb (independent
-                    // from the PCM-driven VU/scope path used for streams).
-                    let obs = crate::mpv::spawn_audio_observer(
-                        self.mpv_driver.socket_name.clone(),
-                        self.broadcast_tx.clone(),
+                }
+                if used_proxy {
+                    info!(
+                        "Playing '{}' via shared proxy: {}",
+                        station.name, stream_url
                     );
-                    self.audio_observer_handle = Some(obs);
+                } else {
+                    info!("Playing '{}' direct URL (proxy unavailable)", station.name);
+                }
 
-                    // Spawn VU/scope PCM task.
-                    // Use PipeWire monitor on Linux if configured, otherwise use ffmpeg from stream.
-                    #[cfg(target_os = "linux")]
-                    let use_pipewire = self.config.viz.pipewire_viz;
-                    #[cfg(not(target_os = "linux"))]
-                    let use_pipewire = false;
-
-                    if use_pipewire {
-                        info!("Using PipeWire/PulseAudio monitor for visualization");
-                        self.vu_task_handle = Some(crate::pipewire_viz::spawn_pipewire_viz_task(
-                            self.config.viz.pipewire_device.clone(),
-                            self.broadcast_tx.clone(),
-                        ));
-                    } else {
-                        // Spawn ffmpeg PCM task for oscilloscope.
-                        // Prefer proxy URL so mpv + ffmpeg share one upstream source.
-                        let tx = self.broadcast_tx.clone();
-                        let handle = tokio::spawn(async move {
-                            loop {
-                                if let Err(e) = run_vu_ffmpeg(&stream_url, &tx).await {
-                                    debug!("VU ffmpeg exited: {e}");
-                                }
-                                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                // Spawn mpv lavfi observer for debug RMS bulb (independent
+                // from the PCM-driven VU/scope path used for streams).
+                let obs = crate::mpv::spawn_audio_observer(
+                    self.mpv_driver.socket_name.clone(),
+                    self.broadcast_tx.clone(),
+                );
+                self.audio_observer_handle = Some(obs);
+
+                // Spawn VU/scope PCM task.
+                // Use PipeWire monitor on Linux if configured, otherwise use ffmpeg from stream.
+                #[cfg(target_os = "linux")]
+                let use_pipewire = self.config.viz.pipewire_viz;
+                #[cfg(not(target_os = "linux"))]
+                let use_pipewire = false;
+
+                if use_pipewire {
+                    info!("Using PipeWire/PulseAudio monitor for visualization");
+                    self.vu_task_handle = Some(crate::pipewire_viz::spawn_pipewire_viz_task(
+                        self.config.viz.pipewire_device.clone(),
+                        self.broadcast_tx.clone(),
+                    ));
+                } else {
+                    // Spawn ffmpeg PCM task for oscilloscope.
+                    // Prefer proxy URL so mpv + ffmpeg share one upstream source.
+                    let tx = self.broadcast_tx.clone();
+                    let handle = tokio::spawn(async move {
+                        loop {
+                            if let Err(e) = run_vu_ffmpeg(&stream_url, false, &tx).await {
+                                debug!("VU ffmpeg exited: {e}");
                             }
-                        });
-                        self.vu_task_handle = Some(handle.abort_handle());
-                    }
-                }
-                None => {
-                    warn!("No mpv handle available for station '{}'", station.name);
-                    self.intend_playing = false;
-                    self.state_manager
-                        .set_playback_status(PlaybackStatus::Error)
-                        .await;
-                    let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                        }
+                    });
+                    self.vu_task_handle = Some(handle.abort_handle());
                 }
             }
+            None => {
+                warn!("No mpv handle available for station '{}'", station.name);
+                self.intend_playing = false;
+                self.state_manager
+                    .set_playback_status(PlaybackStatus::Error)
+                    .await;
+                let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+            }
+        }
+    }
+
+    /// Play a station that isn't in the list through the proxy's `/preview`.
+    async fn preview_station(&mut self, station: Station) -> anyhow::Result<()> {
+        info!("Previewing station: {} (url={})", station.name, station.url);
+        let volume = self.output_volume(&self.state_manager.get_state().await);
+        if let Some(h) = self.vu_task_handle.take() {
+            h.abort();
+        }
+        if let Some(h) = self.audio_observer_handle.take() {
+            h.abort();
+        }
+        self.last_source = (None, None);
+        self.connecting_since = None;
+        self.obs_core_idle = None;
+
+        self.intend_playing = true;
+        self.proxy.set_preview(Some(station.url.clone())).await;
+        self.state_manager.set_previewing(station.clone()).await;
+        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+
+        self.current_station_via_proxy = false;
+        self.current_station_direct_url = Some(station.url.clone());
+        self.timeshift = None;
+        self.load_station_stream(&station, crate::proxy::preview_url(), volume)
+            .await;
+        // Seeking needs a station index; previews always play live.
+        self.timeshift = None;
+        Ok(())
+    }
+
+    /// Append a station to the list and to `[stations] stations_toml`.
+    async fn add_station(&mut self, station: Station) -> anyhow::Result<()> {
+        let state = self.state_manager.get_state().await;
+        if state.stations.iter().any(|s| s.url == station.url) {
+            info!("Station already listed: {} ({})", station.name, station.url);
+            return Ok(());
+        }
+        let path = &self.config.stations.stations_toml;
+        radio_proto::state::append_station_to_toml(path, &state.stations, &station)?;
+        info!("Added station '{}' to {}", station.name, path.display());
+        self.state_manager.add_station(station).await;
+        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+        Ok(())
+    }
+
+    /// Replace the station at `idx` in the list and in `stations.toml`.
+    /// Playback carries on; if the playing station's URL changed it is
+    /// reloaded from the new one.
+    async fn update_station(&mut self, idx: usize, station: Station) -> anyhow::Result<()> {
+        let state = self.state_manager.get_state().await;
+        let Some(old) = state.stations.get(idx).cloned() else {
+            anyhow::bail!("no station at index {}", idx);
+        };
+        validate_station(&station, &state.stations, Some(idx))?;
+        let path = &self.config.stations.stations_toml;
+        update_station_in_toml(path, &state.stations, &old, &station)?;
+        info!("Updated station '{}' in {}", station.name, path.display());
+        let url_changed = old.url != station.url;
+        self.state_manager.update_station(idx, station).await;
+        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+        if url_changed {
+            self.proxy.forget(idx).await;
+            if state.current_station == Some(idx) && self.intend_playing {
+                self.play_station(idx).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the station at `idx` from the list and from `stations.toml`,
+    /// stopping it first if it's playing.
+    async fn delete_station(&mut self, idx: usize) -> anyhow::Result<()> {
+        let state = self.state_manager.get_state().await;
+        let Some(station) = state.stations.get(idx).cloned() else {
+            anyhow::bail!("no station at index {}", idx);
+        };
+        self.check_station_list_change(&state, ListChange::Removed(idx))?;
+        let path = &self.config.stations.stations_toml;
+        remove_station_from_toml(path, &state.stations, &station)?;
+        info!("Deleted station '{}' from {}", station.name, path.display());
+        if state.current_station == Some(idx) && state.current_file.is_none() {
+            self.stop().await?;
+        }
+        self.state_manager.remove_station(idx).await?;
+        self.renumber_stations(ListChange::Removed(idx)).await;
+        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+        Ok(())
+    }
+
+    /// Insert a copy of the station at `idx` right after it.
+    async fn duplicate_station(&mut self, idx: usize) -> anyhow::Result<()> {
+        let state = self.state_manager.get_state().await;
+        let Some(original) = state.stations.get(idx).cloned() else {
+            anyhow::bail!("no station at index {}", idx);
+        };
+        self.check_station_list_change(&state, ListChange::Inserted(idx + 1))?;
+        let copy = Station {
+            name: copy_name(&original.name, &state.stations),
+            ..original.clone()
+        };
+        let path = &self.config.stations.stations_toml;
+        insert_station_after_in_toml(path, &state.stations, &original, &copy)?;
+        info!("Duplicated station '{}' as '{}'", original.name, copy.name);
+        self.state_manager.insert_station(idx + 1, copy).await?;
+        self.renumber_stations(ListChange::Inserted(idx + 1)).await;
+        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+        Ok(())
+    }
+
+    /// Recordings hold their station index until they finish, so the list
+    /// can't be renumbered under one.
+    fn check_station_list_change(
+        &self,
+        state: &DaemonState,
+        change: ListChange,
+    ) -> anyhow::Result<()> {
+        if let Some(rec) = state
+            .recordings
+            .iter()
+            .find(|r| change.moves(r.station_idx))
+        {
+            anyhow::bail!(
+                "stop the recording of {} before changing the station list",
+                rec.station_name
+            );
         }
         Ok(())
     }
 
+    /// Follow a station list change in the core's own index bookkeeping.
+    async fn renumber_stations(&mut self, change: ListChange) {
+        self.last_source.0 = self.last_source.0.and_then(|i| change.apply(i));
+        self.proxy.renumber(change).await;
+    }
+
     async fn stop(&mut self) -> anyhow::Result<()> {
         info!("Stopping playback");
         self.intend_playing = false;
         self.connecting_since = None;
         self.current_station_via_proxy = false;
         self.current_station_direct_url = None;
+        self.timeshift = None;
         if let Some(h) = self.vu_task_handle.take() {
             h.abort();
         }
@@ -750,7 +1039,7 @@ impl DaemonCore {
         start_secs: Option<f64>,
         pause_after: bool,
     ) -> anyhow::Result<()> {
-        let volume = self.state_manager.get_state().await.volume;
+        let volume = self.output_volume(&self.state_manager.get_state().await);
         info!("Playing local file: {}", path);
 
         let new_source = (None, Some(path.clone()));
@@ -758,6 +1047,8 @@ impl DaemonCore {
         self.last_source = new_source;
         self.connecting_since = None;
         self.obs_core_idle = None;
+        self.current_station_via_proxy = false;
+        self.timeshift = None;
 
         self.intend_playing = true;
         self.state_manager.set_playing_file(path.clone()).await?;
@@ -837,18 +1128,42 @@ impl DaemonCore {
     async fn set_volume(&mut self, value: f32) -> anyhow::Result<()> {
         self.state_manager.set_volume(value).await?;
         self.mpv_driver.last_volume = value;
+        // Touching the volume ends an alarm's ramp: the user is awake.
+        self.alarm_ramp = None;
+        let state = self.state_manager.get_state().await;
         if let Some(handle) = self.mpv_handle.as_ref() {
-            handle.set_volume(value).await?;
+            handle.set_volume(self.output_volume(&state)).await?;
         }
         let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
         Ok(())
     }
 
+    async fn set_loudness_gain(&mut self, db: f32) {
+        if (db - self.loudness_gain_db).abs() < 0.05 {
+            return;
+        }
+        info!("[loudness] gain {:+.1} dB", db);
+        self.loudness_gain_db = db;
+        if let Some(handle) = self.mpv_handle.as_ref() {
+            handle.set_audio_filter(db).await;
+        }
+    }
+
     async fn toggle_pause(&mut self) -> anyhow::Result<()> {
         let state = self.state_manager.get_state().await;
-        if state.current_station.is_none() && state.current_file.is_none() {
+        if state.current_station.is_none()
+            && state.current_file.is_none()
+            && state.preview.is_none()
+        {
             return Ok(());
         }
+        // Resuming a timeshifted station: mpv's own cache may have stalled or
+        // skipped ahead, so reload from the proxy buffer at the exact spot.
+        if self.obs_pause {
+            if let Some(ts) = self.timeshift {
+                self.timeshift_seek(ts.behind(self.obs_time_pos)).await?;
+            }
+        }
         if let Some(handle) = self.mpv_handle.as_ref() {
             // Use the locally-observed pause state rather than an IPC round-trip
             // (avoids a 5-second timeout if mpv is buffering).
@@ -861,6 +1176,9 @@ impl DaemonCore {
     async fn seek_relative(&mut self, seconds: f64) -> anyhow::Result<()> {
         let state = self.state_manager.get_state().await;
         if state.current_file.is_none() {
+            if let Some((pos, window)) = self.timeshift_timeline().await {
+                self.timeshift_seek(window - (pos + seconds)).await?;
+            }
             return Ok(());
         }
         if let Some(handle) = self.mpv_handle.as_ref() {
@@ -872,6 +1190,9 @@ impl DaemonCore {
     async fn seek_to(&mut self, seconds: f64) -> anyhow::Result<()> {
         let state = self.state_manager.get_state().await;
         if state.current_file.is_none() {
+            if let Some((_, window)) = self.timeshift_timeline().await {
+                self.timeshift_seek(window - seconds).await?;
+            }
             return Ok(());
         }
         if let Some(handle) = self.mpv_handle.as_ref() {
@@ -880,9 +1201,541 @@ impl DaemonCore {
         Ok(())
     }
 
+    // ── timeshift ─────────────────────────────────────────────────────────────
+
+    async fn jump_to_live(&mut self) -> anyhow::Result<()> {
+        if self.timeshift.is_some() {
+            self.timeshift_seek(0.0).await?;
+        }
+        Ok(())
+    }
+
+    /// Position within the current station's timeshift buffer and the
+    /// buffer's length, both in seconds.
+    async fn timeshift_timeline(&self) -> Option<(f64, f64)> {
+        let ts = self.timeshift?;
+        let idx = self.last_source.0?;
+        let window = self.proxy.timeshift_window(idx).await?;
+        let behind = ts.behind(self.obs_time_pos).min(window);
+        Some((window - behind, window))
+    }
+
+    /// Push the current timeline into `DaemonState`.  Files report mpv's
+    /// position; stations report their place in the timeshift buffer.
+    async fn publish_timeline(&mut self) {
+        let (pos, duration) = if self.last_source.1.is_some() {
+            (self.obs_time_pos, self.obs_duration)
+        } else {
+            match self.timeshift_timeline().await {
+                Some((pos, window)) => (Some(pos), Some(window)),
+                None => (None, None),
+            }
+        };
+        self.state_manager.set_timeline(pos, duration).await;
+    }
+
+    /// Take the ICY title of a station playing through the proxy from the
+    /// proxy: the title of the audio at the listener's position, so a
+    /// timeshift replay shows what was on air then, and it keeps updating
+    /// while mpv is paused.
+    async fn publish_icy(&mut self) {
+        if !self.intend_playing || !self.current_station_via_proxy {
+            return;
+        }
+        let state = self.state_manager.get_state().await;
+        let key = if state.preview.is_some() {
+            None
+        } else if let Some(idx) = state.current_station {
+            Some(idx)
+        } else {
+            return;
+        };
+        let behind = self
+            .timeshift
+            .map(|ts| ts.behind(self.obs_time_pos))
+            .unwrap_or(0.0);
+        let title = self.proxy.title_at(key, behind).await;
+        self.set_icy_title(title, "proxy").await;
+    }
+
+    /// Publish a new ICY title (`None` clears it), ignoring trivial ones.
+    async fn set_icy_title(&mut self, title: Option<String>, from: &str) {
+        let val = title.filter(|t| !t.trim().trim_matches('-').trim().is_empty());
+        if val == self.obs_icy_title {
+            return;
+        }
+        info!("{}: icy-title {:?} → {:?}", from, self.obs_icy_title, val);
+        self.obs_icy_title = val.clone();
+        self.state_manager.set_icy_title(val.clone()).await;
+        if val != self.last_icy {
+            self.last_icy = val.clone();
+            let _ = self.broadcast_tx.send(BroadcastMessage::IcyUpdated(val));
+        }
+    }
+
+    /// Push the proxy's upstream status for what's playing into
+    /// `DaemonState`; true when it changed.
+    async fn publish_upstream(&mut self) -> bool {
+        let state = self.state_manager.get_state().await;
+        let upstream = if !self.intend_playing || !self.current_station_via_proxy {
+            None
+        } else if state.preview.is_some() {
+            self.proxy.upstream_status(None).await
+        } else if let Some(idx) = state.current_station {
+            self.proxy.upstream_status(Some(idx)).await
+        } else {
+            None
+        };
+        if upstream == state.upstream {
+            return false;
+        }
+        self.state_manager.set_upstream(upstream).await;
+        true
+    }
+
+    /// Refresh the health of the proxy's open streams and, while a station
+    /// plays, mpv's cache.  True when any of it changed.
+    async fn publish_diagnostics(&mut self) -> bool {
+        let streams = self.proxy.diagnostics().await;
+        let player = match &self.mpv_handle {
+            Some(handle) if self.intend_playing && self.last_source.1.is_none() => {
+                handle.get_cache().await.ok()
+            }
+            _ => None,
+        };
+        let diagnostics = Diagnostics { streams, player };
+        if diagnostics == self.state_manager.get_state().await.diagnostics {
+            return false;
+        }
+        self.state_manager.set_diagnostics(diagnostics).await;
+        true
+    }
+
+    /// Reload the current station from the proxy, `behind` seconds behind
+    /// live.  mpv keeps its pause state across the reload.
+    async fn timeshift_seek(&mut self, behind: f64) -> anyhow::Result<()> {
+        let Some(ts) = self.timeshift else {
+            return Ok(());
+        };
+        let state = self.state_manager.get_state().await;
+        let Some(idx) = state.current_station else {
+            return Ok(());
+        };
+        let Some(window) = self.proxy.timeshift_window(idx).await else {
+            return Ok(());
+        };
+        let behind = behind.clamp(0.0, window);
+        let is_live = behind < TIMESHIFT_LIVE_EPSILON;
+        if is_live && ts.behind(self.obs_time_pos) < TIMESHIFT_LIVE_EPSILON {
+            debug!("timeshift: idx={} already live", idx);
+            return Ok(());
+        }
+        let Some(handle) = self.mpv_handle.clone() else {
+            return Ok(());
+        };
+
+        let (url, behind) = if is_live {
+            (crate::proxy::proxy_url(idx), 0.0)
+        } else {
+            (crate::proxy::timeshift_url(idx, behind), behind)
+        };
+        info!(
+            "timeshift: idx={} → {:.1}s behind live ({:.0}s buffered)",
+            idx, behind, window
+        );
+        self.timeshift = Some(TimeshiftPos::new(behind));
+        handle.load_stream(&url, self.output_volume(&state)).await?;
+        self.retarget_vu_ffmpeg(url, !is_live);
+        self.publish_timeline().await;
+        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+        Ok(())
+    }
+
+    /// Point the ffmpeg PCM tap at `url` so the scope and VU meter follow
+    /// what mpv is playing rather than the live edge.  A timeshift URL
+    /// serves its backlog as fast as it is read, so the tap then reads it in
+    /// real time (`-re`) instead of racing ahead to live.
+    fn retarget_vu_ffmpeg(&mut self, url: String, timeshifted: bool) {
+        #[cfg(target_os = "linux")]
+        let use_pipewire = self.config.viz.pipewire_viz;
+        #[cfg(not(target_os = "linux"))]
+        let use_pipewire = false;
+        if use_pipewire {
+            return;
+        }
+
+        if let Some(h) = self.vu_task_handle.take() {
+            h.abort();
+        }
+        let tx = self.broadcast_tx.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                if let Err(e) = run_vu_ffmpeg(&url, timeshifted, &tx).await {
+                    debug!("VU ffmpeg exited: {e}");
+                }
+                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            }
+        });
+        self.vu_task_handle = Some(handle.abort_handle());
+    }
+
+    // ── recording ─────────────────────────────────────────────────────────────
+
+    async fn start_recording(&mut self, idx: usize) -> anyhow::Result<()> {
+        if self.recordings.get(&idx).is_some_and(|r| !r.is_finished()) {
+            info!("Recording already running for station idx={}", idx);
+            return Ok(());
+        }
+        let station = self
+            .state_manager
+            .get_state()
+            .await
+            .stations
+            .get(idx)
+            .cloned();
+        let Some(station) = station else {
+            anyhow::bail!("no station at index {}", idx);
+        };
+        let ctx = recorder::RecorderContext {
+            proxy: self.proxy.clone(),
+            state_manager: self.state_manager.clone(),
+            broadcast_tx: self.broadcast_tx.clone(),
+            downloads_dir: self.config.paths.downloads_dir.clone(),
+        };
+        match recorder::start_recording(ctx, idx, station.name.clone(), None).await {
+            Ok(rec) => {
+                self.recordings.insert(idx, rec);
+            }
+            Err(e) => {
+                let msg = format!("recording {} failed: {}", station.name, e);
+                error!("{}", msg);
+                let _ = self.broadcast_tx.send(BroadcastMessage::Log(msg));
+            }
+        }
+        Ok(())
+    }
+
+    fn stop_recording(&mut self, idx: usize, mode: StopMode) {
+        match self.recordings.remove(&idx) {
+            Some(rec) => {
+                info!("Stopping recording idx={} ({:?})", idx, mode);
+                // The task clears its DaemonState entry and broadcasts once
+                // the last segment has been renamed into place.
+                drop(rec.stop(mode));
+            }
+            None => debug!("No recording running for station idx={}", idx),
+        }
+    }
+
+    // ── scheduled recordings ──────────────────────────────────────────────────
+
+    async fn schedule_recording(&mut self, show: ScheduledShow) {
+        if !self.schedule.add(show.clone()) {
+            debug!("[sched] {:?} already scheduled", show.title);
+            return;
+        }
+        info!(
+            "[sched] scheduled {:?} on {} ({}–{})",
+            show.title,
+            schedule::nts_station_name(show.channel),
+            show.start,
+            show.end
+        );
+        self.schedule_changed().await;
+        // The show may already be on air.
+        self.check_schedule().await;
+    }
+
+    async fn unschedule_recording(&mut self, channel: usize, start: i64) {
+        let Some(show) = self.schedule.remove(channel, start) else {
+            return;
+        };
+        info!("[sched] unscheduled {:?}", show.title);
+        self.schedule_failed.remove(&(channel, start));
+        if let Some(idx) = self.recording_idx_for_show(&show) {
+            self.stop_recording(idx, StopMode::Finish);
+        }
+        self.schedule_changed().await;
+    }
+
+    /// Persist the schedule and mirror it into `DaemonState`.
+    async fn schedule_changed(&mut self) {
+        if let Err(e) = self.schedule.save() {
+            error!(
+                "[sched] could not save {}: {}",
+                self.schedule.path().display(),
+                e
+            );
+        }
+        self.state_manager
+            .set_scheduled(self.schedule.shows().to_vec())
+            .await;
+        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+    }
+
+    /// Start recordings for shows whose window has opened and finish the
+    /// ones whose window has closed.  Runs on every heartbeat.
+    async fn check_schedule(&mut self) {
+        let now = chrono::Local::now().timestamp();
+
+        let finished = self.schedule.take_finished(now);
+        for show in &finished {
+            self.schedule_failed.remove(&(show.channel, show.start));
+            match self.recording_idx_for_show(show) {
+                Some(idx) => {
+                    info!("[sched] {:?} is over, finishing recording", show.title);
+                    self.stop_recording(idx, StopMode::Finish);
+                }
+                None => warn!("[sched] {:?} ended without being recorded", show.title),
+            }
+        }
+        if !finished.is_empty() {
+            self.schedule_changed().await;
+        }
+
+        for show in self.schedule.due(now) {
+            if self.recording_idx_for_show(&show).is_none() {
+                self.start_scheduled_recording(show).await;
+            }
+        }
+    }
+
+    fn recording_idx_for_show(&self, show: &ScheduledShow) -> Option<usize> {
+        self.recordings
+            .iter()
+            .find(|(_, rec)| !rec.is_finished() && rec.show() == Some(show))
+            .map(|(idx, _)| *idx)
+    }
+
+    async fn start_scheduled_recording(&mut self, show: ScheduledShow) {
+        let key = (show.channel, show.start);
+        let station_name = schedule::nts_station_name(show.channel);
+        let idx = self
+            .state_manager
+            .get_state()
+            .await
+            .stations
+            .iter()
+            .position(|s| s.name == station_name);
+        let Some(idx) = idx else {
+            if self.schedule_failed.insert(key) {
+                let msg = format!(
+                    "can't record {}: no \"{}\" station",
+                    show.title, station_name
+                );
+                warn!("[sched] {}", msg);
+                let _ = self.broadcast_tx.send(BroadcastMessage::Log(msg));
+            }
+            return;
+        };
+
+        // The previous show keeps recording to the end of its window; `due`
+        // holds this one back until then, so only retry later here.
+        if self
+            .recordings
+            .get(&idx)
+            .is_some_and(|r| r.show().is_some() && !r.is_finished())
+        {
+            debug!("[sched] {:?} waits for the show before it", show.title);
+            return;
+        }
+
+        // A manual recording of the same channel gives way to the show.  Wait
+        // for it so its final state update can't clobber the new recording's.
+        if let Some(rec) = self.recordings.remove(&idx) {
+            if !rec.is_finished() {
+                info!(
+                    "[sched] finishing manual recording of {} for {:?}",
+                    station_name, show.title
+                );
+            }
+            let task = rec.stop(StopMode::Finish);
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(3), task).await;
+        }
+
+        info!("[sched] recording {:?} from {}", show.title, station_name);
+        let ctx = recorder::RecorderContext {
+            proxy: self.proxy.clone(),
+            state_manager: self.state_manager.clone(),
+            broadcast_tx: self.broadcast_tx.clone(),
+            downloads_dir: self.config.paths.downloads_dir.clone(),
+        };
+        match recorder::start_recording(ctx, idx, station_name, Some(show.clone())).await {
+            Ok(rec) => {
+                self.schedule_failed.remove(&key);
+                self.recordings.insert(idx, rec);
+            }
+            Err(e) => {
+                // Retried on the next heartbeat while the window is open.
+                let msg = format!("recording {} failed: {}", show.title, e);
+                error!("[sched] {}", msg);
+                if self.schedule_failed.insert(key) {
+                    let _ = self.broadcast_tx.send(BroadcastMessage::Log(msg));
+                }
+            }
+        }
+    }
+
+    // ── sleep timer / alarm ───────────────────────────────────────────────────
+
+    async fn set_sleep_timer(&mut self, minutes: u64) {
+        if minutes == 0 {
+            return self.cancel_sleep_timer().await;
+        }
+        let secs = minutes * 60;
+        let timer = SleepTimer {
+            ends_at: chrono::Local::now().timestamp() + secs as i64,
+            fade_secs: self.config.timers.sleep_fade_secs.min(secs),
+        };
+        info!(
+            "[timer] sleep in {} min (fade {}s)",
+            minutes, timer.fade_secs
+        );
+        self.state_manager.set_sleep_timer(Some(timer)).await;
+        // A replaced timer may have been mid-fade.
+        self.apply_output_volume().await;
+        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+    }
+
+    async fn cancel_sleep_timer(&mut self) {
+        if self.state_manager.get_state().await.sleep_timer.is_none() {
+            return;
+        }
+        info!("[timer] sleep timer cancelled");
+        self.state_manager.set_sleep_timer(None).await;
+        self.apply_output_volume().await;
+        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+    }
+
+    async fn set_alarm(&mut self, time: Option<String>, target: AlarmTarget) -> anyhow::Result<()> {
+        let time = time.unwrap_or_else(|| self.config.timers.alarm_time.clone());
+        let at = timers::next_occurrence(&time, chrono::Local::now())?;
+        let label = match &target {
+            AlarmTarget::Station { station_idx } => self
+                .state_manager
+                .get_state()
+                .await
+                .stations
+                .get(*station_idx)
+                .map(|s| s.name.clone())
+                .ok_or_else(|| anyhow::anyhow!("no station with index {}", station_idx))?,
+            AlarmTarget::File { path } => std::path::Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.clone()),
+        };
+        info!(
+            "[timer] alarm at {} → {}",
+            at.format("%Y-%m-%d %H:%M"),
+            label
+        );
+        self.state_manager
+            .set_alarm(Some(Alarm {
+                at: at.timestamp(),
+                target,
+                label,
+                ramp_secs: self.config.timers.alarm_ramp_secs,
+            }))
+            .await;
+        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+        Ok(())
+    }
+
+    async fn cancel_alarm(&mut self) {
+        if self.state_manager.get_state().await.alarm.is_none() {
+            return;
+        }
+        info!("[timer] alarm cancelled");
+        self.state_manager.set_alarm(None).await;
+        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+    }
+
+    /// Advance the sleep fade and alarm ramp; stop or start playback when a
+    /// timer comes due.  Runs on every timeline tick.
+    async fn check_timers(&mut self) {
+        let state = self.state_manager.get_state().await;
+        let now = timers::now_secs();
+
+        if let Some(timer) = &state.sleep_timer {
+            if now >= timer.ends_at as f64 {
+                info!("[timer] sleep timer expired, stopping playback");
+                self.state_manager.set_sleep_timer(None).await;
+                if state.current_station.is_some() || state.current_file.is_some() {
+                    if let Err(e) = self.stop().await {
+                        error!("[timer] stop failed: {}", e);
+                    }
+                }
+                let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+            } else if now >= (timer.ends_at - timer.fade_secs as i64) as f64 {
+                self.apply_output_volume().await;
+            }
+        }
+
+        if let Some(alarm) = state.alarm {
+            if now >= alarm.at as f64 {
+                self.fire_alarm(alarm).await;
+            }
+        }
+
+        if let Some((fired, ramp_secs)) = self.alarm_ramp {
+            if fired.elapsed().as_secs() >= ramp_secs {
+                self.alarm_ramp = None;
+            }
+            self.apply_output_volume().await;
+        }
+    }
+
+    async fn fire_alarm(&mut self, alarm: Alarm) {
+        info!("[timer] alarm: starting {}", alarm.label);
+        self.state_manager.set_alarm(None).await;
+        self.alarm_ramp = Some((Instant::now(), alarm.ramp_secs));
+        let result = match alarm.target {
+            AlarmTarget::Station { station_idx } => self.play_station(station_idx).await,
+            AlarmTarget::File { path } => self.play_file(path, None, false).await,
+        };
+        let msg = match result {
+            Ok(()) => format!("alarm: playing {}", alarm.label),
+            Err(e) => {
+                error!("[timer] alarm playback failed: {}", e);
+                self.alarm_ramp = None;
+                format!("alarm failed: {}", e)
+            }
+        };
+        let _ = self.broadcast_tx.send(BroadcastMessage::Log(msg));
+        let _ = self.broadcast_tx.send(BroadcastMessage::StateUpdated);
+    }
+
+    /// The level actually sent to mpv: the user's volume scaled by any sleep
+    /// fade or alarm ramp in progress.
+    fn output_volume(&self, state: &DaemonState) -> f32 {
+        let mut factor = 1.0;
+        if let Some(timer) = &state.sleep_timer {
+            factor *= timers::fade_out_factor(timer, timers::now_secs());
+        }
+        if let Some((fired, ramp_secs)) = self.alarm_ramp {
+            factor *= timers::ramp_up_factor(fired.elapsed().as_secs_f64(), ramp_secs);
+        }
+        state.volume * factor
+    }
+
+    async fn apply_output_volume(&mut self) {
+        let volume = self.output_volume(&self.state_manager.get_state().await);
+        if let Some(handle) = self.mpv_handle.as_ref() {
+            if let Err(e) = handle.set_volume(volume).await {
+                debug!("[timer] set_volume failed: {}", e);
+            }
+        }
+    }
+
     // ── helpers ───────────────────────────────────────────────────────────────
 
     async fn cleanup(&mut self) -> anyhow::Result<()> {
+        // Finish recordings first so no `.part` files are left behind.
+        for (idx, rec) in self.recordings.drain() {
+            info!("DaemonCore: cleanup — finishing recording idx={}", idx);
+            let task = rec.stop(StopMode::Finish);
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(3), task).await;
+        }
         info!("DaemonCore: cleanup — quitting mpv");
         if let Some(handle) = self.mpv_handle.take() {
             // Use quit (not stop) so mpv exits rather than going idle
@@ -1007,8 +1860,11 @@ const VU_SAMPLE_RATE: u32 = 44100;
 
 /// Spawn ffmpeg, decode mono s16le PCM, broadcast only PcmChunk.
 /// RMS / AudioLevel is computed from PcmChunk in the app handler — single source of truth.
+/// `realtime` reads the input at its own pace (`-re`), for sources that
+/// arrive faster than they play.
 async fn run_vu_ffmpeg(
     url: &str,
+    realtime: bool,
     broadcast_tx: &tokio::sync::broadcast::Sender<BroadcastMessage>,
 ) -> anyhow::Result<()> {
     use std::path::PathBuf;
@@ -1018,30 +1874,27 @@ async fn run_vu_ffmpeg(
     let rate = VU_SAMPLE_RATE.to_string();
     let ffmpeg_bin =
         radio_proto::platform::find_ffmpeg_binary().unwrap_or_else(|| PathBuf::from("ffmpeg"));
-    let mut child = Command::new(ffmpeg_bin)
+    let mut command = Command::new(ffmpeg_bin);
+    command.args([
+        "-hide_banner",
+        "-loglevel",
+        "error",
+        "-nostdin",
+        "-fflags",
+        "nobuffer",
+        "-flags",
+        "low_delay",
+        "-probesize",
+        "64k",
+        "-analyzeduration",
+        "200000",
+    ]);
+    if realtime {
+        command.arg("-re");
+    }
+    let mut child = command
         .args([
-            "-hide_banner",
-            "-loglevel",
-            "error",
-            "-nostdin",
-            "-fflags",
-            "nobuffer",
-            "-flags",
-            "low_delay",
-            "-probesize",
-            "64k",
-            "-analyzeduration",
-            "200000",
-            "-i",
-            url,
-            "-vn",
-            "-ac",
-            "1",
-            "-ar",
-            &rate,
-            "-f",
-            "s16le",
-            "pipe:1",
+            "-i", url, "-vn", "-ac", "1", "-ar", &rate, "-f", "s16le", "pipe:1",
         ])
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::null())
@@ -0,0 +1,150 @@
+//! Feeds tagged files in `downloads_dir` into the fingerprint library.
+//!
+//! A file tagged with a title (and usually an artist) is one track; a long
+//! show download with a chapter tracklist is indexed chapter by chapter.
+//! Runs once at startup and again for each finished download; anything
+//! already in the library (by file and chapter) is skipped.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use radio_proto::fingerprint::{file_origin, fingerprint, FingerprintLibrary, FINGERPRINT_RATE};
+use radio_proto::recognizer::decode_file_range;
+use radio_proto::songs::parse_icy;
+use tracing::{debug, info, warn};
+
+use crate::app_state::FileMetadata;
+
+/// Longer stretches are mixes or whole shows, not one track.
+const MAX_TRACK_SECS: f64 = 15.0 * 60.0;
+/// Too little audio to fingerprint usefully.
+const MIN_TRACK_SECS: f64 = 20.0;
+
+/// Index every playable file in `dir`.
+pub async fn index_dir(library: Arc<FingerprintLibrary>, dir: std::path::PathBuf) {
+    let files = crate::app::load_local_files(&dir);
+    let mut added = 0;
+    for file in &files {
+        added += index_file(&library, &file.path).await;
+    }
+    info!(
+        "[fingerprint] indexed {} new tracks from {} ({} known)",
+        added,
+        dir.display(),
+        library.len()
+    );
+}
+
+/// Index one file; returns how many tracks were added.
+pub async fn index_file(library: &FingerprintLibrary, path: &Path) -> usize {
+    let probe_path = path.to_path_buf();
+    let meta = tokio::task::spawn_blocking(move || crate::app::probe_file_metadata(&probe_path))
+        .await
+        .ok()
+        .flatten();
+    let Some(meta) = meta else {
+        return 0;
+    };
+    let mut added = 0;
+    for (track, start, end, chapter) in tracks(&meta) {
+        let origin = file_origin(path, chapter.then_some(start));
+        if library.has_origin(&origin) {
+            continue;
+        }
+        let clip = match decode_file_range(path, start, end - start, FINGERPRINT_RATE, 1).await {
+            Ok(clip) => clip,
+            Err(e) => {
+                warn!("[fingerprint] could not decode {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let Ok(landmarks) = tokio::task::spawn_blocking(move || fingerprint(&clip)).await else {
+            continue;
+        };
+        debug!("[fingerprint] {} → {:?}", origin, track);
+        match library.add(&track, &origin, &landmarks) {
+            Ok(()) => added += 1,
+            Err(e) => warn!("[fingerprint] could not save {:?}: {}", track, e),
+        }
+    }
+    added
+}
+
+/// (track, start, end, from a chapter) for each stretch worth indexing.
+fn tracks(meta: &FileMetadata) -> Vec<(String, f64, f64, bool)> {
+    let fits = |start: f64, end: f64| (MIN_TRACK_SECS..=MAX_TRACK_SECS).contains(&(end - start));
+    if !meta.chapters.is_empty() {
+        return meta
+            .chapters
+            .iter()
+            .filter(|c| fits(c.start_secs, c.end_secs))
+            .filter_map(|c| {
+                let (title, artist) = parse_icy(&c.title);
+                Some((track_name(artist, title?), c.start_secs, c.end_secs, true))
+            })
+            .collect();
+    }
+    match (meta.title.clone(), meta.duration_secs) {
+        (Some(title), Some(len)) if fits(0.0, len) => {
+            vec![(track_name(meta.artist.clone(), title), 0.0, len, false)]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// "Artist – Title", as vibra names tracks.
+fn track_name(artist: Option<String>, title: String) -> String {
+    match artist {
+        Some(artist) => format!("{} \u{2013} {}", artist, title),
+        None => title,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::FileChapter;
+
+    #[test]
+    fn test_tracks_from_tags_and_chapters() {
+        let single = FileMetadata {
+            title: Some("Vitamin C".into()),
+            artist: Some("Can".into()),
+            duration_secs: Some(212.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            tracks(&single),
+            vec![("Can \u{2013} Vitamin C".to_string(), 0.0, 212.0, false)]
+        );
+
+        let show = FileMetadata {
+            title: Some("Morning Show".into()),
+            duration_secs: Some(7200.0),
+            chapters: vec![
+                FileChapter {
+                    title: "Can - Vitamin C".into(),
+                    start_secs: 0.0,
+                    end_secs: 212.0,
+                },
+                FileChapter {
+                    title: "Station ID".into(),
+                    start_secs: 212.0,
+                    end_secs: 220.0,
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            tracks(&show),
+            vec![("Can \u{2013} Vitamin C".to_string(), 0.0, 212.0, true)]
+        );
+
+        let untitled_show = FileMetadata {
+            title: Some("Morning Show".into()),
+            duration_secs: Some(7200.0),
+            ..Default::default()
+        };
+        assert!(tracks(&untitled_show).is_empty());
+    }
+}
@@ -1,26 +1,59 @@
 use crate::core::DaemonEvent;
-use axum::{extract::State, http::StatusCode, response::Json, routing::get, Router};
-use radio_proto::protocol::Command;
+use crate::remote::{DownloadInfo, NtsChannelInfo, RemoteRequest, Reply, StarsSnapshot, MAX_STARS};
+use crate::BroadcastMessage;
+use axum::{
+    extract::{
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+        Path, Query, State,
+    },
+    http::{header, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+    routing::{get, post},
+    Router,
+};
+use futures_util::Stream;
+use radio_proto::protocol::{
+    Alarm, AlarmTarget, Command, DaemonState, Diagnostics, SleepTimer, Station,
+};
+use radio_proto::songs::RecognitionResult;
+use radio_proto::songs_export::{parse_day, ExportFormat, SongsFilter};
 use radio_proto::state::StateManager;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::Infallible;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
-use tokio::sync::mpsc;
-use tracing::{error, info};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tracing::{debug, error, info};
+
+/// How long to wait for the App loop to answer a `RemoteRequest`.
+const REMOTE_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Clone)]
 struct AppState {
     state_manager: Arc<StateManager>,
     event_tx: mpsc::Sender<DaemonEvent>,
+    remote_tx: mpsc::Sender<RemoteRequest>,
+    broadcast_tx: broadcast::Sender<BroadcastMessage>,
 }
 
 #[derive(Serialize)]
 struct ApiState {
     stations: Vec<StationInfo>,
     current_station: Option<usize>,
+    current_file: Option<String>,
     volume: f32,
     is_playing: bool,
+    is_paused: bool,
     icy_title: Option<String>,
+    time_pos_secs: Option<f64>,
+    duration_secs: Option<f64>,
+    sleep_timer: Option<SleepTimer>,
+    alarm: Option<Alarm>,
 }
 
 #[derive(Serialize)]
@@ -35,28 +68,156 @@ struct VolumeStatus {
     volume: u8,
 }
 
+/// Body of every successful command endpoint.
+#[derive(Serialize)]
+struct Ack {
+    ok: bool,
+}
+
+const ACK: Json<Ack> = Json(Ack { ok: true });
+
+/// An error response: `{"error": "..."}` with a matching status code.
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(ErrorBody {
+                error: self.message,
+            }),
+        )
+            .into_response()
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        Self::new(rejection.status(), rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> Self {
+        Self::new(rejection.status(), rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        Self::new(rejection.status(), rejection.body_text())
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+#[derive(Deserialize)]
+struct AlarmQuery {
+    /// "HH:MM"; defaults to `timers.alarm_time`.
+    time: Option<String>,
+    station: Option<usize>,
+    file: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SeekQuery {
+    /// Seconds to move by (negative = back).
+    relative: Option<f64>,
+    /// Absolute position in seconds.
+    to: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct PlayFileBody {
+    path: String,
+    #[serde(default)]
+    start_secs: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct FileStarsQuery {
+    path: String,
+    stars: u8,
+}
+
+#[derive(Deserialize)]
+struct SongsQuery {
+    #[serde(default = "default_songs_limit")]
+    limit: usize,
+    /// Search terms; every word must prefix-match the track, station,
+    /// show or comment.
+    q: Option<String>,
+}
+
+fn default_songs_limit() -> usize {
+    50
+}
+
+/// `format` is csv, json, m3u, md or cue; days are `YYYY-MM-DD`, `today`
+/// or `7d` (seven days ago).
+#[derive(Deserialize)]
+struct ExportQuery {
+    format: ExportFormat,
+    since: Option<String>,
+    until: Option<String>,
+    station: Option<String>,
+    source: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EventsQuery {
+    /// Also stream `level` events (~20/s while playing).
+    #[serde(default)]
+    levels: bool,
+}
+
 pub fn start_server(
     bind_address: String,
     port: u16,
     state_manager: Arc<StateManager>,
     event_tx: mpsc::Sender<DaemonEvent>,
+    remote_tx: mpsc::Sender<RemoteRequest>,
+    broadcast_tx: broadcast::Sender<BroadcastMessage>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         let app_state = AppState {
             state_manager,
             event_tx,
+            remote_tx,
+            broadcast_tx,
         };
 
-        let app = Router::new()
-            .route("/api/state", get(get_state))
-            .route("/api/play/:idx", get(play_station).post(play_station))
-            .route("/api/stop", get(stop).post(stop))
-            .route("/api/next", get(next_station).post(next_station))
-            .route("/api/prev", get(prev_station).post(prev_station))
-            .route("/api/random", get(random_station).post(random_station))
-            .route("/api/volume/:volume", get(set_volume).post(set_volume))
-            .route("/api/volume", get(get_volume))
-            .with_state(app_state);
+        let app = router(app_state);
 
         let addr = format!("{}:{}", bind_address, port);
         let listener = match TcpListener::bind(&addr).await {
@@ -75,7 +236,129 @@ pub fn start_server(
     })
 }
 
-async fn get_state(State(state): State<AppState>) -> Result<Json<ApiState>, StatusCode> {
+/// Every `/api` route, with JSON errors for unknown paths.
+fn router(app_state: AppState) -> Router {
+    Router::new()
+        .route("/api/state", get(get_state))
+        .route("/api/events", get(events))
+        .route("/api/play/:idx", get(play_station).post(play_station))
+        .route(
+            "/api/play/name/:name",
+            get(play_station_by_name).post(play_station_by_name),
+        )
+        .route("/api/play-file", post(play_file))
+        .route("/api/stop", get(stop).post(stop))
+        .route("/api/next", get(next_station).post(next_station))
+        .route("/api/prev", get(prev_station).post(prev_station))
+        .route("/api/random", get(random_station).post(random_station))
+        .route("/api/pause", get(pause).post(pause))
+        .route("/api/resume", get(resume).post(resume))
+        .route("/api/pause/toggle", get(toggle_pause).post(toggle_pause))
+        .route("/api/seek", get(seek).post(seek))
+        .route("/api/volume/:volume", get(set_volume).post(set_volume))
+        .route("/api/volume", get(get_volume))
+        .route("/api/stars", get(get_stars))
+        .route("/api/stars/station/:name/:stars", post(set_station_stars))
+        .route("/api/stars/file", post(set_file_stars))
+        .route("/api/recognize", post(recognize))
+        .route("/api/songs", get(get_songs))
+        .route("/api/songs/export", get(export_songs))
+        .route("/api/nts", get(get_nts))
+        .route("/api/poll", get(get_poll_titles))
+        .route("/api/downloads", get(get_downloads))
+        .route("/api/diagnostics", get(get_diagnostics))
+        .route("/api/sleep", get(get_sleep).delete(cancel_sleep))
+        .route("/api/sleep/:minutes", get(set_sleep).post(set_sleep))
+        .route(
+            "/api/alarm",
+            get(get_alarm).post(set_alarm).delete(cancel_alarm),
+        )
+        .fallback(not_found)
+        .with_state(app_state)
+}
+
+// ── helpers ───────────────────────────────────────────────────────────────────
+
+async fn send_command(state: &AppState, cmd: Command) -> ApiResult<Ack> {
+    if state
+        .event_tx
+        .send(DaemonEvent::ClientCommand(cmd))
+        .await
+        .is_err()
+    {
+        error!("Failed to send command to core");
+        return Err(ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "playback core is not running",
+        ));
+    }
+    Ok(ACK)
+}
+
+/// Ask the App loop for data only it holds (see `remote.rs`).
+async fn ask_app<T>(
+    state: &AppState,
+    request: impl FnOnce(Reply<T>) -> RemoteRequest,
+) -> Result<T, ApiError> {
+    let unavailable = || ApiError::new(StatusCode::SERVICE_UNAVAILABLE, "UI loop is not running");
+    let (reply_tx, reply_rx) = oneshot::channel();
+    state
+        .remote_tx
+        .send(request(reply_tx))
+        .await
+        .map_err(|_| unavailable())?;
+    match tokio::time::timeout(REMOTE_TIMEOUT, reply_rx).await {
+        Ok(Ok(result)) => result.map_err(ApiError::bad_request),
+        Ok(Err(_)) => Err(unavailable()),
+        Err(_) => Err(ApiError::new(
+            StatusCode::GATEWAY_TIMEOUT,
+            "UI loop did not answer in time",
+        )),
+    }
+}
+
+/// Find a station by name: exact (case-insensitive) match first, then the
+/// only station whose name contains `query`.
+fn find_station(stations: &[Station], query: &str) -> Result<usize, ApiError> {
+    if let Some(idx) = stations
+        .iter()
+        .position(|s| s.name.eq_ignore_ascii_case(query))
+    {
+        return Ok(idx);
+    }
+    let needle = query.to_lowercase();
+    let matches: Vec<usize> = stations
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.name.to_lowercase().contains(&needle))
+        .map(|(idx, _)| idx)
+        .collect();
+    match matches.as_slice() {
+        [idx] => Ok(*idx),
+        [] => Err(ApiError::not_found(format!(
+            "no station matching {:?}",
+            query
+        ))),
+        _ => Err(ApiError::bad_request(format!(
+            "{:?} matches {} stations: {}",
+            query,
+            matches.len(),
+            matches
+                .iter()
+                .map(|&i| stations[i].name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+async fn not_found() -> ApiError {
+    ApiError::not_found("no such endpoint")
+}
+
+// ── state & playback ──────────────────────────────────────────────────────────
+
+async fn get_state(State(state): State<AppState>) -> ApiResult<ApiState> {
     let daemon_state = state.state_manager.get_state().await;
 
     let stations: Vec<StationInfo> = daemon_state
@@ -92,9 +375,15 @@ async fn get_state(State(state): State<AppState>) -> Result<Json<ApiState>, Stat
     let api_state = ApiState {
         stations,
         current_station: daemon_state.current_station,
+        current_file: daemon_state.current_file,
         volume: daemon_state.volume,
         is_playing: daemon_state.is_playing,
+        is_paused: daemon_state.is_paused,
         icy_title: daemon_state.icy_title,
+        time_pos_secs: daemon_state.time_pos_secs,
+        duration_secs: daemon_state.duration_secs,
+        sleep_timer: daemon_state.sleep_timer,
+        alarm: daemon_state.alarm,
     };
 
     Ok(Json(api_state))
@@ -102,99 +391,521 @@ async fn get_state(State(state): State<AppState>) -> Result<Json<ApiState>, Stat
 
 async fn play_station(
     State(state): State<AppState>,
-    axum::extract::Path(idx): axum::extract::Path<usize>,
-) -> StatusCode {
+    idx: Result<Path<usize>, PathRejection>,
+) -> ApiResult<Ack> {
+    let Path(idx) = idx?;
+    let count = state.state_manager.get_state().await.stations.len();
+    if idx >= count {
+        return Err(ApiError::not_found(format!(
+            "no station {} ({} stations)",
+            idx, count
+        )));
+    }
     info!("HTTP API: Play station {}", idx);
-    let cmd = Command::Play { station_idx: idx };
-    if state
-        .event_tx
-        .send(DaemonEvent::ClientCommand(cmd))
-        .await
-        .is_err()
-    {
-        error!("Failed to send play command");
-        return StatusCode::INTERNAL_SERVER_ERROR;
+    send_command(&state, Command::Play { station_idx: idx }).await
+}
+
+async fn play_station_by_name(
+    State(state): State<AppState>,
+    name: Result<Path<String>, PathRejection>,
+) -> ApiResult<Ack> {
+    let Path(name) = name?;
+    let idx = find_station(&state.state_manager.get_state().await.stations, &name)?;
+    info!("HTTP API: Play station {:?} (idx={})", name, idx);
+    send_command(&state, Command::Play { station_idx: idx }).await
+}
+
+async fn play_file(
+    State(state): State<AppState>,
+    body: Result<Json<PlayFileBody>, JsonRejection>,
+) -> ApiResult<Ack> {
+    let Json(body) = body?;
+    if !std::path::Path::new(&body.path).is_file() {
+        return Err(ApiError::not_found(format!("no such file: {}", body.path)));
     }
-    StatusCode::OK
+    info!(
+        "HTTP API: Play file {} (start={:?})",
+        body.path, body.start_secs
+    );
+    let cmd = match body.start_secs {
+        Some(start_secs) => Command::PlayFileAt {
+            path: body.path,
+            start_secs,
+        },
+        None => Command::PlayFile { path: body.path },
+    };
+    send_command(&state, cmd).await
 }
 
-async fn stop(State(state): State<AppState>) -> StatusCode {
+async fn stop(State(state): State<AppState>) -> ApiResult<Ack> {
     info!("HTTP API: Stop");
-    if state
-        .event_tx
-        .send(DaemonEvent::ClientCommand(Command::Stop))
-        .await
-        .is_err()
-    {
-        error!("Failed to send stop command");
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    }
-    StatusCode::OK
+    send_command(&state, Command::Stop).await
 }
 
-async fn next_station(State(state): State<AppState>) -> StatusCode {
+async fn next_station(State(state): State<AppState>) -> ApiResult<Ack> {
     info!("HTTP API: Next station");
-    if state
-        .event_tx
-        .send(DaemonEvent::ClientCommand(Command::Next))
-        .await
-        .is_err()
-    {
-        error!("Failed to send next command");
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    }
-    StatusCode::OK
+    send_command(&state, Command::Next).await
 }
 
-async fn prev_station(State(state): State<AppState>) -> StatusCode {
+async fn prev_station(State(state): State<AppState>) -> ApiResult<Ack> {
     info!("HTTP API: Previous station");
-    if state
-        .event_tx
-        .send(DaemonEvent::ClientCommand(Command::Prev))
-        .await
-        .is_err()
-    {
-        error!("Failed to send prev command");
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    }
-    StatusCode::OK
+    send_command(&state, Command::Prev).await
 }
 
-async fn random_station(State(state): State<AppState>) -> StatusCode {
+async fn random_station(State(state): State<AppState>) -> ApiResult<Ack> {
     info!("HTTP API: Random station");
-    if state
-        .event_tx
-        .send(DaemonEvent::ClientCommand(Command::Random))
-        .await
-        .is_err()
-    {
-        error!("Failed to send random command");
-        return StatusCode::INTERNAL_SERVER_ERROR;
+    send_command(&state, Command::Random).await
+}
+
+/// Send `TogglePause` only if it moves playback to `want_paused`.
+async fn set_paused(state: &AppState, want_paused: Option<bool>) -> ApiResult<Ack> {
+    let daemon_state = state.state_manager.get_state().await;
+    if daemon_state.current_station.is_none() && daemon_state.current_file.is_none() {
+        return Err(ApiError::conflict("nothing playing"));
+    }
+    if want_paused == Some(daemon_state.is_paused) {
+        return Ok(ACK);
     }
-    StatusCode::OK
+    send_command(state, Command::TogglePause).await
+}
+
+async fn pause(State(state): State<AppState>) -> ApiResult<Ack> {
+    info!("HTTP API: Pause");
+    set_paused(&state, Some(true)).await
+}
+
+async fn resume(State(state): State<AppState>) -> ApiResult<Ack> {
+    info!("HTTP API: Resume");
+    set_paused(&state, Some(false)).await
+}
+
+async fn toggle_pause(State(state): State<AppState>) -> ApiResult<Ack> {
+    info!("HTTP API: Toggle pause");
+    set_paused(&state, None).await
+}
+
+async fn seek(
+    State(state): State<AppState>,
+    query: Result<Query<SeekQuery>, QueryRejection>,
+) -> ApiResult<Ack> {
+    let Query(query) = query?;
+    let cmd = match (query.relative, query.to) {
+        (Some(seconds), None) => Command::SeekRelative { seconds },
+        (None, Some(seconds)) if seconds >= 0.0 => Command::SeekTo { seconds },
+        (None, Some(_)) => return Err(ApiError::bad_request("`to` must not be negative")),
+        _ => {
+            return Err(ApiError::bad_request(
+                "pass exactly one of `relative` or `to` (seconds)",
+            ))
+        }
+    };
+    let daemon_state = state.state_manager.get_state().await;
+    if daemon_state.current_station.is_none() && daemon_state.current_file.is_none() {
+        return Err(ApiError::conflict("nothing playing"));
+    }
+    info!("HTTP API: Seek {:?}", cmd);
+    send_command(&state, cmd).await
 }
 
 async fn set_volume(
     State(state): State<AppState>,
-    axum::extract::Path(volume): axum::extract::Path<i32>,
-) -> StatusCode {
-    let vol = (volume as f32 / 100.0).clamp(0.0, 1.0);
-    info!("HTTP API: Set volume to {}%", volume);
-    let cmd = Command::Volume { value: vol };
-    if state
-        .event_tx
-        .send(DaemonEvent::ClientCommand(cmd))
-        .await
-        .is_err()
-    {
-        error!("Failed to send volume command");
-        return StatusCode::INTERNAL_SERVER_ERROR;
+    volume: Result<Path<i32>, PathRejection>,
+) -> ApiResult<Ack> {
+    let Path(volume) = volume?;
+    if !(0..=100).contains(&volume) {
+        return Err(ApiError::bad_request("volume must be 0–100"));
     }
-    StatusCode::OK
+    info!("HTTP API: Set volume to {}%", volume);
+    let cmd = Command::Volume {
+        value: volume as f32 / 100.0,
+    };
+    send_command(&state, cmd).await
 }
 
-async fn get_volume(State(state): State<AppState>) -> Json<VolumeStatus> {
+async fn get_volume(State(state): State<AppState>) -> ApiResult<VolumeStatus> {
     let daemon_state = state.state_manager.get_state().await;
     let volume = (daemon_state.volume * 100.0).round() as u8;
-    Json(VolumeStatus { volume })
+    Ok(Json(VolumeStatus { volume }))
+}
+
+// ── library & metadata ────────────────────────────────────────────────────────
+
+async fn get_stars(State(state): State<AppState>) -> ApiResult<StarsSnapshot> {
+    Ok(Json(ask_app(&state, RemoteRequest::Stars).await?))
+}
+
+async fn set_station_stars(
+    State(state): State<AppState>,
+    path: Result<Path<(String, u8)>, PathRejection>,
+) -> ApiResult<Ack> {
+    let Path((name, stars)) = path?;
+    if stars > MAX_STARS {
+        return Err(ApiError::bad_request(format!(
+            "stars must be 0–{}",
+            MAX_STARS
+        )));
+    }
+    let stations = state.state_manager.get_state().await.stations;
+    let name = stations[find_station(&stations, &name)?].name.clone();
+    info!("HTTP API: {} stars for station {:?}", stars, name);
+    ask_app(&state, |reply| RemoteRequest::SetStationStars {
+        name,
+        stars,
+        reply,
+    })
+    .await?;
+    Ok(ACK)
+}
+
+async fn set_file_stars(
+    State(state): State<AppState>,
+    query: Result<Query<FileStarsQuery>, QueryRejection>,
+) -> ApiResult<Ack> {
+    let Query(query) = query?;
+    info!("HTTP API: {} stars for file {}", query.stars, query.path);
+    ask_app(&state, |reply| RemoteRequest::SetFileStars {
+        path: query.path,
+        stars: query.stars,
+        reply,
+    })
+    .await?;
+    Ok(ACK)
+}
+
+async fn recognize(State(state): State<AppState>) -> ApiResult<Ack> {
+    info!("HTTP API: Recognize song");
+    ask_app(&state, RemoteRequest::Recognize).await?;
+    Ok(ACK)
+}
+
+async fn get_songs(
+    State(state): State<AppState>,
+    query: Result<Query<SongsQuery>, QueryRejection>,
+) -> ApiResult<Vec<RecognitionResult>> {
+    let Query(query) = query?;
+    Ok(Json(
+        ask_app(&state, |reply| RemoteRequest::Songs {
+            limit: query.limit,
+            query: query.q,
+            reply,
+        })
+        .await?,
+    ))
+}
+
+async fn export_songs(
+    State(state): State<AppState>,
+    query: Result<Query<ExportQuery>, QueryRejection>,
+) -> Result<Response, ApiError> {
+    let Query(query) = query?;
+    let today = chrono::Local::now().date_naive();
+    let day = |s: Option<String>| {
+        s.map(|s| parse_day(&s, today))
+            .transpose()
+            .map_err(|e| ApiError::bad_request(e.to_string()))
+    };
+    let filter = SongsFilter {
+        since: day(query.since)?,
+        until: day(query.until)?,
+        station: query.station,
+        source: query.source,
+    };
+    let format = query.format;
+    let body = ask_app(&state, |reply| RemoteRequest::ExportSongs {
+        filter,
+        format,
+        reply,
+    })
+    .await?;
+    let disposition = format!("inline; filename=\"songs.{}\"", format.extension());
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+async fn get_nts(State(state): State<AppState>) -> ApiResult<Vec<NtsChannelInfo>> {
+    Ok(Json(ask_app(&state, RemoteRequest::Nts).await?))
+}
+
+async fn get_poll_titles(State(state): State<AppState>) -> ApiResult<HashMap<String, String>> {
+    Ok(Json(ask_app(&state, RemoteRequest::PollTitles).await?))
+}
+
+async fn get_downloads(State(state): State<AppState>) -> ApiResult<Vec<DownloadInfo>> {
+    Ok(Json(ask_app(&state, RemoteRequest::Downloads).await?))
+}
+
+/// Health of the proxy's open streams and mpv's cache, as the `H` pane shows it.
+async fn get_diagnostics(State(state): State<AppState>) -> ApiResult<Diagnostics> {
+    Ok(Json(state.state_manager.get_state().await.diagnostics))
+}
+
+// ── live event feed ───────────────────────────────────────────────────────────
+//
+// `GET /api/events` is a Server-Sent Events stream of the same broadcasts the
+// TUI consumes:
+//
+//   hello   full `DaemonState` on connect
+//   state   `DaemonState` without `stations`, once per new `rev`
+//   resync  full `DaemonState` after this client fell behind and missed events
+//   icy     {"title": ...}
+//   log     {"message": ...}
+//   level   {"rms_db": ...}, only with `?levels=true`
+//
+// Station lists are large and rarely change, so `state` leaves them out; a
+// client that sees `rev` jump or gets `resync` has everything it needs.
+
+async fn events(
+    State(state): State<AppState>,
+    query: Result<Query<EventsQuery>, QueryRejection>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let Query(query) = query?;
+    info!("HTTP API: event stream opened (levels={})", query.levels);
+    // Subscribe before reading the snapshot so nothing falls in between.
+    let feed = EventFeed {
+        rx: state.broadcast_tx.subscribe(),
+        state_manager: state.state_manager.clone(),
+        levels: query.levels,
+        last_rev: None,
+    };
+    let stream = futures_util::stream::unfold((feed, true), |(mut feed, first)| async move {
+        let event = if first {
+            feed.snapshot("hello").await
+        } else {
+            feed.next_event().await?
+        };
+        Some((Ok(event), (feed, false)))
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+struct EventFeed {
+    rx: broadcast::Receiver<BroadcastMessage>,
+    state_manager: Arc<StateManager>,
+    levels: bool,
+    last_rev: Option<u64>,
+}
+
+impl EventFeed {
+    /// Full state, including stations.
+    async fn snapshot(&mut self, name: &str) -> Event {
+        let daemon_state = self.state_manager.get_state().await;
+        self.last_rev = Some(daemon_state.rev);
+        json_event(name, &daemon_state)
+    }
+
+    /// Next event to send, or `None` once the core has shut down.
+    async fn next_event(&mut self) -> Option<Event> {
+        loop {
+            let msg = match self.rx.recv().await {
+                Ok(msg) => msg,
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    debug!("HTTP API: event stream lagged by {} messages", n);
+                    return Some(self.snapshot("resync").await);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            };
+            if let BroadcastMessage::StateUpdated = msg {
+                let daemon_state = self.state_manager.get_state().await;
+                if self.last_rev == Some(daemon_state.rev) {
+                    continue;
+                }
+                self.last_rev = Some(daemon_state.rev);
+                return Some(json_event("state", &state_without_stations(daemon_state)));
+            }
+            if let Some((name, data)) = simple_event(&msg, self.levels) {
+                return Some(json_event(name, &data));
+            }
+        }
+    }
+}
+
+/// `DaemonState` as JSON with the `stations` key removed.
+fn state_without_stations(daemon_state: DaemonState) -> serde_json::Value {
+    let mut value = serde_json::to_value(daemon_state).unwrap_or_default();
+    if let Some(obj) = value.as_object_mut() {
+        obj.remove("stations");
+    }
+    value
+}
+
+/// Events that map straight from a broadcast, without touching state.
+fn simple_event(msg: &BroadcastMessage, levels: bool) -> Option<(&'static str, serde_json::Value)> {
+    match msg {
+        BroadcastMessage::IcyUpdated(title) => Some(("icy", serde_json::json!({ "title": title }))),
+        BroadcastMessage::Log(message) => Some(("log", serde_json::json!({ "message": message }))),
+        BroadcastMessage::FileEnded(path) => {
+            Some(("file_ended", serde_json::json!({ "path": path })))
+        }
+        BroadcastMessage::AudioLevel(rms_db) if levels => {
+            Some(("level", serde_json::json!({ "rms_db": rms_db })))
+        }
+        _ => None,
+    }
+}
+
+fn json_event(name: &str, data: &impl Serialize) -> Event {
+    Event::default()
+        .event(name)
+        .data(serde_json::to_string(data).unwrap_or_else(|_| "null".to_string()))
+}
+
+// ── sleep timer & alarm ───────────────────────────────────────────────────────
+
+async fn get_sleep(State(state): State<AppState>) -> ApiResult<Option<SleepTimer>> {
+    Ok(Json(state.state_manager.get_state().await.sleep_timer))
+}
+
+async fn set_sleep(
+    State(state): State<AppState>,
+    minutes: Result<Path<u64>, PathRejection>,
+) -> ApiResult<Ack> {
+    let Path(minutes) = minutes?;
+    info!("HTTP API: Sleep in {} min", minutes);
+    send_command(&state, Command::SetSleepTimer { minutes }).await
+}
+
+async fn cancel_sleep(State(state): State<AppState>) -> ApiResult<Ack> {
+    info!("HTTP API: Cancel sleep timer");
+    send_command(&state, Command::CancelSleepTimer).await
+}
+
+async fn get_alarm(State(state): State<AppState>) -> ApiResult<Option<Alarm>> {
+    Ok(Json(state.state_manager.get_state().await.alarm))
+}
+
+async fn set_alarm(
+    State(state): State<AppState>,
+    query: Result<Query<AlarmQuery>, QueryRejection>,
+) -> ApiResult<Ack> {
+    let Query(query) = query?;
+    let target = match (query.station, query.file) {
+        (Some(station_idx), None) => {
+            let count = state.state_manager.get_state().await.stations.len();
+            if station_idx >= count {
+                return Err(ApiError::not_found(format!("no station {}", station_idx)));
+            }
+            AlarmTarget::Station { station_idx }
+        }
+        (None, Some(path)) => AlarmTarget::File { path },
+        _ => {
+            return Err(ApiError::bad_request(
+                "pass exactly one of `station` (index) or `file` (path)",
+            ))
+        }
+    };
+    if let Some(time) = &query.time {
+        crate::timers::next_occurrence(time, chrono::Local::now())
+            .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    }
+    info!("HTTP API: Set alarm {:?} → {:?}", query.time, target);
+    send_command(
+        &state,
+        Command::SetAlarm {
+            time: query.time,
+            target,
+        },
+    )
+    .await
+}
+
+async fn cancel_alarm(State(state): State<AppState>) -> ApiResult<Ack> {
+    info!("HTTP API: Cancel alarm");
+    send_command(&state, Command::CancelAlarm).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stations(names: &[&str]) -> Vec<Station> {
+        names
+            .iter()
+            .map(|n| Station {
+                name: n.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_simple_events() {
+        let icy = simple_event(&BroadcastMessage::IcyUpdated(Some("A - B".into())), false);
+        assert_eq!(icy, Some(("icy", serde_json::json!({ "title": "A - B" }))));
+        assert!(simple_event(&BroadcastMessage::AudioLevel(-20.0), false).is_none());
+        assert_eq!(
+            simple_event(&BroadcastMessage::AudioLevel(-20.0), true),
+            Some(("level", serde_json::json!({ "rms_db": -20.0 })))
+        );
+        let pcm = BroadcastMessage::PcmChunk(Arc::new(vec![0.0; 4]));
+        assert!(simple_event(&pcm, true).is_none());
+        assert!(simple_event(&BroadcastMessage::StateUpdated, true).is_none());
+        assert_eq!(
+            simple_event(&BroadcastMessage::FileEnded("/m/a.mp3".into()), false),
+            Some(("file_ended", serde_json::json!({ "path": "/m/a.mp3" })))
+        );
+    }
+
+    #[test]
+    fn test_state_without_stations() {
+        let daemon_state = DaemonState {
+            rev: 7,
+            stations: vec![Station::default()],
+            ..Default::default()
+        };
+        let value = state_without_stations(daemon_state);
+        assert_eq!(value["rev"], 7);
+        assert!(value.get("stations").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_bad_path_and_query_are_json_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let (event_tx, _event_rx) = mpsc::channel(1);
+        let (remote_tx, _remote_rx) = mpsc::channel(1);
+        let app = router(AppState {
+            state_manager: Arc::new(StateManager::new(dir.path().join("state.json"), vec![])),
+            event_tx,
+            remote_tx,
+            broadcast_tx: broadcast::channel(1).0,
+        });
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        for path in [
+            "/api/volume/abc",
+            "/api/seek?relative=x",
+            "/api/songs/export?format=bogus",
+        ] {
+            let response = reqwest::get(format!("{}{}", base, path)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", path);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert!(
+                body["error"].as_str().is_some_and(|e| !e.is_empty()),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_station() {
+        let list = stations(&["NTS 1", "NTS 2", "dublab", "Radio Nova"]);
+        assert_eq!(find_station(&list, "nts 2").unwrap(), 1);
+        assert_eq!(find_station(&list, "DUB").unwrap(), 2);
+        assert_eq!(
+            find_station(&list, "nts").unwrap_err().status,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            find_station(&list, "kexp").unwrap_err().status,
+            StatusCode::NOT_FOUND
+        );
+    }
 }
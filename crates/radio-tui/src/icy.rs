@@ -0,0 +1,276 @@
+//! ICY (Shoutcast/Icecast) in-band metadata helpers.
+//!
+//! When a client sends `Icy-MetaData: 1`, the server interleaves a metadata
+//! block after every `icy-metaint` bytes of audio:
+//!
+//! ```text
+//! [metaint audio bytes][len byte][len*16 metadata bytes][metaint audio bytes]...
+//! ```
+//!
+//! `IcyDemuxer` splits that byte stream back into clean audio plus the
+//! `StreamTitle` values carried in the metadata blocks; `IcyMuxer` does the
+//! reverse for streams the proxy re-serves from its timeshift buffer.
+
+/// Read the `icy-metaint` interval from upstream response headers.
+pub fn metaint_from_headers(headers: &reqwest::header::HeaderMap) -> Option<usize> {
+    headers
+        .get("icy-metaint")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.trim().parse::<usize>().ok())
+        .filter(|&n| n > 0)
+}
+
+/// Extract the `StreamTitle` value from a raw ICY metadata block.
+pub fn parse_stream_title(meta: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(meta)
+        .trim_matches(char::from(0))
+        .trim()
+        .to_string();
+    if text.is_empty() {
+        return None;
+    }
+
+    if let Some(start) = text.find("StreamTitle='") {
+        let rest = &text[start + "StreamTitle='".len()..];
+        if let Some(end) = rest.find("';") {
+            let title = rest[..end].trim();
+            if !title.is_empty() {
+                return Some(title.to_string());
+            }
+        }
+    }
+
+    if let Some(start) = text.find("StreamTitle=\"") {
+        let rest = &text[start + "StreamTitle=\"".len()..];
+        if let Some(end) = rest.find("\";") {
+            let title = rest[..end].trim();
+            if !title.is_empty() {
+                return Some(title.to_string());
+            }
+        }
+    }
+
+    None
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DemuxState {
+    /// Copying audio; this many bytes remain before the next length byte.
+    Audio(usize),
+    /// The next byte is the metadata length byte.
+    MetaLen,
+    /// Collecting a metadata block; this many bytes remain.
+    Meta(usize),
+}
+
+/// Incremental ICY demuxer.  Feed it upstream chunks of any size; it keeps
+/// its position across calls so block boundaries may fall anywhere.
+#[derive(Debug, Clone)]
+pub struct IcyDemuxer {
+    metaint: Option<usize>,
+    state: DemuxState,
+    meta_buf: Vec<u8>,
+}
+
+impl IcyDemuxer {
+    /// `metaint = None` means the stream carries no metadata; every byte is
+    /// passed through as audio.
+    pub fn new(metaint: Option<usize>) -> Self {
+        Self {
+            metaint,
+            state: DemuxState::Audio(metaint.unwrap_or(0)),
+            meta_buf: Vec::new(),
+        }
+    }
+
+    /// Append the audio portion of `chunk` to `audio` and return every
+    /// `StreamTitle` found in metadata blocks completed by this chunk, paired
+    /// with the index into `audio` where that title takes effect.
+    pub fn feed(&mut self, chunk: &[u8], audio: &mut Vec<u8>) -> Vec<(usize, String)> {
+        let Some(metaint) = self.metaint else {
+            audio.extend_from_slice(chunk);
+            return Vec::new();
+        };
+
+        let mut titles = Vec::new();
+        let mut rest = chunk;
+        while !rest.is_empty() {
+            match self.state {
+                DemuxState::Audio(remaining) => {
+                    let n = remaining.min(rest.len());
+                    audio.extend_from_slice(&rest[..n]);
+                    rest = &rest[n..];
+                    self.state = if remaining == n {
+                        DemuxState::MetaLen
+                    } else {
+                        DemuxState::Audio(remaining - n)
+                    };
+                }
+                DemuxState::MetaLen => {
+                    let len = rest[0] as usize * 16;
+                    rest = &rest[1..];
+                    self.state = if len == 0 {
+                        DemuxState::Audio(metaint)
+                    } else {
+                        self.meta_buf.clear();
+                        DemuxState::Meta(len)
+                    };
+                }
+                DemuxState::Meta(remaining) => {
+                    let n = remaining.min(rest.len());
+                    self.meta_buf.extend_from_slice(&rest[..n]);
+                    rest = &rest[n..];
+                    if remaining == n {
+                        if let Some(title) = parse_stream_title(&self.meta_buf) {
+                            titles.push((audio.len(), title));
+                        }
+                        self.meta_buf.clear();
+                        self.state = DemuxState::Audio(metaint);
+                    } else {
+                        self.state = DemuxState::Meta(remaining - n);
+                    }
+                }
+            }
+        }
+        titles
+    }
+}
+
+/// Incremental ICY muxer: interleaves metadata blocks into clean audio every
+/// `metaint` bytes.  A block carries a `StreamTitle` only when the title has
+/// changed since the previous block; otherwise it is the single zero byte.
+#[derive(Debug, Clone)]
+pub struct IcyMuxer {
+    metaint: usize,
+    until_meta: usize,
+    sent_title: Option<String>,
+}
+
+impl IcyMuxer {
+    pub fn new(metaint: usize) -> Self {
+        Self {
+            metaint,
+            until_meta: metaint,
+            sent_title: None,
+        }
+    }
+
+    /// Append `audio` to `out`, inserting metadata blocks as they fall due.
+    /// `title` is the track title in effect for this audio.
+    pub fn push(&mut self, audio: &[u8], title: Option<&str>, out: &mut Vec<u8>) {
+        let mut rest = audio;
+        while !rest.is_empty() {
+            let n = self.until_meta.min(rest.len());
+            out.extend_from_slice(&rest[..n]);
+            rest = &rest[n..];
+            self.until_meta -= n;
+            if self.until_meta == 0 {
+                self.write_block(title, out);
+                self.until_meta = self.metaint;
+            }
+        }
+    }
+
+    fn write_block(&mut self, title: Option<&str>, out: &mut Vec<u8>) {
+        let Some(title) = title.filter(|t| self.sent_title.as_deref() != Some(*t)) else {
+            out.push(0);
+            return;
+        };
+        let mut body = format!("StreamTitle='{}';", title).into_bytes();
+        // The length byte counts 16-byte units, so a block tops out at 4080 bytes.
+        body.truncate(255 * 16);
+        let padded = body.len().div_ceil(16) * 16;
+        body.resize(padded, 0);
+        out.push((padded / 16) as u8);
+        out.extend(body);
+        self.sent_title = Some(title.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta_block(text: &str) -> Vec<u8> {
+        let mut body = text.as_bytes().to_vec();
+        let padded = body.len().div_ceil(16) * 16;
+        body.resize(padded, 0);
+        let mut out = vec![(padded / 16) as u8];
+        out.extend(body);
+        out
+    }
+
+    #[test]
+    fn test_parse_stream_title() {
+        assert_eq!(
+            parse_stream_title(b"StreamTitle='Artist - Song';StreamUrl='';"),
+            Some("Artist - Song".to_string())
+        );
+        assert_eq!(parse_stream_title(b"StreamTitle='';\0\0\0"), None);
+        assert_eq!(parse_stream_title(b"\0\0\0\0"), None);
+    }
+
+    #[test]
+    fn test_demux_strips_metadata() {
+        let mut stream = b"abcd".to_vec();
+        stream.extend(meta_block("StreamTitle='One';"));
+        stream.extend(b"efgh");
+        stream.push(0);
+        stream.extend(b"ijkl");
+        stream.extend(meta_block("StreamTitle='Two';"));
+        stream.extend(b"mn");
+
+        let mut demux = IcyDemuxer::new(Some(4));
+        let mut audio = Vec::new();
+        let titles = demux.feed(&stream, &mut audio);
+        assert_eq!(audio, b"abcdefghijklmn");
+        assert_eq!(
+            titles,
+            vec![(4, "One".to_string()), (12, "Two".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_demux_split_across_chunks() {
+        let mut stream = b"abcd".to_vec();
+        stream.extend(meta_block("StreamTitle='Split Title';"));
+        stream.extend(b"efgh");
+
+        let mut demux = IcyDemuxer::new(Some(4));
+        let mut audio = Vec::new();
+        let mut titles = Vec::new();
+        for byte in &stream {
+            titles.extend(demux.feed(std::slice::from_ref(byte), &mut audio));
+        }
+        assert_eq!(audio, b"abcdefgh");
+        assert_eq!(titles, vec![(4, "Split Title".to_string())]);
+    }
+
+    #[test]
+    fn test_mux_roundtrip() {
+        let mut mux = IcyMuxer::new(4);
+        let mut stream = Vec::new();
+        mux.push(b"abcdef", Some("One"), &mut stream);
+        mux.push(b"gh", Some("One"), &mut stream);
+        mux.push(b"ijklmn", Some("Two"), &mut stream);
+
+        let mut demux = IcyDemuxer::new(Some(4));
+        let mut audio = Vec::new();
+        let titles = demux.feed(&stream, &mut audio);
+        assert_eq!(audio, b"abcdefghijklmn");
+        // "One" goes out once at the first boundary, the unchanged block
+        // after "gh" is empty, and "Two" follows at the next boundary.
+        assert_eq!(
+            titles,
+            vec![(4, "One".to_string()), (12, "Two".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_demux_passthrough_without_metaint() {
+        let mut demux = IcyDemuxer::new(None);
+        let mut audio = Vec::new();
+        assert!(demux.feed(b"raw\x01audio", &mut audio).is_empty());
+        assert_eq!(audio, b"raw\x01audio");
+    }
+}
@@ -0,0 +1,282 @@
+//! EBU R128 / ITU-R BS.1770 loudness metering and per-station normalization.
+//!
+//! `LoudnessMeter` is fed the station PCM tap (mono, 44.1 kHz) as the App
+//! consumes it into `pcm_ring`, and gives momentary (400 ms), short-term (3 s)
+//! and gated integrated loudness in LUFS for the VU meter.
+//!
+//! With `[loudness] normalize`, each station's integrated loudness is learned
+//! while it plays and kept in `loudness.json` in the TUI data dir.  The next
+//! time the station starts, the App asks the core for the gain that brings it
+//! to `target_lufs` (`Command::SetLoudnessGain`, an mpv `volume` filter).  The
+//! tap decodes the stream itself, so it measures the station before that gain.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+const SAMPLE_RATE: f64 = 44_100.0;
+/// Meter step: 100 ms.  Momentary and short-term windows are 4 and 30 steps.
+const STEP_SAMPLES: usize = 4_410;
+const MOMENTARY_STEPS: usize = 4;
+const SHORT_TERM_STEPS: usize = 30;
+/// Integrated loudness gates (BS.1770-4).
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+/// Histogram of 400 ms block loudness, 0.1 LU bins from -70 to +5 LUFS, so
+/// a station can play for days in bounded memory.
+const HIST_BINS: usize = 750;
+/// Weight cap (seconds) of a station's stored measurement, so a station
+/// that changes its mastering is re-learned within a few sessions.
+const LEARNED_MAX_SECS: f32 = 600.0;
+
+fn lufs(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.max(1e-20).log10()
+}
+
+/// Direct form I biquad.
+#[derive(Clone, Copy, Default)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 3],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[1] * self.y[0]
+            - self.a[2] * self.y[1];
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+/// The two K-weighting stages (high shelf, then RLB high-pass), with the
+/// coefficients derived for `rate` as in libebur128.
+fn k_weighting(rate: f64) -> (Biquad, Biquad) {
+    let f0 = 1681.974450955533;
+    let g = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = (std::f64::consts::PI * f0 / rate).tan();
+    let vh = 10f64.powf(g / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        ..Default::default()
+    };
+
+    let f0 = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = (std::f64::consts::PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let highpass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        ..Default::default()
+    };
+    (shelf, highpass)
+}
+
+pub struct LoudnessMeter {
+    shelf: Biquad,
+    highpass: Biquad,
+    step_sum: f64,
+    step_len: usize,
+    /// Mean square of the last `SHORT_TERM_STEPS` steps, oldest first.
+    steps: VecDeque<f64>,
+    /// (block count, summed mean square) per bin.
+    hist: Vec<(u32, f64)>,
+}
+
+impl Default for LoudnessMeter {
+    fn default() -> Self {
+        let (shelf, highpass) = k_weighting(SAMPLE_RATE);
+        Self {
+            shelf,
+            highpass,
+            step_sum: 0.0,
+            step_len: 0,
+            steps: VecDeque::with_capacity(SHORT_TERM_STEPS),
+            hist: vec![(0, 0.0); HIST_BINS],
+        }
+    }
+}
+
+impl LoudnessMeter {
+    /// Start over, e.g. when the source changes.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn push(&mut self, sample: f32) {
+        let y = self.highpass.process(self.shelf.process(sample as f64));
+        self.step_sum += y * y;
+        self.step_len += 1;
+        if self.step_len < STEP_SAMPLES {
+            return;
+        }
+        if self.steps.len() == SHORT_TERM_STEPS {
+            self.steps.pop_front();
+        }
+        self.steps.push_back(self.step_sum / STEP_SAMPLES as f64);
+        self.step_sum = 0.0;
+        self.step_len = 0;
+
+        // Every step closes a 400 ms gating block (75% overlap).
+        if let Some(block) = self.window(MOMENTARY_STEPS) {
+            let level = lufs(block);
+            if level > ABSOLUTE_GATE_LUFS {
+                let bin = (((level - ABSOLUTE_GATE_LUFS) * 10.0) as usize).min(HIST_BINS - 1);
+                self.hist[bin].0 += 1;
+                self.hist[bin].1 += block;
+            }
+        }
+    }
+
+    /// Mean square over the last `n` steps, once there are that many.
+    fn window(&self, n: usize) -> Option<f64> {
+        if self.steps.len() < n {
+            return None;
+        }
+        Some(self.steps.iter().rev().take(n).sum::<f64>() / n as f64)
+    }
+
+    pub fn momentary(&self) -> Option<f32> {
+        self.window(MOMENTARY_STEPS).map(|ms| lufs(ms) as f32)
+    }
+
+    pub fn short_term(&self) -> Option<f32> {
+        self.window(SHORT_TERM_STEPS).map(|ms| lufs(ms) as f32)
+    }
+
+    /// Gated integrated loudness since the last reset; `None` until some
+    /// block is above the absolute gate.
+    pub fn integrated(&self) -> Option<f32> {
+        let gated_mean = |from_bin: usize| {
+            let (n, sum) = self.hist[from_bin..]
+                .iter()
+                .fold((0u64, 0.0), |(n, s), &(c, e)| (n + c as u64, s + e));
+            (n > 0).then(|| sum / n as f64)
+        };
+        let relative_gate = lufs(gated_mean(0)?) + RELATIVE_GATE_LU;
+        let from_bin = ((relative_gate - ABSOLUTE_GATE_LUFS) * 10.0).max(0.0) as usize;
+        gated_mean(from_bin.min(HIST_BINS - 1)).map(|ms| lufs(ms) as f32)
+    }
+
+    /// Seconds of audio above the absolute gate, i.e. not silence.
+    pub fn measured_secs(&self) -> f32 {
+        self.hist.iter().map(|&(c, _)| c).sum::<u32>() as f32 * 0.1
+    }
+}
+
+// ── Learned station loudness ──────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LearnedLoudness {
+    pub lufs: f32,
+    /// Seconds of audio behind `lufs`, capped at `LEARNED_MAX_SECS`.
+    pub secs: f32,
+}
+
+impl LearnedLoudness {
+    /// Fold a session's measurement into what was known before it.
+    pub fn merge(prev: Option<LearnedLoudness>, lufs: f32, secs: f32) -> LearnedLoudness {
+        let Some(prev) = prev else {
+            return LearnedLoudness {
+                lufs,
+                secs: secs.min(LEARNED_MAX_SECS),
+            };
+        };
+        // Average in the power domain, like the integrated measurement itself.
+        let power = |l: f32| 10f32.powf(l / 10.0);
+        let total = prev.secs + secs;
+        let mean = (power(prev.lufs) * prev.secs + power(lufs) * secs) / total;
+        LearnedLoudness {
+            lufs: 10.0 * mean.log10(),
+            secs: total.min(LEARNED_MAX_SECS),
+        }
+    }
+
+    /// Gain that brings this station to `target_lufs`, within ±`max_gain_db`.
+    pub fn gain_db(&self, target_lufs: f32, max_gain_db: f32) -> f32 {
+        (target_lufs - self.lufs).clamp(-max_gain_db, max_gain_db)
+    }
+}
+
+/// Station name → learned loudness.
+pub fn load_learned(path: &Path) -> HashMap<String, LearnedLoudness> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    serde_json::from_str(&content).unwrap_or_default()
+}
+
+pub fn save_learned(path: &Path, learned: &HashMap<String, LearnedLoudness>) -> anyhow::Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(learned)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(meter: &mut LoudnessMeter, freq: f64, amplitude: f64, secs: f64) {
+        let n = (secs * SAMPLE_RATE) as usize;
+        for i in 0..n {
+            let t = i as f64 / SAMPLE_RATE;
+            meter.push((amplitude * (2.0 * std::f64::consts::PI * freq * t).sin()) as f32);
+        }
+    }
+
+    #[test]
+    fn test_sine_loudness() {
+        // A 1 kHz sine at -20 dBFS in one channel reads -23 LUFS.
+        let mut meter = LoudnessMeter::default();
+        assert_eq!(meter.momentary(), None);
+        sine(&mut meter, 1000.0, 0.1, 5.0);
+        for level in [meter.momentary(), meter.short_term(), meter.integrated()] {
+            let level = level.unwrap();
+            assert!((level - -23.0).abs() < 0.2, "level {}", level);
+        }
+        assert!((meter.measured_secs() - 4.7).abs() < 0.15);
+    }
+
+    #[test]
+    fn test_integrated_gating() {
+        // Silence is dropped by the absolute gate and a much quieter passage
+        // by the relative one, so both leave the integrated level alone.
+        let mut meter = LoudnessMeter::default();
+        sine(&mut meter, 1000.0, 0.1, 10.0);
+        sine(&mut meter, 1000.0, 0.0, 10.0);
+        sine(&mut meter, 1000.0, 0.01, 10.0);
+        let integrated = meter.integrated().unwrap();
+        assert!(
+            (integrated - -23.0).abs() < 0.3,
+            "integrated {}",
+            integrated
+        );
+        assert!(meter.momentary().unwrap() < -40.0);
+    }
+
+    #[test]
+    fn test_learned_loudness() {
+        let first = LearnedLoudness::merge(None, -20.0, 60.0);
+        assert_eq!(first.gain_db(-16.0, 12.0), 4.0);
+        let same = LearnedLoudness::merge(Some(first), -20.0, 60.0);
+        assert!((same.lufs - -20.0).abs() < 1e-3);
+        assert_eq!(same.secs, 120.0);
+        let louder = LearnedLoudness::merge(Some(same), -10.0, 600.0);
+        assert!(louder.lufs > -12.0 && louder.lufs < -10.0);
+        assert_eq!(louder.secs, LEARNED_MAX_SECS);
+        assert_eq!(louder.gain_db(-30.0, 12.0), -12.0);
+    }
+}
@@ -1,25 +1,45 @@
 mod action;
 mod app;
 mod app_state;
+mod attach;
+mod auto_id;
 mod component;
 mod components;
 mod core;
 mod download_manager;
+mod fingerprint_index;
 mod focus;
 mod http;
+mod icy;
 mod intent;
 mod latency;
+mod loudness;
 mod mpv;
+#[cfg(target_os = "linux")]
+mod mpris;
 mod nts_download;
 mod pipewire_viz;
 mod proxy;
+mod queue;
+mod recorder;
+mod remote;
+mod schedule;
 mod scope;
+mod silence;
+mod socket;
+mod station_check;
+mod stream_format;
+mod stream_playlist;
 mod theme;
+mod timers;
 mod widgets;
 mod workspace;
 
+use std::sync::Arc;
+
+use radio_proto::state::StateManager;
 use tokio::sync::{broadcast, mpsc};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 #[cfg(feature = "profiling")]
 use pprof::ProfilerGuard;
@@ -37,10 +57,149 @@ pub enum BroadcastMessage {
     AudioLevel(f32),
     /// Raw PCM samples (mono f32 normalised -1..1, 44100 Hz) for scope display.
     PcmChunk(std::sync::Arc<Vec<f32>>),
+    /// A local file played through to its end; the App advances the queue.
+    FileEnded(String),
+}
+
+const USAGE: &str = "usage: r4dio [--headless | --attach HOST[:PORT]]
+       r4dio check-stations [--json] [--mark-dead] [--jobs N] [--timeout SECS]
+
+  --headless            run playback, proxy, polling, recognition and the HTTP
+                        API without the terminal UI; stop with SIGTERM or Ctrl-C
+  --attach HOST[:PORT]  control the r4dio running on HOST (port 9876 unless
+                        given) instead of playing locally
+  check-stations        probe every station and report which are dead (see
+                        `r4dio check-stations --help`)";
+
+/// Command-line options.
+#[derive(Debug, Default)]
+struct Args {
+    headless: bool,
+    /// Remote core address for `--attach`, with the port filled in.
+    attach: Option<String>,
+    /// `check-stations`: probe the stations and exit.
+    check_stations: Option<station_check::CheckOptions>,
+}
+
+impl Args {
+    fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--headless" => parsed.headless = true,
+                "--attach" => {
+                    let Some(addr) = args.next() else {
+                        anyhow::bail!("--attach needs HOST[:PORT]\n\n{}", USAGE);
+                    };
+                    parsed.attach = Some(attach::normalize_addr(&addr));
+                }
+                "check-stations" => {
+                    parsed.check_stations =
+                        Some(station_check::CheckOptions::parse(args.by_ref())?);
+                    break;
+                }
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                other => anyhow::bail!("unknown argument {:?}\n\n{}", other, USAGE),
+            }
+        }
+        if parsed.headless && parsed.attach.is_some() {
+            anyhow::bail!("--headless and --attach cannot be combined");
+        }
+        Ok(parsed)
+    }
+}
+
+/// Resolves on SIGTERM or Ctrl-C.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = sigterm.recv() => info!("SIGTERM received"),
+                    _ = tokio::signal::ctrl_c() => info!("SIGINT received"),
+                }
+            }
+            Err(e) => {
+                error!("cannot listen for SIGTERM: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        info!("Ctrl-C received");
+    }
+}
+
+/// Build `DaemonCore` with the proxy, HTTP API and TCP server around it, and
+/// spawn its event loop.
+async fn start_core(
+    config: &radio_proto::config::Config,
+    event_tx: mpsc::Sender<core::DaemonEvent>,
+    event_rx: mpsc::Receiver<core::DaemonEvent>,
+    broadcast_tx: broadcast::Sender<BroadcastMessage>,
+    remote_tx: mpsc::Sender<remote::RemoteRequest>,
+) -> anyhow::Result<(Arc<StateManager>, tokio::task::JoinHandle<()>)> {
+    // ── Build DaemonCore ─────────────────────────────────────────────────────
+    let daemon_core =
+        core::DaemonCore::new(config.clone(), broadcast_tx.clone(), event_tx.clone()).await?;
+    let state_manager = daemon_core.state_manager();
+
+    // ── Stream proxy for station playback + visual tap ───────────────────────
+    proxy::start_server(daemon_core.proxy_state());
+
+    // ── HTTP server ──────────────────────────────────────────────────────────
+    if config.http.enabled {
+        http::start_server(
+            config.http.bind_address.clone(),
+            config.http.port,
+            state_manager.clone(),
+            event_tx.clone(),
+            remote_tx,
+            broadcast_tx.clone(),
+        );
+    }
+
+    // ── TCP client protocol (r4dio --attach) ─────────────────────────────────
+    if config.tcp.enabled {
+        socket::start_server(
+            config.tcp.bind_address.clone(),
+            config.tcp.port,
+            state_manager.clone(),
+            event_tx,
+            broadcast_tx.clone(),
+        );
+    }
+
+    // ── Send initial state to TUI so stations appear immediately ────────────
+    // The broadcast channel only carries deltas and the in-process TUI gets no
+    // Hello, so we push one StateUpdated now.
+    let _ = broadcast_tx.send(BroadcastMessage::StateUpdated);
+
+    // ── Spawn DaemonCore event loop ──────────────────────────────────────────
+    let core_handle = tokio::spawn(async move {
+        if let Err(e) = daemon_core.run(event_rx).await {
+            tracing::error!("DaemonCore exited with error: {}", e);
+        }
+    });
+
+    Ok((state_manager, core_handle))
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let args = Args::parse(std::env::args().skip(1))?;
+    if let Some(options) = args.check_stations {
+        return station_check::run(options).await;
+    }
+
     // ── Start CPU profiling for entire session ────────────────────────────────
     #[cfg(feature = "profiling")]
     let mut profiler: Option<ProfilerGuard<'_>> = None;
@@ -67,7 +226,11 @@ async fn main() -> anyhow::Result<()> {
     let icy_log_path = data_dir.join("icyticker.log");
 
     let songs_csv_path = data_dir.join("songs.csv");
-    let songs_vds_path = tui_data_dir.join("songs.vds");
+    // songs.jsonl replaced songs.vds; an existing .vds is imported once.
+    let songs = radio_proto::song_store::SongStore::open(
+        &tui_data_dir.join("songs.jsonl"),
+        &tui_data_dir.join("songs.vds"),
+    )?;
     let stars_path = tui_data_dir.join("starred.toml");
     // Seed starred.toml on first run from beside-exe (or data/ subdir) for bundled packages
     if !stars_path.exists() {
@@ -87,9 +250,14 @@ async fn main() -> anyhow::Result<()> {
         }
     }
     let random_history_path = tui_data_dir.join("random_history.json");
+    let queue_path = tui_data_dir.join("queue.json");
+    let playlists_dir = tui_data_dir.join("playlists");
+    let exports_dir = tui_data_dir.join("exports");
     let recent_path = tui_data_dir.join("recent.toml");
     let file_positions_path = tui_data_dir.join("file_positions.toml");
     let ui_state_path = tui_data_dir.join("ui_state.json");
+    let loudness_path = tui_data_dir.join("loudness.json");
+    let fingerprints_path = tui_data_dir.join("fingerprints.tsv");
 
     let log_file = std::fs::OpenOptions::new()
         .create(true)
@@ -109,7 +277,7 @@ async fn main() -> anyhow::Result<()> {
     // Print log path to stderr so the operator can tail it immediately.
     eprintln!("r4dio log: {}", log_path.display());
 
-    tracing::info!("r4dio starting…");
+    tracing::info!("r4dio starting…{}", if args.headless { " (headless)" } else { "" });
 
     // ── Load config ──────────────────────────────────────────────────────────
     let config = radio_proto::config::Config::load().unwrap_or_default();
@@ -126,47 +294,69 @@ async fn main() -> anyhow::Result<()> {
     // ── DaemonEvent channel (TUI/HTTP → DaemonCore) ─────────────────────────
     let (event_tx, event_rx) = mpsc::channel::<core::DaemonEvent>(1024);
 
-    // ── Build DaemonCore ─────────────────────────────────────────────────────
-    let daemon_core =
-        core::DaemonCore::new(config.clone(), broadcast_tx.clone(), event_tx.clone()).await?;
-    let state_manager = daemon_core.state_manager();
+    // ── RemoteRequest channel (HTTP/MPRIS → App loop) ───────────────────────
+    let (remote_tx, remote_rx) = mpsc::channel::<remote::RemoteRequest>(64);
 
-    // ── Stream proxy for station playback + visual tap ───────────────────────
-    proxy::start_server(state_manager.clone());
+    // ── Playback core: local, or a link to a remote one ─────────────────────
+    let (state_manager, core_handle) = match &args.attach {
+        Some(addr) => {
+            info!("attaching to {}", addr);
+            let state_manager = Arc::new(StateManager::mirror());
+            let link = attach::start(
+                addr.clone(),
+                state_manager.clone(),
+                event_rx,
+                broadcast_tx.clone(),
+            );
+            (state_manager, link)
+        }
+        None => {
+            start_core(
+                &config,
+                event_tx.clone(),
+                event_rx,
+                broadcast_tx.clone(),
+                remote_tx.clone(),
+            )
+            .await?
+        }
+    };
 
-    // ── HTTP server ──────────────────────────────────────────────────────────
-    if config.http.enabled {
-        http::start_server(
-            config.http.bind_address.clone(),
-            config.http.port,
+    // ── MPRIS (media keys, playerctl) ────────────────────────────────────────
+    #[cfg(target_os = "linux")]
+    if config.mpris.enabled {
+        mpris::start(
             state_manager.clone(),
             event_tx.clone(),
+            broadcast_tx.clone(),
+            remote_tx,
         );
     }
 
-    // ── Send initial state to TUI so stations appear immediately ────────────
-    // The broadcast channel only carries deltas; the TUI has no TCP handshake
-    // to fetch an initial Hello any more, so we push one StateUpdated now.
-    let _ = broadcast_tx.send(BroadcastMessage::StateUpdated);
-
-    // ── Spawn DaemonCore event loop ──────────────────────────────────────────
-    tokio::spawn(async move {
-        if let Err(e) = daemon_core.run(event_rx).await {
-            tracing::error!("DaemonCore exited with error: {}", e);
-        }
-    });
+    let shutdown_tx = event_tx.clone();
 
-    // ── Run TUI ──────────────────────────────────────────────────────────────
+    // ── Run TUI (or its event loop alone, headless) ─────────────────────────
+    let mut loudness_config = config.loudness.clone();
+    if loudness_config.normalize && config.viz.pipewire_viz {
+        // The monitor hears the output, after volume and gain.
+        warn!("[loudness] normalize needs the ffmpeg PCM tap; disabled with viz.pipewire_viz");
+        loudness_config.normalize = false;
+    }
     let app = app::App::new(
         icy_log_path,
         songs_csv_path,
-        songs_vds_path,
+        songs,
         log_path,
         stars_path,
         random_history_path,
+        queue_path,
+        playlists_dir,
+        exports_dir,
         recent_path,
         file_positions_path,
         ui_state_path,
+        loudness_path,
+        fingerprints_path,
         downloads_dir,
         event_tx,
         state_manager,
@@ -174,8 +364,27 @@ async fn main() -> anyhow::Result<()> {
         config.polling.poll_interval_secs,
         config.polling.max_concurrency,
         config.polling.max_jobs_per_cycle,
+        config.viz.fft_size,
+        config.viz.peak_hold_ms,
+        loudness_config,
+        &config.recognition,
+        config.discover.clone(),
     );
-    app.run(broadcast_rx).await?;
+    if args.headless {
+        app.run_headless(broadcast_rx, remote_rx, shutdown_signal())
+            .await?;
+    } else {
+        app.run(broadcast_rx, remote_rx).await?;
+    }
+
+    // ── Shut the core down so recordings are finalised and mpv exits ─────────
+    let _ = shutdown_tx.send(core::DaemonEvent::Shutdown).await;
+    if tokio::time::timeout(std::time::Duration::from_secs(5), core_handle)
+        .await
+        .is_err()
+    {
+        error!("DaemonCore did not shut down within 5s");
+    }
 
     // ── Write CPU profiling flamegraph ────────────────────────────────────────
     #[cfg(feature = "profiling")]
@@ -200,3 +409,32 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        assert!(!Args::parse(Vec::new()).unwrap().headless);
+        assert!(Args::parse(vec!["--headless".to_string()]).unwrap().headless);
+        assert!(Args::parse(vec!["--bogus".to_string()]).is_err());
+
+        let args = Args::parse(vec!["--attach".to_string(), "mediabox".to_string()]).unwrap();
+        assert_eq!(args.attach.as_deref(), Some("mediabox:9876"));
+        assert!(Args::parse(vec!["--attach".to_string()]).is_err());
+        let both = vec!["--headless", "--attach", "mediabox"];
+        assert!(Args::parse(both.into_iter().map(String::from)).is_err());
+
+        let check = vec!["check-stations", "--json", "--jobs", "4", "--timeout", "3"];
+        let options = Args::parse(check.into_iter().map(String::from))
+            .unwrap()
+            .check_stations
+            .unwrap();
+        assert!(options.json && !options.mark_dead);
+        assert_eq!(options.jobs, 4);
+        assert_eq!(options.timeout, std::time::Duration::from_secs(3));
+        let check = vec!["check-stations", "--jobs"];
+        assert!(Args::parse(check.into_iter().map(String::from)).is_err());
+    }
+}
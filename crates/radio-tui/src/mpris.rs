@@ -0,0 +1,700 @@
+//! MPRIS2 media player interface on the D-Bus session bus (Linux only).
+//!
+//! Registers `org.mpris.MediaPlayer2.r4dio` so desktop media keys,
+//! `playerctl` and shell widgets can control playback.  Method calls become
+//! the same `Command`s the TUI sends to `DaemonCore`; properties are rebuilt
+//! from `DaemonState` whenever the core broadcasts a change.  NTS show titles
+//! live in the App, so they are fetched with a `RemoteRequest` while an NTS
+//! channel is playing.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tracing::{debug, info, warn};
+use zbus::zvariant::{ObjectPath, OwnedValue, Value};
+use zbus::{connection, fdo, interface, Connection};
+
+use radio_proto::protocol::{Command, DaemonState, PlaybackStatus};
+use radio_proto::state::StateManager;
+
+use crate::core::DaemonEvent;
+use crate::remote::RemoteRequest;
+use crate::schedule::nts_station_name;
+use crate::BroadcastMessage;
+
+pub const BUS_NAME: &str = "org.mpris.MediaPlayer2.r4dio";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+/// How long a fetched NTS show title is trusted before asking the App again.
+const NTS_REFRESH: Duration = Duration::from_secs(60);
+const NTS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Connect to the session bus and serve MPRIS until the core shuts down.
+pub fn start(
+    state_manager: Arc<StateManager>,
+    event_tx: mpsc::Sender<DaemonEvent>,
+    broadcast_tx: broadcast::Sender<BroadcastMessage>,
+    remote_tx: mpsc::Sender<RemoteRequest>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let result = match connection::Builder::session() {
+            Ok(builder) => serve(builder, state_manager, event_tx, broadcast_tx, remote_tx).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            warn!("[mpris] not available: {}", e);
+        }
+    })
+}
+
+/// Register the MPRIS objects on the bus `builder` points at and keep their
+/// properties in sync with the core's broadcasts.
+pub async fn serve(
+    builder: connection::Builder<'_>,
+    state_manager: Arc<StateManager>,
+    event_tx: mpsc::Sender<DaemonEvent>,
+    broadcast_tx: broadcast::Sender<BroadcastMessage>,
+    remote_tx: mpsc::Sender<RemoteRequest>,
+) -> anyhow::Result<()> {
+    // Subscribe before the first snapshot so no change falls in between.
+    let mut rx = broadcast_tx.subscribe();
+    let player = Player {
+        event_tx,
+        state: state_manager.get_state().await,
+        nts_show: None,
+    };
+    let conn = builder
+        .name(BUS_NAME)?
+        .serve_at(OBJECT_PATH, Root)?
+        .serve_at(OBJECT_PATH, player)?
+        .build()
+        .await?;
+    info!("[mpris] registered {}", BUS_NAME);
+
+    let mut nts = NtsTitle::default();
+    loop {
+        match rx.recv().await {
+            Ok(BroadcastMessage::StateUpdated) | Ok(BroadcastMessage::IcyUpdated(_)) => {}
+            Ok(_) => continue,
+            Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+        let state = state_manager.get_state().await;
+        let nts_show = nts.lookup(&state, &remote_tx).await;
+        update(&conn, state, nts_show).await?;
+    }
+    Ok(())
+}
+
+/// Swap in a new snapshot and announce the properties that changed.
+async fn update(
+    conn: &Connection,
+    state: DaemonState,
+    nts_show: Option<String>,
+) -> anyhow::Result<()> {
+    let iface_ref = conn
+        .object_server()
+        .interface::<_, Player>(OBJECT_PATH)
+        .await?;
+    let mut player = iface_ref.get_mut().await;
+    let ctxt = iface_ref.signal_context();
+
+    let old_status = player.playback_status();
+    let old_metadata = TrackInfo::new(&player.state, player.nts_show.as_deref());
+    let old_volume = player.state.volume;
+    let old_can_seek = player.can_seek();
+
+    player.state = state;
+    player.nts_show = nts_show;
+
+    if player.playback_status() != old_status {
+        player.playback_status_changed(ctxt).await?;
+    }
+    if TrackInfo::new(&player.state, player.nts_show.as_deref()) != old_metadata {
+        player.metadata_changed(ctxt).await?;
+    }
+    if player.state.volume != old_volume {
+        player.volume_changed(ctxt).await?;
+    }
+    if player.can_seek() != old_can_seek {
+        player.can_seek_changed(ctxt).await?;
+    }
+    Ok(())
+}
+
+// ── NTS show titles ──────────────────────────────────────────────────────────
+
+/// Cached title of the show on air on the NTS channel being played.
+#[derive(Default)]
+struct NtsTitle {
+    channel: Option<usize>,
+    title: Option<String>,
+    fetched_at: Option<Instant>,
+}
+
+impl NtsTitle {
+    async fn lookup(
+        &mut self,
+        state: &DaemonState,
+        remote_tx: &mpsc::Sender<RemoteRequest>,
+    ) -> Option<String> {
+        let channel = nts_channel(state);
+        let fresh = self.fetched_at.is_some_and(|t| t.elapsed() < NTS_REFRESH);
+        if channel != self.channel || !fresh {
+            self.channel = channel;
+            self.title = match channel {
+                Some(ch) => fetch_nts_show(ch, remote_tx).await,
+                None => None,
+            };
+            self.fetched_at = Some(Instant::now());
+        }
+        self.title.clone()
+    }
+}
+
+/// NTS channel of the station being played, if it is one.
+fn nts_channel(state: &DaemonState) -> Option<usize> {
+    let idx = state.current_station?;
+    let name = &state.stations.get(idx)?.name;
+    (0..2).find(|&ch| *name == nts_station_name(ch))
+}
+
+async fn fetch_nts_show(channel: usize, remote_tx: &mpsc::Sender<RemoteRequest>) -> Option<String> {
+    let (reply, rx) = oneshot::channel();
+    remote_tx.send(RemoteRequest::Nts(reply)).await.ok()?;
+    match tokio::time::timeout(NTS_TIMEOUT, rx).await {
+        Ok(Ok(Ok(channels))) => channels
+            .into_iter()
+            .find(|info| info.channel == channel + 1)
+            .and_then(|info| info.now)
+            .map(|show| show.title),
+        _ => {
+            debug!("[mpris] no NTS schedule for channel {}", channel + 1);
+            None
+        }
+    }
+}
+
+// ── Metadata ─────────────────────────────────────────────────────────────────
+
+/// What the `Metadata` property describes, before conversion to D-Bus values.
+#[derive(Debug, Clone, PartialEq)]
+struct TrackInfo {
+    track_id: String,
+    /// ICY title, else the NTS show, else the station or file name.
+    title: Option<String>,
+    /// Station name; widgets show it as the artist line.
+    artist: Option<String>,
+    /// NTS show title while an NTS channel plays.
+    album: Option<String>,
+    url: Option<String>,
+    /// Files only: live streams have no length.
+    length_us: Option<i64>,
+}
+
+impl TrackInfo {
+    fn new(state: &DaemonState, nts_show: Option<&str>) -> Self {
+        let station = state.current_station.and_then(|i| state.stations.get(i));
+        if let (Some(idx), Some(station)) = (state.current_station, station) {
+            let title = state
+                .icy_title
+                .clone()
+                .or_else(|| nts_show.map(str::to_string))
+                .unwrap_or_else(|| station.name.clone());
+            return Self {
+                track_id: format!("/org/r4dio/station/{}", idx),
+                title: Some(title),
+                artist: Some(station.name.clone()),
+                album: nts_show.map(str::to_string),
+                url: Some(station.url.clone()),
+                length_us: None,
+            };
+        }
+        if let Some(path) = &state.current_file {
+            let name = Path::new(path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.clone());
+            return Self {
+                track_id: "/org/r4dio/file".to_string(),
+                title: Some(state.icy_title.clone().unwrap_or(name)),
+                artist: None,
+                album: None,
+                url: Some(format!("file://{}", path)),
+                length_us: state.duration_secs.map(secs_to_us),
+            };
+        }
+        Self {
+            track_id: NO_TRACK.to_string(),
+            title: None,
+            artist: None,
+            album: None,
+            url: None,
+            length_us: None,
+        }
+    }
+
+    fn to_dbus(&self) -> HashMap<String, OwnedValue> {
+        let mut map = HashMap::new();
+        let track_id = ObjectPath::try_from(self.track_id.as_str())
+            .unwrap_or_else(|_| ObjectPath::from_static_str_unchecked(NO_TRACK));
+        map.insert("mpris:trackid".to_string(), owned(track_id));
+        if let Some(title) = &self.title {
+            map.insert("xesam:title".to_string(), owned(title.as_str()));
+        }
+        if let Some(artist) = &self.artist {
+            map.insert("xesam:artist".to_string(), owned(vec![artist.as_str()]));
+        }
+        if let Some(album) = &self.album {
+            map.insert("xesam:album".to_string(), owned(album.as_str()));
+        }
+        if let Some(url) = &self.url {
+            map.insert("xesam:url".to_string(), owned(url.as_str()));
+        }
+        if let Some(length) = self.length_us {
+            map.insert("mpris:length".to_string(), owned(length));
+        }
+        map
+    }
+}
+
+fn owned<'a>(value: impl Into<Value<'a>>) -> OwnedValue {
+    // Only fd-carrying values can fail to convert, and metadata has none.
+    value
+        .into()
+        .try_to_owned()
+        .expect("metadata values are plain data")
+}
+
+fn secs_to_us(secs: f64) -> i64 {
+    (secs * 1_000_000.0) as i64
+}
+
+fn status_name(status: &PlaybackStatus) -> &'static str {
+    match status {
+        PlaybackStatus::Playing | PlaybackStatus::Connecting => "Playing",
+        PlaybackStatus::Paused => "Paused",
+        PlaybackStatus::Idle | PlaybackStatus::Error => "Stopped",
+    }
+}
+
+// ── org.mpris.MediaPlayer2 ───────────────────────────────────────────────────
+
+struct Root;
+
+#[interface(name = "org.mpris.MediaPlayer2")]
+impl Root {
+    fn raise(&self) {}
+
+    fn quit(&self) {}
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> String {
+        "r4dio".to_string()
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+// ── org.mpris.MediaPlayer2.Player ────────────────────────────────────────────
+
+struct Player {
+    event_tx: mpsc::Sender<DaemonEvent>,
+    state: DaemonState,
+    nts_show: Option<String>,
+}
+
+impl Player {
+    async fn send(&self, cmd: Command) -> fdo::Result<()> {
+        debug!("[mpris] {:?}", cmd);
+        self.event_tx
+            .send(DaemonEvent::ClientCommand(cmd))
+            .await
+            .map_err(|_| fdo::Error::Failed("playback core is not running".to_string()))
+    }
+
+    fn is_active(&self) -> bool {
+        matches!(
+            self.state.playback_status,
+            PlaybackStatus::Playing | PlaybackStatus::Connecting
+        )
+    }
+}
+
+#[interface(name = "org.mpris.MediaPlayer2.Player")]
+impl Player {
+    async fn next(&self) -> fdo::Result<()> {
+        self.send(Command::Next).await
+    }
+
+    async fn previous(&self) -> fdo::Result<()> {
+        self.send(Command::Prev).await
+    }
+
+    async fn pause(&self) -> fdo::Result<()> {
+        if self.is_active() && !self.state.is_paused {
+            self.send(Command::TogglePause).await?;
+        }
+        Ok(())
+    }
+
+    async fn play_pause(&self) -> fdo::Result<()> {
+        if self.is_active() || self.state.is_paused {
+            self.send(Command::TogglePause).await
+        } else {
+            self.play().await
+        }
+    }
+
+    async fn stop(&self) -> fdo::Result<()> {
+        self.send(Command::Stop).await
+    }
+
+    /// Resume if paused, else restart the last station or file.
+    async fn play(&self) -> fdo::Result<()> {
+        if self.state.is_paused {
+            return self.send(Command::TogglePause).await;
+        }
+        if self.is_active() {
+            return Ok(());
+        }
+        if let Some(station_idx) = self.state.current_station {
+            self.send(Command::Play { station_idx }).await
+        } else if let Some(path) = self.state.current_file.clone() {
+            self.send(Command::PlayFile { path }).await
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn seek(&self, offset: i64) -> fdo::Result<()> {
+        if !self.can_seek() {
+            return Ok(());
+        }
+        self.send(Command::SeekRelative {
+            seconds: offset as f64 / 1_000_000.0,
+        })
+        .await
+    }
+
+    async fn set_position(&self, track_id: ObjectPath<'_>, position: i64) -> fdo::Result<()> {
+        let current = TrackInfo::new(&self.state, self.nts_show.as_deref());
+        if !self.can_seek() || track_id.as_str() != current.track_id || position < 0 {
+            return Ok(());
+        }
+        self.send(Command::SeekTo {
+            seconds: position as f64 / 1_000_000.0,
+        })
+        .await
+    }
+
+    fn open_uri(&self, _uri: String) -> fdo::Result<()> {
+        Err(fdo::Error::NotSupported(
+            "r4dio plays stations from its own list".to_string(),
+        ))
+    }
+
+    #[zbus(signal)]
+    async fn seeked(ctxt: &zbus::SignalContext<'_>, position: i64) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn playback_status(&self) -> String {
+        status_name(&self.state.playback_status).to_string()
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn set_rate(&self, _rate: f64) {}
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        TrackInfo::new(&self.state, self.nts_show.as_deref()).to_dbus()
+    }
+
+    #[zbus(property)]
+    fn volume(&self) -> f64 {
+        self.state.volume as f64
+    }
+
+    #[zbus(property)]
+    async fn set_volume(&mut self, volume: f64) {
+        let value = volume.clamp(0.0, 1.0) as f32;
+        if self.send(Command::Volume { value }).await.is_err() {
+            warn!("[mpris] volume change dropped: core is not running");
+        }
+    }
+
+    /// Microseconds into the file, or into the timeshift buffer for stations.
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn position(&self) -> i64 {
+        self.state.time_pos_secs.map(secs_to_us).unwrap_or(0)
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        self.state.current_station.is_some() || self.state.current_file.is_some()
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        true
+    }
+
+    /// Files, and stations with a timeshift buffer to seek in.
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        (self.is_active() || self.state.is_paused)
+            && self.state.duration_secs.is_some_and(|d| d > 0.0)
+    }
+
+    #[zbus(property(emits_changed_signal = "const"))]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use radio_proto::protocol::Station;
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Stdio};
+
+    fn test_state() -> DaemonState {
+        DaemonState {
+            stations: vec![
+                Station {
+                    name: "NTS 1".into(),
+                    url: "https://stream-relay-geo.ntslive.net/stream".into(),
+                    ..Default::default()
+                },
+                Station {
+                    name: "FIP".into(),
+                    url: "https://icecast.radiofrance.fr/fip-hifi.aac".into(),
+                    ..Default::default()
+                },
+            ],
+            current_station: Some(0),
+            playback_status: PlaybackStatus::Playing,
+            is_playing: true,
+            volume: 0.5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_track_info() {
+        let mut state = test_state();
+        assert_eq!(nts_channel(&state), Some(0));
+
+        let info = TrackInfo::new(&state, Some("Breakfast Show"));
+        assert_eq!(info.track_id, "/org/r4dio/station/0");
+        assert_eq!(info.title.as_deref(), Some("Breakfast Show"));
+        assert_eq!(info.artist.as_deref(), Some("NTS 1"));
+        assert_eq!(info.album.as_deref(), Some("Breakfast Show"));
+
+        state.icy_title = Some("Artist - Song".into());
+        let info = TrackInfo::new(&state, Some("Breakfast Show"));
+        assert_eq!(info.title.as_deref(), Some("Artist - Song"));
+
+        state.current_station = Some(1);
+        state.icy_title = None;
+        assert_eq!(nts_channel(&state), None);
+        assert_eq!(TrackInfo::new(&state, None).title.as_deref(), Some("FIP"));
+
+        state.current_station = None;
+        state.current_file = Some("/music/show.mp3".into());
+        state.duration_secs = Some(2.5);
+        let info = TrackInfo::new(&state, None);
+        assert_eq!(info.title.as_deref(), Some("show"));
+        assert_eq!(info.length_us, Some(2_500_000));
+
+        state.current_file = None;
+        assert_eq!(TrackInfo::new(&state, None).track_id, NO_TRACK);
+    }
+
+    /// Private `dbus-daemon` for one test; killed on drop.
+    struct TestBus {
+        child: Child,
+        address: String,
+    }
+
+    impl TestBus {
+        fn start() -> Option<Self> {
+            let mut child = std::process::Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address=1"])
+                .stdout(Stdio::piped())
+                .spawn()
+                .ok()?;
+            let mut address = String::new();
+            BufReader::new(child.stdout.take()?)
+                .read_line(&mut address)
+                .ok()?;
+            Some(Self {
+                child,
+                address: address.trim().to_string(),
+            })
+        }
+    }
+
+    impl Drop for TestBus {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_player_on_session_bus() {
+        let Some(bus) = TestBus::start() else {
+            eprintln!("dbus-daemon not found, skipping");
+            return;
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let state_manager = Arc::new(StateManager::new(
+            dir.path().join("state.json"),
+            test_state().stations,
+        ));
+        state_manager.set_playing(1).await.unwrap();
+        state_manager
+            .set_playback_status(PlaybackStatus::Playing)
+            .await;
+        state_manager
+            .set_icy_title(Some("Artist - Song".into()))
+            .await;
+
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let (broadcast_tx, _) = broadcast::channel(16);
+        let (remote_tx, _remote_rx) = mpsc::channel(16);
+        let builder = connection::Builder::address(bus.address.as_str()).unwrap();
+        tokio::spawn(serve(
+            builder,
+            state_manager.clone(),
+            event_tx,
+            broadcast_tx.clone(),
+            remote_tx,
+        ));
+
+        let client = connection::Builder::address(bus.address.as_str())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let player = zbus::Proxy::new(
+            &client,
+            BUS_NAME,
+            OBJECT_PATH,
+            "org.mpris.MediaPlayer2.Player",
+        )
+        .await
+        .unwrap();
+
+        // The service may still be claiming its name.
+        let mut status = None;
+        for _ in 0..50 {
+            if let Ok(s) = player.get_property::<String>("PlaybackStatus").await {
+                status = Some(s);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(status.as_deref(), Some("Playing"));
+
+        let metadata: HashMap<String, OwnedValue> = player.get_property("Metadata").await.unwrap();
+        let title: String = metadata["xesam:title"]
+            .try_clone()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(title, "Artist - Song");
+
+        player.call_method("PlayPause", &()).await.unwrap();
+        match event_rx.recv().await {
+            Some(DaemonEvent::ClientCommand(Command::TogglePause)) => {}
+            _ => panic!("expected TogglePause"),
+        }
+        player.call_method("Next", &()).await.unwrap();
+        match event_rx.recv().await {
+            Some(DaemonEvent::ClientCommand(Command::Next)) => {}
+            _ => panic!("expected Next"),
+        }
+        player.set_property("Volume", 0.25f64).await.unwrap();
+        match event_rx.recv().await {
+            Some(DaemonEvent::ClientCommand(Command::Volume { value })) => assert_eq!(value, 0.25),
+            _ => panic!("expected Volume"),
+        }
+
+        // Property changes follow the core's broadcasts.
+        state_manager
+            .set_playback_status(PlaybackStatus::Paused)
+            .await;
+        let _ = broadcast_tx.send(BroadcastMessage::StateUpdated);
+        let mut status = String::new();
+        for _ in 0..50 {
+            let uncached = zbus::Proxy::new(
+                &client,
+                BUS_NAME,
+                OBJECT_PATH,
+                "org.mpris.MediaPlayer2.Player",
+            )
+            .await
+            .unwrap();
+            status = uncached.get_property("PlaybackStatus").await.unwrap();
+            if status == "Paused" {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(status, "Paused");
+    }
+}
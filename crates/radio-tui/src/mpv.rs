@@ -18,6 +18,7 @@
 /// Platform notes:
 /// - Unix:   Unix domain sockets
 /// - Windows: Named pipes  \\.\pipe\<name>
+use radio_proto::protocol::PlayerCache;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -527,6 +528,22 @@ impl MpvHandle {
         }
     }
 
+    /// How far ahead the demuxer has read, for the diagnostics pane.
+    pub async fn get_cache(&self) -> anyhow::Result<PlayerCache> {
+        let state = self
+            .send(json!(["get_property", "demuxer-cache-state"]))
+            .await?;
+        let stalled = self
+            .send(json!(["get_property", "paused-for-cache"]))
+            .await?;
+        let cache = &state["data"];
+        Ok(PlayerCache {
+            cache_secs: cache["cache-duration"].as_f64(),
+            input_kbps: cache["raw-input-rate"].as_f64().map(|r| r * 8.0 / 1000.0),
+            paused_for_cache: stalled["data"].as_bool().unwrap_or(false),
+        })
+    }
+
     pub async fn seek_to(&self, secs: f64) -> anyhow::Result<()> {
         self.send(json!(["set_property", "time-pos", secs])).await?;
         Ok(())
@@ -571,14 +588,24 @@ impl MpvHandle {
     ///
     /// We poll `af-metadata/meter` via get_property at 50 Hz rather than relying
     /// on observe_property, so this filter just needs to produce fresh data each frame.
-    pub async fn set_audio_filter(&self) {
-        let filter = json!([{
+    ///
+    /// A non-zero `gain_db` (loudness normalization) adds a `volume` filter
+    /// after the meter, so the meter keeps reading the source level.
+    pub async fn set_audio_filter(&self, gain_db: f32) {
+        let mut filters = vec![json!({
             "name": "lavfi",
             "label": "meter",
             "params": { "graph": "astats=metadata=1:reset=0:length=0.02" }
-        }]);
-        match self.send(json!(["set_property", "af", filter])).await {
-            Ok(_) => debug!("mpv: astats audio filter installed (reset=0, length=0.02)"),
+        })];
+        if gain_db.abs() >= 0.05 {
+            filters.push(json!({
+                "name": "lavfi",
+                "label": "gain",
+                "params": { "graph": format!("volume=volume={:.2}dB", gain_db) }
+            }));
+        }
+        match self.send(json!(["set_property", "af", filters])).await {
+            Ok(_) => debug!("mpv: audio filters installed (gain {:+.1} dB)", gain_db),
             Err(e) => warn!("mpv: failed to set astats filter: {}", e),
         }
     }
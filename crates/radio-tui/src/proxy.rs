@@ -1,38 +1,319 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use axum::body::{Body, Bytes};
-use axum::extract::{Path, State};
-use axum::http::StatusCode;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::routing::get;
-use axum::Router;
+use axum::{Json, Router};
 use futures_util::StreamExt;
 use reqwest::Client;
-use tokio::sync::{broadcast, Mutex};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, watch, Mutex, Notify};
 use tracing::{debug, error, info, warn};
 
-use radio_proto::state::StateManager;
+use radio_proto::protocol::{StreamDiagnostics, UpstreamStatus};
+use radio_proto::state::{ListChange, StateManager};
+
+use crate::icy::{metaint_from_headers, IcyDemuxer, IcyMuxer};
+use crate::latency::{self, BroadcastLagMonitor};
+use crate::silence;
+use crate::stream_format::{self, StreamFormat};
+use crate::stream_playlist::{self, Chunk, Opened};
 
 pub const PROXY_PORT: u16 = 8990;
 pub const PROXY_HOST: &str = "127.0.0.1";
 const PROXY_BROADCAST_CAPACITY: usize = 4096;
+/// Metadata interval of the ICY framing served to clients that send
+/// `Icy-MetaData: 1`, live or from the timeshift buffer.
+const CLIENT_METAINT: usize = 16 * 1024;
+/// Title changes kept per shared stream for `/titles/:idx`.
+const TITLE_LOG_LEN: usize = 100;
+/// Upper bound on buffered chunks handed to a timeshift subscriber per body frame.
+const TIMESHIFT_BATCH: usize = 64;
+/// Audio kept per shared stream even with timeshift off, so song recognition
+/// can take a clip of what was just played (see `/clip/:idx`).
+const RECENT_AUDIO_WINDOW: Duration = Duration::from_secs(60);
+/// Stream slot of the station being previewed (`/preview`), which has no
+/// index in the station list.
+const PREVIEW_IDX: usize = usize::MAX;
+/// First wait before reopening a lost upstream; doubles each round up to
+/// `RECONNECT_DELAY_MAX`.
+const RECONNECT_DELAY_MIN: Duration = Duration::from_millis(500);
+const RECONNECT_DELAY_MAX: Duration = Duration::from_secs(8);
+/// Stop trying (and end the listeners' streams) after this long without
+/// the upstream; mpv's own network timeout is about as long.
+const RECONNECT_GIVE_UP: Duration = Duration::from_secs(60);
+/// ICY title of the silence live listeners get while the upstream is
+/// reconnecting.
+const RECONNECT_TITLE: &str = "[reconnecting…]";
+/// How often that silence is sent, and how much of the last audio is
+/// searched for the frame format to copy.
+const GAP_FILL_TICK: Duration = Duration::from_millis(250);
+const GAP_FILL_SOURCE_BYTES: usize = 16 * 1024;
+/// Upstream throughput is averaged over this long.
+const RATE_WINDOW: Duration = Duration::from_secs(3);
+/// Audio buffered before ffprobe is run over it for the diagnostics pane.
+const PROBE_AFTER: Duration = Duration::from_secs(5);
 
 #[derive(Clone)]
 pub struct ProxyState {
     state_manager: Arc<StateManager>,
     client: Client,
     streams: Arc<Mutex<HashMap<usize, Arc<SharedStream>>>>,
+    /// How much audio each shared stream keeps for timeshift; zero disables it.
+    timeshift_window: Duration,
+    /// Highest HLS variant bandwidth to pick, in bits/s; 0 for the best.
+    hls_max_bandwidth: u64,
+    /// Upstream URL served on `/preview`.
+    preview_url: Arc<std::sync::Mutex<Option<String>>>,
+    /// Woken whenever a pump sees a new ICY title.
+    title_changed: Arc<Notify>,
+}
+
+/// Clean audio as the pump hands it to live subscribers, with the ICY title
+/// on air for it.
+#[derive(Debug, Clone)]
+pub struct LiveChunk {
+    pub data: Bytes,
+    pub title: Option<Arc<str>>,
+    /// Silence standing in for a lost upstream; `title` is `RECONNECT_TITLE`.
+    pub filler: bool,
+}
+
+/// A `StreamTitle` change on a shared stream.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TitleChange {
+    pub title: String,
+    /// Bytes of clean audio the stream delivered before this title.
+    pub offset: u64,
+    /// When the audio it starts with arrived, in Unix milliseconds.
+    pub at_ms: i64,
 }
 
 struct SharedStream {
     headers: reqwest::header::HeaderMap,
-    tx: broadcast::Sender<Bytes>,
+    tx: broadcast::Sender<LiveChunk>,
+    /// ICY-stripped audio from the last `timeshift_window` (at least
+    /// `RECENT_AUDIO_WINDOW`).
+    timeshift: Arc<std::sync::Mutex<TimeshiftBuffer>>,
+    /// Sequence number of the newest buffered chunk.  Timeshift subscribers
+    /// wait on this; it closes when the upstream pump exits.
+    timeshift_seq: watch::Sender<u64>,
+    /// URL streaming and how often it was lost.
+    upstream: std::sync::Mutex<UpstreamStatus>,
+    /// The last `TITLE_LOG_LEN` title changes, oldest first.
+    titles: std::sync::Mutex<VecDeque<TitleChange>>,
+    /// Diagnostics: when the upstream was first opened, what it sends and
+    /// how fast, and how often live subscribers fell behind.
+    opened_at: Instant,
+    format: std::sync::Mutex<StreamFormat>,
+    received: std::sync::Mutex<RateMeter>,
+    lag: BroadcastLagMonitor,
+}
+
+impl SharedStream {
+    /// Live and timeshift subscribers.
+    fn listeners(&self) -> usize {
+        self.tx.receiver_count() + self.timeshift_seq.receiver_count()
+    }
+}
+
+/// Bytes per second, averaged over `RATE_WINDOW`.
+#[derive(Debug)]
+struct RateMeter {
+    since: Instant,
+    bytes: u64,
+    rate: f64,
+}
+
+impl RateMeter {
+    fn new(now: Instant) -> Self {
+        Self {
+            since: now,
+            bytes: 0,
+            rate: 0.0,
+        }
+    }
+
+    fn add(&mut self, bytes: usize, now: Instant) {
+        self.bytes += bytes as u64;
+        let elapsed = now.saturating_duration_since(self.since);
+        if elapsed >= RATE_WINDOW {
+            self.rate = self.bytes as f64 / elapsed.as_secs_f64();
+            self.since = now;
+            self.bytes = 0;
+        }
+    }
+
+    /// The last window's rate; once nothing has arrived for a while, what
+    /// did arrive since, so a stalled upstream drops towards zero.
+    fn rate(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.since);
+        if elapsed > RATE_WINDOW * 2 {
+            self.bytes as f64 / elapsed.as_secs_f64()
+        } else {
+            self.rate
+        }
+    }
+}
+
+// ── Timeshift buffer ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+struct TimeshiftChunk {
+    seq: u64,
+    at: Instant,
+    data: Bytes,
+    /// ICY title in effect for this audio.
+    title: Option<Arc<str>>,
+    /// First audio after the upstream was lost and reopened.
+    after_gap: bool,
+}
+
+/// Rolling window of clean audio for one station.  Chunks are timestamped on
+/// arrival; since upstream servers deliver in real time, arrival time is a
+/// good enough clock for "N seconds behind live".
+#[derive(Debug)]
+struct TimeshiftBuffer {
+    chunks: VecDeque<TimeshiftChunk>,
+    window: Duration,
+    next_seq: u64,
+    /// The next chunk pushed follows a gap.
+    gap: bool,
+}
+
+impl TimeshiftBuffer {
+    fn new(window: Duration) -> Self {
+        Self {
+            chunks: VecDeque::new(),
+            window,
+            next_seq: 1,
+            gap: false,
+        }
+    }
+
+    fn push(&mut self, data: Bytes, title: Option<Arc<str>>, at: Instant) {
+        self.chunks.push_back(TimeshiftChunk {
+            seq: self.next_seq,
+            at,
+            data,
+            title,
+            after_gap: std::mem::take(&mut self.gap),
+        });
+        self.next_seq += 1;
+        while self
+            .chunks
+            .front()
+            .is_some_and(|c| at.saturating_duration_since(c.at) > self.window)
+        {
+            self.chunks.pop_front();
+        }
+    }
+
+    /// The audio pushed next doesn't follow on from what came before.
+    fn mark_gap(&mut self) {
+        self.gap = true;
+    }
+
+    /// The newest `max` bytes held (or a little more, whole chunks).
+    fn tail(&self, max: usize) -> Vec<u8> {
+        let mut len = 0;
+        let start = self
+            .chunks
+            .iter()
+            .rev()
+            .take_while(|c| {
+                let more = len < max;
+                len += c.data.len();
+                more
+            })
+            .count();
+        let mut out = Vec::with_capacity(len);
+        for c in self.chunks.iter().skip(self.chunks.len() - start) {
+            out.extend_from_slice(&c.data);
+        }
+        out
+    }
+
+    /// Sequence number of the newest chunk (0 when empty).
+    fn latest_seq(&self) -> u64 {
+        self.next_seq - 1
+    }
+
+    /// Seconds of audio currently held.
+    fn span_secs(&self, now: Instant) -> f64 {
+        self.chunks
+            .front()
+            .map(|c| now.saturating_duration_since(c.at).as_secs_f64())
+            .unwrap_or(0.0)
+    }
+
+    /// `secs` as a duration within the window; `NaN` and negatives are 0.
+    fn clamp_secs(&self, secs: f64) -> Duration {
+        if secs > 0.0 {
+            Duration::from_secs_f64(secs.min(self.window.as_secs_f64()))
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    /// First chunk at or after `behind` seconds before `now`, clamped to the
+    /// oldest chunk held.
+    fn seq_behind(&self, behind: f64, now: Instant) -> u64 {
+        let target = now.checked_sub(self.clamp_secs(behind)).unwrap_or(now);
+        let i = self.chunks.partition_point(|c| c.at < target);
+        self.chunks.get(i).map(|c| c.seq).unwrap_or(self.next_seq)
+    }
+
+    /// Up to `max` chunks starting at `seq`.  When `seq` has already been
+    /// evicted the result starts at the oldest chunk still held.
+    fn chunks_from(&self, seq: u64, max: usize) -> Vec<TimeshiftChunk> {
+        let Some(front) = self.chunks.front() else {
+            return Vec::new();
+        };
+        let skip = seq.saturating_sub(front.seq) as usize;
+        self.chunks.iter().skip(skip).take(max).cloned().collect()
+    }
+
+    /// Title of the audio `behind` seconds before `now`: the last chunk
+    /// that had arrived by then.
+    fn title_behind(&self, behind: f64, now: Instant) -> Option<Arc<str>> {
+        let target = now.checked_sub(self.clamp_secs(behind)).unwrap_or(now);
+        let i = self.chunks.partition_point(|c| c.at <= target);
+        self.chunks.get(i.checked_sub(1)?)?.title.clone()
+    }
+
+    /// Audio that arrived between `behind + secs` and `behind` seconds before
+    /// `now`, clamped to what is held and to the last gap in it.
+    fn clip(&self, behind: f64, secs: f64, now: Instant) -> Vec<u8> {
+        let Some(end) = now.checked_sub(self.clamp_secs(behind)) else {
+            return Vec::new();
+        };
+        let start = end.checked_sub(self.clamp_secs(secs));
+        let mut out = Vec::new();
+        for c in self
+            .chunks
+            .iter()
+            .filter(|c| c.at <= end && start.is_none_or(|s| c.at >= s))
+        {
+            if c.after_gap {
+                out.clear();
+            }
+            out.extend_from_slice(&c.data);
+        }
+        out
+    }
 }
 
 impl ProxyState {
-    pub fn new(state_manager: Arc<StateManager>) -> Self {
+    pub fn new(
+        state_manager: Arc<StateManager>,
+        timeshift_window: Duration,
+        hls_max_bandwidth: u64,
+    ) -> Self {
         let client = Client::builder()
             .redirect(reqwest::redirect::Policy::limited(10))
             .default_headers({
@@ -50,12 +331,155 @@ impl ProxyState {
             state_manager,
             client,
             streams: Arc::new(Mutex::new(HashMap::new())),
+            timeshift_window,
+            hls_max_bandwidth,
+            preview_url: Arc::new(std::sync::Mutex::new(None)),
+            title_changed: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Notified when any shared stream's ICY title changes.
+    pub fn title_changed(&self) -> Arc<Notify> {
+        self.title_changed.clone()
+    }
+
+    /// ICY title of station `idx` (`None`: the preview) `behind` seconds
+    /// before live, while its upstream is open.
+    pub async fn title_at(&self, idx: Option<usize>, behind: f64) -> Option<String> {
+        let key = idx.unwrap_or(PREVIEW_IDX);
+        let shared = self.streams.lock().await.get(&key).cloned()?;
+        let title = shared
+            .timeshift
+            .lock()
+            .unwrap()
+            .title_behind(behind, Instant::now());
+        title.map(|t| t.to_string())
+    }
+
+    /// Point `/preview` at `url` (or nothing).  A preview already streaming
+    /// keeps its upstream until its last listener leaves; new listeners
+    /// get the new URL.
+    pub async fn set_preview(&self, url: Option<String>) {
+        *self.preview_url.lock().unwrap() = url;
+        self.streams.lock().await.remove(&PREVIEW_IDX);
+    }
+
+    /// Stop handing the open upstream of station `idx` to new listeners
+    /// (its URL changed).  Current listeners keep it until they leave.
+    pub async fn forget(&self, idx: usize) {
+        self.streams.lock().await.remove(&idx);
+    }
+
+    /// Re-key open upstreams after the station list changed shape.  A
+    /// removed station's upstream serves its current listeners only.
+    pub async fn renumber(&self, change: ListChange) {
+        let mut streams = self.streams.lock().await;
+        *streams = streams
+            .drain()
+            .filter_map(|(idx, shared)| {
+                if idx == PREVIEW_IDX {
+                    return Some((idx, shared));
+                }
+                change.apply(idx).map(|idx| (idx, shared))
+            })
+            .collect();
+    }
+
+    /// Seconds of audio buffered for a station, or `None` when timeshift is
+    /// disabled or the station's upstream isn't open.
+    pub async fn timeshift_window(&self, idx: usize) -> Option<f64> {
+        if self.timeshift_window.is_zero() {
+            return None;
         }
+        let shared = self.streams.lock().await.get(&idx).cloned()?;
+        let span = shared.timeshift.lock().unwrap().span_secs(Instant::now());
+        Some(span)
+    }
+
+    /// URL and reconnects of the upstream of station `idx` (`None`: the
+    /// preview), while it's open.
+    pub async fn upstream_status(&self, idx: Option<usize>) -> Option<UpstreamStatus> {
+        let key = idx.unwrap_or(PREVIEW_IDX);
+        let shared = self.streams.lock().await.get(&key).cloned()?;
+        let status = shared.upstream.lock().unwrap().clone();
+        Some(status)
+    }
+
+    /// Health of every open upstream, by station index (the preview last).
+    pub async fn diagnostics(&self) -> Vec<StreamDiagnostics> {
+        let streams: Vec<(usize, Arc<SharedStream>)> = self
+            .streams
+            .lock()
+            .await
+            .iter()
+            .map(|(idx, shared)| (*idx, shared.clone()))
+            .collect();
+        let state = self.state_manager.get_state().await;
+        let now = Instant::now();
+        let mut out: Vec<StreamDiagnostics> = streams
+            .into_iter()
+            .map(|(idx, shared)| {
+                let (station_idx, station) = if idx == PREVIEW_IDX {
+                    (None, state.preview.as_deref())
+                } else {
+                    (Some(idx), state.stations.get(idx))
+                };
+                let upstream = shared.upstream.lock().unwrap().clone();
+                let format = shared.format.lock().unwrap().clone();
+                let lag = shared.lag.lag_stats();
+                let buffer = shared.timeshift.lock().unwrap();
+                StreamDiagnostics {
+                    station_idx,
+                    station_name: station.map(|s| s.name.clone()).unwrap_or_default(),
+                    url: upstream.url,
+                    content_type: shared
+                        .headers
+                        .get("content-type")
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string(),
+                    codec: format.codec,
+                    bitrate_kbps: format.bitrate_kbps,
+                    sample_rate: format.sample_rate,
+                    channels: format.channels,
+                    throughput_kbps: shared.received.lock().unwrap().rate(now) * 8.0 / 1000.0,
+                    listeners: shared.listeners(),
+                    lag_events: lag.total_lag_events,
+                    last_lag_chunks: lag.last_lag_chunks,
+                    reconnects: upstream.reconnects,
+                    reconnecting: upstream.reconnecting,
+                    buffered_secs: buffer.span_secs(now),
+                    buffer_secs: buffer.window.as_secs_f64(),
+                    uptime_secs: now.saturating_duration_since(shared.opened_at).as_secs(),
+                }
+            })
+            .collect();
+        out.sort_by_key(|d| d.station_idx.unwrap_or(usize::MAX));
+        out
+    }
+
+    /// Attach an extra subscriber (e.g. the recorder) to a station's shared
+    /// upstream, opening it if needed.  Returns the upstream headers and the
+    /// clean audio, tagged with its ICY title.
+    pub async fn subscribe(
+        &self,
+        idx: usize,
+    ) -> Result<(reqwest::header::HeaderMap, broadcast::Receiver<LiveChunk>), StatusCode> {
+        let shared = self.get_or_start_stream(idx).await?;
+        Ok((shared.headers.clone(), shared.tx.subscribe()))
     }
 
-    async fn station_url(&self, idx: usize) -> Option<String> {
+    /// The station's stream URL followed by its mirrors.
+    async fn station_urls(&self, idx: usize) -> Option<Vec<String>> {
+        if idx == PREVIEW_IDX {
+            let url = self.preview_url.lock().unwrap().clone()?;
+            return Some(vec![url]);
+        }
         let state = self.state_manager.get_state().await;
-        state.stations.get(idx).map(|s| s.url.clone())
+        let station = state.stations.get(idx)?;
+        let mut urls = vec![station.url.clone()];
+        urls.extend(station.mirrors.iter().cloned());
+        Some(urls)
     }
 
     async fn get_or_start_stream(&self, idx: usize) -> Result<Arc<SharedStream>, StatusCode> {
@@ -68,36 +492,54 @@ impl ProxyState {
             return Ok(existing);
         }
 
-        let url = self.station_url(idx).await.ok_or(StatusCode::NOT_FOUND)?;
+        let urls = self.station_urls(idx).await.ok_or(StatusCode::NOT_FOUND)?;
         info!(
             "proxy: opening shared upstream for station {} → {}",
-            idx, url
-        );
-
-        let upstream = self.client.get(&url).send().await.map_err(|e| {
-            error!("proxy: upstream connect failed for idx={}: {}", idx, e);
-            StatusCode::BAD_GATEWAY
-        })?;
-
-        let status = upstream.status();
-        debug!(
-            "proxy: upstream response for idx={}: status={}, headers={:?}",
-            idx,
-            status,
-            upstream.headers()
+            idx, urls[0]
         );
 
-        if !status.is_success() {
-            error!(
-                "proxy: upstream returned {} for idx={}, url={}",
-                status, idx, url
+        let mut upstream = Upstream {
+            client: self.client.clone(),
+            urls,
+            current: 0,
+            hls_max_bandwidth: self.hls_max_bandwidth,
+        };
+        let (url_idx, opened) = open_upstream(&upstream, idx, 0, None)
+            .await
+            .ok_or(StatusCode::BAD_GATEWAY)?;
+        upstream.current = url_idx;
+        if url_idx > 0 {
+            warn!(
+                "proxy: idx={} primary url is down, failing over to mirror {}",
+                idx, upstream.urls[url_idx]
             );
-            return Err(StatusCode::BAD_GATEWAY);
         }
+        debug!(
+            "proxy: upstream for idx={}: headers={:?}",
+            idx, opened.headers
+        );
 
-        let headers = upstream.headers().clone();
-        let (tx, _rx) = broadcast::channel::<Bytes>(PROXY_BROADCAST_CAPACITY);
-        let shared = Arc::new(SharedStream { headers, tx });
+        let headers = opened.headers.clone();
+        let format = StreamFormat::from_headers(&headers);
+        let (tx, _rx) = broadcast::channel::<LiveChunk>(PROXY_BROADCAST_CAPACITY);
+        let (timeshift_seq, _seq_rx) = watch::channel(0u64);
+        let shared = Arc::new(SharedStream {
+            headers,
+            tx,
+            timeshift: Arc::new(std::sync::Mutex::new(TimeshiftBuffer::new(
+                self.timeshift_window.max(RECENT_AUDIO_WINDOW),
+            ))),
+            timeshift_seq,
+            upstream: std::sync::Mutex::new(UpstreamStatus {
+                url: upstream.urls[url_idx].clone(),
+                ..UpstreamStatus::default()
+            }),
+            titles: std::sync::Mutex::new(VecDeque::new()),
+            opened_at: Instant::now(),
+            format: std::sync::Mutex::new(format),
+            received: std::sync::Mutex::new(RateMeter::new(Instant::now())),
+            lag: BroadcastLagMonitor::new(latency::global_telemetry().cloned().unwrap_or_default()),
+        });
 
         self.streams.lock().await.insert(idx, shared.clone());
         debug!(
@@ -105,96 +547,358 @@ impl ProxyState {
             idx, PROXY_BROADCAST_CAPACITY
         );
 
-        let streams = self.streams.clone();
-        let shared_for_task = shared.clone();
-        let url_for_task = url.clone();
-        tokio::spawn(async move {
-            let mut bytes_stream = upstream.bytes_stream();
-            let mut no_receivers_since: Option<Instant> = None;
-            let mut total_bytes: u64 = 0;
-            let mut chunk_count: u64 = 0;
-
-            while let Some(next) = bytes_stream.next().await {
-                let chunk = match next {
-                    Ok(c) => {
-                        total_bytes += c.len() as u64;
-                        chunk_count += 1;
-                        if chunk_count <= 5 || chunk_count % 100 == 0 {
-                            debug!(
-                                "proxy: idx={} received chunk #{}, {} bytes (total: {}), receivers={}",
-                                idx,
-                                chunk_count,
-                                c.len(),
-                                total_bytes,
-                                shared_for_task.tx.receiver_count()
-                            );
-                        }
-                        c
-                    }
-                    Err(e) => {
-                        error!(
-                            "proxy: upstream read error idx={}, url={}, chunks={}, bytes={}, error={}",
-                            idx, url_for_task, chunk_count, total_bytes, e
-                        );
-                        break;
-                    }
-                };
+        tokio::spawn(pump(
+            idx,
+            shared.clone(),
+            opened,
+            upstream,
+            self.streams.clone(),
+            self.title_changed.clone(),
+        ));
 
-                let receiver_count = shared_for_task.tx.receiver_count();
-                if receiver_count == 0 {
-                    if no_receivers_since
-                        .get_or_insert_with(Instant::now)
-                        .elapsed()
-                        >= Duration::from_secs(2)
-                    {
-                        debug!(
-                            "proxy: no subscribers for idx={}, closing upstream after 2s (received {} chunks, {} bytes)",
-                            idx, chunk_count, total_bytes
-                        );
-                        break;
-                    }
+        Ok(shared)
+    }
+}
+
+/// Where a shared stream's audio comes from, for reopening it.
+struct Upstream {
+    client: Client,
+    /// The station's URL followed by its mirrors.
+    urls: Vec<String>,
+    /// Index into `urls` of the one streaming.
+    current: usize,
+    hls_max_bandwidth: u64,
+}
+
+/// Media type of a response (`audio/mpeg`), without parameters.
+fn media_type(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let value = headers.get("content-type")?.to_str().ok()?;
+    let media = value.split(';').next().unwrap_or("").trim();
+    (!media.is_empty()).then(|| media.to_ascii_lowercase())
+}
+
+/// Open the first of the upstream's URLs that answers, starting at `from`
+/// and wrapping around; returns its index and the stream (playlists
+/// resolved, see `stream_playlist::open`).  With `content_type` set, a URL
+/// serving another format is skipped: it can't be spliced into a stream
+/// listeners are already decoding.
+async fn open_upstream(
+    upstream: &Upstream,
+    idx: usize,
+    from: usize,
+    content_type: Option<&str>,
+) -> Option<(usize, Opened)> {
+    let urls = &upstream.urls;
+    for i in (0..urls.len()).map(|n| (from + n) % urls.len()) {
+        let url = &urls[i];
+        match stream_playlist::open(&upstream.client, url, upstream.hls_max_bandwidth).await {
+            Ok(opened) => {
+                let found = media_type(&opened.headers);
+                if content_type.is_some_and(|want| found.as_deref() != Some(want)) {
+                    warn!(
+                        "proxy: idx={} skipping {}: serves {:?}, stream is {:?}",
+                        idx, url, found, content_type
+                    );
                     continue;
                 }
-                no_receivers_since = None;
+                return Some((i, opened));
+            }
+            Err(e) => error!(
+                "proxy: upstream open failed for idx={}, url={}: {}",
+                idx, url, e
+            ),
+        }
+    }
+    None
+}
 
-                if shared_for_task.tx.send(chunk).is_err() {
-                    // All receivers dropped
-                    if chunk_count <= 10 {
+/// Reopen a lost upstream (see `reopen`) while live listeners get silence
+/// tagged `RECONNECT_TITLE`, so their players don't stall or time out.
+async fn reconnect(
+    idx: usize,
+    shared: &Arc<SharedStream>,
+    upstream: &mut Upstream,
+) -> Option<Opened> {
+    shared.upstream.lock().unwrap().reconnecting = true;
+    let filler = tokio::spawn(fill_gap(idx, shared.clone()));
+    let opened = reopen(idx, shared, upstream).await;
+    filler.abort();
+    opened
+}
+
+/// Send live listeners silent frames in the stream's format, in real time,
+/// until aborted.  Nothing for codecs `silence` can't produce.
+async fn fill_gap(idx: usize, shared: Arc<SharedStream>) {
+    let tail = shared.timeshift.lock().unwrap().tail(GAP_FILL_SOURCE_BYTES);
+    let Some(frame) = silence::frame_like(&tail) else {
+        debug!(
+            "proxy: idx={} no silence for this format, listeners wait",
+            idx
+        );
+        return;
+    };
+    let title: Arc<str> = Arc::from(RECONNECT_TITLE);
+    let started = Instant::now();
+    let mut sent = Duration::ZERO;
+    let mut tick = tokio::time::interval(GAP_FILL_TICK);
+    loop {
+        tick.tick().await;
+        let mut data = Vec::new();
+        while sent < started.elapsed() {
+            data.extend_from_slice(&frame.data);
+            sent += frame.duration;
+        }
+        if !data.is_empty() {
+            let _ = shared.tx.send(LiveChunk {
+                data: Bytes::from(data),
+                title: Some(title.clone()),
+                filler: true,
+            });
+        }
+    }
+}
+
+/// Reopen a lost upstream: the URL that was streaming first, then the
+/// others, backing off between rounds.  `None` once the listeners are gone
+/// or after `RECONNECT_GIVE_UP`.
+async fn reopen(idx: usize, shared: &SharedStream, upstream: &mut Upstream) -> Option<Opened> {
+    let content_type = media_type(&shared.headers);
+    let started = Instant::now();
+    let mut delay = RECONNECT_DELAY_MIN;
+    loop {
+        tokio::time::sleep(delay).await;
+        if shared.listeners() == 0 {
+            debug!("proxy: idx={} lost its listeners while reconnecting", idx);
+            return None;
+        }
+        let opened = open_upstream(upstream, idx, upstream.current, content_type.as_deref()).await;
+        if let Some((current, opened)) = opened {
+            upstream.current = current;
+            let mut status = shared.upstream.lock().unwrap();
+            status.url = upstream.urls[current].clone();
+            status.reconnects += 1;
+            status.reconnecting = false;
+            info!(
+                "proxy: idx={} reconnected to {} after {:.1}s (reconnect #{})",
+                idx,
+                status.url,
+                started.elapsed().as_secs_f64(),
+                status.reconnects
+            );
+            return Some(opened);
+        }
+        if started.elapsed() >= RECONNECT_GIVE_UP {
+            error!(
+                "proxy: idx={} giving up on the upstream after {}s",
+                idx,
+                RECONNECT_GIVE_UP.as_secs()
+            );
+            return None;
+        }
+        delay = (delay * 2).min(RECONNECT_DELAY_MAX);
+    }
+}
+
+/// Feed a shared stream from its upstream until the last listener leaves.
+/// ICY metadata is demuxed here, once: subscribers get clean audio tagged
+/// with its title, and each title change is logged with its byte offset and
+/// arrival time.  A lost upstream is reopened while the listeners stay
+/// attached; they hear silence until it is back.
+async fn pump(
+    idx: usize,
+    shared: Arc<SharedStream>,
+    mut opened: Opened,
+    mut upstream: Upstream,
+    streams: Arc<Mutex<HashMap<usize, Arc<SharedStream>>>>,
+    title_changed: Arc<Notify>,
+) {
+    let mut audio = Vec::with_capacity(16 * 1024);
+    let mut pieces: Vec<LiveChunk> = Vec::new();
+    let mut title: Option<Arc<str>> = None;
+    let mut audio_bytes: u64 = 0;
+    let mut no_receivers_since: Option<Instant> = None;
+    let mut probed = false;
+    let mut total_bytes: u64 = 0;
+    let mut chunk_count: u64 = 0;
+
+    'upstream: loop {
+        let url = upstream.urls[upstream.current].clone();
+        let mut demuxer = IcyDemuxer::new(metaint_from_headers(&opened.headers));
+
+        loop {
+            let chunk = match opened.body.next().await {
+                Some(Ok(Chunk::Gap)) => {
+                    shared.timeshift.lock().unwrap().mark_gap();
+                    continue;
+                }
+                Some(Ok(Chunk::Audio(c))) => {
+                    total_bytes += c.len() as u64;
+                    shared.received.lock().unwrap().add(c.len(), Instant::now());
+                    chunk_count += 1;
+                    if chunk_count <= 5 || chunk_count % 100 == 0 {
                         debug!(
-                            "proxy: idx={} broadcast send failed, no active receivers (sent {} chunks)",
-                            idx, chunk_count
+                            "proxy: idx={} received chunk #{}, {} bytes (total: {}), receivers={}",
+                            idx,
+                            chunk_count,
+                            c.len(),
+                            total_bytes,
+                            shared.tx.receiver_count()
                         );
                     }
-                    continue;
+                    c
                 }
+                Some(Err(e)) => {
+                    error!(
+                        "proxy: upstream read error idx={}, url={}, chunks={}, bytes={}, error={}",
+                        idx, url, chunk_count, total_bytes, e
+                    );
+                    break;
+                }
+                None => {
+                    warn!(
+                        "proxy: upstream ended idx={}, url={}, chunks={}, bytes={}",
+                        idx, url, chunk_count, total_bytes
+                    );
+                    break;
+                }
+            };
+
+            audio.clear();
+            pieces.clear();
+            let titles = demuxer.feed(&chunk, &mut audio);
+            // Split at title changes so every piece carries the title that
+            // was on air for it.
+            let mut start = 0;
+            for (offset, t) in titles {
+                if offset > start {
+                    pieces.push(LiveChunk {
+                        data: Bytes::copy_from_slice(&audio[start..offset]),
+                        title: title.clone(),
+                        filler: false,
+                    });
+                }
+                start = offset;
+                if title.as_deref() != Some(t.as_str()) {
+                    info!("proxy: idx={} title → {:?}", idx, t);
+                    let mut log = shared.titles.lock().unwrap();
+                    if log.len() == TITLE_LOG_LEN {
+                        log.pop_front();
+                    }
+                    log.push_back(TitleChange {
+                        title: t.clone(),
+                        offset: audio_bytes + offset as u64,
+                        at_ms: chrono::Utc::now().timestamp_millis(),
+                    });
+                    title_changed.notify_one();
+                    title = Some(Arc::from(t));
+                }
+            }
+            if audio.len() > start {
+                pieces.push(LiveChunk {
+                    data: Bytes::copy_from_slice(&audio[start..]),
+                    title: title.clone(),
+                    filler: false,
+                });
             }
+            audio_bytes += audio.len() as u64;
 
-            info!(
-                "proxy: upstream pump exiting for idx={}, url={}, total chunks={}, total bytes={}",
-                idx, url_for_task, chunk_count, total_bytes
-            );
+            let now = Instant::now();
+            let mut buffer = shared.timeshift.lock().unwrap();
+            for piece in &pieces {
+                buffer.push(piece.data.clone(), piece.title.clone(), now);
+            }
+            let latest = buffer.latest_seq();
+            if !probed && buffer.span_secs(now) >= PROBE_AFTER.as_secs_f64() {
+                probed = true;
+                let clip = buffer.clip(0.0, PROBE_AFTER.as_secs_f64(), now);
+                tokio::spawn(probe_format(idx, shared.clone(), clip));
+            }
+            drop(buffer);
+            shared.timeshift_seq.send_replace(latest);
 
-            let mut map = streams.lock().await;
-            if map
-                .get(&idx)
-                .map(|current| Arc::ptr_eq(current, &shared_for_task))
-                .unwrap_or(false)
-            {
-                map.remove(&idx);
-                debug!("proxy: removed idx={} from active streams map", idx);
+            if shared.listeners() == 0 {
+                if no_receivers_since
+                    .get_or_insert_with(Instant::now)
+                    .elapsed()
+                    >= Duration::from_secs(2)
+                {
+                    debug!(
+                        "proxy: no subscribers for idx={}, closing upstream after 2s (received {} chunks, {} bytes)",
+                        idx, chunk_count, total_bytes
+                    );
+                    break 'upstream;
+                }
+                continue;
             }
-        });
+            no_receivers_since = None;
 
-        Ok(shared)
+            for piece in pieces.drain(..) {
+                if shared.tx.send(piece).is_err() && chunk_count <= 10 {
+                    // All receivers dropped
+                    debug!(
+                        "proxy: idx={} broadcast send failed, no active receivers (sent {} chunks)",
+                        idx, chunk_count
+                    );
+                }
+            }
+        }
+
+        if shared.listeners() == 0 {
+            break;
+        }
+        match reconnect(idx, &shared, &mut upstream).await {
+            Some(next) => opened = next,
+            None => break,
+        }
+        // Don't let recognition clips splice audio from both sides of the gap.
+        shared.timeshift.lock().unwrap().mark_gap();
+    }
+
+    info!(
+        "proxy: upstream pump exiting for idx={}, url={}, total chunks={}, total bytes={}",
+        idx, upstream.urls[upstream.current], chunk_count, total_bytes
+    );
+
+    // Look it up by identity: the station may have been renumbered or
+    // replaced since.
+    let mut map = streams.lock().await;
+    let before = map.len();
+    map.retain(|_, current| !Arc::ptr_eq(current, &shared));
+    if map.len() < before {
+        debug!("proxy: removed idx={} from active streams map", idx);
     }
 }
 
+/// Fill in the stream format its headers left out (or got wrong) from
+/// ffprobe's reading of `clip`.
+async fn probe_format(idx: usize, shared: Arc<SharedStream>, clip: Vec<u8>) {
+    match stream_format::probe(clip).await {
+        Some(probed) => {
+            debug!("proxy: idx={} probed format {:?}", idx, probed);
+            let mut format = shared.format.lock().unwrap();
+            *format = format.clone().refined(probed);
+        }
+        None => debug!("proxy: idx={} ffprobe couldn't read the stream format", idx),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamQuery {
+    /// Start this many seconds behind live, replaying from the timeshift buffer.
+    behind: Option<f64>,
+}
+
 async fn stream_station(
     Path(idx): Path<usize>,
+    Query(query): Query<StreamQuery>,
     State(state): State<ProxyState>,
+    request_headers: HeaderMap,
 ) -> impl IntoResponse {
-    info!("proxy: new subscriber request for idx={}", idx);
-    
+    info!(
+        "proxy: new subscriber request for idx={}, behind={:?}",
+        idx, query.behind
+    );
+
     let shared = match state.get_or_start_stream(idx).await {
         Ok(s) => {
             debug!(
@@ -214,51 +918,213 @@ async fn stream_station(
         }
     };
 
+    let wants_icy = request_headers
+        .get("icy-metadata")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.trim() == "1");
+    if query.behind.is_some_and(|b| !b.is_finite()) {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    if let Some(behind) = query.behind.filter(|b| *b > 0.0) {
+        if !state.timeshift_window.is_zero() {
+            return timeshift_response(idx, &shared, behind, wants_icy);
+        }
+    }
+
+    let builder = client_response(&shared, wants_icy);
+    let muxer = wants_icy.then(|| IcyMuxer::new(CLIENT_METAINT));
+    let stream = futures_util::stream::unfold(
+        (shared.tx.subscribe(), muxer, shared.clone()),
+        |(mut rx, mut muxer, shared)| async move {
+            let mut chunk_num: u64 = 0;
+            loop {
+                match rx.recv().await {
+                    Ok(chunk) => {
+                        chunk_num += 1;
+                        if chunk_num <= 5 || chunk_num % 100 == 0 {
+                            debug!("proxy: subscriber received chunk #{}", chunk_num);
+                        }
+                        let data = match muxer.as_mut() {
+                            Some(m) => {
+                                let mut out = Vec::with_capacity(chunk.data.len() + 64);
+                                m.push(&chunk.data, chunk.title.as_deref(), &mut out);
+                                Bytes::from(out)
+                            }
+                            None => chunk.data,
+                        };
+                        return Some((Ok::<Bytes, std::io::Error>(data), (rx, muxer, shared)));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => shared.lag.record_lag(n),
+                    Err(broadcast::error::RecvError::Closed) => {
+                        debug!("proxy: subscriber stream closed after {} chunks", chunk_num);
+                        return None;
+                    }
+                }
+            }
+        },
+    );
+
+    info!("proxy: streaming response started for idx={}", idx);
+    builder.body(Body::from_stream(stream)).unwrap()
+}
+
+/// The station set with `ProxyState::set_preview`, served like a listed one.
+async fn stream_preview(
+    query: Query<StreamQuery>,
+    state: State<ProxyState>,
+    request_headers: HeaderMap,
+) -> impl IntoResponse {
+    stream_station(Path(PREVIEW_IDX), query, state, request_headers).await
+}
+
+/// Response headers for a subscriber: the upstream's content type and ICY
+/// station headers, with our own `icy-metaint` only for clients that asked
+/// for metadata; everyone else gets clean audio.
+fn client_response(shared: &SharedStream, wants_icy: bool) -> axum::http::response::Builder {
     let mut builder = Response::builder().status(200);
     for (name, value) in &shared.headers {
         let name_str = name.as_str();
-        if name_str.starts_with("icy-")
-            || name_str == "content-type"
-            || name_str == "transfer-encoding"
+        if (name_str.starts_with("icy-") && name_str != "icy-metaint") || name_str == "content-type"
         {
             if let Ok(hv) = axum::http::HeaderValue::from_bytes(value.as_bytes()) {
                 builder = builder.header(name_str, hv);
             }
         }
     }
+    if wants_icy {
+        builder = builder.header("icy-metaint", CLIENT_METAINT.to_string());
+    }
+    builder
+}
 
-    let stream = futures_util::stream::unfold(shared.tx.subscribe(), |mut rx| async move {
-        let mut chunk_num: u64 = 0;
-        loop {
-            match rx.recv().await {
-                Ok(chunk) => {
-                    chunk_num += 1;
-                    if chunk_num <= 5 || chunk_num % 100 == 0 {
-                        debug!("proxy: subscriber received chunk #{}", chunk_num);
+/// Serve a station from its timeshift buffer, starting `behind` seconds before
+/// live and then following the live edge.
+fn timeshift_response(idx: usize, shared: &SharedStream, behind: f64, wants_icy: bool) -> Response {
+    let builder = client_response(shared, wants_icy);
+
+    let buffer = shared.timeshift.clone();
+    let seq_rx = shared.timeshift_seq.subscribe();
+    let start_seq = buffer.lock().unwrap().seq_behind(behind, Instant::now());
+    info!(
+        "proxy: timeshift subscriber for idx={} starting {:.1}s behind live (seq {})",
+        idx, behind, start_seq
+    );
+
+    let muxer = wants_icy.then(|| IcyMuxer::new(CLIENT_METAINT));
+    let stream = futures_util::stream::unfold(
+        (buffer, seq_rx, start_seq, muxer),
+        move |(buffer, mut seq_rx, mut next_seq, mut muxer)| async move {
+            loop {
+                let chunks = buffer
+                    .lock()
+                    .unwrap()
+                    .chunks_from(next_seq, TIMESHIFT_BATCH);
+                if let (Some(first), Some(last)) = (chunks.first(), chunks.last()) {
+                    if first.seq > next_seq {
+                        warn!(
+                            "proxy: timeshift subscriber for idx={} fell out of the buffer, skipped {} chunks",
+                            idx,
+                            first.seq - next_seq
+                        );
                     }
-                    return Some((Ok::<Bytes, std::io::Error>(chunk), rx));
-                }
-                Err(broadcast::error::RecvError::Lagged(n)) => {
-                    warn!("proxy: subscriber lagged by {} chunks", n);
+                    next_seq = last.seq + 1;
+                    let mut out = Vec::with_capacity(chunks.iter().map(|c| c.data.len()).sum());
+                    for c in &chunks {
+                        match muxer.as_mut() {
+                            Some(m) => m.push(&c.data, c.title.as_deref(), &mut out),
+                            None => out.extend_from_slice(&c.data),
+                        }
+                    }
+                    return Some((
+                        Ok::<Bytes, std::io::Error>(Bytes::from(out)),
+                        (buffer, seq_rx, next_seq, muxer),
+                    ));
                 }
-                Err(broadcast::error::RecvError::Closed) => {
-                    debug!("proxy: subscriber stream closed after {} chunks", chunk_num);
+                if seq_rx.changed().await.is_err() {
+                    debug!(
+                        "proxy: timeshift subscriber for idx={} closed with upstream",
+                        idx
+                    );
                     return None;
                 }
             }
-        }
-    });
+        },
+    );
 
-    info!("proxy: streaming response started for idx={}", idx);
     builder.body(Body::from_stream(stream)).unwrap()
 }
 
-pub fn start_server(
-    state_manager: Arc<StateManager>,
-) -> tokio::task::JoinHandle<()> {
-    let proxy_state = ProxyState::new(state_manager);
+#[derive(Debug, Deserialize)]
+struct ClipQuery {
+    /// Clip length in seconds.
+    secs: f64,
+    /// Seconds before live where the clip ends.
+    #[serde(default)]
+    behind: f64,
+}
+
+/// A finished body with the buffered audio of an already-open station, for
+/// song recognition.  Never opens the upstream: 404 when the station isn't
+/// streaming through the proxy or nothing is buffered yet.
+async fn clip_station(
+    Path(idx): Path<usize>,
+    Query(query): Query<ClipQuery>,
+    State(state): State<ProxyState>,
+) -> Response {
+    if !query.secs.is_finite() || !query.behind.is_finite() {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    let Some(shared) = state.streams.lock().await.get(&idx).cloned() else {
+        debug!("proxy: clip for idx={} but no shared stream", idx);
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let data = shared
+        .timeshift
+        .lock()
+        .unwrap()
+        .clip(query.behind, query.secs, Instant::now());
+    if data.is_empty() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    info!(
+        "proxy: clip for idx={}: {:.1}s ending {:.1}s behind live, {} bytes",
+        idx,
+        query.secs,
+        query.behind,
+        data.len()
+    );
+    let mut builder = Response::builder().status(200);
+    if let Some(ct) = shared.headers.get("content-type") {
+        if let Ok(hv) = axum::http::HeaderValue::from_bytes(ct.as_bytes()) {
+            builder = builder.header("content-type", hv);
+        }
+    }
+    builder.body(Body::from(data)).unwrap()
+}
+
+/// The title changes logged for an open station, oldest first; 404 when its
+/// upstream isn't open.
+async fn station_titles(
+    Path(idx): Path<usize>,
+    State(state): State<ProxyState>,
+) -> Result<Json<Vec<TitleChange>>, StatusCode> {
+    let shared = state
+        .streams
+        .lock()
+        .await
+        .get(&idx)
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let titles = shared.titles.lock().unwrap().iter().cloned().collect();
+    Ok(Json(titles))
+}
+
+pub fn start_server(proxy_state: ProxyState) -> tokio::task::JoinHandle<()> {
     let app = Router::new()
         .route("/stream/:idx", get(stream_station))
+        .route("/clip/:idx", get(clip_station))
+        .route("/titles/:idx", get(station_titles))
+        .route("/preview", get(stream_preview))
         .with_state(proxy_state);
 
     tokio::spawn(async move {
@@ -280,3 +1146,195 @@ pub fn start_server(
 pub fn proxy_url(idx: usize) -> String {
     format!("http://{}:{}/stream/{}", PROXY_HOST, PROXY_PORT, idx)
 }
+
+/// Proxy URL of the station being previewed.
+pub fn preview_url() -> String {
+    format!("http://{}:{}/preview", PROXY_HOST, PROXY_PORT)
+}
+
+/// Proxy URL that replays a station from `behind` seconds before live.
+pub fn timeshift_url(idx: usize, behind: f64) -> String {
+    format!("{}?behind={:.1}", proxy_url(idx), behind)
+}
+
+/// Proxy URL for the last `secs` seconds a station played, ending `behind`
+/// seconds before live.
+pub fn clip_url(idx: usize, secs: u64, behind: f64) -> String {
+    format!(
+        "http://{}:{}/clip/{}?secs={}&behind={:.1}",
+        PROXY_HOST, PROXY_PORT, idx, secs, behind
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled(window_secs: u64, chunk_secs: &[u64]) -> (TimeshiftBuffer, Instant) {
+        let t0 = Instant::now();
+        let mut buf = TimeshiftBuffer::new(Duration::from_secs(window_secs));
+        for &s in chunk_secs {
+            buf.push(Bytes::from_static(b"x"), None, t0 + Duration::from_secs(s));
+        }
+        (buf, t0)
+    }
+
+    #[test]
+    fn test_timeshift_buffer_evicts_past_window() {
+        let (buf, t0) = filled(10, &[0, 5, 10, 15]);
+        // Chunk at t=0 is 15s old when the t=15 chunk lands.
+        assert_eq!(buf.chunks.front().map(|c| c.seq), Some(2));
+        assert_eq!(buf.latest_seq(), 4);
+        assert_eq!(buf.span_secs(t0 + Duration::from_secs(15)), 10.0);
+    }
+
+    #[test]
+    fn test_timeshift_buffer_seek_behind() {
+        let (buf, t0) = filled(60, &[0, 10, 20, 30]);
+        let now = t0 + Duration::from_secs(30);
+        assert_eq!(buf.seq_behind(15.0, now), 3);
+        assert_eq!(buf.seq_behind(0.0, now), 4);
+        // Further back than the buffer reaches: start at the oldest chunk.
+        assert_eq!(buf.seq_behind(600.0, now), 1);
+        assert_eq!(buf.seq_behind(f64::INFINITY, now), 1);
+        assert_eq!(buf.seq_behind(f64::NAN, now), 4);
+    }
+
+    #[test]
+    fn test_timeshift_buffer_chunks_from_evicted_seq() {
+        let (buf, _) = filled(10, &[0, 5, 10, 15]);
+        let seqs: Vec<u64> = buf.chunks_from(1, 8).iter().map(|c| c.seq).collect();
+        assert_eq!(seqs, vec![2, 3, 4]);
+        assert!(buf.chunks_from(5, 8).is_empty());
+    }
+
+    #[test]
+    fn test_timeshift_buffer_clip() {
+        let t0 = Instant::now();
+        let mut buf = TimeshiftBuffer::new(Duration::from_secs(60));
+        for (s, b) in [(0, b"a"), (10, b"b"), (20, b"c"), (30, b"d")] {
+            buf.push(Bytes::from_static(b), None, t0 + Duration::from_secs(s));
+        }
+        let now = t0 + Duration::from_secs(30);
+        // The 15 s that ended 5 s ago: chunks at t=10 and t=20.
+        assert_eq!(buf.clip(5.0, 15.0, now), b"bc");
+        // Up to live includes the newest chunk.
+        assert_eq!(buf.clip(0.0, 10.0, now), b"cd");
+        assert!(buf.clip(100.0, 10.0, now).is_empty());
+        assert_eq!(buf.clip(0.0, 1e300, now), b"abcd");
+        assert_eq!(buf.clip(f64::NAN, f64::NAN, now), b"d");
+    }
+
+    #[test]
+    fn test_timeshift_buffer_clip_stops_at_gap() {
+        let t0 = Instant::now();
+        let mut buf = TimeshiftBuffer::new(Duration::from_secs(60));
+        buf.push(Bytes::from_static(b"a"), None, t0);
+        buf.push(Bytes::from_static(b"b"), None, t0 + Duration::from_secs(10));
+        buf.mark_gap();
+        buf.push(Bytes::from_static(b"c"), None, t0 + Duration::from_secs(20));
+        let now = t0 + Duration::from_secs(20);
+        // Only what followed the reconnect; timeshift replay still has it all.
+        assert_eq!(buf.clip(0.0, 30.0, now), b"c");
+        assert_eq!(buf.clip(5.0, 30.0, now), b"ab");
+        assert_eq!(buf.chunks_from(1, 8).len(), 3);
+    }
+
+    #[test]
+    fn test_timeshift_buffer_title_behind() {
+        let t0 = Instant::now();
+        let mut buf = TimeshiftBuffer::new(Duration::from_secs(60));
+        let first: Option<Arc<str>> = Some(Arc::from("A - One"));
+        let second: Option<Arc<str>> = Some(Arc::from("B - Two"));
+        buf.push(Bytes::from_static(b"a"), first.clone(), t0);
+        buf.push(
+            Bytes::from_static(b"b"),
+            second.clone(),
+            t0 + Duration::from_secs(10),
+        );
+        let now = t0 + Duration::from_secs(15);
+        assert_eq!(buf.title_behind(0.0, now), second);
+        assert_eq!(buf.title_behind(7.0, now), first);
+        // Older than anything held: no title.
+        assert_eq!(buf.title_behind(30.0, now), None);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_fills_gap_with_silence() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // MPEG-1 layer III frames: 128 kbps, 44.1 kHz, 417 bytes.
+        let mut frame = vec![0u8; 417];
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x64]);
+        let mut stream = b"HTTP/1.1 200 OK\r\nContent-Type: audio/mpeg\r\n\r\n".to_vec();
+        for _ in 0..8 {
+            stream.extend_from_slice(&frame);
+        }
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/live", listener.local_addr().unwrap());
+        // Streams, drops, fails a reconnect, then streams again.
+        tokio::spawn(async move {
+            let responses = [
+                (stream.clone(), false),
+                (
+                    b"HTTP/1.1 503 Unavailable\r\nContent-Length: 0\r\n\r\n".to_vec(),
+                    false,
+                ),
+                (stream, true),
+            ];
+            for (response, hold) in responses {
+                let (mut conn, _) = listener.accept().await.unwrap();
+                let _ = conn.read(&mut [0; 4096]).await.unwrap();
+                conn.write_all(&response).await.unwrap();
+                if hold {
+                    std::future::pending::<()>().await;
+                }
+            }
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let station = radio_proto::protocol::Station {
+            name: "Test FM".into(),
+            url,
+            ..Default::default()
+        };
+        let state_manager = Arc::new(StateManager::new(
+            dir.path().join("state.json"),
+            vec![station],
+        ));
+        let proxy = ProxyState::new(state_manager, Duration::ZERO, 0);
+        let (_, mut rx) = proxy.subscribe(0).await.unwrap();
+
+        let mut seen = Vec::new();
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let chunk = rx.recv().await.unwrap();
+                let after_gap = seen.contains(&true);
+                seen.push(chunk.filler);
+                if chunk.filler {
+                    assert_eq!(chunk.title.as_deref(), Some(RECONNECT_TITLE));
+                    assert_eq!(&chunk.data[..4], &[0xFF, 0xFB, 0x90, 0x64]);
+                    assert_eq!(chunk.data.len() % 417, 0);
+                } else if after_gap {
+                    break;
+                }
+            }
+        })
+        .await
+        .expect("no audio after the reconnect");
+        assert!(!seen[0], "real audio first");
+        assert!(seen.contains(&true), "silence while reconnecting");
+    }
+
+    #[test]
+    fn test_rate_meter() {
+        let t0 = Instant::now();
+        let mut meter = RateMeter::new(t0);
+        for s in 1..=6 {
+            meter.add(16_000, t0 + Duration::from_secs(s));
+        }
+        assert_eq!(meter.rate(t0 + Duration::from_secs(6)), 16_000.0);
+        // Nothing since: the rate decays instead of sticking.
+        assert_eq!(meter.rate(t0 + Duration::from_secs(14)), 0.0);
+    }
+}
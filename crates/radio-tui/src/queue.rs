@@ -0,0 +1,282 @@
+//! Play queue and saved playlists.
+//!
+//! The queue belongs to the App, like `random_history`: local files and
+//! stations (by name) persisted to `queue.json` in the TUI data dir.  When the
+//! core reports that a file played to its end (`FileEnded`), the App plays the
+//! head of the queue.  A station never ends, so `n` moves past one.
+//!
+//! Playlists are named queues in the `playlists/` dir, as M3U or TOML:
+//!
+//! ```text
+//! #EXTM3U                          [[items]]
+//! #EXTINF:-1,NTS 1                 station = "NTS 1"
+//! https://stream-relay-geo...      [[items]]
+//! /music/mix.mp3                   file = "/music/mix.mp3"
+//! ```
+//!
+//! M3U stream URLs are matched back to stations by URL, then by the
+//! `#EXTINF` title; relative file paths resolve against the playlist's dir.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use radio_proto::protocol::Station;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueItem {
+    /// Local file by path.
+    File(String),
+    /// Station by name, so the entry survives edits to the station list.
+    Station(String),
+}
+
+impl QueueItem {
+    /// Display name: file stem or station name.
+    pub fn label(&self) -> String {
+        match self {
+            QueueItem::File(path) => Path::new(path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.clone()),
+            QueueItem::Station(name) => name.clone(),
+        }
+    }
+}
+
+pub fn load_queue(path: &Path) -> Vec<QueueItem> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_default()
+}
+
+pub fn save_queue(path: &Path, queue: &[QueueItem]) -> anyhow::Result<()> {
+    std::fs::write(path, serde_json::to_string(queue)?)?;
+    Ok(())
+}
+
+/// Move entry `idx` by `delta` places, clamped to the queue.  Returns the
+/// entry's new index.
+pub fn move_item(queue: &mut [QueueItem], idx: usize, delta: isize) -> usize {
+    if idx >= queue.len() {
+        return idx;
+    }
+    let to = idx
+        .saturating_add_signed(delta)
+        .min(queue.len().saturating_sub(1));
+    if to < idx {
+        queue[to..=idx].rotate_right(1);
+    } else {
+        queue[idx..=to].rotate_left(1);
+    }
+    to
+}
+
+// ── Playlists ─────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize)]
+struct TomlPlaylist {
+    #[serde(default)]
+    items: Vec<QueueItem>,
+}
+
+fn is_playlist(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("m3u" | "m3u8" | "toml")
+    )
+}
+
+fn is_m3u(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("m3u" | "m3u8")
+    )
+}
+
+/// Playlist file names in `dir`, sorted.
+pub fn list_playlists(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && is_playlist(p))
+        .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .collect();
+    names.sort_by_key(|n| n.to_lowercase());
+    names
+}
+
+/// File name for a playlist called `name`: TOML unless it already ends in
+/// `.m3u`/`.m3u8`/`.toml`.
+pub fn playlist_file_name(name: &str) -> anyhow::Result<String> {
+    let name = name.trim();
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        anyhow::bail!("invalid playlist name {:?}", name);
+    }
+    if is_playlist(Path::new(name)) {
+        Ok(name.to_string())
+    } else {
+        Ok(format!("{}.toml", name))
+    }
+}
+
+/// Write `items` to `dir/file_name`.  Stations missing from `stations` are
+/// left out of M3U playlists, which need a URL.
+pub fn save_playlist(
+    dir: &Path,
+    file_name: &str,
+    items: &[QueueItem],
+    stations: &[Station],
+) -> anyhow::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(file_name);
+    let content = if is_m3u(&path) {
+        to_m3u(items, stations)
+    } else {
+        toml::to_string_pretty(&TomlPlaylist {
+            items: items.to_vec(),
+        })?
+    };
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// Read the playlist `dir/file_name`.
+pub fn load_playlist(
+    dir: &Path,
+    file_name: &str,
+    stations: &[Station],
+) -> anyhow::Result<Vec<QueueItem>> {
+    let path = dir.join(file_name);
+    let content = std::fs::read_to_string(&path)?;
+    if is_m3u(&path) {
+        Ok(parse_m3u(&content, dir, stations))
+    } else {
+        Ok(toml::from_str::<TomlPlaylist>(&content)?.items)
+    }
+}
+
+fn to_m3u(items: &[QueueItem], stations: &[Station]) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    for item in items {
+        match item {
+            QueueItem::File(path) => {
+                out.push_str(&format!("#EXTINF:-1,{}\n{}\n", item.label(), path));
+            }
+            QueueItem::Station(name) => match stations.iter().find(|s| &s.name == name) {
+                Some(st) => out.push_str(&format!("#EXTINF:-1,{}\n{}\n", st.name, st.url)),
+                None => warn!("[queue] station {:?} not found, left out of M3U", name),
+            },
+        }
+    }
+    out
+}
+
+fn parse_m3u(content: &str, base_dir: &Path, stations: &[Station]) -> Vec<QueueItem> {
+    let mut items = Vec::new();
+    let mut title: Option<String> = None;
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            title = info.split_once(',').map(|(_, t)| t.trim().to_string());
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        let entry_title = title.take();
+        if let Some(path) = line.strip_prefix("file://") {
+            items.push(QueueItem::File(path.to_string()));
+        } else if line.contains("://") {
+            let station = stations.iter().find(|s| s.url == line).or_else(|| {
+                stations
+                    .iter()
+                    .find(|s| Some(&s.name) == entry_title.as_ref())
+            });
+            match station {
+                Some(st) => items.push(QueueItem::Station(st.name.clone())),
+                None => warn!("[queue] no station for {}, skipped", line),
+            }
+        } else {
+            let path = base_dir.join(line);
+            items.push(QueueItem::File(path.to_string_lossy().into_owned()));
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn station(name: &str, url: &str) -> Station {
+        Station {
+            name: name.into(),
+            url: url.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_move_item() {
+        let mut q: Vec<QueueItem> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|s| QueueItem::File(s.to_string()))
+            .collect();
+        assert_eq!(move_item(&mut q, 0, 2), 2);
+        assert_eq!(q[2], QueueItem::File("a".into()));
+        assert_eq!(move_item(&mut q, 2, -5), 0);
+        assert_eq!(q[0], QueueItem::File("a".into()));
+        assert_eq!(move_item(&mut q, 3, 1), 3);
+        assert_eq!(q[3], QueueItem::File("d".into()));
+    }
+
+    #[test]
+    fn test_playlist_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let stations = vec![station("FIP", "https://fip.example/live")];
+        let items = vec![
+            QueueItem::File("/music/a.mp3".into()),
+            QueueItem::Station("FIP".into()),
+        ];
+        for name in ["mix", "mix.m3u"] {
+            let file_name = playlist_file_name(name).unwrap();
+            save_playlist(dir.path(), &file_name, &items, &stations).unwrap();
+            let loaded = load_playlist(dir.path(), &file_name, &stations).unwrap();
+            assert_eq!(loaded, items);
+        }
+        assert_eq!(list_playlists(dir.path()), vec!["mix.m3u", "mix.toml"]);
+        assert!(playlist_file_name("../x").is_err());
+    }
+
+    #[test]
+    fn test_parse_m3u() {
+        let stations = vec![
+            station("FIP", "https://fip.example/live"),
+            station("NTS 1", "https://nts.example/1"),
+        ];
+        let m3u = "#EXTM3U\n\
+                   #EXTINF:-1,NTS 1\n\
+                   https://nts.example/other-relay\n\
+                   track.flac\n\
+                   https://unknown.example/stream\n\
+                   file:///music/b.mp3\n";
+        let items = parse_m3u(m3u, Path::new("/lists"), &stations);
+        assert_eq!(
+            items,
+            vec![
+                QueueItem::Station("NTS 1".into()),
+                QueueItem::File("/lists/track.flac".into()),
+                QueueItem::File("/music/b.mp3".into()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,396 @@
+//! Stream recorder — tees a station's proxied stream into files under `downloads_dir`.
+//!
+//! The recorder is just another subscriber on the proxy's `SharedStream`
+//! broadcast channel, so it shares the upstream connection with mpv and the
+//! scope tap and keeps it open even when playback moves to another station.
+//!
+//! The proxy hands it clean audio tagged with the ICY title on air, and
+//! every `StreamTitle` change starts a new segment file at the byte where
+//! the title changed upstream.  Segments are written as
+//! `<name>.<ext>.part` and renamed once finished, so the Files workspace only
+//! ever lists complete recordings.
+//!
+//! Scheduled show recordings (see `schedule`) ignore title changes: the whole
+//! window goes into one file named like an NTS download and tagged with the
+//! show's metadata once it is finished.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Local};
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::{broadcast, oneshot};
+use tracing::{debug, info, warn};
+
+use radio_proto::protocol::{RecordingStatus, ScheduledShow};
+use radio_proto::state::StateManager;
+
+use crate::nts_download::parser::safe_filename;
+use crate::nts_download::{metadata, EpisodeMetadata};
+use crate::proxy::{LiveChunk, ProxyState};
+use crate::schedule;
+use crate::BroadcastMessage;
+
+/// How a recording should end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopMode {
+    /// Finish the segment in progress and keep it.
+    Finish,
+    /// Delete the segment in progress.
+    Discard,
+}
+
+/// Handle to a running recording task, owned by `DaemonCore`.
+pub struct Recording {
+    stop_tx: Option<oneshot::Sender<StopMode>>,
+    task: tokio::task::JoinHandle<()>,
+    show: Option<ScheduledShow>,
+}
+
+impl Recording {
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// The scheduled show this recording is for, if any.
+    pub fn show(&self) -> Option<&ScheduledShow> {
+        self.show.as_ref()
+    }
+
+    /// Ask the task to stop.  Returns the join handle so callers can wait for
+    /// the final rename to land on disk.
+    pub fn stop(mut self, mode: StopMode) -> tokio::task::JoinHandle<()> {
+        if let Some(tx) = self.stop_tx.take() {
+            let _ = tx.send(mode);
+        }
+        self.task
+    }
+}
+
+/// Everything the recording task needs from `DaemonCore`.
+pub struct RecorderContext {
+    pub proxy: ProxyState,
+    pub state_manager: Arc<StateManager>,
+    pub broadcast_tx: broadcast::Sender<BroadcastMessage>,
+    pub downloads_dir: PathBuf,
+}
+
+/// Subscribe to the proxy for `station_idx` and start writing segments.
+/// With a `show`, everything goes into one file tagged after the show.
+pub async fn start_recording(
+    ctx: RecorderContext,
+    station_idx: usize,
+    station_name: String,
+    show: Option<ScheduledShow>,
+) -> anyhow::Result<Recording> {
+    let (headers, rx) = ctx
+        .proxy
+        .subscribe(station_idx)
+        .await
+        .map_err(|code| anyhow::anyhow!("proxy could not open stream ({})", code))?;
+
+    tokio::fs::create_dir_all(&ctx.downloads_dir).await?;
+
+    let content_type = headers
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let ext = extension_for_content_type(content_type);
+    info!(
+        "[rec] starting recording idx={} station={} content-type={:?} ext={}",
+        station_idx, station_name, content_type, ext
+    );
+
+    let episode = show.as_ref().map(schedule::episode_metadata);
+    let started = Local::now();
+    let base = base_name(&station_name, episode.as_ref(), started, None);
+    let segment = Segment::create(&ctx.downloads_dir, &base, ext, started, None).await?;
+
+    let mut status = RecordingStatus {
+        station_idx,
+        station_name: station_name.clone(),
+        started_at: started.timestamp(),
+        segment_path: segment.part_path.to_string_lossy().to_string(),
+        segments_done: 0,
+    };
+    ctx.state_manager.set_recording(status.clone()).await;
+    let _ = ctx.broadcast_tx.send(BroadcastMessage::StateUpdated);
+
+    let (stop_tx, stop_rx) = oneshot::channel();
+    let task = tokio::spawn(async move {
+        let mode = run_recording(&ctx, &mut status, rx, stop_rx, segment, ext, episode).await;
+        info!(
+            "[rec] recording finished idx={} station={} segments={} ({:?})",
+            status.station_idx, status.station_name, status.segments_done, mode
+        );
+        ctx.state_manager.clear_recording(status.station_idx).await;
+        let _ = ctx.broadcast_tx.send(BroadcastMessage::StateUpdated);
+    });
+
+    Ok(Recording {
+        stop_tx: Some(stop_tx),
+        task,
+        show,
+    })
+}
+
+async fn run_recording(
+    ctx: &RecorderContext,
+    status: &mut RecordingStatus,
+    mut rx: broadcast::Receiver<LiveChunk>,
+    mut stop_rx: oneshot::Receiver<StopMode>,
+    mut segment: Segment,
+    ext: &'static str,
+    episode: Option<EpisodeMetadata>,
+) -> StopMode {
+    let mode = loop {
+        tokio::select! {
+            stop = &mut stop_rx => {
+                break stop.unwrap_or(StopMode::Finish);
+            }
+            next = rx.recv() => {
+                let chunk = match next {
+                    Ok(c) => c,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("[rec] idx={} lagged by {} chunks — recording has a gap", status.station_idx, n);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        info!("[rec] idx={} upstream closed", status.station_idx);
+                        break StopMode::Finish;
+                    }
+                };
+                // The proxy's silence while it reconnects: leave the gap.
+                if chunk.filler {
+                    continue;
+                }
+
+                // Show recordings stay in one file regardless of titles.
+                let title = chunk.title.as_deref().filter(|_| episode.is_none());
+                if let Some(title) = title.filter(|t| segment.title.as_deref() != Some(*t)) {
+                    if segment.title.is_none() {
+                        // First title of the recording: name the running file after it.
+                        segment.title = Some(title.to_string());
+                    } else {
+                        let title = title.to_string();
+                        debug!("[rec] idx={} title change → {:?}", status.station_idx, title);
+                        let now = Local::now();
+                        let base = base_name(&status.station_name, None, now, Some(&title));
+                        match Segment::create(&ctx.downloads_dir, &base, ext, now, Some(title)).await {
+                            Ok(next_seg) => {
+                                let prev = std::mem::replace(&mut segment, next_seg);
+                                if finish_segment(prev, &ctx.downloads_dir, &status.station_name, ext, None).await.is_some() {
+                                    status.segments_done += 1;
+                                }
+                                status.segment_path = segment.part_path.to_string_lossy().to_string();
+                                ctx.state_manager.set_recording(status.clone()).await;
+                                let _ = ctx.broadcast_tx.send(BroadcastMessage::StateUpdated);
+                            }
+                            Err(e) => {
+                                warn!("[rec] idx={} could not open next segment: {} — continuing in current file", status.station_idx, e);
+                            }
+                        }
+                    }
+                }
+                if let Err(e) = segment.write(&chunk.data).await {
+                    warn!("[rec] idx={} write failed: {}", status.station_idx, e);
+                    let _ = ctx.broadcast_tx.send(BroadcastMessage::Log(format!("recording failed: {}", e)));
+                    break StopMode::Finish;
+                }
+            }
+        }
+    };
+
+    match mode {
+        StopMode::Finish => {
+            let saved = finish_segment(
+                segment,
+                &ctx.downloads_dir,
+                &status.station_name,
+                ext,
+                episode.as_ref(),
+            )
+            .await;
+            if let Some(path) = saved {
+                status.segments_done += 1;
+                if let Some(meta) = episode.as_ref() {
+                    match metadata::write_metadata(&path, meta, None).await {
+                        Ok(()) => info!("[rec] tagged {} as {:?}", path.display(), meta.title),
+                        Err(e) => warn!("[rec] could not tag {}: {}", path.display(), e),
+                    }
+                }
+            }
+        }
+        StopMode::Discard => segment.discard().await,
+    }
+    mode
+}
+
+// ── Segments ──────────────────────────────────────────────────────────────────
+
+struct Segment {
+    part_path: PathBuf,
+    writer: BufWriter<tokio::fs::File>,
+    started: DateTime<Local>,
+    title: Option<String>,
+    bytes: u64,
+}
+
+impl Segment {
+    async fn create(
+        dir: &Path,
+        base: &str,
+        ext: &str,
+        started: DateTime<Local>,
+        title: Option<String>,
+    ) -> std::io::Result<Self> {
+        let part_path = dir.join(format!("{}.{}.part", base, ext));
+        let file = tokio::fs::File::create(&part_path).await?;
+        Ok(Self {
+            part_path,
+            writer: BufWriter::new(file),
+            started,
+            title,
+            bytes: 0,
+        })
+    }
+
+    async fn write(&mut self, data: &[u8]) -> std::io::Result<()> {
+        self.writer.write_all(data).await?;
+        self.bytes += data.len() as u64;
+        Ok(())
+    }
+
+    async fn discard(mut self) {
+        let _ = self.writer.flush().await;
+        drop(self.writer);
+        let _ = tokio::fs::remove_file(&self.part_path).await;
+    }
+}
+
+/// Flush and move a segment to its final name.  Empty segments are deleted.
+/// Returns the final path when a file was kept.
+async fn finish_segment(
+    mut seg: Segment,
+    dir: &Path,
+    station_name: &str,
+    ext: &str,
+    episode: Option<&EpisodeMetadata>,
+) -> Option<PathBuf> {
+    if let Err(e) = seg.writer.flush().await {
+        warn!("[rec] flush failed for {}: {}", seg.part_path.display(), e);
+    }
+    drop(seg.writer);
+
+    if seg.bytes == 0 {
+        let _ = tokio::fs::remove_file(&seg.part_path).await;
+        return None;
+    }
+
+    let base = base_name(station_name, episode, seg.started, seg.title.as_deref());
+    let final_path = unique_path(dir, &base, ext).await;
+    match tokio::fs::rename(&seg.part_path, &final_path).await {
+        Ok(()) => {
+            info!("[rec] saved {} ({} bytes)", final_path.display(), seg.bytes);
+            Some(final_path)
+        }
+        Err(e) => {
+            warn!(
+                "[rec] could not rename {} → {}: {}",
+                seg.part_path.display(),
+                final_path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Show recordings are named like NTS downloads ("Show - 2024-05-01");
+/// everything else by station, start time and ICY title.
+fn base_name(
+    station_name: &str,
+    episode: Option<&EpisodeMetadata>,
+    started: DateTime<Local>,
+    title: Option<&str>,
+) -> String {
+    match episode {
+        Some(meta) => meta.file_base_name(),
+        None => segment_base_name(station_name, started, title),
+    }
+}
+
+fn segment_base_name(station_name: &str, started: DateTime<Local>, title: Option<&str>) -> String {
+    let stamp = started.format("%Y-%m-%d %H.%M.%S");
+    match title.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(t) => safe_filename(&format!("{} - {} - {}", station_name, stamp, t)),
+        None => safe_filename(&format!("{} - {}", station_name, stamp)),
+    }
+}
+
+async fn unique_path(dir: &Path, base: &str, ext: &str) -> PathBuf {
+    let first = dir.join(format!("{}.{}", base, ext));
+    if tokio::fs::metadata(&first).await.is_err() {
+        return first;
+    }
+    for n in 2.. {
+        let candidate = dir.join(format!("{} ({}).{}", base, n, ext));
+        if tokio::fs::metadata(&candidate).await.is_err() {
+            return candidate;
+        }
+    }
+    unreachable!()
+}
+
+/// Map an upstream `Content-Type` to a file extension the Files workspace
+/// recognises.  Unknown types fall back to mp3, by far the most common codec
+/// on Icecast/Shoutcast servers.
+pub fn extension_for_content_type(content_type: &str) -> &'static str {
+    let ct = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    match ct.as_str() {
+        "audio/aac" | "audio/aacp" | "audio/x-aac" | "audio/x-aacp" => "aac",
+        "audio/ogg" | "application/ogg" | "audio/vorbis" => "ogg",
+        "audio/opus" => "opus",
+        "audio/flac" | "audio/x-flac" => "flac",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/mp4" | "audio/x-m4a" => "m4a",
+        "video/mp2t" => "ts",
+        _ => "mp3",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_extension_for_content_type() {
+        assert_eq!(extension_for_content_type("audio/mpeg"), "mp3");
+        assert_eq!(extension_for_content_type("audio/aacp"), "aac");
+        assert_eq!(
+            extension_for_content_type("application/ogg; codecs=opus"),
+            "ogg"
+        );
+        assert_eq!(extension_for_content_type("video/MP2T"), "ts");
+        assert_eq!(extension_for_content_type(""), "mp3");
+    }
+
+    #[test]
+    fn test_segment_base_name() {
+        let t = Local.with_ymd_and_hms(2024, 5, 1, 21, 3, 9).unwrap();
+        assert_eq!(
+            segment_base_name("NTS 1", t, Some("Artist - A/B: Mix")),
+            "NTS 1 - 2024-05-01 21.03.09 - Artist - A-B- Mix"
+        );
+        assert_eq!(
+            segment_base_name("NTS 1", t, Some("  ")),
+            "NTS 1 - 2024-05-01 21.03.09"
+        );
+    }
+}
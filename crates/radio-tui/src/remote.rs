@@ -0,0 +1,146 @@
+//! Remote requests for state the App loop owns.
+//!
+//! Playback control goes straight to `DaemonCore` as `Command`s, but star
+//! ratings, the songs history, NTS schedules, poll annotations and downloads
+//! live in `AppState`.  The HTTP API asks for those with a `RemoteRequest`;
+//! the App answers on the request's oneshot channel from its event loop.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+use radio_proto::songs::RecognitionResult;
+use radio_proto::songs_export::{ExportFormat, SongsFilter};
+
+use crate::app_state::{DownloadStatus, NtsChannel, NtsShow};
+
+/// Reply channel: `Err` carries a message for the API error body.
+pub type Reply<T> = oneshot::Sender<Result<T, String>>;
+
+pub enum RemoteRequest {
+    Stars(Reply<StarsSnapshot>),
+    /// Rate a station by name; 0 clears the rating.
+    SetStationStars {
+        name: String,
+        stars: u8,
+        reply: Reply<()>,
+    },
+    /// Rate a local file by path; 0 clears the rating.
+    SetFileStars {
+        path: String,
+        stars: u8,
+        reply: Reply<()>,
+    },
+    /// Identify the song playing now, exactly like `i` in the TUI.  The
+    /// result lands in the songs history.
+    Recognize(Reply<()>),
+    /// Newest `limit` songs history rows, newest first; only those
+    /// matching `query` (words, prefix-matched) when given.
+    Songs {
+        limit: usize,
+        query: Option<String>,
+        reply: Reply<Vec<RecognitionResult>>,
+    },
+    /// The whole songs history, filtered and rendered in `format`.
+    ExportSongs {
+        filter: SongsFilter,
+        format: ExportFormat,
+        reply: Reply<String>,
+    },
+    Nts(Reply<Vec<NtsChannelInfo>>),
+    /// Last polled "now playing" label per station name.
+    PollTitles(Reply<HashMap<String, String>>),
+    Downloads(Reply<Vec<DownloadInfo>>),
+}
+
+/// Highest star rating the TUI cycles through.
+pub const MAX_STARS: u8 = 3;
+
+#[derive(Debug, Serialize)]
+pub struct StarsSnapshot {
+    pub stations: HashMap<String, u8>,
+    pub files: HashMap<String, u8>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NtsShowInfo {
+    pub title: String,
+    /// RFC 3339 local times.
+    pub start: String,
+    pub end: String,
+    pub location: String,
+    pub description: String,
+    pub genres: Vec<String>,
+    pub moods: Vec<String>,
+    pub is_replay: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NtsChannelInfo {
+    /// 1 or 2.
+    pub channel: usize,
+    pub now: Option<NtsShowInfo>,
+    pub next: Vec<NtsShowInfo>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DownloadInfo {
+    pub url: String,
+    /// "downloading", "downloaded", "failed" or "not_downloaded".
+    pub status: &'static str,
+    /// 0.0–1.0 while downloading.
+    pub progress: Option<f32>,
+    pub error: Option<String>,
+}
+
+impl From<&NtsShow> for NtsShowInfo {
+    fn from(show: &NtsShow) -> Self {
+        let location = if show.location_long.is_empty() {
+            show.location_short.clone()
+        } else {
+            show.location_long.clone()
+        };
+        Self {
+            title: show.broadcast_title.clone(),
+            start: show.start.to_rfc3339(),
+            end: show.end.to_rfc3339(),
+            location,
+            description: show.description.clone(),
+            genres: show.genres.clone(),
+            moods: show.moods.clone(),
+            is_replay: show.is_replay,
+        }
+    }
+}
+
+impl NtsChannelInfo {
+    pub fn new(channel: usize, data: Option<&NtsChannel>, error: Option<&String>) -> Self {
+        Self {
+            channel: channel + 1,
+            now: data.map(|ch| NtsShowInfo::from(&ch.now)),
+            next: data
+                .map(|ch| ch.upcoming.iter().map(NtsShowInfo::from).collect())
+                .unwrap_or_default(),
+            error: error.cloned(),
+        }
+    }
+}
+
+impl DownloadInfo {
+    pub fn new(url: &str, status: &DownloadStatus) -> Self {
+        let (label, progress, error) = match status {
+            DownloadStatus::NotDownloaded => ("not_downloaded", None, None),
+            DownloadStatus::Downloading(p) => ("downloading", Some(*p), None),
+            DownloadStatus::Downloaded => ("downloaded", None, None),
+            DownloadStatus::Failed(e) => ("failed", None, Some(e.clone())),
+        };
+        Self {
+            url: url.to_string(),
+            status: label,
+            progress,
+            error,
+        }
+    }
+}
@@ -0,0 +1,230 @@
+//! Scheduled NTS show recordings.
+//!
+//! Shows marked from the NTS panel are kept in `schedule.toml` under the data
+//! dir so they survive restarts.  `DaemonCore` checks the schedule on every
+//! heartbeat: a show's recording starts `PREROLL_SECS` before its slot and is
+//! finished `POSTROLL_SECS` after it, since NTS hand-overs rarely land on the
+//! exact minute.  Back-to-back shows on one channel hand over when the
+//! earlier show's window closes, so its post-roll is never cut short.  A show
+//! stays in the schedule until its window has passed, so a restart mid-show
+//! picks the recording back up.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use radio_proto::protocol::ScheduledShow;
+
+use crate::nts_download::parser::safe_filename;
+use crate::nts_download::EpisodeMetadata;
+
+/// Seconds to start recording before a show's scheduled start.
+pub const PREROLL_SECS: i64 = 30;
+/// Seconds to keep recording after a show's scheduled end.
+pub const POSTROLL_SECS: i64 = 60;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ScheduleFile {
+    #[serde(default)]
+    shows: Vec<ScheduledShow>,
+}
+
+/// The persisted list of scheduled shows.
+pub struct Schedule {
+    path: PathBuf,
+    shows: Vec<ScheduledShow>,
+}
+
+impl Schedule {
+    /// Load the schedule from `path`.  A missing or unreadable file yields an
+    /// empty schedule.
+    pub fn load(path: PathBuf) -> Self {
+        let shows = match std::fs::read_to_string(&path) {
+            Ok(content) => match toml::from_str::<ScheduleFile>(&content) {
+                Ok(file) => file.shows,
+                Err(e) => {
+                    warn!("[sched] could not parse {}: {}", path.display(), e);
+                    Vec::new()
+                }
+            },
+            Err(_) => Vec::new(),
+        };
+        Self { path, shows }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = ScheduleFile {
+            shows: self.shows.clone(),
+        };
+        std::fs::write(&self.path, toml::to_string_pretty(&file)?)?;
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn shows(&self) -> &[ScheduledShow] {
+        &self.shows
+    }
+
+    /// Add a show, keeping the list in start order.  Returns false if the
+    /// slot is already scheduled.
+    pub fn add(&mut self, show: ScheduledShow) -> bool {
+        if self
+            .shows
+            .iter()
+            .any(|s| s.is_same_slot(show.channel, show.start))
+        {
+            return false;
+        }
+        self.shows.push(show);
+        self.shows.sort_by_key(|s| (s.start, s.channel));
+        true
+    }
+
+    pub fn remove(&mut self, channel: usize, start: i64) -> Option<ScheduledShow> {
+        let pos = self
+            .shows
+            .iter()
+            .position(|s| s.is_same_slot(channel, start))?;
+        Some(self.shows.remove(pos))
+    }
+
+    /// Shows whose recording window is open at `now`, leaving out any that
+    /// waits for an earlier show on the same channel to close its window.
+    pub fn due(&self, now: i64) -> Vec<ScheduledShow> {
+        let open = |s: &ScheduledShow| s.start - PREROLL_SECS <= now && now < s.end + POSTROLL_SECS;
+        self.shows
+            .iter()
+            .filter(|s| open(s))
+            .filter(|s| {
+                !self
+                    .shows
+                    .iter()
+                    .any(|p| p.channel == s.channel && p.start < s.start && open(p))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Remove and return shows whose recording window has closed.
+    pub fn take_finished(&mut self, now: i64) -> Vec<ScheduledShow> {
+        let (done, keep) = std::mem::take(&mut self.shows)
+            .into_iter()
+            .partition(|s| now >= s.end + POSTROLL_SECS);
+        self.shows = keep;
+        done
+    }
+}
+
+/// Station name carrying an NTS channel's live stream.
+pub fn nts_station_name(channel: usize) -> String {
+    format!("NTS {}", channel + 1)
+}
+
+/// Tag data for a finished show recording, in the shape the NTS downloader
+/// already writes through `metadata::write_metadata`.
+pub fn episode_metadata(show: &ScheduledShow) -> EpisodeMetadata {
+    let date = Local
+        .timestamp_opt(show.start, 0)
+        .single()
+        .unwrap_or_else(Local::now)
+        .date_naive();
+    EpisodeMetadata {
+        title: show.title.clone(),
+        safe_title: safe_filename(&show.title),
+        date,
+        artists: Vec::new(),
+        parsed_artists: Vec::new(),
+        station: show.location.clone(),
+        genres: show.genres.clone(),
+        tracks: Vec::new(),
+        image_url: String::new(),
+        description: show.description.clone(),
+        source_url: format!("https://www.nts.live/{}", show.channel + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn show(channel: usize, start: i64, end: i64) -> ScheduledShow {
+        ScheduledShow {
+            channel,
+            title: format!("Show {}", start),
+            start,
+            end,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_schedule_windows() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sched = Schedule::load(dir.path().join("schedule.toml"));
+        assert!(sched.add(show(0, 10_000, 13_600)));
+        assert!(sched.add(show(1, 3_600, 7_200)));
+        assert!(!sched.add(show(0, 10_000, 13_600)));
+        assert_eq!(sched.shows()[0].start, 3_600);
+
+        // Pre-roll opens the window early, post-roll keeps it open late.
+        assert!(sched.due(3_600 - PREROLL_SECS - 1).is_empty());
+        assert_eq!(sched.due(3_600 - PREROLL_SECS).len(), 1);
+        assert_eq!(sched.due(7_200 + POSTROLL_SECS - 1).len(), 1);
+
+        let done = sched.take_finished(7_200 + POSTROLL_SECS);
+        assert_eq!(done.len(), 1);
+        assert_eq!(done[0].channel, 1);
+        assert_eq!(sched.shows().len(), 1);
+    }
+
+    #[test]
+    fn test_back_to_back_shows_hand_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sched = Schedule::load(dir.path().join("schedule.toml"));
+        sched.add(show(0, 3_600, 7_200));
+        sched.add(show(0, 7_200, 10_800));
+        sched.add(show(1, 7_200, 10_800));
+
+        // The next show's pre-roll doesn't cut the earlier one's post-roll.
+        let starts = |shows: Vec<ScheduledShow>| -> Vec<(usize, i64)> {
+            shows.iter().map(|s| (s.channel, s.start)).collect()
+        };
+        assert_eq!(
+            starts(sched.due(7_200 - PREROLL_SECS)),
+            [(0, 3_600), (1, 7_200)]
+        );
+        assert_eq!(
+            starts(sched.due(7_200 + POSTROLL_SECS - 1)),
+            [(0, 3_600), (1, 7_200)]
+        );
+
+        // Once its window closes, the next show takes over on that heartbeat.
+        let now = 7_200 + POSTROLL_SECS;
+        assert_eq!(starts(sched.take_finished(now)), [(0, 3_600)]);
+        assert_eq!(starts(sched.due(now)), [(0, 7_200), (1, 7_200)]);
+    }
+
+    #[test]
+    fn test_schedule_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schedule.toml");
+        let mut sched = Schedule::load(path.clone());
+        let mut s = show(1, 1_000, 2_000);
+        s.genres = vec!["Ambient".to_string()];
+        sched.add(s.clone());
+        sched.save().unwrap();
+
+        let mut reloaded = Schedule::load(path);
+        assert_eq!(reloaded.shows(), &[s]);
+        assert!(reloaded.remove(1, 1_000).is_some());
+        assert!(reloaded.shows().is_empty());
+    }
+}
@@ -1,7 +1,8 @@
 // Scope display module — ported from scope-tui (https://github.com/alemi/scope-tui)
-// Adapted for use in r4dio: stripped to oscilloscope-only, no crate dependency.
+// Adapted for use in r4dio: oscilloscope plus an FFT spectrum (spectrum.rs), no crate dependency.
 
 pub mod oscilloscope;
+pub mod spectrum;
 
 use ratatui::{
     style::{Color, Style},
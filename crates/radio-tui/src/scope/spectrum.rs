@@ -0,0 +1,176 @@
+// Spectrum analysis for the spectrum and spectrogram scope modes.
+// Same approach as scope-tui's spectroscope (Hann window + rustfft over the
+// newest samples), folded into log-spaced bands for a terminal-width display.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+
+/// Sample rate of `AppState::pcm_ring`.
+pub const SAMPLE_RATE: f32 = 44_100.0;
+pub const MIN_FFT_SIZE: usize = 512;
+pub const MAX_FFT_SIZE: usize = 16384;
+/// Band edges, Hz.  Streams are rarely encoded above 16 kHz.
+const MIN_FREQ: f32 = 30.0;
+const MAX_FREQ: f32 = 16_000.0;
+/// Bottom of the dB scale; anything quieter reads as silence.
+pub const FLOOR_DB: f32 = -120.0;
+/// Peak markers fall this fast once their hold time is up.
+const PEAK_FALL_DB_PER_SEC: f32 = 30.0;
+
+/// Round a configured window size to a supported power of two.
+pub fn clamp_fft_size(size: usize) -> usize {
+    size.clamp(MIN_FFT_SIZE, MAX_FFT_SIZE).next_power_of_two()
+}
+
+pub struct Analyzer {
+    size: usize,
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    buf: Vec<Complex<f32>>,
+}
+
+impl Analyzer {
+    pub fn new(size: usize) -> Self {
+        let size = clamp_fft_size(size);
+        let fft = FftPlanner::new().plan_fft_forward(size);
+        let window = (0..size)
+            .map(|i| {
+                let x = std::f32::consts::PI * i as f32 / size as f32;
+                x.sin().powi(2) // Hann
+            })
+            .collect();
+        Self {
+            size,
+            fft,
+            window,
+            buf: vec![Complex::default(); size],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// dBFS level of `n_bands` log-spaced bands (lowest first) over the
+    /// newest `size()` samples of `ring`.  A full-scale sine reads 0 dB.
+    pub fn bands(&mut self, ring: &VecDeque<f32>, n_bands: usize) -> Vec<f32> {
+        let n = self.size;
+        let skip = ring.len().saturating_sub(n);
+        let pad = n - (ring.len() - skip);
+        self.buf.fill(Complex::default());
+        for (i, &s) in ring.iter().skip(skip).enumerate() {
+            self.buf[pad + i] = Complex::new(s * self.window[pad + i], 0.0);
+        }
+        self.fft.process(&mut self.buf);
+
+        // Hann coherent gain is 1/2, and a real sine splits over ±f.
+        let norm = 4.0 / n as f32;
+        let bin_hz = SAMPLE_RATE / n as f32;
+        let nyquist_bin = n / 2;
+        let top = MAX_FREQ.min(SAMPLE_RATE / 2.0);
+        let ratio = (top / MIN_FREQ).powf(1.0 / n_bands.max(1) as f32);
+
+        (0..n_bands)
+            .map(|b| {
+                let lo_hz = MIN_FREQ * ratio.powi(b as i32);
+                let lo = ((lo_hz / bin_hz) as usize).min(nyquist_bin - 1);
+                let hi = (((lo_hz * ratio) / bin_hz).ceil() as usize).clamp(lo + 1, nyquist_bin);
+                let mag = self.buf[lo..hi]
+                    .iter()
+                    .map(|c| c.norm())
+                    .fold(0.0_f32, f32::max);
+                (20.0 * (mag * norm).max(1e-9).log10()).max(FLOOR_DB)
+            })
+            .collect()
+    }
+}
+
+/// Peak markers that hold for a while, then fall.
+pub struct PeakHold {
+    hold: Duration,
+    peaks: Vec<f32>,
+    held_since: Vec<Instant>,
+    last_update: Instant,
+}
+
+impl PeakHold {
+    pub fn new(hold: Duration) -> Self {
+        Self {
+            hold,
+            peaks: Vec::new(),
+            held_since: Vec::new(),
+            last_update: Instant::now(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.hold.is_zero()
+    }
+
+    pub fn update(&mut self, levels: &[f32], now: Instant) -> &[f32] {
+        if self.peaks.len() != levels.len() {
+            self.peaks = levels.to_vec();
+            self.held_since = vec![now; levels.len()];
+        }
+        let fall = PEAK_FALL_DB_PER_SEC * now.duration_since(self.last_update).as_secs_f32();
+        self.last_update = now;
+        for (i, &level) in levels.iter().enumerate() {
+            if level >= self.peaks[i] {
+                self.peaks[i] = level;
+                self.held_since[i] = now;
+            } else if now.duration_since(self.held_since[i]) > self.hold {
+                self.peaks[i] = (self.peaks[i] - fall).max(level);
+            }
+        }
+        &self.peaks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sine_lands_in_its_band() {
+        let mut analyzer = Analyzer::new(4096);
+        let ring: VecDeque<f32> = (0..8192)
+            .map(|i| (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / SAMPLE_RATE).sin())
+            .collect();
+        let n_bands = 64;
+        let bands = analyzer.bands(&ring, n_bands);
+        let (loudest, level) = bands
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        let ratio = (MAX_FREQ / MIN_FREQ).powf(1.0 / n_bands as f32);
+        let lo = MIN_FREQ * ratio.powi(loudest as i32);
+        assert!(
+            lo <= 1000.0 && 1000.0 < lo * ratio,
+            "band {} from {} Hz",
+            loudest,
+            lo
+        );
+        assert!(*level > -3.0 && *level < 1.0, "level {}", level);
+        assert!(bands[0] < -60.0);
+    }
+
+    #[test]
+    fn test_peak_hold() {
+        let t0 = Instant::now();
+        let mut peaks = PeakHold::new(Duration::from_millis(500));
+        peaks.update(&[-10.0], t0);
+        assert_eq!(
+            peaks.update(&[-50.0], t0 + Duration::from_millis(400)),
+            &[-10.0]
+        );
+        // Past the hold, the marker falls at 30 dB/s but not below the level.
+        let p = peaks.update(&[-50.0], t0 + Duration::from_millis(900))[0];
+        assert!((p - -25.0).abs() < 0.1, "peak {}", p);
+        let p = peaks.update(&[-50.0], t0 + Duration::from_secs(5))[0];
+        assert_eq!(p, -50.0);
+    }
+}
@@ -0,0 +1,190 @@
+//! Silent audio frames in a stream's own format, which the proxy sends to
+//! live listeners while it reconnects a lost upstream so their players keep
+//! receiving audio instead of stalling.
+//!
+//! The format is copied from the last frame header found in the audio
+//! received before the loss: MPEG audio layer III (`audio/mpeg`) or AAC in
+//! ADTS (`audio/aac`, `audio/aacp`).  Other codecs get no filler.
+
+use std::time::Duration;
+
+/// One silent frame and how much audio it plays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SilentFrame {
+    pub data: Vec<u8>,
+    pub duration: Duration,
+}
+
+/// A silent frame matching the last complete frame in `audio`.
+pub fn frame_like(audio: &[u8]) -> Option<SilentFrame> {
+    // The last frame may be cut short; a header counts only when the next
+    // one follows where it says.
+    (0..audio.len().saturating_sub(4)).rev().find_map(|i| {
+        let frame = mp3_frame(&audio[i..]).or_else(|| adts_frame(&audio[i..]))?;
+        let next = audio.get(i + frame.len..)?;
+        (next.is_empty() || frame.matches(next)).then(|| frame.silence())
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Mp3,
+    Adts,
+}
+
+/// A frame header found in the stream.
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    kind: Kind,
+    /// The header's first bytes (4 for MPEG audio, 7 for ADTS).
+    header: [u8; 7],
+    /// Length of the whole frame in the stream.
+    len: usize,
+    samples: u32,
+    sample_rate: u32,
+}
+
+const MP3_BITRATES_V1: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const MP3_BITRATES_V2: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+const ADTS_SAMPLE_RATES: [u32; 13] = [
+    96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+];
+
+/// An MPEG audio layer III header at the start of `b`.
+fn mp3_frame(b: &[u8]) -> Option<Frame> {
+    if b.len() < 4 || b[0] != 0xFF || b[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+    let version = (b[1] >> 3) & 0b11; // 3 = MPEG-1, 2 = MPEG-2, 0 = MPEG-2.5
+    let layer = (b[1] >> 1) & 0b11; // 1 = layer III
+    let bitrate_idx = (b[2] >> 4) as usize;
+    let rate_idx = ((b[2] >> 2) & 0b11) as usize;
+    if version == 1 || layer != 1 || bitrate_idx == 0 || bitrate_idx == 15 || rate_idx == 3 {
+        return None;
+    }
+    let base_rate = [44100, 48000, 32000][rate_idx];
+    let (sample_rate, kbps, samples) = match version {
+        3 => (base_rate, MP3_BITRATES_V1[bitrate_idx], 1152),
+        2 => (base_rate / 2, MP3_BITRATES_V2[bitrate_idx], 576),
+        _ => (base_rate / 4, MP3_BITRATES_V2[bitrate_idx], 576),
+    };
+    let padding = ((b[2] >> 1) & 1) as usize;
+    let len = (samples / 8 * kbps * 1000 / sample_rate) as usize + padding;
+    let mut header = [0; 7];
+    header[..4].copy_from_slice(&b[..4]);
+    Some(Frame {
+        kind: Kind::Mp3,
+        header,
+        len,
+        samples,
+        sample_rate,
+    })
+}
+
+/// An ADTS (AAC) header at the start of `b`, for mono or stereo.
+fn adts_frame(b: &[u8]) -> Option<Frame> {
+    if b.len() < 7 || b[0] != 0xFF || b[1] & 0xF6 != 0xF0 {
+        return None;
+    }
+    let rate_idx = ((b[2] >> 2) & 0b1111) as usize;
+    let channels = ((b[2] & 1) << 2) | (b[3] >> 6);
+    let len = (((b[3] & 0b11) as usize) << 11) | ((b[4] as usize) << 3) | (b[5] >> 5) as usize;
+    let sample_rate = *ADTS_SAMPLE_RATES.get(rate_idx)?;
+    if !(1..=2).contains(&channels) || len < 7 {
+        return None;
+    }
+    let mut header = [0; 7];
+    header.copy_from_slice(&b[..7]);
+    Some(Frame {
+        kind: Kind::Adts,
+        header,
+        len,
+        samples: 1024,
+        sample_rate,
+    })
+}
+
+impl Frame {
+    /// `next` starts with a frame of the same format.
+    fn matches(&self, next: &[u8]) -> bool {
+        let next = match self.kind {
+            Kind::Mp3 => mp3_frame(next),
+            Kind::Adts => adts_frame(next),
+        };
+        next.is_some_and(|n| n.sample_rate == self.sample_rate && n.header[..2] == self.header[..2])
+    }
+
+    fn silence(&self) -> SilentFrame {
+        let data = match self.kind {
+            Kind::Mp3 => {
+                // Same version, bitrate, rate and channel mode; no CRC, no
+                // padding.  All-zero side info decodes as silence.
+                let mut data = vec![0; self.len - ((self.header[2] >> 1) & 1) as usize];
+                data[..4].copy_from_slice(&self.header[..4]);
+                data[1] |= 1;
+                data[2] &= !0b10;
+                data
+            }
+            Kind::Adts => {
+                // One element with no scale factor bands (all spectral
+                // coefficients zero), then ID_END: a single channel element
+                // for mono, a channel pair for stereo.
+                let channels = ((self.header[2] & 1) << 2) | (self.header[3] >> 6);
+                let payload: &[u8] = if channels == 1 {
+                    &[0x00, 0x00, 0x00, 0x07]
+                } else {
+                    &[0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E]
+                };
+                let len = 7 + payload.len();
+                let mut data = self.header.to_vec();
+                data[1] |= 1; // no CRC
+                data[3] = (data[3] & 0xFC) | (len >> 11) as u8;
+                data[4] = (len >> 3) as u8;
+                data[5] = ((len as u8 & 0b111) << 5) | 0x1F;
+                data[6] = 0xFC;
+                data.extend_from_slice(payload);
+                data
+            }
+        };
+        SilentFrame {
+            data,
+            duration: Duration::from_secs_f64(self.samples as f64 / self.sample_rate as f64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_silent_frames() {
+        // MPEG-1 layer III, 128 kbps, 44.1 kHz, joint stereo, padded: 418 bytes.
+        let mut frame = vec![0u8; 418];
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x92, 0x64]);
+        let mut audio = vec![0x42; 100];
+        audio.extend_from_slice(&frame);
+        audio.extend_from_slice(&frame);
+        audio.extend_from_slice(&frame[..50]);
+        let silent = frame_like(&audio).unwrap();
+        assert_eq!(&silent.data[..4], &[0xFF, 0xFB, 0x90, 0x64]);
+        assert_eq!(silent.data.len(), 417);
+        assert!(silent.data[4..].iter().all(|b| *b == 0));
+        assert_eq!(silent.duration.as_micros(), 26122);
+
+        // AAC-LC, 44.1 kHz, stereo, 300-byte frames.
+        let mut frame = vec![0xAAu8; 300];
+        frame[..7].copy_from_slice(&[0xFF, 0xF1, 0x50, 0x80, 0x25, 0x9F, 0xFC]);
+        let audio = [frame.clone(), frame].concat();
+        let silent = frame_like(&audio).unwrap();
+        assert_eq!(
+            silent.data,
+            [0xFF, 0xF1, 0x50, 0x80, 0x01, 0xDF, 0xFC, 0x20, 0, 0, 0, 0, 0, 0x0E]
+        );
+        assert_eq!(adts_frame(&silent.data).unwrap().len, silent.data.len());
+
+        assert_eq!(frame_like(b"OggS\0\x02 not an mpeg stream"), None);
+    }
+}
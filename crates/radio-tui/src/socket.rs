@@ -0,0 +1,206 @@
+//! TCP client protocol server.
+//!
+//! Speaks the length-prefixed `radio_proto::protocol::Message` framing: each
+//! client gets a `Hello` with the full state on connect, then every core
+//! broadcast (state, ICY, log, audio level, PCM) as it happens.  Commands
+//! from the client go into the same `DaemonEvent` channel the TUI and HTTP
+//! API use.  `r4dio --attach` (see `attach.rs`) is the client.
+
+use std::sync::Arc;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{error, info, warn};
+
+use radio_proto::protocol::{Broadcast, Message, PROTOCOL_VERSION};
+use radio_proto::state::StateManager;
+
+use crate::core::DaemonEvent;
+use crate::BroadcastMessage;
+
+/// Largest frame accepted; a full state with a big station list is well below.
+const MAX_FRAME: usize = 16 * 1024 * 1024;
+
+pub fn start_server(
+    bind_address: String,
+    port: u16,
+    state_manager: Arc<StateManager>,
+    event_tx: mpsc::Sender<DaemonEvent>,
+    broadcast_tx: broadcast::Sender<BroadcastMessage>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let addr = format!("{}:{}", bind_address, port);
+        match TcpListener::bind(&addr).await {
+            Ok(listener) => {
+                info!("[tcp] listening at {}", addr);
+                serve(listener, state_manager, event_tx, broadcast_tx).await;
+            }
+            Err(e) => error!("[tcp] failed to bind {}: {}", addr, e),
+        }
+    })
+}
+
+/// Accept clients on `listener` forever.
+pub async fn serve(
+    listener: TcpListener,
+    state_manager: Arc<StateManager>,
+    event_tx: mpsc::Sender<DaemonEvent>,
+    broadcast_tx: broadcast::Sender<BroadcastMessage>,
+) {
+    let mut client_id = 0usize;
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                client_id += 1;
+                let id = client_id;
+                info!("[tcp] client {} connected from {}", id, peer);
+                let state_manager = state_manager.clone();
+                let event_tx = event_tx.clone();
+                let broadcast_rx = broadcast_tx.subscribe();
+                tokio::spawn(async move {
+                    if let Err(e) =
+                        handle_client(stream, state_manager, event_tx, broadcast_rx).await
+                    {
+                        warn!("[tcp] client {}: {}", id, e);
+                    }
+                    info!("[tcp] client {} disconnected", id);
+                });
+            }
+            Err(e) => error!("[tcp] accept failed: {}", e),
+        }
+    }
+}
+
+async fn handle_client(
+    stream: TcpStream,
+    state_manager: Arc<StateManager>,
+    event_tx: mpsc::Sender<DaemonEvent>,
+    mut broadcast_rx: broadcast::Receiver<BroadcastMessage>,
+) -> anyhow::Result<()> {
+    stream.set_nodelay(true)?;
+    let (mut read_half, mut write_half) = stream.into_split();
+    let mut read_buf = Vec::new();
+
+    let state = state_manager.get_state().await;
+    let hello = Broadcast::Hello {
+        protocol_version: PROTOCOL_VERSION,
+        daemon_rev: state.rev,
+        state,
+    };
+    write_half
+        .write_all(&Message::Broadcast(hello).encode()?)
+        .await?;
+
+    loop {
+        let broadcast = tokio::select! {
+            msg = read_message(&mut read_half, &mut read_buf) => match msg? {
+                Some(Message::Command(cmd)) => {
+                    info!("[tcp] command {:?}", cmd);
+                    if event_tx.send(DaemonEvent::ClientCommand(cmd)).await.is_err() {
+                        return Ok(());
+                    }
+                    continue;
+                }
+                Some(Message::Broadcast(_)) => continue,
+                None => return Ok(()),
+            },
+            msg = broadcast_rx.recv() => match msg {
+                Ok(BroadcastMessage::StateUpdated) => state_broadcast(&state_manager).await,
+                Ok(BroadcastMessage::IcyUpdated(title)) => Broadcast::Icy { title },
+                Ok(BroadcastMessage::Log(message)) => Broadcast::Log { message },
+                Ok(BroadcastMessage::AudioLevel(rms_db)) => Broadcast::AudioLevel { rms_db },
+                Ok(BroadcastMessage::PcmChunk(samples)) => Broadcast::Pcm {
+                    samples: samples.to_vec(),
+                },
+                Ok(BroadcastMessage::FileEnded(path)) => Broadcast::FileEnded { path },
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    // Slow link: the state snapshot is what matters, audio
+                    // frames can be dropped.
+                    warn!("[tcp] client lagged by {} messages, resyncing", n);
+                    state_broadcast(&state_manager).await
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+        };
+        write_half
+            .write_all(&Message::Broadcast(broadcast).encode()?)
+            .await?;
+    }
+}
+
+async fn state_broadcast(state_manager: &StateManager) -> Broadcast {
+    Broadcast::State {
+        data: state_manager.get_state().await,
+    }
+}
+
+// ── Framing ──────────────────────────────────────────────────────────────────
+
+/// Read the next message, buffering partial frames in `buf` between calls.
+/// `Ok(None)` means the peer closed the connection.  Cancel-safe: bytes are
+/// only consumed from the socket into `buf`.
+pub async fn read_message<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+) -> anyhow::Result<Option<Message>> {
+    let mut tmp = [0u8; 8192];
+    loop {
+        match take_frame(buf)? {
+            Some(Ok(msg)) => return Ok(Some(msg)),
+            Some(Err(e)) => warn!("[tcp] dropping undecodable frame: {}", e),
+            None => {}
+        }
+        let n = reader.read(&mut tmp).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&tmp[..n]);
+    }
+}
+
+/// Pop one complete frame off the front of `buf`, if there is one.  The
+/// outer error is a broken stream; the inner one a single bad frame.
+fn take_frame(buf: &mut Vec<u8>) -> anyhow::Result<Option<anyhow::Result<Message>>> {
+    if buf.len() < 4 {
+        return Ok(None);
+    }
+    let len = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]) as usize;
+    if len > MAX_FRAME {
+        anyhow::bail!(
+            "frame of {} bytes exceeds the {} byte limit",
+            len,
+            MAX_FRAME
+        );
+    }
+    if buf.len() < 4 + len {
+        return Ok(None);
+    }
+    let msg = Message::decode(&buf[..4 + len]).map(|(msg, _)| msg);
+    buf.drain(..4 + len);
+    Ok(Some(msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use radio_proto::protocol::Command;
+
+    #[test]
+    fn test_take_frame_partial() {
+        let frame = Message::Command(Command::Next).encode().unwrap();
+        let mut buf = frame[..6].to_vec();
+        assert!(take_frame(&mut buf).unwrap().is_none());
+
+        buf.extend_from_slice(&frame[6..]);
+        buf.extend_from_slice(&frame[..2]);
+        match take_frame(&mut buf).unwrap() {
+            Some(Ok(Message::Command(Command::Next))) => {}
+            _ => panic!("expected Next"),
+        }
+        assert_eq!(buf, frame[..2]);
+
+        let mut bad = vec![0xff, 0xff, 0xff, 0xff];
+        assert!(take_frame(&mut bad).is_err());
+    }
+}
@@ -0,0 +1,613 @@
+//! Station health check: `r4dio check-stations`, and `X` in the station list.
+//!
+//! Every station's `url` is opened the way the proxy would open it: redirects
+//! are followed by hand so the chain can be reported, `.pls` / `.m3u`
+//! wrappers are resolved and HLS playlists parsed with `stream_playlist`,
+//! and an ICY stream is read through an `IcyDemuxer` until its first title,
+//! like the poller does.  When `url` fails its `mirrors` are tried in turn,
+//! as the proxy would fail over to them.  A station is dead when no audio (or
+//! no usable playlist) comes back from any of them within the timeout.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::Client;
+use serde::Serialize;
+
+use radio_proto::protocol::Station;
+use radio_proto::state::update_station_in_toml;
+
+use crate::icy::{metaint_from_headers, IcyDemuxer};
+use crate::stream_format::StreamFormat;
+use crate::stream_playlist::{
+    is_playlist_response, parse_hls, read_text, resolve_relative_url, wrapper_target, Hls,
+};
+
+/// Tag `--mark-dead` gives stations that failed the check.
+pub const DEAD_TAG: &str = "dead";
+/// Redirects and playlist hops followed before giving up.
+const MAX_HOPS: usize = 10;
+/// Metadata intervals read for an ICY title, and for how long.
+const ICY_TITLE_BLOCKS: usize = 3;
+const ICY_TITLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub const USAGE: &str =
+    "usage: r4dio check-stations [--json] [--mark-dead] [--jobs N] [--timeout SECS]
+
+  --json        print the results as JSON instead of a table
+  --mark-dead   tag stations that fail `dead` in stations.toml (and untag
+                the ones that stream again)
+  --jobs N      stations checked at once (default 16)
+  --timeout S   seconds to wait for the first audio byte (default 10)";
+
+#[derive(Debug, Clone)]
+pub struct CheckOptions {
+    pub json: bool,
+    pub mark_dead: bool,
+    pub jobs: usize,
+    pub timeout: Duration,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        Self {
+            json: false,
+            mark_dead: false,
+            jobs: 16,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl CheckOptions {
+    /// Parse the arguments after `check-stations`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => options.json = true,
+                "--mark-dead" => options.mark_dead = true,
+                "--jobs" | "--timeout" => {
+                    let Some(n) = args.next().and_then(|v| v.parse::<u64>().ok()) else {
+                        anyhow::bail!("{} needs a number\n\n{}", arg, USAGE);
+                    };
+                    if arg == "--jobs" {
+                        options.jobs = (n as usize).max(1);
+                    } else {
+                        options.timeout = Duration::from_secs(n.max(1));
+                    }
+                }
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                other => anyhow::bail!("unknown argument {:?}\n\n{}", other, USAGE),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// What one station's `url` (or, when that fails, a mirror) answered.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StationCheck {
+    pub name: String,
+    pub url: String,
+    /// Status of the last response (`None` when none came back).
+    pub status: Option<u16>,
+    /// Every URL after `url`: redirect targets and playlist entries.
+    pub redirects: Vec<String>,
+    pub content_type: String,
+    pub codec: Option<String>,
+    pub bitrate_kbps: Option<u32>,
+    /// The server interleaves ICY metadata (`icy-metaint`).
+    pub icy: bool,
+    /// The first `StreamTitle`, when one arrived within a few blocks.
+    pub icy_title: Option<String>,
+    pub hls: bool,
+    /// The mirror that answered when `url` didn't.
+    pub mirror: Option<String>,
+    /// From the first request to the first byte of audio (or of the HLS
+    /// playlist).
+    pub ttfb_ms: Option<u64>,
+    /// Why the station is dead; `None` when it streams.
+    pub error: Option<String>,
+}
+
+impl StationCheck {
+    pub fn is_dead(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+/// Client for checks: redirects are followed by `check_station` itself.
+pub fn client() -> Client {
+    Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .user_agent(concat!("r4dio/", env!("CARGO_PKG_VERSION")))
+        .default_headers({
+            let mut h = HeaderMap::new();
+            h.insert("Icy-MetaData", HeaderValue::from_static("1"));
+            h
+        })
+        .build()
+        .expect("failed to build reqwest client for station checks")
+}
+
+/// Check every station, `jobs` at a time; results in station order.
+pub async fn check_all(stations: &[Station], jobs: usize, timeout: Duration) -> Vec<StationCheck> {
+    let client = client();
+    let client = &client;
+    // Cloned stations: borrowed ones make rustc reject `app.rs` spawning this
+    // future ("implementation of `FnOnce` is not general enough").
+    futures_util::stream::iter(stations.iter().cloned())
+        .map(|station| async move { check_station(client, &station, timeout).await })
+        .buffered(jobs.max(1))
+        .collect()
+        .await
+}
+
+pub async fn check_station(client: &Client, station: &Station, timeout: Duration) -> StationCheck {
+    let mut check = check_url(client, &station.name, &station.url, timeout).await;
+    for mirror in &station.mirrors {
+        if !check.is_dead() {
+            break;
+        }
+        let answer = check_url(client, &station.name, mirror, timeout).await;
+        if !answer.is_dead() {
+            check = StationCheck {
+                url: station.url.clone(),
+                mirror: Some(mirror.clone()),
+                ..answer
+            };
+        }
+    }
+    if let (Some(e), false) = (&mut check.error, station.mirrors.is_empty()) {
+        e.push_str(" (mirrors down too)");
+    }
+    check
+}
+
+async fn check_url(client: &Client, name: &str, url: &str, timeout: Duration) -> StationCheck {
+    let mut check = StationCheck {
+        name: name.to_string(),
+        url: url.to_string(),
+        ..StationCheck::default()
+    };
+    let started = Instant::now();
+    let opened = tokio::time::timeout(timeout, open(client, &mut check, started)).await;
+    match opened {
+        Ok(Ok(Some((response, first, metaint)))) => {
+            check.icy_title = read_title(response, first, metaint).await;
+        }
+        Ok(Ok(None)) => {}
+        Ok(Err(e)) => check.error = Some(e),
+        Err(_) => check.error = Some(format!("no audio within {}s", timeout.as_secs())),
+    }
+    check
+}
+
+/// Follow `check.url` to a stream and read its first bytes, filling in
+/// `check` on the way.  Returns the body, its first chunk and the metadata
+/// interval when the stream carries ICY titles.
+async fn open(
+    client: &Client,
+    check: &mut StationCheck,
+    started: Instant,
+) -> Result<Option<(reqwest::Response, Vec<u8>, usize)>, String> {
+    let mut url = check.url.clone();
+    for _ in 0..MAX_HOPS {
+        let mut response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| request_error(&e))?;
+        let status = response.status();
+        check.status = Some(status.as_u16());
+        check.content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        if status.is_redirection() {
+            let location = response
+                .headers()
+                .get("location")
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| format!("HTTP {} without a Location", status.as_u16()))?;
+            url = resolve_relative_url(&url, location)?;
+            check.redirects.push(url.clone());
+            continue;
+        }
+        if !status.is_success() {
+            return Err(format!("HTTP {}", status.as_u16()));
+        }
+
+        let headers = response.headers().clone();
+        if is_playlist_response(&url, &headers) {
+            let text = read_text(response).await?;
+            match parse_hls(&url, &text) {
+                Some(Hls::Master(variants)) => {
+                    check.hls = true;
+                    let top = variants.iter().max_by_key(|v| v.bandwidth);
+                    check.bitrate_kbps = top.map(|v| (v.bandwidth / 1000) as u32);
+                    check.codec = top.and_then(|v| v.codec.clone());
+                    check.ttfb_ms = Some(started.elapsed().as_millis() as u64);
+                    return if variants.is_empty() {
+                        Err("HLS master playlist lists no variants".to_string())
+                    } else {
+                        Ok(None)
+                    };
+                }
+                Some(Hls::Media(media)) => {
+                    check.hls = true;
+                    check.ttfb_ms = Some(started.elapsed().as_millis() as u64);
+                    return if media.segments.is_empty() {
+                        Err("HLS playlist lists no segments".to_string())
+                    } else if media.encrypted {
+                        Err("HLS segments are encrypted".to_string())
+                    } else {
+                        Ok(None)
+                    };
+                }
+                None => {
+                    url = wrapper_target(&url, &text)?.ok_or("empty playlist")?;
+                    check.redirects.push(url.clone());
+                    continue;
+                }
+            }
+        }
+        if check.content_type.starts_with("text/html") {
+            return Err("a web page, not a stream".to_string());
+        }
+
+        let format = StreamFormat::from_headers(&headers);
+        check.codec = format.codec;
+        check.bitrate_kbps = format.bitrate_kbps;
+        let metaint = metaint_from_headers(&headers);
+        check.icy = metaint.is_some();
+        let first = response
+            .chunk()
+            .await
+            .map_err(|e| request_error(&e))?
+            .ok_or("the stream ended before any audio")?
+            .to_vec();
+        check.ttfb_ms = Some(started.elapsed().as_millis() as u64);
+        return Ok(metaint.map(|metaint| (response, first, metaint)));
+    }
+    Err(format!("more than {} redirects", MAX_HOPS))
+}
+
+/// The first ICY title within `ICY_TITLE_BLOCKS` metadata intervals.
+async fn read_title(
+    mut response: reqwest::Response,
+    first: Vec<u8>,
+    metaint: usize,
+) -> Option<String> {
+    let deadline = Instant::now() + ICY_TITLE_TIMEOUT;
+    let mut demuxer = IcyDemuxer::new(Some(metaint));
+    let mut audio = Vec::new();
+    let mut chunk = first;
+    let mut bytes_read = 0;
+    loop {
+        bytes_read += chunk.len();
+        audio.clear();
+        if let Some((_, title)) = demuxer.feed(&chunk, &mut audio).into_iter().next() {
+            return Some(title);
+        }
+        if bytes_read >= ICY_TITLE_BLOCKS * (metaint + 1) {
+            return None;
+        }
+        let remain = deadline.saturating_duration_since(Instant::now());
+        chunk = tokio::time::timeout(remain, response.chunk())
+            .await
+            .ok()?
+            .ok()??
+            .to_vec();
+    }
+}
+
+/// reqwest's own messages name the URL again; keep the cause.
+fn request_error(e: &reqwest::Error) -> String {
+    if e.is_timeout() {
+        "timed out".to_string()
+    } else if e.is_connect() {
+        let mut cause: &dyn std::error::Error = e;
+        while let Some(next) = cause.source() {
+            cause = next;
+        }
+        format!("connection failed: {}", cause)
+    } else {
+        e.to_string()
+    }
+}
+
+/// Tag the stations that failed `DEAD_TAG` in the TOML station file at
+/// `path`, and untag the ones that stream again.  `checks` are in
+/// `stations` order.  Returns how many stations changed.
+pub fn mark_dead(
+    path: &Path,
+    stations: &[Station],
+    checks: &[StationCheck],
+) -> anyhow::Result<usize> {
+    let mut current = stations.to_vec();
+    let mut changed = 0;
+    for (i, check) in checks.iter().enumerate() {
+        let old = current[i].clone();
+        let tagged = old.tags.iter().any(|t| t == DEAD_TAG);
+        if tagged == check.is_dead() {
+            continue;
+        }
+        let mut new = old.clone();
+        if check.is_dead() {
+            new.tags.push(DEAD_TAG.to_string());
+        } else {
+            new.tags.retain(|t| t != DEAD_TAG);
+        }
+        update_station_in_toml(path, &current, &old, &new)?;
+        current[i] = new;
+        changed += 1;
+    }
+    Ok(changed)
+}
+
+/// One row per station, dead ones first, and a count.
+pub fn table(checks: &[StationCheck]) -> String {
+    let mut rows: Vec<&StationCheck> = checks.iter().collect();
+    rows.sort_by_key(|c| !c.is_dead());
+    let name_width = rows
+        .iter()
+        .map(|c| c.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp(7, 32);
+
+    let mut out = format!(
+        "{:<6} {:>7}  {:<14} {:<4} {:<w$}  {}\n",
+        "STATUS",
+        "TTFB",
+        "FORMAT",
+        "ICY",
+        "STATION",
+        "NOTES",
+        w = name_width
+    );
+    for c in rows {
+        let status = match (c.is_dead(), c.status) {
+            (true, _) => "dead".to_string(),
+            (false, Some(code)) => code.to_string(),
+            (false, None) => "-".to_string(),
+        };
+        let ttfb = c
+            .ttfb_ms
+            .map(|ms| format!("{}ms", ms))
+            .unwrap_or_else(|| "-".to_string());
+        let format = match (&c.codec, c.bitrate_kbps, c.hls) {
+            (codec, kbps, true) => format!(
+                "hls {}{}",
+                codec
+                    .as_ref()
+                    .map(|c| format!("{} ", c))
+                    .unwrap_or_default(),
+                kbps.map(|k| format!("{}k", k)).unwrap_or_default()
+            ),
+            (Some(codec), Some(kbps), _) => format!("{} {}k", codec, kbps),
+            (Some(codec), None, _) => codec.clone(),
+            (None, Some(kbps), _) => format!("{}k", kbps),
+            (None, None, _) => "-".to_string(),
+        };
+        let icy = if c.icy { "yes" } else { "-" };
+        let name: String = c.name.chars().take(name_width).collect();
+        let mut notes = Vec::new();
+        if let Some(e) = &c.error {
+            notes.push(e.clone());
+        }
+        if let Some(mirror) = &c.mirror {
+            notes.push(format!("url down, via mirror {}", mirror));
+        }
+        if !c.redirects.is_empty() {
+            notes.push(format!("→ {}", c.redirects.join(" → ")));
+        }
+        if let Some(title) = &c.icy_title {
+            notes.push(format!("\"{}\"", title));
+        }
+        out.push_str(&format!(
+            "{:<6} {:>7}  {:<14} {:<4} {:<w$}  {}\n",
+            status,
+            ttfb,
+            format.trim(),
+            icy,
+            name,
+            notes.join("  "),
+            w = name_width
+        ));
+    }
+    let dead = checks.iter().filter(|c| c.is_dead()).count();
+    out.push_str(&format!("\n{} stations, {} dead\n", checks.len(), dead));
+    out
+}
+
+/// `r4dio check-stations`: check the configured stations and print the
+/// results, optionally tagging the dead ones in `stations.toml`.
+pub async fn run(options: CheckOptions) -> anyhow::Result<()> {
+    let config = radio_proto::config::Config::load().unwrap_or_default();
+    let stations = crate::core::load_stations(&config).await?;
+    if stations.is_empty() {
+        anyhow::bail!("no stations to check");
+    }
+    eprintln!(
+        "checking {} stations, {} at a time…",
+        stations.len(),
+        options.jobs
+    );
+    let checks = check_all(&stations, options.jobs, options.timeout).await;
+
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+    } else {
+        print!("{}", table(&checks));
+    }
+
+    if options.mark_dead {
+        let path = &config.stations.stations_toml;
+        if !path.exists() {
+            anyhow::bail!(
+                "--mark-dead edits {}, which doesn't exist (stations came from elsewhere)",
+                path.display()
+            );
+        }
+        let file_stations = radio_proto::state::load_stations_from_toml(path)?;
+        if file_stations != stations {
+            anyhow::bail!("{} changed during the check; run it again", path.display());
+        }
+        let changed = mark_dead(path, &stations, &checks)?;
+        eprintln!(
+            "updated the {:?} tag on {} stations in {}",
+            DEAD_TAG,
+            changed,
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve `responses` to consecutive connections.
+    async fn serve(responses: Vec<Vec<u8>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut conn, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let _ = conn.read(&mut buf).await.unwrap();
+                conn.write_all(&response).await.unwrap();
+            }
+        });
+        base
+    }
+
+    #[tokio::test]
+    async fn test_check_station_follows_redirect_to_icy_stream() {
+        let title = b"StreamTitle='Can - Vitamin C';";
+        let blocks = title.len().div_ceil(16);
+        let mut body = vec![0u8; 8];
+        body.push(blocks as u8);
+        body.extend_from_slice(title);
+        body.resize(9 + blocks * 16, 0);
+        body.extend_from_slice(&[0u8; 8]);
+        let mut stream = b"HTTP/1.1 200 OK\r\nContent-Type: audio/mpeg\r\nicy-br: 128\r\n\
+icy-metaint: 8\r\nConnection: close\r\n\r\n"
+            .to_vec();
+        stream.extend_from_slice(&body);
+        let base = serve(vec![
+            b"HTTP/1.1 302 Found\r\nLocation: /live\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_vec(),
+            stream,
+        ])
+        .await;
+
+        let station = Station {
+            name: "Test FM".into(),
+            url: format!("{}/stream", base),
+            ..Station::default()
+        };
+        let check = check_station(&client(), &station, Duration::from_secs(5)).await;
+        assert!(!check.is_dead(), "{:?}", check.error);
+        assert_eq!(check.status, Some(200));
+        assert_eq!(check.redirects, vec![format!("{}/live", base)]);
+        assert_eq!(check.codec.as_deref(), Some("mp3"));
+        assert_eq!(check.bitrate_kbps, Some(128));
+        assert!(check.icy);
+        assert_eq!(check.icy_title.as_deref(), Some("Can - Vitamin C"));
+        assert!(check.ttfb_ms.is_some());
+
+        let base = serve(vec![
+            b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec(),
+        ])
+        .await;
+        let gone = Station {
+            url: format!("{}/gone", base),
+            ..station
+        };
+        let check = check_station(&client(), &gone, Duration::from_secs(5)).await;
+        assert_eq!(check.error.as_deref(), Some("HTTP 404"));
+        assert!(table(&[check]).contains("1 stations, 1 dead"));
+
+        // A dead primary with a live mirror is not dead.
+        let down = serve(vec![
+            b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_vec(),
+        ])
+        .await;
+        let mirror = serve(vec![
+            b"HTTP/1.1 200 OK\r\nContent-Type: audio/aac\r\nConnection: close\r\n\r\n\xff\xf1"
+                .to_vec(),
+        ])
+        .await;
+        let mirrored = Station {
+            url: format!("{}/live", down),
+            mirrors: vec![format!("{}/gone", base), format!("{}/live", mirror)],
+            ..gone.clone()
+        };
+        let check = check_station(&client(), &mirrored, Duration::from_secs(5)).await;
+        assert!(!check.is_dead(), "{:?}", check.error);
+        assert_eq!(check.url, mirrored.url);
+        assert_eq!(check.mirror, Some(format!("{}/live", mirror)));
+        assert_eq!(check.codec.as_deref(), Some("aac"));
+        assert!(table(&[check]).contains("via mirror"));
+
+        let base = serve(vec![b"HTTP/1.1 200 OK\r\n\
+Content-Type: application/vnd.apple.mpegurl\r\nConnection: close\r\n\r\n\
+#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=64000,CODECS=\"mp4a.40.5\"\nlo.m3u8\n\
+#EXT-X-STREAM-INF:BANDWIDTH=320000,CODECS=\"mp4a.40.2\"\nhi.m3u8\n"
+            .to_vec()])
+        .await;
+        let hls = Station {
+            url: format!("{}/master.m3u8", base),
+            ..gone
+        };
+        let check = check_station(&client(), &hls, Duration::from_secs(5)).await;
+        assert!(!check.is_dead(), "{:?}", check.error);
+        assert_eq!(check.codec.as_deref(), Some("aac"));
+        assert!(table(&[check]).contains("hls aac 320k"));
+    }
+
+    #[test]
+    fn test_mark_dead() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stations.toml");
+        std::fs::write(
+            &path,
+            "# favourites\n[[station]]\nname = \"A\"\nurl = \"http://a/\"\ntags = [\"jazz\"]\n\n\
+[[station]]\nname = \"B\"\nurl = \"http://b/\"\ntags = [\"dead\"]\n",
+        )
+        .unwrap();
+        let stations = radio_proto::state::load_stations_from_toml(&path).unwrap();
+        let checks = vec![
+            StationCheck {
+                error: Some("HTTP 404".into()),
+                ..StationCheck::default()
+            },
+            StationCheck::default(),
+        ];
+        assert_eq!(mark_dead(&path, &stations, &checks).unwrap(), 2);
+
+        let stations = radio_proto::state::load_stations_from_toml(&path).unwrap();
+        assert_eq!(stations[0].tags, vec!["jazz", "dead"]);
+        assert!(stations[1].tags.is_empty());
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .starts_with("# favourites"));
+        assert_eq!(mark_dead(&path, &stations, &checks).unwrap(), 0);
+    }
+}
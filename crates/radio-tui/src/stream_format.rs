@@ -0,0 +1,186 @@
+//! Stream format for the diagnostics pane (`H`): what the upstream declares
+//! in its headers, refined by running ffprobe over a few seconds of audio
+//! from the proxy's buffer.
+
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use tokio::io::AsyncWriteExt;
+
+/// ffprobe is given this long before the probe is abandoned.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamFormat {
+    pub codec: Option<String>,
+    pub bitrate_kbps: Option<u32>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+}
+
+impl StreamFormat {
+    /// What the upstream declares: its content type, `icy-br`, `icy-sr` and
+    /// `ice-audio-info` (`ice-samplerate=44100;ice-bitrate=128;ice-channels=2`).
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+        };
+        let mut format = Self {
+            codec: header("content-type")
+                .and_then(codec_for_content_type)
+                .map(str::to_string),
+            bitrate_kbps: header("icy-br").and_then(header_number),
+            sample_rate: header("icy-sr").and_then(header_number),
+            channels: None,
+        };
+        if let Some(info) = header("ice-audio-info").or_else(|| header("icy-audio-info")) {
+            for pair in info.split(';') {
+                let Some((key, value)) = pair.split_once('=') else {
+                    continue;
+                };
+                let value = header_number(value);
+                let key = key
+                    .trim()
+                    .trim_start_matches("ice-")
+                    .trim_start_matches("icy-");
+                match key {
+                    "bitrate" => format.bitrate_kbps = format.bitrate_kbps.or(value),
+                    "samplerate" => format.sample_rate = format.sample_rate.or(value),
+                    "channels" => format.channels = format.channels.or(value),
+                    _ => {}
+                }
+            }
+        }
+        format
+    }
+
+    /// This format with `probed`'s fields wherever it has them.
+    pub fn refined(self, probed: StreamFormat) -> Self {
+        Self {
+            codec: probed.codec.or(self.codec),
+            bitrate_kbps: probed.bitrate_kbps.or(self.bitrate_kbps),
+            sample_rate: probed.sample_rate.or(self.sample_rate),
+            channels: probed.channels.or(self.channels),
+        }
+    }
+}
+
+/// Servers sometimes repeat the value (`icy-br: 128,128`); the first counts.
+fn header_number(value: &str) -> Option<u32> {
+    value
+        .split(',')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|n| *n > 0)
+}
+
+fn codec_for_content_type(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    Some(match mime.as_str() {
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/aac" | "audio/aacp" | "audio/x-aac" => "aac",
+        "audio/ogg" | "application/ogg" => "ogg",
+        "audio/opus" => "opus",
+        "audio/flac" | "audio/x-flac" => "flac",
+        _ => return None,
+    })
+}
+
+/// Run ffprobe over `audio`, fed on stdin.  `None` when ffprobe is missing
+/// or can't make sense of it.
+pub async fn probe(audio: Vec<u8>) -> Option<StreamFormat> {
+    let ffprobe_bin =
+        radio_proto::platform::find_ffprobe_binary().unwrap_or_else(|| PathBuf::from("ffprobe"));
+    let mut child = tokio::process::Command::new(ffprobe_bin)
+        .args([
+            "-v",
+            "quiet",
+            "-print_format",
+            "json",
+            "-show_streams",
+            "-i",
+            "pipe:0",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .ok()?;
+    let mut stdin = child.stdin.take()?;
+    // ffprobe stops reading once it has seen enough; the broken pipe is fine.
+    let feed = async move {
+        let _ = stdin.write_all(&audio).await;
+    };
+    let (_, output) = tokio::time::timeout(PROBE_TIMEOUT, async {
+        tokio::join!(feed, child.wait_with_output())
+    })
+    .await
+    .ok()?;
+    parse_ffprobe(&output.ok()?.stdout)
+}
+
+/// The first audio stream of `ffprobe -show_streams -print_format json`.
+fn parse_ffprobe(output: &[u8]) -> Option<StreamFormat> {
+    let json: serde_json::Value = serde_json::from_slice(output).ok()?;
+    let stream = json["streams"]
+        .as_array()?
+        .iter()
+        .find(|s| s["codec_type"] == "audio")?;
+    // Numbers come as strings, except the channel count.
+    let number = |v: &serde_json::Value| v.as_u64().or_else(|| v.as_str()?.parse().ok());
+    Some(StreamFormat {
+        codec: stream["codec_name"].as_str().map(str::to_string),
+        bitrate_kbps: number(&stream["bit_rate"])
+            .map(|bps| (bps / 1000) as u32)
+            .filter(|kbps| *kbps > 0),
+        sample_rate: number(&stream["sample_rate"]).map(|n| n as u32),
+        channels: number(&stream["channels"]).map(|n| n as u32),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_format_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("audio/aacp"));
+        headers.insert("icy-br", HeaderValue::from_static("64,64"));
+        headers.insert(
+            "ice-audio-info",
+            HeaderValue::from_static("ice-samplerate=48000;ice-bitrate=128;ice-channels=2"),
+        );
+        let format = StreamFormat::from_headers(&headers);
+        assert_eq!(
+            format,
+            StreamFormat {
+                codec: Some("aac".into()),
+                bitrate_kbps: Some(64),
+                sample_rate: Some(48000),
+                channels: Some(2),
+            }
+        );
+
+        let output = br#"{"streams": [
+            {"index": 0, "codec_name": "mjpeg", "codec_type": "video"},
+            {"index": 1, "codec_name": "mp3", "codec_type": "audio",
+             "sample_rate": "44100", "channels": 2, "bit_rate": "192000"}
+        ]}"#;
+        let probed = parse_ffprobe(output).unwrap();
+        assert_eq!(probed.bitrate_kbps, Some(192));
+        let refined = format.refined(probed);
+        assert_eq!(refined.codec.as_deref(), Some("mp3"));
+        assert_eq!(refined.sample_rate, Some(44100));
+        assert_eq!(parse_ffprobe(br#"{"streams": []}"#), None);
+    }
+}
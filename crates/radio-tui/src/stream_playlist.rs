@@ -0,0 +1,691 @@
+//! Stream playlists: `.pls` / `.m3u` wrappers that name a stream, and HLS
+//! (`.m3u8`) playlists that cut one into segments.
+//!
+//! The proxy opens every upstream through `open`.  A wrapper is resolved to
+//! the stream it names; an HLS master playlist picks a variant by bandwidth
+//! (`[proxy] hls_max_bandwidth`), and a media playlist is followed by a task
+//! that fetches each new segment once and hands them out at the pace they
+//! play.  Either way the caller gets one continuous body, so mpv, ffmpeg,
+//! recording and recognition can all share it.  The poller uses
+//! `fetch_playlist_target` for wrappers too.
+
+use std::time::{Duration, Instant};
+
+use axum::body::Bytes;
+use chrono::{DateTime, FixedOffset};
+use futures_util::stream::BoxStream;
+use futures_util::StreamExt;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::Client;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// Bodies longer than this aren't read as playlists.
+const MAX_PLAYLIST_BYTES: usize = 512 * 1024;
+/// Wrappers naming wrappers are followed this deep.
+const MAX_WRAPPER_DEPTH: usize = 3;
+/// HLS audio handed out ahead of real time, like an Icecast server's burst
+/// on connect, so players start with a buffer.
+const HLS_LEAD: Duration = Duration::from_secs(10);
+/// Segments back from the live edge where HLS playback starts.
+const HLS_START_SEGMENTS: usize = 3;
+/// Per playlist or segment request.
+const HLS_FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+/// Failed requests in a row before the HLS body ends with an error.
+const HLS_MAX_FAILURES: u32 = 5;
+/// `EXT-X-PROGRAM-DATE-TIME` drift between segments that counts as a gap.
+const HLS_PDT_TOLERANCE_MS: i64 = 1_000;
+
+pub fn is_playlist_url(url: &str) -> bool {
+    let l = url.to_ascii_lowercase();
+    l.ends_with(".m3u") || l.ends_with(".m3u8") || l.ends_with(".pls")
+}
+
+pub fn looks_hls_url(url: &str) -> bool {
+    url.to_ascii_lowercase().contains(".m3u8")
+}
+
+pub fn resolve_relative_url(base: &str, candidate: &str) -> Result<String, String> {
+    if candidate.starts_with("http://") || candidate.starts_with("https://") {
+        return Ok(candidate.to_string());
+    }
+    let base_url = reqwest::Url::parse(base).map_err(|e| e.to_string())?;
+    base_url
+        .join(candidate)
+        .map(|u| u.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// The first entry of a `.pls` or `.m3u` wrapper fetched from `url`.
+pub fn wrapper_target(url: &str, body: &str) -> Result<Option<String>, String> {
+    let pls = url.to_ascii_lowercase().ends_with(".pls")
+        || body
+            .trim_start()
+            .to_ascii_lowercase()
+            .starts_with("[playlist]");
+    if pls {
+        for line in body.lines() {
+            let l = line.trim();
+            if l.to_ascii_lowercase().starts_with("file") {
+                if let Some((_, v)) = l.split_once('=') {
+                    return resolve_relative_url(url, v.trim()).map(Some);
+                }
+            }
+        }
+        return Ok(None);
+    }
+
+    for line in body.lines() {
+        let l = line.trim();
+        if l.is_empty() || l.starts_with('#') {
+            continue;
+        }
+        return resolve_relative_url(url, l).map(Some);
+    }
+    Ok(None)
+}
+
+pub async fn fetch_playlist_target(client: &Client, url: &str) -> Result<Option<String>, String> {
+    let body = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    wrapper_target(url, &body)
+}
+
+// ── HLS playlists ─────────────────────────────────────────────────────────────
+
+/// One `EXT-X-STREAM-INF` entry of a master playlist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    /// Peak bits per second.
+    pub bandwidth: u64,
+    /// Audio codec named by `CODECS`, as `StreamFormat` names it.
+    pub codec: Option<String>,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Media sequence number.
+    pub seq: u64,
+    pub url: String,
+    pub duration: Duration,
+    /// Wall-clock time of the segment's first sample.
+    pub program_date_time: Option<DateTime<FixedOffset>>,
+    /// `EXT-X-DISCONTINUITY`: doesn't follow on from the segment before.
+    pub discontinuity: bool,
+    /// `EXT-X-MAP` initialization section (fragmented MP4) it needs first.
+    pub map: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaPlaylist {
+    pub target_duration: Duration,
+    pub segments: Vec<Segment>,
+    /// `EXT-X-ENDLIST`: no segments will be added.
+    pub ended: bool,
+    /// Segments are encrypted (`EXT-X-KEY` other than `NONE`).
+    pub encrypted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Hls {
+    Master(Vec<Variant>),
+    Media(MediaPlaylist),
+}
+
+/// Value of attribute `name` in an attribute list (`A=1,B="x,y"`).
+fn attribute(list: &str, name: &str) -> Option<String> {
+    let mut rest = list;
+    while !rest.is_empty() {
+        let (key, after) = rest.split_once('=')?;
+        let (value, next) = match after.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"')?;
+                let next = quoted[end + 1..].trim_start_matches(',');
+                (&quoted[..end], next)
+            }
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+        if key.trim().eq_ignore_ascii_case(name) {
+            return Some(value.trim().to_string());
+        }
+        rest = next;
+    }
+    None
+}
+
+/// The first audio codec in an RFC 6381 `CODECS` list (`"avc1.64001f,mp4a.40.2"`).
+fn audio_codec(codecs: &str) -> Option<&'static str> {
+    codecs.split(',').find_map(|c| {
+        let c = c.trim().to_ascii_lowercase();
+        Some(match c.as_str() {
+            // MPEG-1/2 audio in MP4: object types 0x6B, 0x69 and 34.
+            "mp3" | "mp4a.6b" | "mp4a.69" | "mp4a.40.34" => "mp3",
+            _ if c == "mp4a" || c.starts_with("mp4a.") => "aac",
+            "opus" => "opus",
+            "flac" => "flac",
+            "ac-3" => "ac3",
+            "ec-3" => "eac3",
+            _ => return None,
+        })
+    })
+}
+
+fn parse_program_date_time(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value)
+        .or_else(|_| DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z"))
+        .ok()
+}
+
+/// Parse an HLS playlist fetched from `url`; `None` when `body` is some
+/// other kind of playlist (a plain `.m3u` wrapper).
+pub fn parse_hls(url: &str, body: &str) -> Option<Hls> {
+    let mut lines = body.lines().map(str::trim).filter(|l| !l.is_empty());
+    if lines.next() != Some("#EXTM3U") {
+        return None;
+    }
+
+    if body.contains("#EXT-X-STREAM-INF") {
+        let mut variants = Vec::new();
+        let mut bandwidth = None;
+        let mut codec = None;
+        for line in lines {
+            if let Some(attrs) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+                bandwidth = Some(
+                    attribute(attrs, "BANDWIDTH")
+                        .and_then(|b| b.parse().ok())
+                        .unwrap_or(0),
+                );
+                codec = attribute(attrs, "CODECS")
+                    .and_then(|c| audio_codec(&c))
+                    .map(str::to_string);
+            } else if !line.starts_with('#') {
+                if let (Some(bandwidth), Ok(url)) =
+                    (bandwidth.take(), resolve_relative_url(url, line))
+                {
+                    let codec = codec.take();
+                    variants.push(Variant {
+                        bandwidth,
+                        codec,
+                        url,
+                    });
+                }
+            }
+        }
+        return Some(Hls::Master(variants));
+    }
+    if !body.contains("#EXT-X-TARGETDURATION") {
+        return None;
+    }
+
+    let mut playlist = MediaPlaylist::default();
+    let mut seq = 0;
+    let mut duration = Duration::ZERO;
+    let mut program_date_time = None;
+    let mut discontinuity = false;
+    let mut map = None;
+    for line in lines {
+        if let Some(v) = line.strip_prefix("#EXT-X-TARGETDURATION:") {
+            playlist.target_duration = Duration::from_secs(v.trim().parse().unwrap_or(0));
+        } else if let Some(v) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:") {
+            seq = v.trim().parse().unwrap_or(0);
+        } else if let Some(v) = line.strip_prefix("#EXTINF:") {
+            // A malformed duration (including inf/NaN) counts as a full
+            // target duration.
+            let secs = v.split(',').next().unwrap_or("").trim();
+            duration = match secs.parse::<f64>() {
+                Ok(s) if s.is_finite() => Duration::from_secs_f64(s.max(0.0)),
+                _ => playlist.target_duration,
+            };
+        } else if let Some(v) = line.strip_prefix("#EXT-X-PROGRAM-DATE-TIME:") {
+            program_date_time = parse_program_date_time(v.trim());
+        } else if line == "#EXT-X-DISCONTINUITY" {
+            discontinuity = true;
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-MAP:") {
+            map = attribute(attrs, "URI").and_then(|u| resolve_relative_url(url, &u).ok());
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-KEY:") {
+            playlist.encrypted = attribute(attrs, "METHOD").is_some_and(|m| m != "NONE");
+        } else if line == "#EXT-X-ENDLIST" {
+            playlist.ended = true;
+        } else if !line.starts_with('#') {
+            let Ok(segment_url) = resolve_relative_url(url, line) else {
+                continue;
+            };
+            playlist.segments.push(Segment {
+                seq,
+                url: segment_url,
+                duration,
+                program_date_time: program_date_time.take(),
+                discontinuity: std::mem::take(&mut discontinuity),
+                map: map.clone(),
+            });
+            seq += 1;
+        }
+    }
+    Some(Hls::Media(playlist))
+}
+
+/// The highest-bandwidth variant within `max_bandwidth` bits/s (0: no
+/// limit), or the lowest one when none fits.
+pub fn pick_variant(variants: &[Variant], max_bandwidth: u64) -> Option<&Variant> {
+    let fits = |v: &&Variant| max_bandwidth == 0 || v.bandwidth <= max_bandwidth;
+    variants
+        .iter()
+        .filter(fits)
+        .max_by_key(|v| v.bandwidth)
+        .or_else(|| variants.iter().min_by_key(|v| v.bandwidth))
+}
+
+// ── Opening streams ───────────────────────────────────────────────────────────
+
+/// What an upstream body yields.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Chunk {
+    Audio(Bytes),
+    /// What follows doesn't continue what came before (a missed or
+    /// discontinuous HLS segment).
+    Gap,
+}
+
+/// An upstream ready to read: the headers to show listeners and the body.
+pub struct Opened {
+    pub headers: HeaderMap,
+    pub body: BoxStream<'static, Result<Chunk, String>>,
+}
+
+pub fn is_playlist_response(url: &str, headers: &HeaderMap) -> bool {
+    let content_type = headers
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    content_type.contains("mpegurl")
+        || content_type.contains("scpls")
+        || (is_playlist_url(url) && !content_type.starts_with("audio/"))
+}
+
+pub async fn read_text(mut response: reqwest::Response) -> Result<String, String> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        body.extend_from_slice(&chunk);
+        if body.len() > MAX_PLAYLIST_BYTES {
+            return Err("playlist too large".to_string());
+        }
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+async fn get(client: &Client, url: &str) -> Result<reqwest::Response, String> {
+    let response = tokio::time::timeout(HLS_FETCH_TIMEOUT, client.get(url).send())
+        .await
+        .map_err(|_| format!("timed out fetching {}", url))?
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+    Ok(response)
+}
+
+/// GET `url` and resolve it to a stream: plain streams are passed through,
+/// wrappers followed, HLS playlists followed segment by segment.
+pub async fn open(client: &Client, url: &str, max_bandwidth: u64) -> Result<Opened, String> {
+    let mut url = url.to_string();
+    for _ in 0..=MAX_WRAPPER_DEPTH {
+        let response = client.get(&url).send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("upstream returned {}", response.status()));
+        }
+        if !is_playlist_response(&url, response.headers()) {
+            let headers = response.headers().clone();
+            let body = response
+                .bytes_stream()
+                .map(|r| r.map(Chunk::Audio).map_err(|e| e.to_string()))
+                .boxed();
+            return Ok(Opened { headers, body });
+        }
+
+        // Relative entries are relative to where redirects ended up.
+        let base = response.url().to_string();
+        let text = read_text(response).await?;
+        match parse_hls(&base, &text) {
+            Some(Hls::Master(variants)) => {
+                let variant = pick_variant(&variants, max_bandwidth)
+                    .ok_or("HLS master playlist lists no variants")?;
+                info!(
+                    "hls: {} → variant {} ({} kb/s of {} variants)",
+                    base,
+                    variant.url,
+                    variant.bandwidth / 1000,
+                    variants.len()
+                );
+                let media = fetch_media_playlist(client, &variant.url).await?;
+                return follow_hls(client.clone(), variant.url.clone(), media).await;
+            }
+            Some(Hls::Media(media)) => return follow_hls(client.clone(), base, media).await,
+            None => {
+                let target = wrapper_target(&base, &text)?.ok_or("empty playlist")?;
+                debug!("playlist: {} → {}", base, target);
+                url = target;
+            }
+        }
+    }
+    Err("playlists nest too deep".to_string())
+}
+
+async fn fetch_media_playlist(client: &Client, url: &str) -> Result<MediaPlaylist, String> {
+    let text = read_text(get(client, url).await?).await?;
+    match parse_hls(url, &text) {
+        Some(Hls::Media(media)) => Ok(media),
+        _ => Err(format!("{} is not an HLS media playlist", url)),
+    }
+}
+
+/// Content type of a segment, from its response or its extension.
+fn segment_content_type(url: &str, headers: &HeaderMap) -> String {
+    let declared = headers
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty() && v != "application/octet-stream");
+    if let Some(declared) = declared {
+        return declared;
+    }
+    let path = url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(url)
+        .to_ascii_lowercase();
+    let by_extension = if path.ends_with(".aac") {
+        "audio/aac"
+    } else if path.ends_with(".mp3") {
+        "audio/mpeg"
+    } else if path.ends_with(".mp4") || path.ends_with(".m4s") || path.ends_with(".m4a") {
+        "audio/mp4"
+    } else {
+        "video/mp2t"
+    };
+    by_extension.to_string()
+}
+
+/// Start following `media`: the first segment is fetched here, for its
+/// content type and to fail early, the rest by `run_hls`.
+async fn follow_hls(client: Client, url: String, media: MediaPlaylist) -> Result<Opened, String> {
+    if media.encrypted {
+        return Err("encrypted HLS streams aren't supported".to_string());
+    }
+    let start = if media.ended {
+        0
+    } else {
+        media.segments.len().saturating_sub(HLS_START_SEGMENTS)
+    };
+    let first = media
+        .segments
+        .get(start)
+        .cloned()
+        .ok_or("HLS playlist has no segments")?;
+
+    let (tx, rx) = mpsc::channel(8);
+    if let Some(map) = &first.map {
+        let init = get(&client, map).await?.bytes().await;
+        let _ = tx
+            .send(Ok(Chunk::Audio(init.map_err(|e| e.to_string())?)))
+            .await;
+    }
+    let response = get(&client, &first.url).await?;
+    let content_type = segment_content_type(&first.url, response.headers());
+    let data = response.bytes().await.map_err(|e| e.to_string())?;
+    let _ = tx.send(Ok(Chunk::Audio(data))).await;
+    info!(
+        "hls: following {} from segment {} ({}, {} segments listed)",
+        url,
+        first.seq,
+        content_type,
+        media.segments.len()
+    );
+
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(&content_type) {
+        headers.insert("content-type", value);
+    }
+    tokio::spawn(run_hls(client, url, media, first, tx));
+    let body = futures_util::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    })
+    .boxed();
+    Ok(Opened { headers, body })
+}
+
+/// Hand out the segments after `sent`, re-polling the playlist for new
+/// ones, until the body is dropped, the playlist ends or requests keep
+/// failing.  Segments go out when they're due to play, `HLS_LEAD` early.
+async fn run_hls(
+    client: Client,
+    url: String,
+    mut media: MediaPlaylist,
+    sent: Segment,
+    tx: mpsc::Sender<Result<Chunk, String>>,
+) {
+    let mut playhead = Instant::now() + sent.duration;
+    let mut next_seq = sent.seq + 1;
+    let mut map = sent.map.clone();
+    let mut expected_pdt = sent.program_date_time.map(|t| t + sent.duration);
+    let mut failures = 0;
+
+    loop {
+        if let Some(oldest) = media.segments.first().map(|s| s.seq) {
+            if oldest > next_seq {
+                warn!(
+                    "hls: {} fell behind the playlist, skipping {} segments",
+                    url,
+                    oldest - next_seq
+                );
+                next_seq = oldest;
+                if tx.send(Ok(Chunk::Gap)).await.is_err() {
+                    return;
+                }
+            }
+        }
+
+        let due: Vec<Segment> = media
+            .segments
+            .iter()
+            .filter(|s| s.seq >= next_seq)
+            .cloned()
+            .collect();
+        for segment in due {
+            let ready = Instant::now() + HLS_LEAD;
+            if playhead > ready {
+                tokio::time::sleep(playhead - ready).await;
+            }
+            if tx.is_closed() {
+                return;
+            }
+
+            let jumped = match (segment.program_date_time, expected_pdt) {
+                (Some(at), Some(expected)) => {
+                    (at - expected).num_milliseconds().abs() > HLS_PDT_TOLERANCE_MS
+                }
+                _ => false,
+            };
+            if segment.discontinuity || jumped {
+                debug!("hls: {} discontinuity at segment {}", url, segment.seq);
+                let _ = tx.send(Ok(Chunk::Gap)).await;
+            }
+            if segment.map != map {
+                if let Some(init) = &segment.map {
+                    match get(&client, init).await {
+                        Ok(r) => match r.bytes().await {
+                            Ok(b) => {
+                                let _ = tx.send(Ok(Chunk::Audio(b))).await;
+                            }
+                            Err(e) => warn!("hls: init section {}: {}", init, e),
+                        },
+                        Err(e) => warn!("hls: init section {}: {}", init, e),
+                    }
+                }
+                map = segment.map.clone();
+            }
+
+            let fetched = match get(&client, &segment.url).await {
+                Ok(r) => r.bytes().await.map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+            match fetched {
+                Ok(data) => {
+                    failures = 0;
+                    if tx.send(Ok(Chunk::Audio(data))).await.is_err() {
+                        return;
+                    }
+                }
+                Err(e) => {
+                    failures += 1;
+                    warn!("hls: segment {} failed: {}", segment.seq, e);
+                    if failures >= HLS_MAX_FAILURES {
+                        let _ = tx.send(Err(e)).await;
+                        return;
+                    }
+                    let _ = tx.send(Ok(Chunk::Gap)).await;
+                }
+            }
+            playhead += segment.duration;
+            // A segment without its own date continues from the last one.
+            expected_pdt = segment
+                .program_date_time
+                .or(expected_pdt)
+                .map(|t| t + segment.duration);
+            next_seq = segment.seq + 1;
+        }
+
+        if media.ended {
+            info!("hls: {} ended", url);
+            return;
+        }
+        // RFC 8216 §6.3.4: wait about half a target duration between reloads.
+        let wait = (media.target_duration / 2).max(Duration::from_secs(1));
+        tokio::time::sleep(wait).await;
+        if tx.is_closed() {
+            return;
+        }
+        match fetch_media_playlist(&client, &url).await {
+            Ok(next) => {
+                failures = 0;
+                media = next;
+            }
+            Err(e) => {
+                failures += 1;
+                warn!("hls: reloading {} failed: {}", url, e);
+                if failures >= HLS_MAX_FAILURES {
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MASTER: &str = "#EXTM3U
+#EXT-X-STREAM-INF:BANDWIDTH=320000,CODECS=\"mp4a.40.2\"
+hi/index.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=64000,CODECS=\"mp4a.40.5\"
+lo/index.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=128000,CODECS=\"mp4a.40.2\"
+https://cdn.example/mid/index.m3u8
+";
+
+    const MEDIA: &str = "#EXTM3U
+#EXT-X-VERSION:3
+#EXT-X-TARGETDURATION:6
+#EXT-X-MEDIA-SEQUENCE:4120
+#EXT-X-PROGRAM-DATE-TIME:2024-05-01T12:00:00.000Z
+#EXTINF:6.000,
+seg4120.aac
+#EXTINF:6.000,
+seg4121.aac
+#EXT-X-DISCONTINUITY
+#EXT-X-PROGRAM-DATE-TIME:2024-05-01T12:00:30.000+00:00
+#EXTINF:5.5,title=\"x\"
+seg4122.aac?token=1
+";
+
+    #[test]
+    fn test_wrapper_target() {
+        let pls = "[playlist]\nNumberOfEntries=1\nFile1=http://s.example:8000/live\n";
+        assert_eq!(
+            wrapper_target("http://r.example/listen", pls),
+            Ok(Some("http://s.example:8000/live".into()))
+        );
+        let m3u = "#EXTM3U\n#EXTINF:-1,Radio\nstream.mp3\n";
+        assert_eq!(
+            wrapper_target("http://r.example/radio.m3u", m3u),
+            Ok(Some("http://r.example/stream.mp3".into()))
+        );
+        assert_eq!(parse_hls("http://r.example/radio.m3u", m3u), None);
+    }
+
+    #[test]
+    fn test_master_playlist_variants() {
+        let Some(Hls::Master(variants)) = parse_hls("https://r.example/live/master.m3u8", MASTER)
+        else {
+            panic!("not a master playlist");
+        };
+        assert_eq!(variants.len(), 3);
+        assert_eq!(variants[0].url, "https://r.example/live/hi/index.m3u8");
+        assert_eq!(variants[1].bandwidth, 64_000);
+        assert_eq!(variants[1].codec.as_deref(), Some("aac"));
+        assert_eq!(pick_variant(&variants, 0).unwrap().bandwidth, 320_000);
+        assert_eq!(pick_variant(&variants, 200_000).unwrap().bandwidth, 128_000);
+        assert_eq!(pick_variant(&variants, 10_000).unwrap().bandwidth, 64_000);
+    }
+
+    #[test]
+    fn test_media_playlist_segments() {
+        let Some(Hls::Media(media)) = parse_hls("https://r.example/live/lo/index.m3u8", MEDIA)
+        else {
+            panic!("not a media playlist");
+        };
+        assert_eq!(media.target_duration, Duration::from_secs(6));
+        assert!(!media.ended && !media.encrypted);
+        let seqs: Vec<u64> = media.segments.iter().map(|s| s.seq).collect();
+        assert_eq!(seqs, [4120, 4121, 4122]);
+        let last = &media.segments[2];
+        assert_eq!(last.url, "https://r.example/live/lo/seg4122.aac?token=1");
+        assert_eq!(last.duration, Duration::from_millis(5500));
+        assert!(last.discontinuity && !media.segments[1].discontinuity);
+        let first_pdt = media.segments[0].program_date_time.unwrap();
+        assert_eq!(
+            (last.program_date_time.unwrap() - first_pdt).num_seconds(),
+            30
+        );
+        assert_eq!(media.segments[1].program_date_time, None);
+        assert_eq!(
+            segment_content_type(&last.url, &HeaderMap::new()),
+            "audio/aac"
+        );
+
+        for odd in ["inf", "NaN", "-inf", "soon"] {
+            let body = MEDIA.replace("#EXTINF:5.5,", &format!("#EXTINF:{},", odd));
+            let Some(Hls::Media(media)) = parse_hls("https://r.example/live/lo/index.m3u8", &body)
+            else {
+                panic!("not a media playlist");
+            };
+            assert_eq!(
+                media.segments[2].duration,
+                Duration::from_secs(6),
+                "{}",
+                odd
+            );
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Sleep timer and wake-up alarm helpers.
+//!
+//! `DaemonCore` owns both timers and checks them on its 1-second timeline
+//! tick.  Neither touches the stored volume: while the sleep timer fades out
+//! or an alarm ramps in, the core scales the level it sends to mpv by the
+//! factors computed here, so the user's volume is intact once they finish.
+
+use chrono::{DateTime, Duration, Local, NaiveTime};
+
+use radio_proto::protocol::SleepTimer;
+
+/// Next local wall-clock occurrence of `time` ("HH:MM") strictly after `now`.
+pub fn next_occurrence(time: &str, now: DateTime<Local>) -> anyhow::Result<DateTime<Local>> {
+    let t = NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .map_err(|_| anyhow::anyhow!("invalid alarm time {:?} (expected HH:MM)", time))?;
+    let mut date = now.date_naive();
+    // Two tries: today, else tomorrow.  `earliest` resolves the DST fold; a
+    // time inside the DST gap doesn't exist that day and moves on.
+    for _ in 0..2 {
+        if let Some(at) = date.and_time(t).and_local_timezone(Local).earliest() {
+            if at > now {
+                return Ok(at);
+            }
+        }
+        date += Duration::days(1);
+    }
+    anyhow::bail!("alarm time {} does not occur in the next day", time)
+}
+
+/// Volume multiplier for a running sleep timer at unix time `now`:
+/// 1.0 until the fade starts, falling linearly to 0.0 at `ends_at`.
+pub fn fade_out_factor(timer: &SleepTimer, now: f64) -> f32 {
+    let left = timer.ends_at as f64 - now;
+    if timer.fade_secs == 0 {
+        return if left > 0.0 { 1.0 } else { 0.0 };
+    }
+    (left / timer.fade_secs as f64).clamp(0.0, 1.0) as f32
+}
+
+/// Volume multiplier `elapsed` seconds into an alarm's ramp: 0.0 → 1.0.
+pub fn ramp_up_factor(elapsed: f64, ramp_secs: u64) -> f32 {
+    if ramp_secs == 0 {
+        return 1.0;
+    }
+    (elapsed / ramp_secs as f64).clamp(0.0, 1.0) as f32
+}
+
+/// Current unix time in (fractional) seconds.
+pub fn now_secs() -> f64 {
+    Local::now().timestamp_millis() as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Timelike};
+
+    #[test]
+    fn test_next_occurrence() {
+        let now = Local.with_ymd_and_hms(2024, 5, 10, 22, 15, 0).unwrap();
+        let later = next_occurrence("23:00", now).unwrap();
+        assert_eq!(later.date_naive(), now.date_naive());
+        assert_eq!((later.hour(), later.minute()), (23, 0));
+
+        let tomorrow = next_occurrence("07:30", now).unwrap();
+        assert_eq!(tomorrow.date_naive(), now.date_naive().succ_opt().unwrap());
+        assert_eq!((tomorrow.hour(), tomorrow.minute()), (7, 30));
+
+        // The current minute has already started, so it means tomorrow.
+        assert!(next_occurrence("22:15", now).unwrap() > now + Duration::hours(23));
+        assert!(next_occurrence("25:00", now).is_err());
+        assert!(next_occurrence("soon", now).is_err());
+    }
+
+    #[test]
+    fn test_fade_and_ramp() {
+        let timer = SleepTimer {
+            ends_at: 1_000,
+            fade_secs: 100,
+        };
+        assert_eq!(fade_out_factor(&timer, 800.0), 1.0);
+        assert_eq!(fade_out_factor(&timer, 950.0), 0.5);
+        assert_eq!(fade_out_factor(&timer, 1_000.0), 0.0);
+
+        assert_eq!(ramp_up_factor(0.0, 120), 0.0);
+        assert_eq!(ramp_up_factor(30.0, 120), 0.25);
+        assert_eq!(ramp_up_factor(500.0, 120), 1.0);
+        assert_eq!(ramp_up_factor(0.0, 0), 1.0);
+    }
+}
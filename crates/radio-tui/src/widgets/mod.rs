@@ -2,5 +2,6 @@ pub mod filter_input;
 pub mod pane_chrome;
 pub mod progress_bar;
 pub mod scrollable_list;
+pub mod station_form;
 pub mod status_bar;
 pub mod toast;
@@ -0,0 +1,228 @@
+//! StationForm — the station editor, drawn over the bottom of the station
+//! list (`E` edit, `A` new).
+//!
+//!   ↑ / ↓   — previous / next field
+//!   Enter   — validate and save (the form stays open on an error)
+//!   Esc     — cancel
+//!
+//! Mirrors and tags are comma-separated.  The description isn't edited here; an edited
+//! station keeps its own.
+
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use radio_proto::protocol::Station;
+use radio_proto::state::validate_station;
+
+use crate::theme::{
+    C_ERROR, C_FILTER_BG, C_FILTER_FG, C_MUTED, C_PANEL_BORDER_FOCUSED, C_PRIMARY, C_SECONDARY,
+};
+
+/// (label, placeholder) per field, in `Station` order.
+const FIELDS: [(&str, &str); 8] = [
+    ("name", "display name"),
+    ("url", "stream URL"),
+    ("mirrors", "fallback stream URLs (optional)"),
+    ("mixtape_url", "archive / mixtape URL (optional)"),
+    ("network", "parent brand: NTS, SomaFM, BBC…"),
+    ("tags", "jazz, talk, community…"),
+    ("city", ""),
+    ("country", ""),
+];
+const LABEL_WIDTH: usize = 12;
+
+pub enum FormAction {
+    /// Save: the station index edited (`None` for a new station) and the result.
+    Save(Option<usize>, Box<Station>),
+    Cancelled,
+    None,
+}
+
+pub struct StationForm {
+    /// Station being edited; `None` while adding one.
+    target: Option<usize>,
+    /// The station as opened, for the fields the form doesn't show.
+    base: Station,
+    inputs: Vec<Input>,
+    focus: usize,
+    error: Option<String>,
+    active: bool,
+}
+
+impl StationForm {
+    pub fn new() -> Self {
+        Self {
+            target: None,
+            base: Station::default(),
+            inputs: vec![Input::default(); FIELDS.len()],
+            focus: 0,
+            error: None,
+            active: false,
+        }
+    }
+
+    /// Open on `station`: the one at `target`, or a template for a new one.
+    pub fn open(&mut self, target: Option<usize>, station: &Station) {
+        let values = [
+            station.name.clone(),
+            station.url.clone(),
+            station.mirrors.join(", "),
+            station.mixtape_url.clone(),
+            station.network.clone(),
+            station.tags.join(", "),
+            station.city.clone(),
+            station.country.clone(),
+        ];
+        self.inputs = values.into_iter().map(Input::new).collect();
+        self.target = target;
+        self.base = station.clone();
+        self.focus = 0;
+        self.error = None;
+        self.active = true;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+        self.error = None;
+    }
+
+    /// Rows `draw` needs: the fields, a hint/error line and the borders.
+    pub fn height(&self) -> u16 {
+        FIELDS.len() as u16 + 3
+    }
+
+    fn station(&self) -> Station {
+        let value = |i: usize| self.inputs[i].value().trim().to_string();
+        let list = |i: usize| {
+            value(i)
+                .split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect()
+        };
+        Station {
+            name: value(0),
+            url: value(1),
+            mirrors: list(2),
+            mixtape_url: value(3),
+            network: value(4),
+            tags: list(5),
+            city: value(6),
+            country: value(7),
+            ..self.base.clone()
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, stations: &[Station]) -> FormAction {
+        match key.code {
+            KeyCode::Esc => {
+                self.close();
+                return FormAction::Cancelled;
+            }
+            KeyCode::Up => self.focus = self.focus.saturating_sub(1),
+            KeyCode::Down => self.focus = (self.focus + 1).min(FIELDS.len() - 1),
+            KeyCode::Enter => {
+                let station = self.station();
+                match validate_station(&station, stations, self.target) {
+                    Ok(()) => {
+                        self.close();
+                        return FormAction::Save(self.target, Box::new(station));
+                    }
+                    Err(e) => self.error = Some(e.to_string()),
+                }
+            }
+            _ => {
+                self.inputs[self.focus].handle_event(&Event::Key(key));
+                self.error = None;
+            }
+        }
+        FormAction::None
+    }
+
+    /// Draw the form over the bottom of `area`.
+    pub fn draw(&self, frame: &mut Frame, area: Rect) {
+        let height = self.height().min(area.height);
+        let area = Rect {
+            y: area.y + area.height - height,
+            height,
+            ..area
+        };
+        let title = if self.target.is_some() {
+            " edit station "
+        } else {
+            " new station "
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(C_PANEL_BORDER_FOCUSED))
+            .title(Span::styled(
+                title,
+                Style::default().fg(C_PRIMARY).add_modifier(Modifier::BOLD),
+            ));
+        let inner = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let value_width = (inner.width as usize).saturating_sub(LABEL_WIDTH + 1);
+        let mut lines: Vec<Line> = Vec::with_capacity(FIELDS.len() + 1);
+        for (i, ((label, placeholder), input)) in FIELDS.iter().zip(&self.inputs).enumerate() {
+            let focused = i == self.focus;
+            let label_style = if focused {
+                Style::default().fg(C_FILTER_FG)
+            } else {
+                Style::default().fg(C_SECONDARY)
+            };
+            let scroll = input.visual_scroll(value_width);
+            let value = if input.value().is_empty() {
+                Span::styled(placeholder.to_string(), Style::default().fg(C_MUTED))
+            } else {
+                let fg = if focused { C_FILTER_FG } else { C_PRIMARY };
+                Span::styled(input.value()[scroll..].to_string(), Style::default().fg(fg))
+            };
+            let row_style = if focused {
+                Style::default().bg(C_FILTER_BG)
+            } else {
+                Style::default()
+            };
+            lines.push(
+                Line::from(vec![
+                    Span::styled(format!(" {:<w$}", label, w = LABEL_WIDTH), label_style),
+                    value,
+                ])
+                .style(row_style),
+            );
+            if focused && (i as u16) < inner.height {
+                let x = inner.x + 1 + LABEL_WIDTH as u16 + (input.visual_cursor() - scroll) as u16;
+                frame.set_cursor_position((x.min(inner.x + inner.width - 1), inner.y + i as u16));
+            }
+        }
+        lines.push(match &self.error {
+            Some(e) => Line::from(Span::styled(
+                format!(" {}", e),
+                Style::default().fg(C_ERROR),
+            )),
+            None => Line::from(Span::styled(
+                " ↑↓ field · enter save · esc cancel",
+                Style::default().fg(C_MUTED),
+            )),
+        });
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+impl Default for StationForm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
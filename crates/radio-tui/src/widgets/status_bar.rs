@@ -8,6 +8,8 @@ use ratatui::{
     Frame,
 };
 
+use radio_proto::protocol::DaemonState;
+
 use crate::theme::{
     C_ACCENT, C_MODE_COMMAND, C_MODE_FILTER, C_MODE_NORMAL, C_MUTED, C_PLAYING, C_SECONDARY,
     C_SEPARATOR,
@@ -76,7 +78,8 @@ pub fn draw_separator(frame: &mut Frame, area: Rect) {
     frame.render_widget(Paragraph::new(line), area);
 }
 
-/// Draw the keybindings footer bar (one row).
+/// Draw the keybindings footer bar (one row), with any sleep timer or alarm
+/// from `daemon_state`.
 pub fn draw_keys_bar(
     frame: &mut Frame,
     area: Rect,
@@ -84,6 +87,7 @@ pub fn draw_keys_bar(
     workspace: crate::action::Workspace,
     mpv_audio_level: f32,
     auto_polling_enabled: bool,
+    daemon_state: &DaemonState,
 ) {
     let (label, label_color, bulb, show_bulb) = match mode {
         InputMode::Filter => ("FILTER", C_MODE_FILTER, C_MODE_FILTER, false),
@@ -123,14 +127,32 @@ pub fn draw_keys_bar(
         left_spans.push(Span::styled(poll_label, Style::default().fg(poll_color)));
         left_spans.push(Span::raw(" "));
     }
+    if let Some(timer) = &daemon_state.sleep_timer {
+        let left = (timer.ends_at - chrono::Local::now().timestamp()).max(0);
+        left_spans.push(Span::styled(
+            format!("sleep:{}:{:02}", left / 60, left % 60),
+            Style::default().fg(C_ACCENT),
+        ));
+        left_spans.push(Span::raw(" "));
+    }
+    if let Some(alarm) = &daemon_state.alarm {
+        let at = chrono::DateTime::from_timestamp(alarm.at, 0)
+            .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+            .unwrap_or_default();
+        left_spans.push(Span::styled(
+            format!("alarm:{}", at),
+            Style::default().fg(C_ACCENT),
+        ));
+        left_spans.push(Span::raw(" "));
+    }
 
     let keys = match mode {
         InputMode::Normal => match workspace {
             crate::action::Workspace::Radio => {
-                " ↑↓/jk select  Enter play/stop  Space pause  ←→ vol  n/P/r/R playback  p polling  !/@ NTS  o scope  Tab/1-4 panes  / filter  K keys  L logs  ? help  q quit"
+                " ↑↓/jk select  Enter play/stop  Space pause  ,/. rewind  e live  ←→ vol  n/P/r/R playback  p polling  w rec  z/Z sleep/alarm  !/@ NTS  o scope  Tab/1-4 panes  / filter  K keys  L logs  ? help  q quit"
             }
             crate::action::Workspace::Files => {
-                " ↑↓/jk select  Enter play/stop  Space pause  ,/. seek (Shift=±5m)  ←→ vol  n/P/r/R playback  p polling  Tab/1-4 panes  / filter  K keys  L logs  ? help  q quit"
+                " ↑↓/jk select  Enter play/stop  Space pause  ,/. seek (Shift=±5m)  ←→ vol  n/P/r/R playback  a queue  p polling  z/Z sleep/alarm  Tab/1-5 panes  / filter  K keys  L logs  ? help  q quit"
             }
         },
         InputMode::Filter => " type to filter  Up/Down move  Enter keep  Esc clear+close  Tab next pane",
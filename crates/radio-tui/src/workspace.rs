@@ -23,6 +23,10 @@ pub enum RightPane {
     Nts2,
     /// Oscilloscope waveform display
     Scope,
+    /// Station directory search (full right column)
+    Discover,
+    /// Stream health (full right column)
+    Diagnostics,
 }
 
 pub struct WorkspaceManager {
@@ -34,7 +38,7 @@ pub struct WorkspaceManager {
 
     // ── Files workspace state ─────────────────────────────────────────────────
     pub files_right_maximized: bool,
-    /// Which right pane has focus in files mode (FileMeta=0, IcyTicker=1, SongsTicker=2)
+    /// Which right pane has focus in files mode (FileMeta=0, QueuePanel=1, IcyTicker=2, SongsTicker=3)
     pub files_right_focus: u8,
 
     // ── Shared UI ─────────────────────────────────────────────────────────────
@@ -106,10 +110,17 @@ impl WorkspaceManager {
                 RightPane::Scope => {
                     vec![ComponentId::StationList, ComponentId::ScopePanel]
                 }
+                RightPane::Discover => {
+                    vec![ComponentId::StationList, ComponentId::DiscoverPanel]
+                }
+                RightPane::Diagnostics => {
+                    vec![ComponentId::StationList, ComponentId::DiagnosticsPanel]
+                }
             },
             Workspace::Files => vec![
                 ComponentId::FileList,
                 ComponentId::FileMeta,
+                ComponentId::QueuePanel,
                 ComponentId::IcyTicker,
                 ComponentId::SongsTicker,
             ],
@@ -176,6 +187,30 @@ impl WorkspaceManager {
         }
     }
 
+    /// Toggle the station discovery panel.
+    pub fn toggle_discover(&mut self) {
+        if self.workspace == Workspace::Radio {
+            if self.radio_right_pane == RightPane::Discover {
+                self.radio_right_pane = RightPane::Tickers;
+            } else {
+                self.radio_right_pane = RightPane::Discover;
+            }
+            self.rebuild_focus_ring();
+        }
+    }
+
+    /// Toggle the stream diagnostics panel.
+    pub fn toggle_diagnostics(&mut self) {
+        if self.workspace == Workspace::Radio {
+            if self.radio_right_pane == RightPane::Diagnostics {
+                self.radio_right_pane = RightPane::Tickers;
+            } else {
+                self.radio_right_pane = RightPane::Diagnostics;
+            }
+            self.rebuild_focus_ring();
+        }
+    }
+
     /// Toggle whether the right pane is maximized in the current workspace.
     pub fn toggle_right_maximized(&mut self) {
         match self.workspace {